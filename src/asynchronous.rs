@@ -0,0 +1,156 @@
+//! Equity calculations for async code, such as a server
+//! Enabled with the `tokio` feature, every simulation runs on tokio's
+//! blocking thread pool so it never holds up the runtime
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use tokio::task::JoinHandle;
+
+use crate::compare::Scenario;
+use crate::equity::{default_threads, run_range_calculation, HandResult, SimulationSettings};
+use crate::error::Error;
+use crate::game::Game;
+
+/// A simulation running on the blocking thread pool, stopped when it is
+/// dropped before it finishes
+struct Calculation {
+    task: JoinHandle<Vec<HandResult>>,
+    stop: Arc<AtomicBool>,
+}
+
+///////////////////////////////////////////////
+/// Calculation Implementations
+///////////////////////////////////////////////
+impl Calculation {
+    /// Check the scenario and start simulating it on `threads` worker threads
+    fn spawn(
+        game: Game,
+        scenario: Scenario,
+        iterations: u32,
+        threads: usize,
+    ) -> Result<Self, Error> {
+        scenario.check(game)?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let flag = stop.clone();
+        let task = tokio::task::spawn_blocking(move || {
+            let settings = SimulationSettings {
+                threads,
+                stop: Some(&flag),
+                ..SimulationSettings::new(iterations)
+            };
+            let Scenario {
+                board,
+                dead,
+                ranges,
+            } = scenario;
+            run_range_calculation(
+                game,
+                board,
+                dead,
+                ranges,
+                &settings,
+                &mut rand::thread_rng(),
+            )
+        });
+
+        Ok(Calculation { task, stop })
+    }
+
+    /// Wait for the results, passing on a panic of the simulation
+    async fn join(mut self) -> Vec<HandResult> {
+        match (&mut self.task).await {
+            Ok(results) => results,
+            Err(error) => std::panic::resume_unwind(error.into_panic()),
+        }
+    }
+}
+
+impl Drop for Calculation {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Simulate a scenario without blocking the async runtime, on every logical
+/// CPU like `run_range_calculation`
+/// Dropping the future stops the simulation at its next iteration
+pub async fn calculate_equity_async(
+    game: Game,
+    scenario: Scenario,
+    iterations: u32,
+) -> Result<Vec<HandResult>, Error> {
+    let calculation = Calculation::spawn(game, scenario, iterations, default_threads())?;
+    Ok(calculation.join().await)
+}
+
+/// Simulate many scenarios at once, sharing the logical CPUs between them
+/// Each scenario is checked on its own, so one that can not be played
+/// gives an error without stopping the others
+pub async fn calculate_many_async(
+    game: Game,
+    scenarios: Vec<Scenario>,
+    iterations: u32,
+) -> Vec<Result<Vec<HandResult>, Error>> {
+    let threads = (default_threads() / scenarios.len().max(1)).max(1);
+    let calculations = scenarios
+        .into_iter()
+        .map(|scenario| Calculation::spawn(game, scenario, iterations, threads))
+        .collect::<Vec<_>>();
+
+    // Every simulation is already running, so waiting on them in order
+    // takes as long as the slowest
+    let mut results = Vec::with_capacity(calculations.len());
+    for calculation in calculations {
+        results.push(match calculation {
+            Ok(calculation) => Ok(calculation.join().await),
+            Err(error) => Err(error),
+        });
+    }
+    results
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::hole;
+    use crate::range::Range;
+
+    fn scenario(hands: [[crate::card::Card; 2]; 2]) -> Scenario {
+        Scenario {
+            board: vec![],
+            dead: vec![],
+            ranges: hands.into_iter().map(Range::from_combo).collect(),
+        }
+    }
+
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    #[test]
+    fn test_calculate_equity_async() {
+        let aces = scenario([hole!("AhAs"), hole!("KdKh")]);
+        let results = block_on(calculate_equity_async(Game::Holdem, aces, 10_000)).unwrap();
+        assert_eq!(results[0].showdowns(), 10_000);
+        assert!((results[0].equity() - 0.82).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_calculate_many_async() {
+        let scenarios = vec![
+            scenario([hole!("AhAs"), hole!("KdKh")]),
+            scenario([hole!("AhAs"), hole!("AhKh")]),
+            scenario([hole!("7c2d"), hole!("KdKh")]),
+        ];
+        let results = block_on(calculate_many_async(Game::Holdem, scenarios, 5_000));
+
+        assert!(results[0].as_ref().unwrap()[0].equity() > 0.75);
+        assert!(matches!(results[1], Err(Error::DuplicateCard { .. })));
+        assert!(results[2].as_ref().unwrap()[0].equity() < 0.2);
+    }
+}
@@ -0,0 +1,139 @@
+//! Card removal, how many combos of a range a hand makes impossible
+//! because it holds one of their cards
+
+use crate::card::Card;
+use crate::error::Error;
+use crate::game::Game;
+use crate::outs::made_hand;
+use crate::poker_hand::HandCategory;
+use crate::range::Range;
+
+/// Combos of a range and how many of them are blocked
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BlockedCount {
+    /// Combos that could be held before looking at the hand
+    pub combos: usize,
+
+    /// Of those, combos sharing a card with the hand
+    pub blocked: usize,
+}
+
+impl BlockedCount {
+    /// Combos that are still possible
+    pub fn left(&self) -> usize {
+        self.combos - self.blocked
+    }
+
+    /// Fraction of the combos that are blocked
+    pub fn fraction(&self) -> f64 {
+        self.blocked as f64 / self.combos.max(1) as f64
+    }
+
+    fn add(&mut self, blocked: bool) {
+        self.combos += 1;
+        self.blocked += usize::from(blocked);
+    }
+}
+
+/// Combos of a range blocked by a hand, in total and by what they make
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Blockers {
+    /// Every combo of the range
+    pub total: BlockedCount,
+
+    /// Combos by the category of hand they make on the board, indexed by
+    /// `HandCategory as usize`, None before the flop
+    pub categories: Option<[BlockedCount; 9]>,
+}
+
+impl Blockers {
+    /// Counts of the combos making a category, None before the flop
+    pub fn category(&self, category: HandCategory) -> Option<BlockedCount> {
+        self.categories.map(|counts| counts[category as usize])
+    }
+}
+
+/// Count the combos of `range` that `hand` blocks
+/// Combos using a board or dead card are left out as they were never possible
+/// Every combo counts once, so weighted ranges are rejected
+pub fn blockers(
+    game: Game,
+    hand: &[Card],
+    range: &Range,
+    board: &[Card],
+    dead: &[Card],
+) -> Result<Blockers, Error> {
+    if range.is_weighted() {
+        return Err(Error::WeightedCombos);
+    }
+
+    let known = board.iter().chain(dead).cloned().collect::<Vec<_>>();
+    let mut result = Blockers {
+        categories: (board.len() >= 3).then(Default::default),
+        ..Blockers::default()
+    };
+
+    for combo in range.without_cards(&known).combos() {
+        let blocked = combo.iter().any(|card| hand.contains(card));
+        result.total.add(blocked);
+
+        if let Some(categories) = &mut result.categories {
+            let category = made_hand(game, combo, board).category();
+            categories[category as usize].add(blocked);
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::card::cards_from_str;
+
+    #[test]
+    fn test_blockers() {
+        let hand = cards_from_str("AhKh").unwrap();
+        let board = cards_from_str("Qh7h2h").unwrap();
+        let range = Range::parse("AA,KK,QQ,AKs,T9s").unwrap();
+        let result = blockers(Game::Holdem, &hand, &range, &board, &[]).unwrap();
+
+        // QhQx is impossible with the Qh on the board
+        assert_eq!(result.total.combos, 6 + 6 + 3 + 4 + 4);
+        // Three combos each of aces and kings, and AhKh itself
+        assert_eq!(result.total.blocked, 3 + 3 + 1);
+
+        // Of the flushes only AhKh and Th9h, and the hand holds AhKh
+        let flushes = result.category(HandCategory::Flush).unwrap();
+        assert_eq!(
+            flushes,
+            BlockedCount {
+                combos: 2,
+                blocked: 1
+            }
+        );
+        let sets = result.category(HandCategory::ThreeOfAKind).unwrap();
+        assert_eq!(
+            sets,
+            BlockedCount {
+                combos: 3,
+                blocked: 0
+            }
+        );
+        assert_eq!(sets.left(), 3);
+
+        let preflop = blockers(Game::Holdem, &hand, &range, &[], &[]).unwrap();
+        assert_eq!(preflop.total.combos, 6 + 6 + 6 + 4 + 4);
+        assert_eq!(preflop.category(HandCategory::Pair), None);
+    }
+
+    #[test]
+    fn test_weighted_range() {
+        let hand = cards_from_str("AhKh").unwrap();
+        let range = Range::parse("AA,KK:0.5").unwrap();
+        assert!(matches!(
+            blockers(Game::Holdem, &hand, &range, &[], &[]),
+            Err(Error::WeightedCombos)
+        ));
+    }
+}
@@ -0,0 +1,234 @@
+//! The community cards and the street they make
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use crate::card::{cards_from_str, check_distinct, Card};
+use crate::error::Error;
+use crate::game::Game;
+
+/// Community cards, none before the flop and three to five after it,
+/// parsed from a string such as "7h2hKs"
+/// In Courchevel the first flop card is already out before the flop
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Board(Vec<Card>);
+
+/// A betting round of a game with a board, named after the cards dealt
+/// before it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Street {
+    Preflop,
+    Flop,
+    Turn,
+    River,
+}
+
+///////////////////////////////////////////////
+/// Street Implementations
+///////////////////////////////////////////////
+impl Street {
+    /// Every street in the order they are played
+    pub const ALL: [Street; 4] = [Street::Preflop, Street::Flop, Street::Turn, Street::River];
+
+    /// Number of board cards on the street
+    pub fn cards(self) -> usize {
+        match self {
+            Street::Preflop => 0,
+            Street::Flop => 3,
+            Street::Turn => 4,
+            Street::River => 5,
+        }
+    }
+
+    /// The street after this one, None on the river
+    pub fn next(self) -> Option<Street> {
+        match self {
+            Street::Preflop => Some(Street::Flop),
+            Street::Flop => Some(Street::Turn),
+            Street::Turn => Some(Street::River),
+            Street::River => None,
+        }
+    }
+
+    /// Name of the street, such as "Flop"
+    pub fn name(self) -> &'static str {
+        match self {
+            Street::Preflop => "Preflop",
+            Street::Flop => "Flop",
+            Street::Turn => "Turn",
+            Street::River => "River",
+        }
+    }
+}
+
+impl Display for Street {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Street {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Street::ALL
+            .into_iter()
+            .find(|street| street.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| {
+                format!(
+                    "Unknown street \"{}\", expected preflop, flop, turn or river",
+                    s
+                )
+            })
+    }
+}
+
+///////////////////////////////////////////////
+/// Board Implementations
+///////////////////////////////////////////////
+impl Board {
+    /// A board of `game`, checking it has a number of cards the game can be
+    /// played with and no card twice
+    pub fn new(game: Game, cards: Vec<Card>) -> Result<Self, Error> {
+        if !game.has_board() && !cards.is_empty() {
+            return Err(Error::NoBoard(game));
+        }
+        if !game.is_valid_board(cards.len()) {
+            return Err(Error::InvalidBoard(cards.len()));
+        }
+        check_distinct(&cards, "the board")?;
+
+        Ok(Board(cards))
+    }
+
+    /// A board from cards that have already been checked
+    pub(crate) fn from_checked(cards: Vec<Card>) -> Self {
+        Board(cards)
+    }
+
+    /// The cards of the board, in the order they were dealt
+    pub fn cards(&self) -> &[Card] {
+        &self.0
+    }
+
+    /// The street the board is on, Courchevel's exposed card being
+    /// dealt before the flop
+    pub fn street(&self) -> Street {
+        match self.0.len() {
+            0..=2 => Street::Preflop,
+            3 => Street::Flop,
+            4 => Street::Turn,
+            _ => Street::River,
+        }
+    }
+
+    /// Deal the flop onto an empty board
+    pub fn add_flop(&mut self, flop: [Card; 3]) -> Result<Street, Error> {
+        if !self.0.is_empty() {
+            return Err(Error::InvalidBoard(self.0.len() + 3));
+        }
+        check_distinct(&flop, "the flop")?;
+
+        self.0.extend(flop);
+        Ok(self.street())
+    }
+
+    /// Deal the turn or the river, returning the street it starts
+    pub fn add_card(&mut self, card: Card) -> Result<Street, Error> {
+        if !matches!(self.street(), Street::Flop | Street::Turn) {
+            return Err(Error::InvalidBoard(self.0.len() + 1));
+        }
+        if self.0.contains(&card) {
+            return Err(Error::DuplicateCard {
+                card,
+                first: "the board".to_string(),
+                second: "the board".to_string(),
+            });
+        }
+
+        self.0.push(card);
+        Ok(self.street())
+    }
+
+    /// The board as it was on an earlier street, or the whole board
+    /// when it has not reached it yet
+    pub fn on(&self, street: Street) -> Board {
+        Board(self.0[..street.cards().min(self.0.len())].to_vec())
+    }
+}
+
+impl FromStr for Board {
+    type Err = Error;
+
+    /// A hold'em board, see `Board::new` for other games
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Board::new(Game::Holdem, cards_from_str(s)?)
+    }
+}
+
+impl From<Board> for Vec<Card> {
+    fn from(board: Board) -> Self {
+        board.0
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.0.iter().try_for_each(|card| write!(f, "{}", card))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::card;
+
+    #[test]
+    fn test_streets() {
+        let mut board = Board::default();
+        assert_eq!(board.street(), Street::Preflop);
+        assert!(board.add_card(card!("Ks")).is_err());
+
+        let flop = [card!("7h"), card!("2h"), card!("Ks")];
+        assert_eq!(board.add_flop(flop).unwrap(), Street::Flop);
+        assert_eq!(board.add_card(card!("Ad")).unwrap(), Street::Turn);
+        assert!(matches!(
+            board.add_card(card!("7h")),
+            Err(Error::DuplicateCard { .. })
+        ));
+        assert_eq!(board.add_card(card!("9c")).unwrap(), Street::River);
+        assert!(matches!(
+            board.add_card(card!("Tc")),
+            Err(Error::InvalidBoard(6))
+        ));
+
+        assert_eq!(board.to_string(), "7h2hKsAd9c");
+        assert_eq!(board.on(Street::Flop).to_string(), "7h2hKs");
+        assert_eq!(board.on(Street::Preflop).street(), Street::Preflop);
+        assert_eq!(Street::Turn.next(), Some(Street::River));
+        assert_eq!(Street::ALL.map(Street::cards), [0, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_parse_board() {
+        let board = "7h2hKs".parse::<Board>().unwrap();
+        assert_eq!(board.cards().len(), 3);
+        assert!("".parse::<Board>().unwrap().cards().is_empty());
+        assert!(matches!(
+            "7h2h".parse::<Board>(),
+            Err(Error::InvalidBoard(2))
+        ));
+        assert!(matches!(
+            "7h2h7h".parse::<Board>(),
+            Err(Error::DuplicateCard { .. })
+        ));
+
+        // Courchevel shows the first flop card before the flop
+        let exposed = Board::new(Game::Courchevel, vec![card!("7h")]).unwrap();
+        assert_eq!(exposed.street(), Street::Preflop);
+        assert!(matches!(
+            Board::new(Game::Stud, vec![card!("7h")]),
+            Err(Error::NoBoard(Game::Stud))
+        ));
+    }
+}
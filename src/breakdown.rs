@@ -0,0 +1,233 @@
+//! Where a hand's equity on the flop or turn comes from: the hand it has
+//! made already, draws one card away, or backdoor draws needing both the
+//! turn and the river
+//! Every runout is enumerated and tagged by how the hand got to the
+//! category it ends with, so the parts add up to the equity
+//! Only the high hand is looked at, in split pot games as well
+
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+
+use crate::card::Card;
+use crate::deck::Deck;
+use crate::error::Error;
+use crate::evaluator::{Evaluator, EvaluatorKind};
+use crate::game::Game;
+use crate::poker_hand::HandCategory;
+use crate::poker_utils::for_each_combination;
+use crate::range::Range;
+
+/// How a runout gave a hand the category it ends with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EquitySource {
+    /// The hand ends with the category it has now
+    MadeHand,
+
+    /// The hand improves to a category that one card of the runout makes
+    Draw,
+
+    /// The hand improves to a category that takes both cards of the runout
+    Backdoor,
+}
+
+/// Equity of a hand against a range, split by where it comes from and by
+/// the category the hand ends with
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct EquityBreakdown {
+    /// Share of the pot won from each source, indexed by `EquitySource` and
+    /// then by `HandCategory`, ties counting half
+    pub shares: [[f64; 9]; 3],
+}
+
+///////////////////////////////////////////////
+/// EquitySource Implementations
+///////////////////////////////////////////////
+impl EquitySource {
+    /// Every source, made hands first
+    pub const ALL: [EquitySource; 3] = [
+        EquitySource::MadeHand,
+        EquitySource::Draw,
+        EquitySource::Backdoor,
+    ];
+
+    /// Name of the source, such as "Backdoor draws"
+    pub fn name(self) -> &'static str {
+        match self {
+            EquitySource::MadeHand => "Made hand",
+            EquitySource::Draw => "Direct draws",
+            EquitySource::Backdoor => "Backdoor draws",
+        }
+    }
+}
+
+impl Display for EquitySource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+///////////////////////////////////////////////
+/// EquityBreakdown Implementations
+///////////////////////////////////////////////
+impl EquityBreakdown {
+    /// The whole equity, every part added up
+    pub fn equity(&self) -> f64 {
+        self.shares.iter().flatten().sum()
+    }
+
+    /// Equity from one source, whatever the hand ends with
+    pub fn source(&self, source: EquitySource) -> f64 {
+        self.shares[source as usize].iter().sum()
+    }
+
+    /// Equity from one source ending with one category, such as the equity
+    /// from backdoor flushes
+    pub fn part(&self, source: EquitySource, category: HandCategory) -> f64 {
+        self.shares[source as usize][category as usize]
+    }
+}
+
+/// Equity of `hand` against every combo of `range` on a flop or turn board,
+/// broken down by where it comes from
+/// Combos count by their weight in the range, and cards held, on the board
+/// or `dead` are removed from the range first
+pub fn equity_breakdown(
+    game: Game,
+    hand: &[Card],
+    range: &Range,
+    board: &[Card],
+    dead: &[Card],
+) -> Result<EquityBreakdown, Error> {
+    if !game.has_board() {
+        return Err(Error::NoBoard(game));
+    }
+    if !matches!(board.len(), 3 | 4) {
+        return Err(Error::NotFlopOrTurn(board.len()));
+    }
+    let known = [hand, board, dead].concat();
+    let range = range.without_cards(&known);
+    if range.is_empty() {
+        return Err(Error::EmptyRange("the range".to_string()));
+    }
+
+    let evaluator = EvaluatorKind::default().evaluator();
+    let deck = Deck::without(&known);
+    let mut shares = [[0.0; 9]; 3];
+    let mut total = 0.0;
+    let mut river = board.to_vec();
+
+    for_each_combination(deck.remaining(), 5 - board.len(), |runout| {
+        river.truncate(board.len());
+        river.extend_from_slice(runout);
+        let mine = game.rules().best_hand(hand, &river, evaluator);
+        let source = source_of(game, hand, board, runout, evaluator);
+
+        for (combo, weight) in range.combos().iter().zip(range.weights()) {
+            if combo.iter().any(|card| runout.contains(card)) {
+                continue;
+            }
+            let share = match mine.cmp(&game.rules().best_hand(combo, &river, evaluator)) {
+                Ordering::Greater => 1.0,
+                Ordering::Equal => 0.5,
+                Ordering::Less => 0.0,
+            };
+            shares[source as usize][mine.category() as usize] += share * weight;
+            total += weight;
+        }
+    });
+
+    for share in shares.iter_mut().flatten() {
+        *share /= total;
+    }
+    Ok(EquityBreakdown { shares })
+}
+
+/// How `runout` took the hand from what it has on `board` to what it ends
+/// with
+fn source_of(
+    game: Game,
+    hand: &[Card],
+    board: &[Card],
+    runout: &[Card],
+    evaluator: &dyn Evaluator,
+) -> EquitySource {
+    let category = |cards: &[Card]| {
+        let board = [board, cards].concat();
+        game.rules().best_hand(hand, &board, evaluator).category()
+    };
+    let now = category(&[]);
+    let end = category(runout);
+    if end == now {
+        EquitySource::MadeHand
+    } else if runout.iter().any(|card| category(&[*card]) >= end) {
+        EquitySource::Draw
+    } else {
+        EquitySource::Backdoor
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::card::cards_from_str;
+    use crate::equity::run_exact_calculation;
+
+    fn cards(s: &str) -> Vec<Card> {
+        cards_from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_flush_draw() {
+        let range = Range::parse("AA").unwrap();
+        let board = cards("7h2hKs");
+        let breakdown =
+            equity_breakdown(Game::Holdem, &cards("9h8h"), &range, &board, &[]).unwrap();
+
+        let ranges = vec![Range::from_cards(cards("9h8h")), range];
+        let equity =
+            run_exact_calculation(Game::Holdem, board, vec![], ranges).unwrap()[0].equity();
+        assert!((breakdown.equity() - equity).abs() < 1e-9);
+
+        // Nine high never beats aces, so it all comes from improving, most
+        // of it from the flush draw and some from runner runner straights
+        assert_eq!(breakdown.source(EquitySource::MadeHand), 0.0);
+        let flush = breakdown.part(EquitySource::Draw, HandCategory::Flush);
+        assert!(
+            flush > breakdown.source(EquitySource::Draw) / 2.0,
+            "{:?}",
+            breakdown
+        );
+        assert!(breakdown.part(EquitySource::Backdoor, HandCategory::Straight) > 0.0);
+        assert!(breakdown.part(EquitySource::Backdoor, HandCategory::TwoPair) > 0.0);
+        assert_eq!(
+            breakdown.part(EquitySource::Backdoor, HandCategory::Flush),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_made_hand_on_the_turn() {
+        let range = Range::parse("QQ,JTs").unwrap();
+        let board = cards("Kh7c2dKd");
+        let breakdown =
+            equity_breakdown(Game::Holdem, &cards("AhKs"), &range, &board, &[]).unwrap();
+
+        // Trips are ahead of the range, filling up when the board pairs, and
+        // only one card is to come so nothing is a backdoor draw
+        assert_eq!(breakdown.source(EquitySource::Backdoor), 0.0);
+        let trips = breakdown.part(EquitySource::MadeHand, HandCategory::ThreeOfAKind);
+        let full_house = breakdown.part(EquitySource::Draw, HandCategory::FullHouse);
+        assert!((0.7..0.8).contains(&trips), "{:?}", breakdown);
+        assert!((0.15..0.25).contains(&full_house), "{:?}", breakdown);
+        assert!(breakdown.equity() > 0.95);
+
+        assert!(matches!(
+            equity_breakdown(Game::Holdem, &cards("AhKs"), &range, &cards("Kh7c"), &[]),
+            Err(Error::NotFlopOrTurn(2))
+        ));
+        assert!(matches!(
+            equity_breakdown(Game::Stud, &cards("AhKs"), &range, &board, &[]),
+            Err(Error::NoBoard(Game::Stud))
+        ));
+    }
+}
@@ -0,0 +1,258 @@
+//! Suit isomorphism, so spots that only differ by a relabelling of the
+//! suits, such as AhKh against AsKs on a rainbow board, share one form
+//! Exact enumerations are memoized by that form
+
+use std::array;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use crate::card::{Card, Suit};
+use crate::equity::HandResult;
+use crate::game::Game;
+use crate::range::Range;
+
+/// Most exact results kept before the memo starts over
+const MEMO_SIZE: usize = 256;
+
+/// A spot with its suits relabelled so that every isomorphic spot gives
+/// the same form, the smallest of all 24 relabellings
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Canonical {
+    /// Every combo of each hand with its cards sorted, the combos sorted too
+    pub hands: Vec<Vec<Vec<Card>>>,
+
+    /// Board cards, sorted
+    pub board: Vec<Card>,
+
+    /// Dead cards, sorted
+    pub dead: Vec<Card>,
+}
+
+/// Exact results of a canonical spot, with combos in the canonical order
+type Memo = HashMap<(Game, Canonical), Vec<HandResult>>;
+
+/// The canonical form of the hands and board, and the suit each suit was
+/// relabelled to, indexed by `Suit as usize`
+pub fn canonicalize(hands: &[Range], board: &[Card]) -> (Canonical, [Suit; 4]) {
+    canonicalize_spot(hands, board, &[])
+}
+
+/// Canonical form of a spot with dead cards too
+pub fn canonicalize_spot(hands: &[Range], board: &[Card], dead: &[Card]) -> (Canonical, [Suit; 4]) {
+    relabellings()
+        .map(|suits| {
+            let hands = hands
+                .iter()
+                .map(|range| {
+                    let mut combos = relabel_combos(range, suits);
+                    combos.sort();
+                    combos
+                })
+                .collect();
+            let form = Canonical {
+                hands,
+                board: relabel(board, suits),
+                dead: relabel(dead, suits),
+            };
+            (form, suits)
+        })
+        .min_by(|(a, _), (b, _)| a.cmp(b))
+        .unwrap()
+}
+
+/// Every way of relabelling the four suits
+pub(crate) fn relabellings() -> impl Iterator<Item = [Suit; 4]> {
+    (0..256)
+        .map(|i: usize| array::from_fn(|suit| Suit::ALL[i >> (2 * suit) & 3]))
+        .filter(|suits: &[Suit; 4]| (1..4).all(|a| !suits[..a].contains(&suits[a])))
+}
+
+/// The cards with their suits relabelled, sorted
+pub(crate) fn relabel(cards: &[Card], suits: [Suit; 4]) -> Vec<Card> {
+    let mut cards = cards
+        .iter()
+        .map(|card| Card {
+            rank: card.rank,
+            suit: suits[card.suit as usize],
+        })
+        .collect::<Vec<_>>();
+    cards.sort();
+    cards
+}
+
+/// Every combo of the range relabelled, in range order
+fn relabel_combos(range: &Range, suits: [Suit; 4]) -> Vec<Vec<Card>> {
+    range
+        .combos()
+        .iter()
+        .map(|combo| relabel(combo, suits))
+        .collect()
+}
+
+/// Index in the range of the combo at each position of the canonical form
+fn canonical_order(range: &Range, suits: [Suit; 4]) -> Vec<usize> {
+    let combos = relabel_combos(range, suits);
+    let mut order = (0..combos.len()).collect::<Vec<_>>();
+    order.sort_by(|&a, &b| combos[a].cmp(&combos[b]));
+    order
+}
+
+/// Results of a spot with the combos moved into the canonical order and
+/// relabelled, from the ranges the results were run with and the suits
+/// returned with their canonical form
+pub fn canonical_results(
+    ranges: &[Range],
+    suits: [Suit; 4],
+    results: &[HandResult],
+) -> Vec<HandResult> {
+    let mut canonical = results.to_vec();
+    for (player, range) in ranges.iter().enumerate() {
+        for (position, index) in canonical_order(range, suits).into_iter().enumerate() {
+            let combo = &mut canonical[player].combos[position];
+            *combo = results[player].combos[index].clone();
+            combo.cards = relabel(&combo.cards, suits);
+        }
+    }
+    canonical
+}
+
+/// Results of a canonical form moved back into the order and suits of
+/// isomorphic ranges, undoing `canonical_results`
+pub fn restore_results(
+    ranges: &[Range],
+    suits: [Suit; 4],
+    canonical: &[HandResult],
+) -> Vec<HandResult> {
+    let mut results = canonical.to_vec();
+    for (player, range) in ranges.iter().enumerate() {
+        for (position, index) in canonical_order(range, suits).into_iter().enumerate() {
+            let combo = &mut results[player].combos[index];
+            *combo = canonical[player].combos[position].clone();
+            combo.cards = range.combos()[index].clone();
+        }
+    }
+    results
+}
+
+fn memo() -> &'static Mutex<Memo> {
+    static MEMO: OnceLock<Mutex<Memo>> = OnceLock::new();
+    MEMO.get_or_init(Default::default)
+}
+
+/// Exact results of a spot, calling `run` only the first time its canonical
+/// form is seen
+/// The ranges must already have every combo holding a known card removed
+/// Weighted ranges are always run, as the form leaves out the weights
+pub(crate) fn memoize<F: FnOnce() -> Vec<HandResult>>(
+    game: Game,
+    ranges: &[Range],
+    board: &[Card],
+    dead: &[Card],
+    run: F,
+) -> Vec<HandResult> {
+    if ranges.iter().any(Range::is_weighted) {
+        return run();
+    }
+
+    let (form, suits) = canonicalize_spot(ranges, board, dead);
+    let key = (game, form);
+
+    if let Some(canonical) = memo().lock().unwrap().get(&key) {
+        return restore_results(ranges, suits, canonical);
+    }
+
+    let results = run();
+
+    let mut memo = memo().lock().unwrap();
+    if memo.len() >= MEMO_SIZE {
+        memo.clear();
+    }
+    memo.insert(key, canonical_results(ranges, suits, &results));
+
+    results
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::board::Board;
+    use crate::card::cards_from_str;
+    use crate::equity::{empty_results, remove_known_cards, run_exact, run_exact_calculation};
+
+    fn spot(hands: [&str; 2], board: &str) -> Canonical {
+        let hands = hands.map(|h| Range::parse(h).unwrap());
+        canonicalize(&hands, &cards_from_str(board).unwrap()).0
+    }
+
+    #[test]
+    fn test_canonicalize() {
+        // Hearts and spades swap on a rainbow board
+        assert_eq!(
+            spot(["AhKh", "QQ"], "2c7d9s"),
+            spot(["AsKs", "QQ"], "2c7d9h")
+        );
+        assert_eq!(
+            spot(["AhKh", "QQ"], "2c7d9s"),
+            spot(["KcAc", "QQ"], "9h2s7d")
+        );
+
+        // A flush draw is not the same spot as a backdoor one
+        assert_ne!(
+            spot(["AhKh", "QQ"], "Kh7h2c"),
+            spot(["AsKs", "QQ"], "Kh7h2c")
+        );
+
+        let (form, suits) = canonicalize(&[Range::parse("AhKh").unwrap()], &[]);
+        assert_eq!(form.hands[0][0][0].suit, Suit::Spades);
+        assert_eq!(suits[Suit::Hearts as usize], Suit::Spades);
+    }
+
+    #[test]
+    fn test_memoized_results_follow_suits() {
+        let exact = |hands: [&str; 2], board: &str| {
+            let ranges = hands.map(|h| Range::parse(h).unwrap()).to_vec();
+            let board = cards_from_str(board).unwrap();
+            let memoized =
+                run_exact_calculation(Game::Holdem, board.clone(), vec![], ranges.clone()).unwrap();
+
+            let (deck, ranges) = remove_known_cards(&board, &[], ranges);
+            assert_eq!(
+                memoized,
+                run_exact(Game::Holdem, deck, ranges, Board::from_checked(board)).unwrap()
+            );
+            memoized
+        };
+
+        let hearts = exact(["AhQh", "KK,77"], "Kh7h2c4d");
+        let spades = exact(["AsQs", "KK,77"], "Ks7s2c4d");
+        assert_eq!(hearts[0].wins, spades[0].wins);
+        assert_ne!(hearts[1].combos[0].cards, spades[1].combos[0].cards);
+    }
+
+    #[test]
+    fn test_weighted_ranges_are_not_memoized() {
+        // The same combos with different weights share a canonical form
+        let board = cards_from_str("Jc8d3h").unwrap();
+        let first = [
+            Range::parse("AA:0.5,KK").unwrap(),
+            Range::parse("QQ").unwrap(),
+        ];
+        let second = [
+            Range::parse("AA,KK:0.5").unwrap(),
+            Range::parse("QQ").unwrap(),
+        ];
+        assert_eq!(
+            canonicalize(&first, &board).0,
+            canonicalize(&second, &board).0
+        );
+
+        let mut runs = 0;
+        for ranges in [&first, &second, &first] {
+            memoize(Game::Holdem, ranges, &board, &[], || {
+                runs += 1;
+                empty_results(ranges)
+            });
+        }
+        assert_eq!(runs, 3);
+    }
+}
@@ -1,212 +1,642 @@
-use std::{
-    cmp::Ordering,
-    fmt::{Display, Formatter},
-};
-
-/// Defines card structures such as Rank, Card, Suit
-/// And the helper functions that they need
-
-/// Standard 2-A card rankings
-#[derive(Clone, Copy, Ord, Eq)]
-pub enum Rank {
-    Two = 2,
-    Three = 3,
-    Four = 4,
-    Five = 5,
-    Six = 6,
-    Seven = 7,
-    Eight = 8,
-    Nine = 9,
-    Ten = 10,
-    Jack = 11,
-    Queen = 12,
-    King = 13,
-    Ace = 14,
-}
-
-/// Suit Enumeration
-#[derive(Clone, Copy, PartialEq, Eq)]
-pub enum Suit {
-    Spades = 0,
-    Hearts = 1,
-    Clubs = 2,
-    Diamonds = 3,
-}
-
-/// Combination of a rank and suit
-#[derive(Clone, Copy, Eq, PartialEq)]
-pub struct Card {
-    pub rank: Rank,
-    pub suit: Suit,
-}
-
-///////////////////////////////////////////////
-/// Rank Implementations
-///////////////////////////////////////////////
-impl Rank {
-    /// Numeric Value of a rank
-    pub fn value(&self) -> u32 {
-        *self as u32
-    }
-
-    /// Next card in rankings for finding straights
-    pub fn next(&self) -> Rank {
-        match self {
-            Rank::Two => Rank::Three,
-            Rank::Three => Rank::Four,
-            Rank::Four => Rank::Five,
-            Rank::Five => Rank::Six,
-            Rank::Six => Rank::Seven,
-            Rank::Seven => Rank::Eight,
-            Rank::Eight => Rank::Nine,
-            Rank::Nine => Rank::Ten,
-            Rank::Ten => Rank::Jack,
-            Rank::Jack => Rank::Queen,
-            Rank::Queen => Rank::King,
-            Rank::King => Rank::Ace,
-            Rank::Ace => Rank::Two,
-        }
-    }
-
-    /// Convert rank to string for display
-    pub fn to_string(&self) -> String {
-        match self {
-            Rank::Two => "2",
-            Rank::Three => "3",
-            Rank::Four => "4",
-            Rank::Five => "5",
-            Rank::Six => "6",
-            Rank::Seven => "7",
-            Rank::Eight => "8",
-            Rank::Nine => "9",
-            Rank::Ten => "T",
-            Rank::Jack => "J",
-            Rank::Queen => "Q",
-            Rank::King => "K",
-            Rank::Ace => "A",
-        }
-        .to_string()
-    }
-}
-
-impl Display for Rank {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_str(self.to_string().as_str())
-    }
-}
-
-impl std::fmt::Debug for Rank {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_str(self.to_string().as_str())
-    }
-}
-
-impl PartialEq for Rank {
-    fn eq(&self, other: &Self) -> bool {
-        self.value() == other.value()
-    }
-}
-
-impl PartialOrd for Rank {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.value().partial_cmp(&other.value())
-    }
-}
-
-///////////////////////////////////////////////
-/// Suit Implementations
-///////////////////////////////////////////////
-impl std::fmt::Debug for Suit {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_str(match self {
-            Suit::Spades => "s",
-            Suit::Hearts => "h",
-            Suit::Clubs => "c",
-            Suit::Diamonds => "d",
-        })
-    }
-}
-
-impl std::fmt::Debug for Card {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_fmt(format_args!("{:?}{:?}", self.rank, self.suit))
-    }
-}
-
-#[derive(Debug)]
-#[allow(dead_code)]
-pub struct ParseCardError {
-    msg: String,
-}
-
-impl<'a> Into<ParseCardError> for &'a str {
-    fn into(self: &'a str) -> ParseCardError {
-        ParseCardError {
-            msg: self.to_string(),
-        }
-    }
-}
-
-///////////////////////////////////////////////
-/// Card Implementations
-///////////////////////////////////////////////
-impl Card {
-    pub fn new(rank: Rank, suit: Suit) -> Self {
-        Self { rank, suit }
-    }
-
-    pub fn from_string(s: &str) -> Result<Self, ParseCardError> {
-        let mut chars = s.chars().map(|c| c.to_lowercase().next().unwrap());
-
-        let rank = match chars.next().ok_or("Empty String".into())? {
-            '2' => Rank::Two,
-            '3' => Rank::Three,
-            '4' => Rank::Four,
-            '5' => Rank::Five,
-            '6' => Rank::Six,
-            '7' => Rank::Seven,
-            '8' => Rank::Eight,
-            '9' => Rank::Nine,
-            'T' => Rank::Ten,
-            't' => Rank::Ten,
-            'j' => Rank::Jack,
-            'q' => Rank::Queen,
-            'k' => Rank::King,
-            'a' => Rank::Ace,
-            _ => {
-                return Err("Unmatched Rank".into());
-            }
-        };
-
-        let suit = match chars.next().ok_or("No Suit".into())? {
-            's' => Suit::Spades,
-            'h' => Suit::Hearts,
-            'c' => Suit::Clubs,
-            'd' => Suit::Diamonds,
-            _ => {
-                return Err("Unmatched Suit".into());
-            }
-        };
-
-        Ok(Self::new(rank, suit))
-    }
-}
-
-///
-/// Get a vector of cards from a string
-/// such as "AhKsQh2c"
-///
-pub fn cards_from_str(s: &str) -> Vec<Card> {
-    if s.is_empty() {
-        return vec![];
-    }
-
-    // Group into two characters
-    // First character is rank, second is suit
-    s.chars()
-        .collect::<Vec<_>>()
-        .chunks(2)
-        .map(|chunk| chunk.iter().collect::<String>())
-        .map(|card| Card::from_string(&card).expect(&format!("Error Parsing String: {}", s)))
-        .collect()
-}
+//! Defines card structures such as Rank, Card, Suit
+//! And the helper functions that they need
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::Error;
+use crate::game::Game;
+use crate::preflop::StartingHandClass;
+
+/// Standard 2-A card rankings
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Rank {
+    Two = 2,
+    Three = 3,
+    Four = 4,
+    Five = 5,
+    Six = 6,
+    Seven = 7,
+    Eight = 8,
+    Nine = 9,
+    Ten = 10,
+    Jack = 11,
+    Queen = 12,
+    King = 13,
+    Ace = 14,
+}
+
+/// Suit Enumeration
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Suit {
+    Spades = 0,
+    Hearts = 1,
+    Clubs = 2,
+    Diamonds = 3,
+}
+
+/// Combination of a rank and suit
+#[derive(Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Hash)]
+pub struct Card {
+    pub rank: Rank,
+    pub suit: Suit,
+}
+
+/// The two hole cards of a hold'em hand, parsed from a string such as "AhKh"
+/// The higher card is always first, so "KhAh" is the same hand
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HoleCards([Card; 2]);
+
+///////////////////////////////////////////////
+/// Rank Implementations
+///////////////////////////////////////////////
+impl Rank {
+    /// All ranks from lowest to highest
+    pub const ALL: [Rank; 13] = [
+        Rank::Two,
+        Rank::Three,
+        Rank::Four,
+        Rank::Five,
+        Rank::Six,
+        Rank::Seven,
+        Rank::Eight,
+        Rank::Nine,
+        Rank::Ten,
+        Rank::Jack,
+        Rank::Queen,
+        Rank::King,
+        Rank::Ace,
+    ];
+
+    /// Parse a rank from its character, case insensitive
+    pub const fn from_char(c: char) -> Option<Rank> {
+        match c.to_ascii_lowercase() {
+            '2' => Some(Rank::Two),
+            '3' => Some(Rank::Three),
+            '4' => Some(Rank::Four),
+            '5' => Some(Rank::Five),
+            '6' => Some(Rank::Six),
+            '7' => Some(Rank::Seven),
+            '8' => Some(Rank::Eight),
+            '9' => Some(Rank::Nine),
+            't' => Some(Rank::Ten),
+            'j' => Some(Rank::Jack),
+            'q' => Some(Rank::Queen),
+            'k' => Some(Rank::King),
+            'a' => Some(Rank::Ace),
+            _ => None,
+        }
+    }
+
+    /// Numeric Value of a rank
+    pub fn value(&self) -> u32 {
+        *self as u32
+    }
+
+    /// Next card in rankings for finding straights
+    pub fn next(&self) -> Rank {
+        match self {
+            Rank::Two => Rank::Three,
+            Rank::Three => Rank::Four,
+            Rank::Four => Rank::Five,
+            Rank::Five => Rank::Six,
+            Rank::Six => Rank::Seven,
+            Rank::Seven => Rank::Eight,
+            Rank::Eight => Rank::Nine,
+            Rank::Nine => Rank::Ten,
+            Rank::Ten => Rank::Jack,
+            Rank::Jack => Rank::Queen,
+            Rank::Queen => Rank::King,
+            Rank::King => Rank::Ace,
+            Rank::Ace => Rank::Two,
+        }
+    }
+
+    /// Convert rank to a single character for display
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Rank::Two => "2",
+            Rank::Three => "3",
+            Rank::Four => "4",
+            Rank::Five => "5",
+            Rank::Six => "6",
+            Rank::Seven => "7",
+            Rank::Eight => "8",
+            Rank::Nine => "9",
+            Rank::Ten => "T",
+            Rank::Jack => "J",
+            Rank::Queen => "Q",
+            Rank::King => "K",
+            Rank::Ace => "A",
+        }
+    }
+
+    /// Name of the rank, such as "Ace" or "Seven"
+    pub fn name(self) -> &'static str {
+        match self {
+            Rank::Two => "Two",
+            Rank::Three => "Three",
+            Rank::Four => "Four",
+            Rank::Five => "Five",
+            Rank::Six => "Six",
+            Rank::Seven => "Seven",
+            Rank::Eight => "Eight",
+            Rank::Nine => "Nine",
+            Rank::Ten => "Ten",
+            Rank::Jack => "Jack",
+            Rank::Queen => "Queen",
+            Rank::King => "King",
+            Rank::Ace => "Ace",
+        }
+    }
+
+    /// Plural name of the rank, such as "Aces" or "Sixes"
+    pub fn plural(self) -> &'static str {
+        match self {
+            Rank::Two => "Twos",
+            Rank::Three => "Threes",
+            Rank::Four => "Fours",
+            Rank::Five => "Fives",
+            Rank::Six => "Sixes",
+            Rank::Seven => "Sevens",
+            Rank::Eight => "Eights",
+            Rank::Nine => "Nines",
+            Rank::Ten => "Tens",
+            Rank::Jack => "Jacks",
+            Rank::Queen => "Queens",
+            Rank::King => "Kings",
+            Rank::Ace => "Aces",
+        }
+    }
+}
+
+impl Display for Rank {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::fmt::Debug for Rank {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+///////////////////////////////////////////////
+/// Suit Implementations
+///////////////////////////////////////////////
+impl Suit {
+    /// All four suits
+    pub const ALL: [Suit; 4] = [Suit::Spades, Suit::Hearts, Suit::Clubs, Suit::Diamonds];
+
+    /// Parse a suit from its character, case insensitive
+    pub const fn from_char(c: char) -> Option<Suit> {
+        match c.to_ascii_lowercase() {
+            's' => Some(Suit::Spades),
+            'h' => Some(Suit::Hearts),
+            'c' => Some(Suit::Clubs),
+            'd' => Some(Suit::Diamonds),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Suit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(self, f)
+    }
+}
+
+impl std::fmt::Debug for Suit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Suit::Spades => "s",
+            Suit::Hearts => "h",
+            Suit::Clubs => "c",
+            Suit::Diamonds => "d",
+        })
+    }
+}
+
+impl std::fmt::Debug for Card {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("{:?}{:?}", self.rank, self.suit))
+    }
+}
+
+/// Cards are written as their two characters, such as "Ah"
+impl Serialize for Card {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Card {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Card::from_string(&s).map_err(de::Error::custom)
+    }
+}
+
+/// Ranks are written as their character, such as "A" or "T"
+impl Serialize for Rank {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Rank {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        single_char(&s)
+            .and_then(Rank::from_char)
+            .ok_or_else(|| de::Error::custom(format!("unknown rank \"{}\"", s)))
+    }
+}
+
+/// Suits are written as their character, such as "h"
+impl Serialize for Suit {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Suit {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        single_char(&s)
+            .and_then(Suit::from_char)
+            .ok_or_else(|| de::Error::custom(format!("unknown suit \"{}\"", s)))
+    }
+}
+
+/// The only character of a string, None when it has more or none
+fn single_char(s: &str) -> Option<char> {
+    let mut chars = s.chars();
+    chars.next().filter(|_| chars.next().is_none())
+}
+
+/// Error from parsing a card string
+/// Positions are the 1 based character index in the parsed string
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ParseCardError {
+    #[error("no card given")]
+    Empty,
+
+    #[error("unknown rank '{found}' at character {position}")]
+    InvalidRank { found: char, position: usize },
+
+    #[error("unknown suit '{found}' at character {position}")]
+    InvalidSuit { found: char, position: usize },
+
+    #[error("missing suit after '{rank}' at character {position}")]
+    MissingSuit { rank: char, position: usize },
+
+    #[error("unexpected '{found}' after the card at character {position}")]
+    TrailingCharacters { found: char, position: usize },
+}
+
+impl ParseCardError {
+    /// Shift the position of the error, used when a card is part of a longer string
+    fn offset(self, by: usize) -> Self {
+        use ParseCardError::*;
+        match self {
+            Empty => Empty,
+            InvalidRank { found, position } => InvalidRank {
+                found,
+                position: position + by,
+            },
+            InvalidSuit { found, position } => InvalidSuit {
+                found,
+                position: position + by,
+            },
+            MissingSuit { rank, position } => MissingSuit {
+                rank,
+                position: position + by,
+            },
+            TrailingCharacters { found, position } => TrailingCharacters {
+                found,
+                position: position + by,
+            },
+        }
+    }
+}
+
+///////////////////////////////////////////////
+/// Card Implementations
+///////////////////////////////////////////////
+impl Card {
+    pub const fn new(rank: Rank, suit: Suit) -> Self {
+        Self { rank, suit }
+    }
+
+    pub fn from_string(s: &str) -> Result<Self, ParseCardError> {
+        let mut chars = s.chars();
+
+        let rank_char = chars.next().ok_or(ParseCardError::Empty)?;
+        let rank = Rank::from_char(rank_char).ok_or(ParseCardError::InvalidRank {
+            found: rank_char,
+            position: 1,
+        })?;
+
+        let suit_char = chars.next().ok_or(ParseCardError::MissingSuit {
+            rank: rank_char,
+            position: 1,
+        })?;
+        let suit = Suit::from_char(suit_char).ok_or(ParseCardError::InvalidSuit {
+            found: suit_char,
+            position: 2,
+        })?;
+
+        if let Some(found) = chars.next() {
+            return Err(ParseCardError::TrailingCharacters { found, position: 3 });
+        }
+
+        Ok(Self::new(rank, suit))
+    }
+}
+
+impl Display for Card {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.rank, self.suit)
+    }
+}
+
+impl FromStr for Card {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Card::from_string(s)
+    }
+}
+
+impl TryFrom<&str> for Card {
+    type Error = ParseCardError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        Card::from_string(s)
+    }
+}
+
+///////////////////////////////////////////////
+/// HoleCards Implementations
+///////////////////////////////////////////////
+impl HoleCards {
+    /// Two different cards, in either order
+    pub fn new(a: Card, b: Card) -> Result<Self, Error> {
+        check_distinct(&[a, b], "the hole cards")?;
+        Ok(HoleCards([a.max(b), a.min(b)]))
+    }
+
+    /// The hole cards, the higher card first
+    pub fn cards(&self) -> [Card; 2] {
+        self.0
+    }
+
+    /// The starting hand the cards make once suits are ignored
+    pub fn to_class(&self) -> StartingHandClass {
+        StartingHandClass::of(self.0[0], self.0[1])
+    }
+}
+
+impl FromStr for HoleCards {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let cards = cards_from_str(s)?;
+        match cards[..] {
+            [] => Err(ParseCardError::Empty.into()),
+            [a, b] => HoleCards::new(a, b),
+            _ => Err(Error::WrongHoleCards {
+                game: Game::Holdem,
+                expected: 2,
+                found: cards.len(),
+            }),
+        }
+    }
+}
+
+impl From<HoleCards> for [Card; 2] {
+    fn from(hole_cards: HoleCards) -> Self {
+        hole_cards.0
+    }
+}
+
+impl Display for HoleCards {
+    /// The starting hand, such as "AKs"
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_class())
+    }
+}
+
+/// Parse exactly `N` cards such as "AhKd" in a const context, for the
+/// `card!` and `hole!` macros
+/// None when the string is not `N` cards or a card is listed twice
+#[doc(hidden)]
+pub const fn parse_const<const N: usize>(s: &str) -> Option<[Card; N]> {
+    let bytes = s.as_bytes();
+    if bytes.len() != N * 2 {
+        return None;
+    }
+
+    let mut cards = [Card::new(Rank::Two, Suit::Spades); N];
+    let mut i = 0;
+    while i < N {
+        let (Some(rank), Some(suit)) = (
+            Rank::from_char(bytes[2 * i] as char),
+            Suit::from_char(bytes[2 * i + 1] as char),
+        ) else {
+            return None;
+        };
+
+        // Cards can not be compared with == in a const fn
+        let mut j = 0;
+        while j < i {
+            if cards[j].rank as u8 == rank as u8 && cards[j].suit as u8 == suit as u8 {
+                return None;
+            }
+            j += 1;
+        }
+
+        cards[i] = Card::new(rank, suit);
+        i += 1;
+    }
+
+    Some(cards)
+}
+
+/// A card checked when compiling, such as `card!("Ah")`
+#[macro_export]
+macro_rules! card {
+    ($card:literal) => {{
+        const CARD: $crate::Card = match $crate::card::parse_const::<1>($card) {
+            Some([card]) => card,
+            None => panic!(concat!("\"", $card, "\" is not a card such as Ah")),
+        };
+        CARD
+    }};
+}
+
+/// Hole cards checked when compiling, as an array such as
+/// `hole!("AhKh")` for `[Card; 2]`
+#[macro_export]
+macro_rules! hole {
+    ($cards:literal) => {{
+        const CARDS: [$crate::Card; $cards.len() / 2] = match $crate::card::parse_const($cards) {
+            Some(cards) => cards,
+            None => panic!(concat!(
+                "\"",
+                $cards,
+                "\" is not a set of different cards such as AhKh"
+            )),
+        };
+        CARDS
+    }};
+}
+
+/// Check no card is listed twice in the named group of cards
+pub(crate) fn check_distinct(cards: &[Card], name: &str) -> Result<(), Error> {
+    for (i, card) in cards.iter().enumerate() {
+        if cards[..i].contains(card) {
+            return Err(Error::DuplicateCard {
+                card: *card,
+                first: name.to_string(),
+                second: name.to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+///
+/// Get a vector of cards from a string
+/// such as "AhKsQh2c"
+///
+pub fn cards_from_str(s: &str) -> Result<Vec<Card>, ParseCardError> {
+    // Group into two characters
+    // First character is rank, second is suit
+    s.chars()
+        .collect::<Vec<_>>()
+        .chunks(2)
+        .enumerate()
+        .map(|(i, chunk)| {
+            Card::from_string(&chunk.iter().collect::<String>()).map_err(|e| e.offset(i * 2))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_cards_from_str() {
+        assert_eq!(cards_from_str("").unwrap(), vec![]);
+        assert_eq!(
+            cards_from_str("AhtD").unwrap(),
+            vec![
+                Card::new(Rank::Ace, Suit::Hearts),
+                Card::new(Rank::Ten, Suit::Diamonds)
+            ]
+        );
+
+        assert_eq!(
+            cards_from_str("2c8x"),
+            Err(ParseCardError::InvalidSuit {
+                found: 'x',
+                position: 4
+            })
+        );
+        assert_eq!(
+            cards_from_str("2c1d"),
+            Err(ParseCardError::InvalidRank {
+                found: '1',
+                position: 3
+            })
+        );
+        assert_eq!(
+            cards_from_str("AhK"),
+            Err(ParseCardError::MissingSuit {
+                rank: 'K',
+                position: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_cards_in_sets() {
+        use std::collections::{BTreeSet, HashSet};
+
+        let cards = cards_from_str("KsAh2dAhAs").unwrap();
+        assert_eq!(cards.iter().collect::<HashSet<_>>().len(), 4);
+
+        // Sorted by rank, then by suit
+        let sorted = cards.into_iter().collect::<BTreeSet<_>>();
+        assert_eq!(
+            sorted.into_iter().collect::<Vec<_>>(),
+            cards_from_str("2dKsAsAh").unwrap()
+        );
+
+        let json = serde_json::to_string(&(Rank::Ten, Suit::Clubs)).unwrap();
+        assert_eq!(json, r#"["T","c"]"#);
+        assert_eq!(
+            serde_json::from_str::<(Rank, Suit)>(&json).unwrap(),
+            (Rank::Ten, Suit::Clubs)
+        );
+        assert!(serde_json::from_str::<Rank>(r#""AK""#).is_err());
+        assert!(serde_json::from_str::<Suit>(r#""x""#).is_err());
+    }
+
+    #[test]
+    fn test_card_macros() {
+        assert_eq!(card!("Ah"), Card::new(Rank::Ace, Suit::Hearts));
+        assert_eq!(card!("tD"), Card::new(Rank::Ten, Suit::Diamonds));
+        assert_eq!(hole!("AhKh"), [card!("Ah"), card!("Kh")]);
+        assert_eq!(hole!("AhKhQhJh").len(), 4);
+
+        assert_eq!(parse_const::<2>("AhAh"), None);
+        assert_eq!(parse_const::<2>("AhK"), None);
+        assert_eq!(parse_const::<1>("Ax"), None);
+        assert_eq!(parse_const::<1>("Ä"), None);
+    }
+
+    #[test]
+    fn test_parse_cards() {
+        let ace = Card::new(Rank::Ace, Suit::Hearts);
+        assert_eq!("Ah".parse(), Ok(ace));
+        assert_eq!("ah".parse(), Ok(ace));
+        assert_eq!(Card::try_from("Ah"), Ok(ace));
+        assert_eq!(ace.to_string(), "Ah");
+        assert_eq!(
+            "AhK".parse::<Card>(),
+            Err(ParseCardError::TrailingCharacters {
+                found: 'K',
+                position: 3
+            })
+        );
+
+        let hole_cards = "KhAh".parse::<HoleCards>().unwrap();
+        assert_eq!(<[Card; 2]>::from(hole_cards)[0], ace);
+        assert_eq!(hole_cards, "AhKh".parse().unwrap());
+        assert_eq!(hole_cards.to_string(), "AKs");
+        assert_eq!("7d7c".parse::<HoleCards>().unwrap().to_string(), "77");
+        assert!(matches!(
+            "AhKhQh".parse::<HoleCards>(),
+            Err(Error::WrongHoleCards { found: 3, .. })
+        ));
+        assert!(matches!(
+            "AhAh".parse::<HoleCards>(),
+            Err(Error::DuplicateCard { .. })
+        ));
+        assert!(matches!(
+            "".parse::<HoleCards>(),
+            Err(Error::Card(ParseCardError::Empty))
+        ));
+        assert!(matches!(
+            "AhKx".parse::<HoleCards>(),
+            Err(Error::Card(ParseCardError::InvalidSuit { .. }))
+        ));
+    }
+}
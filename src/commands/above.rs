@@ -0,0 +1,128 @@
+//! `above` subcommand, every holding with at least a given equity against
+//! the hands given, for answering what it takes to continue
+
+use equity_cli::{ComboResult, Error};
+use serde_json::json;
+
+use super::equity::{self, cards_string, Format};
+use super::ev::parse_chance;
+
+/// Arguments of the above subcommand
+#[derive(clap::Args, Debug)]
+#[group(skip)]
+pub struct Args {
+    /// Equity a holding needs, as a percentage or a fraction
+    #[arg(long, value_parser = parse_chance)]
+    at_least: f64,
+
+    /// Every two card holding is dealt against the hands given, so a flop or
+    /// turn with --exact gives every holding its exact equity
+    #[command(flatten, next_help_heading = "Equity")]
+    equity: equity::Args,
+}
+
+/// Print every holding with at least the equity asked for, best first
+pub fn run(args: &Args) -> Result<(), Error> {
+    args.equity
+        .check_equity_only(&[Format::Text, Format::Json])?;
+    let (holdings, above) = holdings_above(&args.equity, args.at_least)?;
+
+    if args.equity.format() == Format::Json {
+        let combos = above
+            .iter()
+            .map(|combo| json!({ "cards": cards_string(&combo.cards), "equity": combo.equity() }))
+            .collect::<Vec<_>>();
+        println!(
+            "{}",
+            json!({
+                "against": args.equity.hand_names(),
+                "at_least": args.at_least,
+                "holdings": holdings,
+                "combos": combos,
+            })
+        );
+        return Ok(());
+    }
+
+    println!(
+        "{} of {} holdings have at least {:.2}% against {}",
+        above.len(),
+        holdings,
+        args.at_least * 100.0,
+        args.equity.hand_names().join(", ")
+    );
+    for combo in above {
+        println!(
+            "{:<10} {:>7.2}%",
+            cards_string(&combo.cards),
+            combo.equity() * 100.0
+        );
+    }
+
+    Ok(())
+}
+
+/// Number of holdings dealt against the hands given, and the ones with at
+/// least `at_least` equity, best first
+fn holdings_above(args: &equity::Args, at_least: f64) -> Result<(usize, Vec<ComboResult>), Error> {
+    let equity = args.with_first_hand("random");
+    let spot = equity::parse_spot(&equity)?;
+    let results = equity::calculate(&equity, spot);
+
+    let holdings = results[0].combos.iter().filter(|c| c.showdowns > 0).count();
+    let above = results[0]
+        .combos_at_least(at_least)
+        .into_iter()
+        .cloned()
+        .collect();
+    Ok((holdings, above))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    #[command(no_binary_name = true)]
+    struct Cli {
+        #[command(flatten)]
+        args: equity::Args,
+    }
+
+    #[test]
+    fn test_holdings_above() {
+        // On the river only the straights beat the set of aces, and nothing
+        // ties it
+        let args = Cli::parse_from(["AhAd", "-b", "AsKd7c4h2s", "--exact", "-q"]).args;
+        let (holdings, above) = holdings_above(&args, 0.5).unwrap();
+        assert_eq!(holdings, 990);
+        assert_eq!(above.len(), 16);
+        assert!(above.iter().all(|c| c.equity() == 1.0));
+        assert!(above.iter().all(|c| cards_string(&c.cards).contains('5')));
+
+        let (_, all) = holdings_above(&args, 0.0).unwrap();
+        assert_eq!(all.len(), 990);
+        assert!(all.windows(2).all(|w| w[0].equity() >= w[1].equity()));
+    }
+
+    #[test]
+    fn test_rejects_unused_equity_flags() {
+        let run = |flags: &[&str]| {
+            let argv = [&["AhAd", "-b", "AsKd7c4h2s", "-q"], flags].concat();
+            let equity = Cli::parse_from(argv).args;
+            run(&Args {
+                at_least: 0.5,
+                equity,
+            })
+        };
+        assert!(matches!(
+            run(&["--categories"]),
+            Err(Error::UnusedFlag("--categories"))
+        ));
+        assert!(matches!(
+            run(&["--no-header"]),
+            Err(Error::UnusedFlag("--no-header"))
+        ));
+    }
+}
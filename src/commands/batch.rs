@@ -0,0 +1,417 @@
+//! `batch` subcommand, runs every scenario in a CSV or JSON file
+//! Each scenario is handled like the arguments of the equity subcommand
+//! and gives one result row
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use clap::Parser;
+use equity_cli::{default_threads, EquityResult, Error, HandResult};
+use indicatif::{ProgressBar, ProgressStyle};
+use serde_json::Value;
+
+use super::equity::{self, calculate, cards_string, parse_spot};
+
+/// Columns a scenario can set, each one matches the equity flag of the same name
+const COLUMNS: [&str; 12] = [
+    "hands",
+    "board",
+    "flop",
+    "turn",
+    "river",
+    "dead",
+    "game",
+    "iterations",
+    "target_error",
+    "seed",
+    "exact",
+    "vs_random",
+];
+
+/// Columns of the output, one row per scenario
+pub const HEADER: [&str; 8] = [
+    "scenario",
+    "hands",
+    "board",
+    "dead",
+    "showdowns",
+    "equity",
+    "std_error",
+    "error",
+];
+
+/// Arguments of the batch subcommand
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// File of scenarios, CSV with a header row or a JSON array of objects
+    /// Files ending in .json are read as JSON, anything else as CSV
+    /// Columns: hands, board, flop, turn, river, dead, game, iterations,
+    /// target_error, seed, exact and vs_random, only hands is required
+    #[arg()]
+    file: PathBuf,
+
+    /// Number of scenarios to run at the same time
+    #[arg(short, long, default_value_t = 1)]
+    jobs: usize,
+
+    /// Worker threads of each scenario
+    /// Defaults to the number of logical CPUs shared between the jobs
+    #[arg(short, long)]
+    threads: Option<usize>,
+
+    /// Seed for scenarios that do not set their own
+    #[arg(short, long)]
+    seed: Option<u64>,
+
+    /// Hide the progress bar
+    #[arg(short, long)]
+    quiet: bool,
+
+    /// Leave out the header row, for appending to an existing file
+    #[arg(long)]
+    no_header: bool,
+}
+
+/// One scenario as the column names and values it was given with
+pub type Scenario = Vec<(String, String)>;
+
+/// Equity arguments parsed from a scenario instead of the command line
+#[derive(Parser, Debug)]
+#[command(no_binary_name = true)]
+struct ScenarioArgs {
+    #[command(flatten)]
+    equity: equity::Args,
+}
+
+/// Run every scenario in the file and print one row for each
+pub fn run(args: &Args) -> Result<(), Error> {
+    let scenarios = read_scenarios(&args.file)?;
+
+    let jobs = args.jobs.clamp(1, scenarios.len().max(1));
+    let threads = args
+        .threads
+        .unwrap_or_else(|| (default_threads() / jobs).max(1));
+
+    let bar = if args.quiet {
+        ProgressBar::hidden()
+    } else {
+        ProgressBar::new(scenarios.len() as u64).with_style(
+            ProgressStyle::with_template("{bar:40} {pos}/{len} scenarios [ETA {eta}]").unwrap(),
+        )
+    };
+
+    // Workers take the next scenario until none are left, rows are
+    // kept in the order of the file
+    let next = AtomicUsize::new(0);
+    let rows = Mutex::new(vec![vec![]; scenarios.len()]);
+    thread::scope(|scope| {
+        for _ in 0..jobs {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(scenario) = scenarios.get(i) else {
+                    break;
+                };
+                let row = result_row(i + 1, scenario, threads, args.seed);
+                rows.lock().unwrap()[i] = row;
+                bar.inc(1);
+            });
+        }
+    });
+    bar.finish_and_clear();
+
+    let mut writer = csv::Writer::from_writer(std::io::stdout());
+    if !args.no_header {
+        writer.write_record(HEADER)?;
+    }
+    for row in rows.into_inner().unwrap() {
+        writer.write_record(row)?;
+    }
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Read the scenarios of a CSV or JSON file, checking every column is known
+fn read_scenarios(path: &Path) -> Result<Vec<Scenario>, Error> {
+    let json = path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("json"));
+    let scenarios = if json {
+        let value = serde_json::from_reader(std::fs::File::open(path)?)?;
+        json_scenarios(value)?
+    } else {
+        csv_scenarios(csv::Reader::from_path(path)?)?
+    };
+
+    for (i, scenario) in scenarios.iter().enumerate() {
+        check_scenario(i + 1, scenario)?;
+    }
+
+    Ok(scenarios)
+}
+
+/// Check every column of a scenario is known and that it has hands
+pub fn check_scenario(index: usize, scenario: &Scenario) -> Result<(), Error> {
+    let invalid = |reason: String| Error::InvalidScenario { index, reason };
+    if let Some((column, _)) = scenario
+        .iter()
+        .find(|(c, _)| !COLUMNS.contains(&c.as_str()))
+    {
+        return Err(invalid(format!("unknown column \"{}\"", column)));
+    }
+    if !scenario
+        .iter()
+        .any(|(c, v)| c == "hands" && !v.trim().is_empty())
+    {
+        return Err(invalid("no hands given".to_string()));
+    }
+
+    Ok(())
+}
+
+/// Scenarios from the rows of a CSV file with a header row
+fn csv_scenarios<R: std::io::Read>(mut reader: csv::Reader<R>) -> Result<Vec<Scenario>, Error> {
+    let header = reader.headers()?.clone();
+
+    reader
+        .records()
+        .map(|record| {
+            let record = record?;
+            let scenario = header
+                .iter()
+                .zip(record.iter())
+                .map(|(column, value)| (column.trim().to_string(), value.to_string()))
+                .collect();
+            Ok(scenario)
+        })
+        .collect()
+}
+
+/// Scenarios from a JSON array of objects
+/// Values can be strings, numbers or booleans, and hands can also be an array
+fn json_scenarios(value: Value) -> Result<Vec<Scenario>, Error> {
+    let Value::Array(items) = value else {
+        return Err(Error::InvalidScenario {
+            index: 0,
+            reason: "expected an array of scenarios".to_string(),
+        });
+    };
+
+    items
+        .into_iter()
+        .enumerate()
+        .map(|(i, item)| json_scenario(i + 1, item))
+        .collect()
+}
+
+/// Scenario from a single JSON object
+pub fn json_scenario(index: usize, item: Value) -> Result<Scenario, Error> {
+    let invalid = |reason: String| Error::InvalidScenario { index, reason };
+    let Value::Object(fields) = item else {
+        return Err(invalid("expected an object".to_string()));
+    };
+
+    fields
+        .into_iter()
+        .map(|(column, value)| {
+            let value = match value {
+                Value::String(s) => s,
+                Value::Array(hands) => hands
+                    .iter()
+                    .map(|h| h.as_str().map(str::to_string))
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(|| invalid(format!("{} must be a list of strings", column)))?
+                    .join(" "),
+                Value::Null => String::new(),
+                other => other.to_string(),
+            };
+            Ok((column, value))
+        })
+        .collect()
+}
+
+/// Equity command line for a scenario, after the shared options
+fn scenario_argv(scenario: &Scenario, threads: usize, seed: Option<u64>) -> Vec<String> {
+    let mut argv = vec!["--quiet".to_string(), format!("--threads={}", threads)];
+    let mut hands = vec![];
+
+    for (column, value) in scenario {
+        let value = value.trim();
+        match column.as_str() {
+            _ if value.is_empty() => {}
+            "hands" => hands.extend(value.split_whitespace().map(str::to_string)),
+            "exact" => {
+                if matches!(value.to_ascii_lowercase().as_str(), "true" | "yes" | "1") {
+                    argv.push("--exact".to_string());
+                }
+            }
+            _ => argv.push(format!("--{}={}", column.replace('_', "-"), value)),
+        }
+    }
+
+    let has_seed = scenario
+        .iter()
+        .any(|(c, v)| c == "seed" && !v.trim().is_empty());
+    if let (Some(seed), false) = (seed, has_seed) {
+        argv.push(format!("--seed={}", seed));
+    }
+
+    argv.push("--".to_string());
+    argv.extend(hands);
+    argv
+}
+
+/// Run a scenario and describe the outcome as an output row
+/// A scenario that can not be run gives a row with only the error filled in
+pub fn result_row(
+    index: usize,
+    scenario: &Scenario,
+    threads: usize,
+    seed: Option<u64>,
+) -> Vec<String> {
+    let column = |name: &str| {
+        scenario
+            .iter()
+            .find(|(c, _)| c == name)
+            .map_or(String::new(), |(_, v)| v.trim().to_string())
+    };
+
+    let mut row = vec![index.to_string(), column("hands")];
+    match run_scenario(scenario, threads, seed) {
+        Ok((exact, result)) => {
+            let joined = |value: &dyn Fn(&HandResult) -> String| {
+                result
+                    .per_hand
+                    .iter()
+                    .map(value)
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            };
+            row.extend([
+                cards_string(&result.board),
+                cards_string(&result.dead),
+                result.iterations.to_string(),
+                joined(&|r| r.equity().to_string()),
+                if exact {
+                    String::new()
+                } else {
+                    joined(&|r| r.std_error().to_string())
+                },
+                String::new(),
+            ]);
+        }
+        Err(error) => {
+            row.extend([column("board"), column("dead")]);
+            row.extend(["", "", ""].map(String::from));
+            row.push(error);
+        }
+    }
+    row
+}
+
+/// Equity arguments of a scenario, with the message of the first problem
+/// if they can not be parsed
+pub fn parse_scenario(
+    scenario: &Scenario,
+    threads: usize,
+    seed: Option<u64>,
+) -> Result<equity::Args, String> {
+    let argv = scenario_argv(scenario, threads, seed);
+    let args = ScenarioArgs::try_parse_from(argv).map_err(|e| {
+        // Keep the message of the clap error without its usage text
+        let message = e.to_string();
+        let first = message.lines().next().unwrap_or_default();
+        first.trim_start_matches("error: ").to_string()
+    })?;
+
+    Ok(args.equity)
+}
+
+/// Parse and run a scenario, giving whether it was enumerated exactly and
+/// the results
+fn run_scenario(
+    scenario: &Scenario,
+    threads: usize,
+    seed: Option<u64>,
+) -> Result<(bool, EquityResult), String> {
+    let args = parse_scenario(scenario, threads, seed)?;
+
+    let spot = parse_spot(&args).map_err(|e| e.to_string())?;
+    let (board, dead) = (spot.board.clone(), spot.dead.clone());
+    let results = calculate(&args, spot);
+
+    Ok((
+        args.exact,
+        EquityResult::new(args.hand_names(), board, dead, results),
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn scenario(fields: &[(&str, &str)]) -> Scenario {
+        fields
+            .iter()
+            .map(|(c, v)| (c.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_csv_and_json_scenarios() {
+        let data = "hands,board,iterations\nAhAs KdKh,7c8c9c,1000\n\"QQ+,AKs\" 7c2d,,\n";
+        let from_csv = csv_scenarios(csv::Reader::from_reader(data.as_bytes())).unwrap();
+
+        let value = serde_json::json!([
+            {"hands": ["AhAs", "KdKh"], "board": "7c8c9c", "iterations": 1000},
+            {"hands": "QQ+,AKs 7c2d", "board": "", "iterations": null},
+        ]);
+        let mut from_json = json_scenarios(value).unwrap();
+        for fields in &mut from_json {
+            fields.sort();
+        }
+        let mut sorted = from_csv.clone();
+        for fields in &mut sorted {
+            fields.sort();
+        }
+
+        assert_eq!(sorted, from_json);
+        assert_eq!(from_csv[1][0].1, "QQ+,AKs 7c2d");
+    }
+
+    #[test]
+    fn test_scenario_rows() {
+        let aces = scenario(&[
+            ("hands", "AhAs KdKh"),
+            ("board", "AdKsQc"),
+            ("iterations", "2000"),
+            ("exact", ""),
+        ]);
+        let row = result_row(1, &aces, 1, Some(7));
+        assert_eq!(row.len(), HEADER.len());
+        assert_eq!(row[2], "AdKsQc");
+        assert_eq!(row[4], "2000");
+        assert_eq!(row[7], "");
+
+        let exact = scenario(&[
+            ("hands", "AhAs KdKh"),
+            ("board", "AdKsQc2c"),
+            ("exact", "true"),
+        ]);
+        let row = result_row(2, &exact, 1, None);
+        assert_eq!(row[4], "44");
+        assert_eq!(row[6], "");
+
+        let invalid = scenario(&[("hands", "AhAs AhKh")]);
+        let row = result_row(3, &invalid, 1, None);
+        assert_eq!(row.len(), HEADER.len());
+        assert!(row[7].contains("Ah"), "{}", row[7]);
+
+        let bad_flag = scenario(&[("hands", "AhAs KdKh"), ("game", "canasta")]);
+        let row = result_row(4, &bad_flag, 1, None);
+        assert!(!row[7].is_empty());
+        assert!(!row[7].contains('\n'));
+    }
+}
@@ -0,0 +1,204 @@
+//! `beats` subcommand, every holding that beats, ties or loses to a hand
+//! on a complete board, counted by category
+
+use std::cmp::Ordering;
+
+use equity_cli::{
+    check_duplicate_cards, what_beats_me, Card, ComboShowdown, Error, Game, HandCategory, Range,
+};
+use serde_json::json;
+
+use super::equity::{cards_string, kicker_name};
+use super::parse_cards;
+
+/// Outcomes against us in the order they are shown, with their names
+const OUTCOMES: [(Ordering, &str); 3] = [
+    (Ordering::Greater, "Beat you"),
+    (Ordering::Equal, "Tie"),
+    (Ordering::Less, "Lose"),
+];
+
+/// Arguments of the beats subcommand
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Hole cards to compare against every holding
+    /// Example: AhQd
+    #[arg(long)]
+    hand: String,
+
+    /// Complete five card board
+    /// Example: AcKs7d7h2c
+    #[arg(short, long)]
+    board: String,
+
+    /// Dead cards that have been exposed or folded
+    #[arg(short, long, default_value = "")]
+    dead: String,
+
+    /// Only look at the combos of this range instead of every holding
+    #[arg(long)]
+    range: Option<String>,
+
+    /// Game to compare in: holdem, omaha or omaha8, only the high hand counts
+    #[arg(short, long, default_value_t = Game::Holdem)]
+    game: Game,
+
+    /// List every holding that beats or ties the hand
+    #[arg(short, long)]
+    list: bool,
+
+    /// Print the counts and every holding as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+/// Compare the hand against every holding and print the counts
+pub fn run(args: &Args) -> Result<(), Error> {
+    if !args.game.has_board() {
+        return Err(Error::NoBoard(args.game).input("--game", &args.game.to_string()));
+    }
+    let hand = parse_cards("--hand", &args.hand)?;
+    args.game
+        .check_hole_cards(hand.len())
+        .map_err(|e| e.input("--hand", &args.hand))?;
+
+    let board = parse_cards("--board", &args.board)?;
+    let Ok(complete) = <[Card; 5]>::try_from(board.clone()) else {
+        let error = Error::WrongStreetSize {
+            street: "board".to_string(),
+            expected: 5,
+            found: board.len(),
+        };
+        return Err(error.input("--board", &args.board));
+    };
+    let dead = parse_cards("--dead", &args.dead)?;
+    check_duplicate_cards(&[
+        ("--hand".to_string(), hand.clone()),
+        ("--board".to_string(), board.clone()),
+        ("--dead".to_string(), dead.clone()),
+    ])?;
+
+    let range = args
+        .range
+        .as_deref()
+        .map(|range| Range::parse(range).map_err(|e| Error::from(e).input("--range", range)))
+        .transpose()?;
+
+    let result = what_beats_me(args.game, &hand, complete, &dead, range.as_ref())
+        .map_err(|e| e.input("--range", args.range.as_deref().unwrap_or_default()))?;
+
+    if args.json {
+        let combos = |outcome| {
+            result
+                .with_outcome(outcome)
+                .map(|combo| {
+                    json!({
+                        "cards": cards_string(&combo.cards),
+                        "hand": combo.hand.to_string(),
+                        "kicker": combo.kicker.map(|kicker| kicker.name()),
+                    })
+                })
+                .collect::<Vec<_>>()
+        };
+        let categories = HandCategory::ALL
+            .iter()
+            .map(|&category| {
+                json!({
+                    "category": category.name(),
+                    "beat": result.count(category, Ordering::Greater),
+                    "tie": result.count(category, Ordering::Equal),
+                    "lose": result.count(category, Ordering::Less),
+                })
+            })
+            .collect::<Vec<_>>();
+        println!(
+            "{}",
+            json!({
+                "hand": cards_string(&hand),
+                "board": cards_string(&board),
+                "dead": cards_string(&dead),
+                "made": result.hand.to_string(),
+                "plays_the_board": result.plays_the_board,
+                "categories": categories,
+                "beat": combos(Ordering::Greater),
+                "tie": combos(Ordering::Equal),
+                "lose": combos(Ordering::Less),
+            })
+        );
+        return Ok(());
+    }
+
+    println!("Hand: {:?}", hand);
+    println!("Board: {:?}", board);
+    println!("You have {}", result.hand);
+    if result.plays_the_board {
+        println!("You are playing the board");
+    }
+    println!();
+
+    println!(
+        "{:<17} {:>8} {:>8} {:>8}",
+        "Category", OUTCOMES[0].1, OUTCOMES[1].1, OUTCOMES[2].1
+    );
+    for category in HandCategory::ALL.iter().rev() {
+        let counts = OUTCOMES.map(|(outcome, _)| result.count(*category, outcome));
+        if counts.iter().any(|count| *count > 0) {
+            println!(
+                "{:<17} {:>8} {:>8} {:>8}",
+                category.name(),
+                counts[0],
+                counts[1],
+                counts[2]
+            );
+        }
+    }
+
+    let totals = OUTCOMES.map(|(outcome, _)| result.with_outcome(outcome).count());
+    let share = |count: usize| count as f64 / result.combos.len().max(1) as f64 * 100.0;
+    println!(
+        "{:<17} {:>8} {:>8} {:>8}",
+        "Total", totals[0], totals[1], totals[2]
+    );
+    println!(
+        "{:<17} {:>7.2}% {:>7.2}% {:>7.2}%",
+        "",
+        share(totals[0]),
+        share(totals[1]),
+        share(totals[2])
+    );
+    let kickers = OUTCOMES.map(|(outcome, _)| result.kicker_count(outcome));
+    if kickers.iter().any(|count| *count > 0) {
+        println!(
+            "{:<17} {:>8} {:>8} {:>8}",
+            "On a kicker", kickers[0], kickers[1], kickers[2]
+        );
+    }
+
+    if args.list {
+        for (outcome, name) in &OUTCOMES[..2] {
+            if result.with_outcome(*outcome).next().is_none() {
+                continue;
+            }
+            println!();
+            println!("{}:", name);
+            for combo in result.with_outcome(*outcome) {
+                print_combo(combo);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Print a holding, the hand it makes and the kicker that beat us
+fn print_combo(combo: &ComboShowdown) {
+    match combo.kicker {
+        Some(kicker) => println!(
+            "{:<10} {}, {} kicker",
+            cards_string(&combo.cards),
+            combo.hand,
+            kicker_name(kicker)
+        ),
+        None => println!("{:<10} {}", cards_string(&combo.cards), combo.hand),
+    }
+}
@@ -0,0 +1,139 @@
+//! `blockers` subcommand, how many combos of a range a hand blocks
+//! broken down by the hand each combo makes on the board
+
+use equity_cli::{blockers, check_duplicate_cards, BlockedCount, Error, Game, HandCategory, Range};
+use serde_json::json;
+
+use super::equity::cards_string;
+use super::parse_cards;
+
+/// Arguments of the blockers subcommand
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Hole cards doing the blocking
+    /// Example: AhKh
+    #[arg(long)]
+    hand: String,
+
+    /// Range of the opponent, such as "QQ+,AKs,T9s"
+    #[arg(long)]
+    range: String,
+
+    /// Current board, combos are grouped by what they make on it
+    /// Example: Qh7h2h
+    #[arg(short, long, default_value = "")]
+    board: String,
+
+    /// Dead cards that have been exposed or folded
+    #[arg(short, long, default_value = "")]
+    dead: String,
+
+    /// Game the range is for: holdem, omaha or omaha8
+    #[arg(short, long, default_value_t = Game::Holdem)]
+    game: Game,
+
+    /// Print the counts as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+/// Count and print the combos the hand blocks
+pub fn run(args: &Args) -> Result<(), Error> {
+    if !args.game.has_board() {
+        return Err(Error::NoBoard(args.game).input("--game", &args.game.to_string()));
+    }
+    let hand = parse_cards("--hand", &args.hand)?;
+    args.game
+        .check_hole_cards(hand.len())
+        .map_err(|e| e.input("--hand", &args.hand))?;
+
+    let range =
+        Range::parse(&args.range).map_err(|e| Error::from(e).input("--range", &args.range))?;
+    for combo in range.combos() {
+        args.game
+            .check_hole_cards(combo.len())
+            .map_err(|e| e.input("--range", &args.range))?;
+    }
+
+    let board = parse_cards("--board", &args.board)?;
+    if !args.game.is_valid_board(board.len()) {
+        return Err(Error::InvalidBoard(board.len()).input("--board", &args.board));
+    }
+    let dead = parse_cards("--dead", &args.dead)?;
+    check_duplicate_cards(&[
+        ("--hand".to_string(), hand.clone()),
+        ("--board".to_string(), board.clone()),
+        ("--dead".to_string(), dead.clone()),
+    ])?;
+
+    let result = blockers(args.game, &hand, &range, &board, &dead)
+        .map_err(|e| e.input("--range", &args.range))?;
+    let categories = HandCategory::ALL
+        .iter()
+        .filter_map(|&category| Some((category, result.category(category)?)))
+        .filter(|(_, count)| count.combos > 0)
+        .collect::<Vec<_>>();
+
+    if args.json {
+        let categories = result.categories.is_some().then(|| {
+            categories
+                .iter()
+                .map(|(category, count)| {
+                    json!({
+                        "category": category.name(),
+                        "combos": count.combos,
+                        "blocked": count.blocked,
+                    })
+                })
+                .collect::<Vec<_>>()
+        });
+        println!(
+            "{}",
+            json!({
+                "hand": cards_string(&hand),
+                "range": args.range,
+                "board": cards_string(&board),
+                "dead": cards_string(&dead),
+                "combos": result.total.combos,
+                "blocked": result.total.blocked,
+                "categories": categories,
+            })
+        );
+        return Ok(());
+    }
+
+    println!("Hand: {:?}", hand);
+    println!("Board: {:?}", board);
+    println!(
+        "Range: {} combos, {} blocked ({:.2}%), {} left",
+        result.total.combos,
+        result.total.blocked,
+        result.total.fraction() * 100.0,
+        result.total.left()
+    );
+
+    if result.categories.is_some() {
+        println!();
+        println!(
+            "{:<17} {:>7} {:>8} {:>8} {:>8}",
+            "Category", "Combos", "Blocked", "%", "Left"
+        );
+        for (category, count) in categories {
+            print_row(category.name(), count);
+        }
+    }
+
+    Ok(())
+}
+
+/// Print the counts of one category
+fn print_row(name: &str, count: BlockedCount) {
+    println!(
+        "{:<17} {:>7} {:>8} {:>7.2}% {:>8}",
+        name,
+        count.combos,
+        count.blocked,
+        count.fraction() * 100.0,
+        count.left()
+    );
+}
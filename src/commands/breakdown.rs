@@ -0,0 +1,136 @@
+//! `breakdown` subcommand, where a hand's equity against a range on the
+//! flop or turn comes from: its made hand, direct draws or backdoor draws
+
+use equity_cli::{
+    check_duplicate_cards, equity_breakdown, made_hand, EquityBreakdown, EquitySource, Error, Game,
+    HandCategory, Range,
+};
+use serde_json::json;
+
+use super::equity::cards_string;
+use super::parse_cards;
+
+/// Arguments of the breakdown subcommand
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Hole cards to break the equity of down
+    /// Example: 9h8h
+    #[arg(long)]
+    hand: String,
+
+    /// Range of the opponent, such as "QQ+,AKs,T9s"
+    #[arg(long)]
+    range: String,
+
+    /// Flop or turn
+    /// Example: 7h2hKs
+    #[arg(short, long)]
+    board: String,
+
+    /// Dead cards that have been exposed or folded
+    #[arg(short, long, default_value = "")]
+    dead: String,
+
+    /// Game to break the equity down in: holdem, omaha or omaha8
+    /// Only the high hand is looked at in omaha8
+    #[arg(short, long, default_value_t = Game::Holdem)]
+    game: Game,
+
+    /// Print the breakdown as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+/// Break down and print the equity of the hand
+pub fn run(args: &Args) -> Result<(), Error> {
+    if !args.game.has_board() {
+        return Err(Error::NoBoard(args.game).input("--game", &args.game.to_string()));
+    }
+    let hand = parse_cards("--hand", &args.hand)?;
+    args.game
+        .check_hole_cards(hand.len())
+        .map_err(|e| e.input("--hand", &args.hand))?;
+
+    let range =
+        Range::parse(&args.range).map_err(|e| Error::from(e).input("--range", &args.range))?;
+    for combo in range.combos() {
+        args.game
+            .check_hole_cards(combo.len())
+            .map_err(|e| e.input("--range", &args.range))?;
+    }
+
+    let board = parse_cards("--board", &args.board)?;
+    if !matches!(board.len(), 3 | 4) {
+        return Err(Error::NotFlopOrTurn(board.len()).input("--board", &args.board));
+    }
+    let dead = parse_cards("--dead", &args.dead)?;
+    check_duplicate_cards(&[
+        ("--hand".to_string(), hand.clone()),
+        ("--board".to_string(), board.clone()),
+        ("--dead".to_string(), dead.clone()),
+    ])?;
+
+    let breakdown = equity_breakdown(args.game, &hand, &range, &board, &dead)
+        .map_err(|e| e.input("--range", &args.range))?;
+
+    if args.json {
+        let sources = EquitySource::ALL
+            .iter()
+            .map(|&source| {
+                json!({
+                    "source": source.name(),
+                    "equity": breakdown.source(source),
+                    "categories": parts(&breakdown, source)
+                        .iter()
+                        .map(|(category, equity)| {
+                            json!({ "category": category.name(), "equity": equity })
+                        })
+                        .collect::<Vec<_>>(),
+                })
+            })
+            .collect::<Vec<_>>();
+        println!(
+            "{}",
+            json!({
+                "hand": cards_string(&hand),
+                "range": args.range,
+                "board": cards_string(&board),
+                "game": args.game.to_string(),
+                "equity": breakdown.equity(),
+                "sources": sources,
+            })
+        );
+        return Ok(());
+    }
+
+    println!("Hand: {:?}", hand);
+    println!("Range: {}", args.range);
+    println!("Board: {:?}", board);
+    println!("You have {}", made_hand(args.game, &hand, &board));
+
+    println!();
+    println!("{:<19} {:>7.2}%", "Equity", breakdown.equity() * 100.0);
+    for source in EquitySource::ALL {
+        println!();
+        println!(
+            "{:<19} {:>7.2}%",
+            source.name(),
+            breakdown.source(source) * 100.0
+        );
+        for (category, equity) in parts(&breakdown, source) {
+            println!("  {:<17} {:>7.2}%", category.name(), equity * 100.0);
+        }
+    }
+
+    Ok(())
+}
+
+/// Categories the hand wins with from a source, leaving out the ones that
+/// never win
+fn parts(breakdown: &EquityBreakdown, source: EquitySource) -> Vec<(HandCategory, f64)> {
+    HandCategory::ALL
+        .into_iter()
+        .map(|category| (category, breakdown.part(source, category)))
+        .filter(|(_, equity)| *equity > 0.0)
+        .collect()
+}
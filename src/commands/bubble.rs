@@ -0,0 +1,117 @@
+//! `bubble` subcommand, the bubble factor and the equity needed to call an
+//! all in once tournament payouts are taken into account
+
+use equity_cli::{all_in_call, Error};
+
+use super::equity::{self, Format};
+use super::ev::parse_chance;
+
+/// Arguments of the bubble subcommand
+#[derive(clap::Args, Debug)]
+#[group(skip)]
+pub struct Args {
+    /// Chip stacks of every player before the hand, comma separated
+    /// Example: 5000,3000,1500,1500
+    #[arg(long, value_delimiter = ',', required = true)]
+    chips: Vec<f64>,
+
+    /// Payouts from first place down, comma separated
+    /// Example: 50,30,20
+    #[arg(long, value_delimiter = ',', required = true)]
+    payouts: Vec<f64>,
+
+    /// Player calling the all in, counting from 1 in the order of the stacks
+    #[arg(long, value_parser = clap::value_parser!(u16).range(1..))]
+    hero: u16,
+
+    /// Player moving all in, counting from 1 in the order of the stacks
+    #[arg(long, value_parser = clap::value_parser!(u16).range(1..))]
+    villain: u16,
+
+    /// Chips already in the pot from other players, such as blinds and antes
+    #[arg(long, default_value_t = 0.0)]
+    pot: f64,
+
+    /// Equity of the calling hand, as a percentage or a fraction, instead of
+    /// working it out from hands
+    #[arg(long, value_parser = parse_chance)]
+    equity: Option<f64>,
+
+    /// Hands can be given to check the first hand's equity against the price
+    #[command(flatten, next_help_heading = "Equity")]
+    hands: equity::Args,
+}
+
+/// Print the prize equity of every outcome, the bubble factor and the
+/// equity needed to call, and whether to call when the equity is known
+pub fn run(args: &Args) -> Result<(), Error> {
+    args.hands.check_equity_only(&[Format::Text])?;
+    let (hero, villain) = (usize::from(args.hero) - 1, usize::from(args.villain) - 1);
+    let call = all_in_call(&args.chips, &args.payouts, hero, villain, args.pot)?;
+
+    let equity = match args.equity {
+        Some(equity) => Some(equity),
+        None if args.hands.hand_names().is_empty() => None,
+        None => {
+            let spot = equity::parse_spot(&args.hands)?;
+            Some(equity::calculate(&args.hands, spot)[0].equity())
+        }
+    };
+
+    println!(
+        "Player {} calling {} against player {}'s all in",
+        args.hero, call.risk, args.villain
+    );
+    println!(
+        "Prize equity: {:.2} folding, {:.2} winning, {:.2} losing",
+        call.fold, call.win, call.lose
+    );
+    println!("Bubble factor: {:.2}", call.bubble_factor());
+    println!("Chip EV needs: {:.2}%", call.chip_threshold() * 100.0);
+    println!("ICM needs: {:.2}%", call.icm_threshold() * 100.0);
+    println!("Risk premium: {:.2}%", call.risk_premium() * 100.0);
+
+    if let Some(equity) = equity {
+        let needed = call.icm_threshold();
+        println!(
+            "You need {:.2}%, you have {:.2}% - {} ({:+.2} in prizes)",
+            needed * 100.0,
+            equity * 100.0,
+            if equity >= needed { "call" } else { "fold" },
+            call.call_value(equity)
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    #[command(no_binary_name = true)]
+    struct Cli {
+        #[command(flatten)]
+        args: Args,
+    }
+
+    #[test]
+    fn test_rejects_unused_equity_flags() {
+        let run = |flags: &[&str]| {
+            let table = ["--chips", "5000,3000,2000", "--payouts", "50,30,20"];
+            let argv = [&table, &["--hero", "2", "--villain", "1"], flags].concat();
+            run(&Cli::parse_from(argv).args)
+        };
+        assert!(run(&["--equity", "40%"]).is_ok());
+        assert!(matches!(
+            run(&["AKs", "QQ", "--kickers"]),
+            Err(Error::UnusedFlag("--kickers"))
+        ));
+        assert!(matches!(
+            run(&["AKs", "QQ", "--condition", "flush"]),
+            Err(Error::UnusedFlag("--condition"))
+        ));
+    }
+}
@@ -0,0 +1,127 @@
+//! Results kept on disk so later runs can skip the calculation, and the
+//! `cache` subcommand to manage them
+
+use std::collections::hash_map::DefaultHasher;
+use std::env;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+
+use equity_cli::{Error, HandResult};
+use serde_json::{json, Value};
+
+/// Directory of cached equities, one file per spot
+const RESULTS_DIR: &str = "results";
+
+/// Arguments of the cache subcommand
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    #[command(subcommand)]
+    action: Action,
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum Action {
+    /// Delete every cached result
+    Clear,
+
+    /// Print the directory results are cached in
+    Path,
+}
+
+/// Clear the cache or show where it is
+pub fn run(args: &Args) -> Result<(), Error> {
+    let Some(dir) = cache_dir() else {
+        println!("No cache directory, neither XDG_CACHE_HOME nor HOME is set");
+        return Ok(());
+    };
+
+    match args.action {
+        Action::Clear => {
+            if dir.exists() {
+                fs::remove_dir_all(&dir)?;
+            }
+            println!("Cleared {}", dir.display());
+        }
+        Action::Path => println!("{}", dir.display()),
+    }
+    Ok(())
+}
+
+/// Cache directory, $XDG_CACHE_HOME/equity-cli or ~/.cache/equity-cli
+/// None when neither variable is set
+pub fn cache_dir() -> Option<PathBuf> {
+    let base = env::var_os("XDG_CACHE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+
+    Some(base.join("equity-cli"))
+}
+
+/// Path of a cache file in the cache directory
+pub fn cache_path(name: &str) -> Option<PathBuf> {
+    Some(cache_dir()?.join(name))
+}
+
+/// File holding the results of a key, named after its hash
+fn results_path(key: &str) -> Option<PathBuf> {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    cache_path(RESULTS_DIR).map(|dir| dir.join(format!("{:016x}.json", hasher.finish())))
+}
+
+/// Cached results of a key, None when missing or unreadable
+/// Every combo is left without its cards
+pub fn read_results(key: &str) -> Option<Vec<HandResult>> {
+    let text = fs::read_to_string(results_path(key)?).ok()?;
+    let entry = serde_json::from_str::<Value>(&text).ok()?;
+
+    // Another key with the same hash
+    if entry["key"] != key {
+        return None;
+    }
+    serde_json::from_value(entry["results"].clone()).ok()
+}
+
+/// Cache the results of a key, which is only ever a shortcut so failing to
+/// write them is not an error
+pub fn write_results(key: &str, results: &[HandResult]) {
+    let Some(path) = results_path(key) else {
+        return;
+    };
+
+    let results = results.iter().map(without_cards).collect::<Vec<_>>();
+    let entry = json!({ "key": key, "results": results });
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    let _ = fs::write(&path, entry.to_string());
+}
+
+/// A copy of a result without the cards of each combo
+fn without_cards(result: &HandResult) -> HandResult {
+    let mut result = result.clone();
+    for combo in &mut result.combos {
+        combo.cards.clear();
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use equity_cli::{run_exact_calculation, Game, Range};
+
+    #[test]
+    fn test_results_round_trip() {
+        let ranges = ["AhKh", "QQ,JJ"].map(|r| Range::parse(r).unwrap()).to_vec();
+        let board = equity_cli::cards_from_str("Qh7h2c4d").unwrap();
+        let results = run_exact_calculation(Game::Holdem, board, vec![], ranges).unwrap();
+
+        let json = json!(results.iter().map(without_cards).collect::<Vec<_>>());
+        let read = serde_json::from_value::<Vec<HandResult>>(json).unwrap();
+        assert!(read[0].combos.iter().all(|combo| combo.cards.is_empty()));
+        assert_eq!(read, results.iter().map(without_cards).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,233 @@
+//! `compare` subcommand, the change in the first hand's equity between two
+//! scenarios, simulated from common random numbers
+
+use equity_cli::{
+    default_threads, run_comparison, Comparison, Error, Game, Scenario, SimulationSettings, Z_95,
+};
+use indicatif::{ProgressBar, ProgressStyle};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde_json::json;
+
+use super::equity::{cards_string, catch_interrupt, check_known_cards, interrupted};
+use super::{parse_cards, parse_hands};
+
+/// Arguments of the compare subcommand
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Board of the first scenario
+    /// Example: 7h2hKs
+    #[arg(short, long, default_value = "")]
+    board: String,
+
+    /// Dead cards of the first scenario
+    #[arg(short, long, default_value = "")]
+    dead: String,
+
+    /// Board of the second scenario, the same as --board when not given
+    #[arg(long, required_unless_present_any = ["other_dead", "other_villain"])]
+    other_board: Option<String>,
+
+    /// Dead cards of the second scenario, the same as --dead when not given
+    #[arg(long)]
+    other_dead: Option<String>,
+
+    /// Hand or range replacing every hand after the first in the second
+    /// scenario, repeated for each opponent
+    #[arg(long, value_name = "HAND")]
+    other_villain: Vec<String>,
+
+    /// Game to simulate: holdem, omaha, omaha8, omaha5, courchevel,
+    /// pineapple, deuce7 or badugi
+    #[arg(short, long, default_value_t = Game::Holdem)]
+    game: Game,
+
+    /// Number of iterations, each playing both scenarios
+    #[arg(short, long, default_value_t = 100_000)]
+    iterations: u32,
+
+    /// Number of worker threads
+    #[arg(short, long, default_value_t = default_threads())]
+    threads: usize,
+
+    /// Seed for the random number generator
+    #[arg(short, long)]
+    seed: Option<u64>,
+
+    /// Hide the progress bar
+    #[arg(short, long)]
+    quiet: bool,
+
+    /// Print the comparison as JSON
+    #[arg(long)]
+    json: bool,
+
+    /// Hands or ranges of the first scenario, such as AhKh QQ
+    /// The first hand is the one compared
+    #[arg(required = true)]
+    hands: Vec<String>,
+}
+
+/// Simulate both scenarios and print the change in equity
+pub fn run(args: &Args) -> Result<(), Error> {
+    if !args.game.has_board() {
+        return Err(Error::NoBoard(args.game).input("--game", &args.game.to_string()));
+    }
+
+    let first = scenario(
+        args,
+        &args.hands,
+        ("--board", &args.board),
+        ("--dead", &args.dead),
+    )?;
+
+    let mut hands = args.hands.clone();
+    if !args.other_villain.is_empty() {
+        hands.truncate(1);
+        hands.extend(args.other_villain.iter().cloned());
+    }
+    let board = match &args.other_board {
+        Some(board) => ("--other-board", board.as_str()),
+        None => ("--board", args.board.as_str()),
+    };
+    let dead = match &args.other_dead {
+        Some(dead) => ("--other-dead", dead.as_str()),
+        None => ("--dead", args.dead.as_str()),
+    };
+    let second = scenario(args, &hands, board, dead)?;
+
+    let bar = if args.quiet {
+        ProgressBar::hidden()
+    } else {
+        ProgressBar::new(args.iterations as u64).with_style(
+            ProgressStyle::with_template("{bar:40} {pos}/{len} [{per_sec}, ETA {eta}]").unwrap(),
+        )
+    };
+    let progress = |done| bar.set_position(done as u64);
+    let settings = SimulationSettings {
+        threads: args.threads,
+        progress: &progress,
+        stop: Some(catch_interrupt()),
+        ..SimulationSettings::new(args.iterations)
+    };
+    let mut rng = match args.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+
+    let scenarios = [first, second];
+    let comparison = run_comparison(args.game, scenarios.clone(), &settings, &mut rng)?;
+    bar.finish_and_clear();
+    if interrupted() {
+        eprintln!(
+            "Interrupted after {} of {} iterations",
+            comparison.deals, args.iterations
+        );
+    }
+
+    let hands = [args.hands.clone(), hands];
+    if args.json {
+        print_json(args, &scenarios, &hands, &comparison);
+    } else {
+        print_text(&scenarios, &hands, &comparison);
+    }
+    Ok(())
+}
+
+/// Parse and check one scenario, from its hands and its named board and
+/// dead card arguments
+fn scenario(
+    args: &Args,
+    hands: &[String],
+    (board_name, board): (&str, &str),
+    (dead_name, dead): (&str, &str),
+) -> Result<Scenario, Error> {
+    let ranges = parse_hands(args.game, hands, 0..0)?;
+    let board_cards = parse_cards(board_name, board)?;
+    if !args.game.is_valid_board(board_cards.len()) {
+        return Err(Error::InvalidBoard(board_cards.len()).input(board_name, board));
+    }
+    let dead_cards = parse_cards(dead_name, dead)?;
+    check_known_cards(
+        &ranges,
+        vec![
+            (board_name.to_string(), board_cards.clone()),
+            (dead_name.to_string(), dead_cards.clone()),
+        ],
+    )?;
+
+    Ok(Scenario {
+        board: board_cards,
+        dead: dead_cards,
+        ranges,
+    })
+}
+
+/// Print both scenarios side by side and the change between them
+fn print_text(scenarios: &[Scenario; 2], hands: &[Vec<String>; 2], comparison: &Comparison) {
+    let row = |name: &str, [first, second]: [String; 2]| {
+        println!("{:<10} {:>16} {:>16}", name, first, second);
+    };
+
+    row("", ["First".to_string(), "Second".to_string()]);
+    row("Hand 1", hands.each_ref().map(|hands| hands[0].clone()));
+    row(
+        "Opponents",
+        hands.each_ref().map(|hands| hands[1..].join(" ")),
+    );
+    row(
+        "Board",
+        scenarios.each_ref().map(|s| cards_string(&s.board)),
+    );
+    if scenarios.iter().any(|s| !s.dead.is_empty()) {
+        row("Dead", scenarios.each_ref().map(|s| cards_string(&s.dead)));
+    }
+    row(
+        "Equity",
+        comparison
+            .equities()
+            .map(|equity| format!("{:.2}%", equity * 100.0)),
+    );
+
+    println!();
+    println!(
+        "Change: {:+.2}% ± {:.2}%",
+        comparison.delta() * 100.0,
+        Z_95 * comparison.delta_std_error() * 100.0
+    );
+    println!(
+        "Simulated separately the change would be ± {:.2}%",
+        Z_95 * comparison.independent_std_error() * 100.0
+    );
+}
+
+/// Print the comparison as a single JSON object
+fn print_json(
+    args: &Args,
+    scenarios: &[Scenario; 2],
+    hands: &[Vec<String>; 2],
+    comparison: &Comparison,
+) {
+    let (low, high) = comparison.confidence_interval();
+    let scenario = |i: usize| {
+        json!({
+            "hands": hands[i],
+            "board": cards_string(&scenarios[i].board),
+            "dead": cards_string(&scenarios[i].dead),
+            "equity": comparison.equities()[i],
+        })
+    };
+
+    println!(
+        "{}",
+        json!({
+            "game": args.game.to_string(),
+            "deals": comparison.deals,
+            "scenarios": [scenario(0), scenario(1)],
+            "change": comparison.delta(),
+            "std_error": comparison.delta_std_error(),
+            "confidence_interval": [low, high],
+            "independent_std_error": comparison.independent_std_error(),
+        })
+    );
+}
@@ -0,0 +1,113 @@
+//! `coolers` subcommand, how often two ranges run into set over set, flush
+//! over flush and the other classic coolers, over every board
+
+use equity_cli::{check_duplicate_cards, find_coolers, Cooler, Error, Game, Range, Street};
+use serde_json::json;
+
+use super::equity::cards_string;
+use super::parse_cards;
+
+/// Arguments of the coolers subcommand
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// The two ranges, such as "TT+" "77,88"
+    #[arg(required = true, num_args = 2)]
+    ranges: Vec<String>,
+
+    /// Flop or turn to look at every runout from, every board when empty
+    /// Example: Kh7c2d
+    #[arg(short, long, default_value = "")]
+    board: String,
+
+    /// Dead cards that have been exposed or folded
+    #[arg(short, long, default_value = "")]
+    dead: String,
+
+    /// Street the hands are shown down on: flop, turn or river
+    /// Defaults to the flop with no board and the river with one
+    #[arg(long)]
+    street: Option<Street>,
+
+    /// Game to deal: holdem, omaha or omaha8
+    /// Only the high hand is looked at in omaha8
+    #[arg(short, long, default_value_t = Game::Holdem)]
+    game: Game,
+
+    /// Print the frequencies as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+/// Count and print the coolers between the ranges
+pub fn run(args: &Args) -> Result<(), Error> {
+    if !args.game.has_board() {
+        return Err(Error::NoBoard(args.game).input("--game", &args.game.to_string()));
+    }
+    let ranges = args
+        .ranges
+        .iter()
+        .map(|value| {
+            let range = Range::parse(value).map_err(|e| Error::from(e).input("range", value))?;
+            for combo in range.combos() {
+                args.game
+                    .check_hole_cards(combo.len())
+                    .map_err(|e| e.input("range", value))?;
+            }
+            Ok(range)
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let board = parse_cards("--board", &args.board)?;
+    let dead = parse_cards("--dead", &args.dead)?;
+    check_duplicate_cards(&[
+        ("--board".to_string(), board.clone()),
+        ("--dead".to_string(), dead.clone()),
+    ])?;
+    let street = args.street.unwrap_or(match board.is_empty() {
+        true => Street::Flop,
+        false => Street::River,
+    });
+
+    let coolers = find_coolers(args.game, &ranges[0], &ranges[1], &board, &dead, street)
+        .map_err(|e| e.input("--board", &args.board))?;
+
+    if args.json {
+        let frequencies = Cooler::ALL
+            .iter()
+            .map(|&cooler| {
+                json!({
+                    "cooler": cooler.name(),
+                    "frequency": coolers.frequency(cooler),
+                    "first_on_top": coolers.first_share(cooler),
+                })
+            })
+            .collect::<Vec<_>>();
+        println!(
+            "{}",
+            json!({
+                "ranges": args.ranges,
+                "board": cards_string(&board),
+                "game": args.game.to_string(),
+                "street": street.name().to_lowercase(),
+                "coolers": frequencies,
+            })
+        );
+        return Ok(());
+    }
+
+    println!("Ranges: {} vs {}", args.ranges[0], args.ranges[1]);
+    println!("Board: {:?}", board);
+    println!("Shown down on the {}", street.name().to_lowercase());
+    println!();
+    println!("{:<27} {:>8} {:>13}", "Cooler", "Chance", "First on top");
+    for cooler in Cooler::ALL {
+        println!(
+            "{:<27} {:>7.3}% {:>12.2}%",
+            cooler.name(),
+            coolers.frequency(cooler) * 100.0,
+            coolers.first_share(cooler) * 100.0
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,2297 @@
+//! `equity` subcommand, the equity of hands or ranges against each other
+
+use std::fs;
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Once;
+use std::time::{Duration, Instant};
+
+use equity_cli::{
+    canonical_results, canonicalize_spot, check_duplicate_cards, default_threads,
+    example_showdowns, kicker_pot_frequency, max_runs, restore_results, run_condition_calculation,
+    run_exact_calculation, run_filtered_exact_calculation, run_it_calculation,
+    run_kicker_calculation, run_pairwise_calculation, run_range_calculation,
+    run_side_pot_calculation, run_street_calculation, side_pots, Card, ComboResult,
+    ConditionResult, EquityResult, Error, EvaluatorKind, ExampleShowdown, Game, HandCategory,
+    HandResult, KickerResult, PairwiseResult, Pot, Range, Rank, RunItResult, RunoutFilter, Sampler,
+    SidePotResult, SimulationSettings, StartingHandClass, StreetResult, Suit, CONVERGENCE_BATCH,
+    PROGRESS_INTERVAL, Z_95,
+};
+use indicatif::{ProgressBar, ProgressStyle};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde_json::{json, Value};
+
+use super::cache::{cache_path, read_results, write_results};
+use super::grid::{self, Label, Palette};
+use super::{parse_cards, parse_hands};
+
+/// Cache file of starting hands against random hands
+const VS_RANDOM_CACHE: &str = "vs-random.json";
+
+/// Set by the first Ctrl+C during a simulation
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// How the results are printed
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Human readable table
+    Text,
+
+    /// A single JSON document
+    Json,
+
+    /// One row per hand with a header row
+    Csv,
+}
+
+/// How often --refine prints the equities so far
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Refine {
+    Every(Duration),
+    Iterations(u32),
+}
+
+/// Arguments of the equity subcommand
+#[derive(clap::Args, Debug, Clone)]
+pub struct Args {
+    /// Number of iterations
+    /// Defaults to 100000, or a limit of 10000000 with --target-error and
+    /// no limit with --max-time
+    #[arg(short, long)]
+    iterations: Option<u32>,
+
+    /// Simulate for this long, such as 2s or 500ms, running as many
+    /// iterations as fit instead of a set number
+    #[arg(long, value_name = "TIME", value_parser = parse_duration, conflicts_with = "exact")]
+    max_time: Option<Duration>,
+
+    /// Keep simulating until the standard error of every equity is below this
+    /// Given as a percentage such as 0.1% or a fraction such as 0.001
+    #[arg(long, value_parser = parse_error_target)]
+    target_error: Option<f64>,
+
+    /// Current board
+    /// Cards should use two letters each
+    /// Example: 5c6hQs
+    #[arg(short, long, default_value = "")]
+    board: String,
+
+    /// Flop cards, used instead of --board
+    /// Example: 5c6hQs
+    #[arg(long, conflicts_with = "board")]
+    flop: Option<String>,
+
+    /// Turn card, needs a --flop
+    #[arg(long, conflicts_with = "board")]
+    turn: Option<String>,
+
+    /// River card, needs a --turn
+    #[arg(long, conflicts_with = "board")]
+    river: Option<String>,
+
+    /// Dead cards that have been exposed or folded
+    /// Example: 2c7h
+    #[arg(short, long, default_value = "")]
+    dead: String,
+
+    /// Game to simulate: holdem, omaha, omaha8 (Omaha hi-lo, eight or better)
+    /// omaha5 (five card Omaha), courchevel (omaha5 with one flop card preflop)
+    /// pineapple (three hole cards, two play), stud (seven card stud)
+    /// razz (stud won by the lowest ace to five hand), deuce7 (2-7 single draw)
+    /// or badugi
+    #[arg(short, long, default_value_t = Game::Holdem)]
+    game: Game,
+
+    /// Hand evaluator for simulations: naive (every five card combination)
+    /// lookup (rank bitmasks and tables) or simd (lookup in batches, with the
+    /// simd feature)
+    /// Defaults to lookup when built with the fast-eval feature
+    #[arg(long, default_value_t)]
+    evaluator: EvaluatorKind,
+
+    /// How the runout is drawn in simulations: random, stratified to
+    /// spread the first card to come evenly over the deck, which lowers the
+    /// standard error for the same number of iterations, or sobol to pick
+    /// every card from a low discrepancy sequence, which converges faster
+    /// but whose margin is worked out as if it were random
+    #[arg(long, default_value_t, conflicts_with_all = ["exact", "run_it"])]
+    sampler: Sampler,
+
+    /// Only count the runouts that meet these conditions, comma separated,
+    /// such as "no-flush" or "turn:h" for the equity when a heart comes
+    /// flush, paired and straight look at the whole board and take a no-
+    /// prefix, and turn:, river: or runout:, any card to come, take a rank,
+    /// suit or card and a ! prefix
+    #[arg(
+        long,
+        value_name = "FILTER",
+        conflicts_with_all = ["sampler", "run_it", "streets", "pairwise", "stacks"]
+    )]
+    runout_filter: Option<RunoutFilter>,
+
+    /// Also split every hand's equity by whether the runout meets this
+    /// condition, written like --runout-filter, showing the equity when it
+    /// does and when it does not side by side from the same deals
+    /// Can be given more than once
+    #[arg(
+        long,
+        value_name = "FILTER",
+        conflicts_with_all = ["exact", "target_error", "sampler", "refine", "run_it", "streets", "pairwise", "stacks", "runout_filter"]
+    )]
+    condition: Vec<RunoutFilter>,
+
+    /// Also show how often every hand wins and loses the pot on a kicker
+    /// and how often it only plays the board
+    #[arg(
+        long,
+        conflicts_with_all = ["exact", "target_error", "sampler", "refine", "run_it", "streets", "pairwise", "stacks", "runout_filter", "condition"]
+    )]
+    kickers: bool,
+
+    /// Number of worker threads
+    /// Defaults to the number of logical CPUs
+    #[arg(short, long, default_value_t = default_threads())]
+    threads: usize,
+
+    /// Seed for the random number generator
+    /// Runs with the same inputs, seed and thread count give the same results
+    #[arg(short, long)]
+    seed: Option<u64>,
+
+    /// Enumerate every possible runout instead of sampling
+    /// Not available with weighted ranges
+    #[arg(short, long)]
+    pub exact: bool,
+
+    /// Hide the progress bar
+    #[arg(short, long)]
+    quiet: bool,
+
+    /// Output format of the results
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    /// Print the results as JSON, the same as --format json
+    #[arg(long, conflicts_with = "format")]
+    json: bool,
+
+    /// Leave out the header row of CSV output, for appending to an existing file
+    #[arg(long)]
+    no_header: bool,
+
+    /// Break down the equity of each range by combo
+    #[arg(short, long)]
+    combos: bool,
+
+    /// Show how often each hand finishes as a pair, flush and so on
+    /// and how often it wins when it does
+    #[arg(long)]
+    categories: bool,
+
+    /// Show how the equity of each range is spread over its combos
+    #[arg(long)]
+    histogram: bool,
+
+    /// Show the equity of each starting hand of a range on a 13x13 grid,
+    /// suited hands above the pairs and offsuit below
+    /// With --format csv only the grids are printed, one block per range
+    #[arg(long)]
+    grid: bool,
+
+    /// Draw a chart of the equity of each hand on every street up to the
+    /// board, or the --grid of each range, to a .png or .svg file
+    #[cfg(feature = "plot")]
+    #[arg(long, value_name = "PATH")]
+    plot: Option<std::path::PathBuf>,
+
+    /// Number of buckets in the --histogram
+    #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u16).range(2..=100))]
+    buckets: u16,
+
+    /// Add a hand with the range in a file, as exported from PioSolver
+    /// ("AA,KK:0.5") or GTO+ ("[50.0]KK,AKs[/50.0]"), after the other hands
+    #[arg(long, value_name = "PATH")]
+    range_file: Vec<String>,
+
+    /// Add this many opponents holding any two cards
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=9))]
+    vs_random: Option<u8>,
+
+    /// Run the rest of the board out this many times per deal and show
+    /// how much that lowers the swings compared to running it once
+    #[arg(
+        long,
+        value_name = "RUNS",
+        value_parser = clap::value_parser!(u32).range(2..),
+        conflicts_with_all = ["exact", "target_error", "combos", "categories"]
+    )]
+    run_it: Option<u32>,
+
+    /// Show every deal down on each street to come, to see how often each
+    /// hand is ahead on the flop and turn before its equity at the river
+    #[arg(
+        long,
+        conflicts_with_all = ["exact", "target_error", "sampler", "refine", "run_it"]
+    )]
+    streets: bool,
+
+    /// Also show every two hands down against each other on the same
+    /// deals, the heads up equities of a multiway pot with the cards of the
+    /// other hands dead
+    #[arg(
+        long,
+        conflicts_with_all = ["exact", "target_error", "sampler", "refine", "run_it", "streets"]
+    )]
+    pairwise: bool,
+
+    /// Stack every hand is all in for, comma separated in the order of the
+    /// hands, to pay out the main pot and side pots and show each hand's
+    /// expected chips
+    /// Example: 2000,1500,800
+    #[arg(
+        long,
+        value_delimiter = ',',
+        conflicts_with_all = ["exact", "target_error", "sampler", "refine", "run_it", "streets", "pairwise"]
+    )]
+    stacks: Vec<f64>,
+
+    /// Also deal this many example runouts and show each one down, with
+    /// every hand's best five cards, what they make and who wins
+    #[arg(long, value_name = "N")]
+    explain: Option<usize>,
+
+    /// Do not read or write cached results
+    #[arg(long)]
+    no_cache: bool,
+
+    /// Print the equities so far on stderr as the simulation runs, every
+    /// interval such as 1s or 250ms, or every number of iterations such as
+    /// 50000, until --iterations or --target-error is reached
+    /// Defaults to every second, --refine=EVERY picks another interval
+    #[arg(
+        long,
+        value_name = "EVERY",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "1s",
+        value_parser = parse_refine,
+        conflicts_with_all = ["exact", "run_it"]
+    )]
+    refine: Option<Refine>,
+
+    /// Hands to compare.
+    /// Hands should be separated by space, and use two letters for each hand
+    /// such as AhAs or KdQd
+    /// Ranges are also accepted, such as "QQ+,AKs,AJo+" or "A5s-A2s"
+    /// and "random" is any two cards
+    /// Weights such as "AA:0.5,KQs:0.25" deal a share of the combos
+    /// Named ranges such as "@utg-open" or "@top10%" are listed by --list-ranges
+    /// In stud each hand is every card dealt to the player so far, up and down
+    /// such as AhKs7c, and folded up cards go in --dead
+    /// In pineapple a hand that has discarded is two cards, and the discard goes in --dead
+    /// In deuce7 and badugi each hand is the cards kept, and the rest are drawn
+    #[arg()]
+    pub hands: Vec<String>,
+}
+
+impl Args {
+    /// Output format, taking --json into account
+    pub fn format(&self) -> Format {
+        if self.json {
+            Format::Json
+        } else {
+            self.format
+        }
+    }
+
+    /// The hands given, then the path of each --range-file and one "random"
+    /// for each --vs-random opponent
+    pub fn hand_names(&self) -> Vec<String> {
+        let random = (0..self.vs_random.unwrap_or(0)).map(|_| "random".to_string());
+        self.hands
+            .iter()
+            .chain(&self.range_file)
+            .cloned()
+            .chain(random)
+            .collect()
+    }
+
+    /// The same arguments with `hand` dealt first, ahead of the hands given
+    pub fn with_first_hand(&self, hand: &str) -> Args {
+        let mut args = self.clone();
+        args.hands.insert(0, hand.to_string());
+        args
+    }
+
+    /// Iterations to run, or the most to run when aiming for a target error
+    pub fn iterations(&self) -> u32 {
+        match (self.iterations, self.target_error, self.max_time) {
+            (Some(iterations), _, _) => iterations,
+            (None, _, Some(_)) => u32::MAX,
+            (None, Some(_), None) => 10_000_000,
+            (None, None, None) => 100_000,
+        }
+    }
+
+    /// Reject the modes and outputs of the equity subcommand, for the
+    /// subcommands that only use the equity of each hand and print it in
+    /// one of `formats`
+    pub fn check_equity_only(&self, formats: &[Format]) -> Result<(), Error> {
+        #[cfg(feature = "plot")]
+        let plot = self.plot.is_some();
+        #[cfg(not(feature = "plot"))]
+        let plot = false;
+
+        let given = [
+            ("--run-it", self.run_it.is_some()),
+            ("--streets", self.streets),
+            ("--pairwise", self.pairwise),
+            ("--stacks", !self.stacks.is_empty()),
+            ("--condition", !self.condition.is_empty()),
+            ("--kickers", self.kickers),
+            ("--explain", self.explain.is_some()),
+            ("--combos", self.combos),
+            ("--categories", self.categories),
+            ("--histogram", self.histogram),
+            ("--grid", self.grid),
+            ("--no-header", self.no_header),
+            ("--json", self.json && !formats.contains(&Format::Json)),
+            ("--format", !formats.contains(&self.format())),
+            ("--plot", plot),
+        ];
+
+        match given.into_iter().find(|(_, given)| *given) {
+            Some((flag, _)) => Err(Error::UnusedFlag(flag)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Parse a standard error target such as "0.1%" or "0.001"
+pub fn parse_error_target(s: &str) -> Result<f64, String> {
+    let (number, scale) = match s.strip_suffix('%') {
+        Some(percent) => (percent, 100.0),
+        None => (s, 1.0),
+    };
+
+    match number.trim().parse::<f64>() {
+        Ok(value) if value > 0.0 && value / scale < 1.0 => Ok(value / scale),
+        _ => Err(format!(
+            "expected a positive percentage such as 0.1%, got \"{}\"",
+            s
+        )),
+    }
+}
+
+/// Parse a duration such as "2s", "1.5s" or "250ms"
+pub fn parse_duration(s: &str) -> Result<Duration, String> {
+    let (number, scale) = match s.strip_suffix("ms") {
+        Some(ms) => (ms, 1000.0),
+        None => (s.strip_suffix('s').unwrap_or(s), 1.0),
+    };
+
+    match number.trim().parse::<f64>() {
+        Ok(value) if value > 0.0 && value.is_finite() => Ok(Duration::from_secs_f64(value / scale)),
+        _ => Err(format!(
+            "expected a time such as 2s or 250ms, got \"{}\"",
+            s
+        )),
+    }
+}
+
+/// Parse how often to refine, a duration or a whole number of iterations
+fn parse_refine(s: &str) -> Result<Refine, String> {
+    match s.parse::<u32>() {
+        Ok(0) => Err("expected at least one iteration".to_string()),
+        Ok(iterations) => Ok(Refine::Iterations(iterations)),
+        Err(_) => parse_duration(s).map(Refine::Every),
+    }
+}
+
+/// Parse the board from either --board or the --flop, --turn and --river flags
+/// Each street is returned separately, named after its flag
+fn parse_board(args: &Args) -> Result<Vec<(String, Vec<Card>)>, Error> {
+    if args.flop.is_none() && args.turn.is_none() && args.river.is_none() {
+        let board = parse_cards("--board", &args.board)?;
+        if !args.game.is_valid_board(board.len()) {
+            return Err(Error::InvalidBoard(board.len()).input("--board", &args.board));
+        }
+        return Ok(vec![("--board".to_string(), board)]);
+    }
+
+    let streets = [
+        ("--flop", &args.flop, 3),
+        ("--turn", &args.turn, 1),
+        ("--river", &args.river, 1),
+    ];
+
+    let mut board = vec![];
+    for (i, (name, value, expected)) in streets.iter().enumerate() {
+        let Some(value) = value else {
+            continue;
+        };
+
+        // Each street needs the one before it
+        if let Some((before, None, _)) = i.checked_sub(1).map(|b| streets[b]) {
+            return Err(Error::MissingStreet {
+                street: name.to_string(),
+                before: before.to_string(),
+            });
+        }
+
+        let cards = parse_cards(name, value)?;
+        if cards.len() != *expected {
+            let error = Error::WrongStreetSize {
+                street: name.trim_start_matches('-').to_string(),
+                expected: *expected,
+                found: cards.len(),
+            };
+            return Err(error.input(name, value));
+        }
+        board.push((name.to_string(), cards));
+    }
+
+    Ok(board)
+}
+
+/// Parsed and checked inputs of a calculation
+pub struct Spot {
+    pub hands: Vec<Range>,
+    pub board: Vec<Card>,
+    pub dead: Vec<Card>,
+}
+
+/// Calculate and print the equity of every hand
+pub fn run(args: &Args) -> Result<(), Error> {
+    let spot = parse_spot(args)?;
+    #[cfg(feature = "plot")]
+    if let Some(path) = &args.plot {
+        super::plot::check_path(path)?;
+    }
+    if let Some(runs) = args.run_it {
+        if !args.game.has_board() {
+            return Err(Error::NoBoard(args.game).input("--run-it", &runs.to_string()));
+        }
+        let most = max_runs(
+            args.game,
+            spot.hands.len(),
+            spot.board.len(),
+            spot.dead.len(),
+        );
+        if let Some(most) = most.filter(|most| runs > *most) {
+            return Err(Error::TooManyRuns { runs, most });
+        }
+    }
+    if args.streets {
+        if !args.game.has_board() {
+            return Err(Error::NoBoard(args.game).input("--game", &args.game.to_string()));
+        }
+        if spot.board.len() == 5 {
+            return Err(Error::NoStreetsLeft.input("--board", &cards_string(&spot.board)));
+        }
+    }
+    if (args.pairwise || args.kickers || !args.stacks.is_empty() || !args.condition.is_empty())
+        && !args.game.has_board()
+    {
+        return Err(Error::NoBoard(args.game).input("--game", &args.game.to_string()));
+    }
+    if args.explain.is_some() && !args.game.has_board() {
+        return Err(Error::NoBoard(args.game).input("--game", &args.game.to_string()));
+    }
+    let examples = args.explain.map(|count| {
+        example_showdowns(
+            args.game,
+            &spot.board,
+            &spot.dead,
+            spot.hands.clone(),
+            args.runout_filter.as_ref(),
+            count,
+            &mut rng(args),
+        )
+    });
+    let pots = match args.stacks.is_empty() {
+        true => None,
+        false => Some(check_stacks(args, spot.hands.len())?),
+    };
+    if args.format() == Format::Text {
+        print_header(args, &spot);
+    }
+
+    let (board, dead) = (spot.board.clone(), spot.dead.clone());
+    #[cfg(feature = "plot")]
+    let hands = spot.hands.clone();
+    let (mut run_it, mut streets, mut pairwise, mut stacks) = (None, None, None, None);
+    let (mut conditions, mut kickers) = (None, None);
+    let results = if let Some(runs) = args.run_it {
+        let results = calculate_run_it(args, spot, runs)?;
+        let showdowns = results.iter().map(|r| r.showdowns.clone()).collect();
+        run_it = Some(results);
+        showdowns
+    } else if args.streets {
+        let results = calculate_streets(args, spot)?;
+        let showdowns = results.iter().map(|r| r.showdowns.clone()).collect();
+        streets = Some(results);
+        showdowns
+    } else if args.pairwise {
+        let results = calculate_pairwise(args, spot)?;
+        let showdowns = results.iter().map(|r| r.showdowns.clone()).collect();
+        pairwise = Some(results);
+        showdowns
+    } else if !args.condition.is_empty() {
+        let results = calculate_conditions(args, spot)?;
+        let showdowns = results.iter().map(|r| r.showdowns.clone()).collect();
+        conditions = Some(results);
+        showdowns
+    } else if args.kickers {
+        let results = calculate_kickers(args, spot)?;
+        let showdowns = results.iter().map(|r| r.showdowns.clone()).collect();
+        kickers = Some(results);
+        showdowns
+    } else if !args.stacks.is_empty() {
+        let results = calculate_side_pots(args, spot)?;
+        let showdowns = results.iter().map(|r| r.showdowns.clone()).collect();
+        stacks = Some(results);
+        showdowns
+    } else {
+        calculate(args, spot)
+    };
+    let done = match &run_it {
+        Some(run_it) => run_it[0].deals,
+        None => results[0].showdowns(),
+    };
+    if let Some(filter) = args.runout_filter.as_ref().filter(|_| done == 0) {
+        return Err(Error::NoMatchingRunout(filter.to_string()));
+    }
+    if interrupted() {
+        eprintln!(
+            "Interrupted after {} of {} iterations",
+            done,
+            args.iterations()
+        );
+    } else if args.max_time.is_some() && args.format() == Format::Text {
+        println!("Ran {} iterations", done);
+    }
+
+    let result = EquityResult {
+        hands: args.hand_names(),
+        board,
+        dead,
+        iterations: done,
+        per_hand: results,
+    };
+    match args.format() {
+        Format::Text => {
+            print_text(args, &result);
+            if let Some(run_it) = &run_it {
+                print_run_it(run_it);
+            }
+            if let Some(streets) = &streets {
+                print_streets(streets);
+            }
+            if let Some(pairwise) = &pairwise {
+                print_pairwise(pairwise);
+            }
+            if let Some(conditions) = &conditions {
+                print_conditions(&args.condition, conditions);
+            }
+            if let Some(kickers) = &kickers {
+                print_kickers(kickers);
+            }
+            if let (Some(pots), Some(stacks)) = (&pots, &stacks) {
+                print_side_pots(pots, stacks);
+            }
+            if let Some(examples) = &examples {
+                print_examples(examples);
+            }
+        }
+        Format::Json => print_json(
+            args,
+            &result,
+            run_it.as_deref(),
+            streets.as_deref(),
+            pairwise.as_deref(),
+            conditions.as_deref(),
+            kickers.as_deref(),
+            pots.as_deref().zip(stacks.as_deref()),
+            examples.as_deref(),
+        ),
+        Format::Csv if args.grid => print_grid_csv(&result)?,
+        Format::Csv => write_csv(std::io::stdout(), args, &result)?,
+    }
+
+    #[cfg(feature = "plot")]
+    if let Some(path) = &args.plot {
+        let EquityResult {
+            board,
+            dead,
+            per_hand,
+            ..
+        } = result;
+        plot(args, path, Spot { hands, board, dead }, &per_hand)?;
+    }
+
+    Ok(())
+}
+
+/// Draw the grids of the ranges, or the equity on each street up to the
+/// board with the streets before it calculated the same way
+#[cfg(feature = "plot")]
+fn plot(
+    args: &Args,
+    path: &std::path::Path,
+    spot: Spot,
+    results: &[HandResult],
+) -> Result<(), Error> {
+    use super::plot;
+    use equity_cli::{Board, Street};
+
+    if args.grid {
+        let grids = args
+            .hand_names()
+            .into_iter()
+            .zip(results)
+            .filter(|(_, result)| dealt_combos(result).len() >= 2)
+            .map(|(hand, result)| (hand, result.equity_grid()))
+            .collect::<Vec<_>>();
+        return plot::equity_grids(path, &grids);
+    }
+
+    let board = Board::new(args.game, spot.board.clone())?;
+    let streets = Street::ALL
+        .into_iter()
+        .filter(|street| *street <= board.street())
+        .collect::<Vec<_>>();
+    let mut equities = streets[..streets.len() - 1]
+        .iter()
+        .map(|street| {
+            let earlier = Spot {
+                hands: spot.hands.clone(),
+                board: board.on(*street).into(),
+                dead: spot.dead.clone(),
+            };
+            calculate(args, earlier)
+                .iter()
+                .map(HandResult::equity)
+                .collect()
+        })
+        .collect::<Vec<Vec<f64>>>();
+    equities.push(results.iter().map(HandResult::equity).collect());
+
+    let names = streets
+        .iter()
+        .map(|street| street.name())
+        .collect::<Vec<_>>();
+    plot::street_equities(path, &names, &args.hand_names(), &equities)
+}
+
+/// Parse every input, checking that no card is used twice
+/// and that every range can still be dealt
+pub fn parse_spot(args: &Args) -> Result<Spot, Error> {
+    let files = args.hands.len()..args.hands.len() + args.range_file.len();
+    let hands = parse_hands(args.game, &args.hand_names(), files)?;
+
+    let streets = parse_board(args)?;
+    let board = streets
+        .iter()
+        .flat_map(|(_, cards)| cards.iter().cloned())
+        .collect::<Vec<_>>();
+
+    let dead = parse_cards("--dead", &args.dead)?;
+
+    if let Some(filter) = args
+        .runout_filter
+        .as_ref()
+        .filter(|_| !args.game.has_board())
+    {
+        return Err(Error::NoBoard(args.game).input("--runout-filter", &filter.to_string()));
+    }
+    if !args.game.has_board() {
+        if streets.iter().any(|(_, cards)| !cards.is_empty()) {
+            return Err(Error::NoBoard(args.game).input("--game", &args.game.to_string()));
+        }
+        if hands.len() * args.game.hole_cards() + dead.len() > 52 {
+            return Err(Error::DeckTooSmall {
+                cards: args.game.hole_cards(),
+                players: hands.len(),
+                dead: dead.len(),
+            });
+        }
+    }
+
+    let mut known = streets;
+    known.push(("--dead".to_string(), dead.clone()));
+    check_known_cards(&hands, known)?;
+
+    if args.exact && hands.iter().any(Range::is_weighted) {
+        return Err(Error::WeightedExact);
+    }
+
+    Ok(Spot { hands, board, dead })
+}
+
+/// Check that no card is used twice between the known hole cards and
+/// the named groups of board and dead cards, and that every range can
+/// still be dealt once those cards are removed
+pub fn check_known_cards(hands: &[Range], cards: Vec<(String, Vec<Card>)>) -> Result<(), Error> {
+    let mut known = hands
+        .iter()
+        .enumerate()
+        .filter(|(_, range)| range.len() == 1)
+        .map(|(i, range)| (format!("hand {}", i + 1), range.combos()[0].clone()))
+        .collect::<Vec<_>>();
+    known.extend(cards);
+    check_duplicate_cards(&known)?;
+
+    // Ranges need at least one combo that does not use a known card
+    for (i, range) in hands.iter().enumerate() {
+        let name = format!("hand {}", i + 1);
+        let removed = known
+            .iter()
+            .filter(|(other, _)| *other != name)
+            .flat_map(|(_, cards)| cards.iter().cloned())
+            .collect::<Vec<_>>();
+
+        if range.without_cards(&removed).is_empty() {
+            return Err(Error::EmptyRange(name));
+        }
+    }
+
+    Ok(())
+}
+
+/// Print the hands and board that are about to be run
+fn print_header(args: &Args, spot: &Spot) {
+    // Print out Hands it will run
+    for (i, hand) in args.hand_names().iter().enumerate() {
+        match spot.hands[i].combos() {
+            [combo] => println!("Hand {}: {:?}", i + 1, combo),
+            combos if spot.hands[i].is_weighted() => println!(
+                "Hand {}: {} ({} combos, {:.2} weighted)",
+                i + 1,
+                hand,
+                combos.len(),
+                spot.hands[i].total_weight()
+            ),
+            combos => println!("Hand {}: {} ({} combos)", i + 1, hand, combos.len()),
+        }
+    }
+
+    // Print out board
+    if args.game.has_board() {
+        println!("Board: {:?}", spot.board);
+    }
+    if !spot.dead.is_empty() {
+        println!("Dead: {:?}", spot.dead);
+    }
+    if let Some(filter) = &args.runout_filter {
+        println!("Runouts: {}", filter);
+    }
+
+    if args.exact {
+        println!("Enumerating all runouts...");
+    } else if let Some(max_time) = args.max_time {
+        println!("Running for up to {:?}...", max_time);
+    } else {
+        match args.target_error {
+            Some(target) => println!(
+                "Running until the standard error is below {:.3}% (at most {} iterations)...",
+                target * 100.0,
+                args.iterations()
+            ),
+            None => match args.run_it {
+                Some(runs) => println!(
+                    "Running {} deals, each run out {} times...",
+                    args.iterations(),
+                    runs
+                ),
+                None => println!("Running {} iterations...", args.iterations()),
+            },
+        }
+    }
+}
+
+/// Run the calculation, or read its results from the cache
+pub fn calculate(args: &Args, spot: Spot) -> Vec<HandResult> {
+    let key = cache_key(args, &spot);
+    if let Some(results) = key.as_deref().and_then(read_cached) {
+        return results;
+    }
+
+    let cached = spot_cache(args, &spot);
+    if let Some(cached) = &cached {
+        if let Some(canonical) = read_results(&cached.key) {
+            return restore_results(&cached.ranges, cached.suits, &canonical);
+        }
+    }
+
+    let results = run_spot(args, spot);
+    if interrupted() {
+        return results;
+    }
+    if let Some(key) = key {
+        write_cached(&key, &results);
+    }
+    if let Some(cached) = cached {
+        let canonical = canonical_results(&cached.ranges, cached.suits, &results);
+        write_results(&cached.key, &canonical);
+    }
+    results
+}
+
+/// Run the calculation, exactly or by simulation with a progress bar
+fn run_spot(args: &Args, spot: Spot) -> Vec<HandResult> {
+    let Spot { hands, board, dead } = spot;
+
+    if args.exact {
+        let results = match &args.runout_filter {
+            Some(filter) => run_filtered_exact_calculation(args.game, board, dead, hands, filter),
+            None => run_exact_calculation(args.game, board, dead, hands),
+        };
+        return results.expect("parse_spot rejects weighted ranges with --exact");
+    }
+
+    let bar = progress_bar(args);
+    let progress = |done| bar.set_position(done as u64);
+    let settings = SimulationSettings {
+        iterations: args.iterations(),
+        target_error: args.target_error,
+        threads: args.threads,
+        progress: &progress,
+        progress_interval: PROGRESS_INTERVAL,
+        evaluator: args.evaluator.evaluator(),
+        sampler: args.sampler,
+        runout_filter: args.runout_filter.as_ref(),
+        stop: Some(catch_interrupt()),
+        deadline: args.max_time.map(|time| Instant::now() + time),
+    };
+
+    let results = match args.refine {
+        Some(refine) => {
+            let spot = Spot { hands, board, dead };
+            simulate_refining(args, spot, refine, &settings, &bar)
+        }
+        None => run_range_calculation(args.game, board, dead, hands, &settings, &mut rng(args)),
+    };
+
+    bar.finish_and_clear();
+    results
+}
+
+/// Simulate in batches, printing the equities so far whenever `refine`
+/// comes round, and stop at the iteration budget or the target error
+/// Timed refinement runs in batches of `CONVERGENCE_BATCH` iterations and
+/// prints after the first batch, so there is a rough estimate straight away
+fn simulate_refining(
+    args: &Args,
+    spot: Spot,
+    refine: Refine,
+    settings: &SimulationSettings,
+    bar: &ProgressBar,
+) -> Vec<HandResult> {
+    let size = match refine {
+        Refine::Every(_) => CONVERGENCE_BATCH,
+        Refine::Iterations(iterations) => iterations,
+    };
+    let mut rng = rng(args);
+    let start = Instant::now();
+    let mut printed: Option<Instant> = None;
+
+    let mut results: Vec<HandResult> = vec![];
+    let mut done = 0;
+    while done < settings.iterations {
+        let offset = done;
+        let progress = move |n| bar.set_position((offset + n) as u64);
+        let batch = SimulationSettings {
+            iterations: size.min(settings.iterations - done),
+            target_error: None,
+            progress: &progress,
+            ..*settings
+        };
+        let (hands, board, dead) = (spot.hands.clone(), spot.board.clone(), spot.dead.clone());
+        let totals = run_range_calculation(args.game, board, dead, hands, &batch, &mut rng);
+        done += batch.iterations;
+
+        if results.is_empty() {
+            results = totals;
+        } else {
+            for (total, result) in results.iter_mut().zip(&totals) {
+                total.merge(result);
+            }
+        }
+
+        let due = match refine {
+            Refine::Every(interval) => printed.is_none_or(|at| at.elapsed() >= interval),
+            Refine::Iterations(_) => true,
+        };
+        if due {
+            bar.suspend(|| print_estimate(start.elapsed(), done, &results));
+            printed = Some(Instant::now());
+        }
+
+        let converged = settings
+            .target_error
+            .is_some_and(|target| results.iter().all(|r| r.std_error() <= target));
+        let expired = settings
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline);
+        if converged || expired || interrupted() {
+            break;
+        }
+    }
+
+    results
+}
+
+/// Print one line of the equities so far with their margins
+fn print_estimate(elapsed: Duration, iterations: u32, results: &[HandResult]) {
+    let hands = results
+        .iter()
+        .enumerate()
+        .map(|(i, result)| {
+            format!(
+                "Hand {} {:.2}% ±{:.2}%",
+                i + 1,
+                result.equity() * 100.0,
+                Z_95 * result.std_error() * 100.0
+            )
+        })
+        .collect::<Vec<_>>();
+    eprintln!(
+        "{:>7.2}s {:>10} iterations  {}",
+        elapsed.as_secs_f64(),
+        iterations,
+        hands.join("  ")
+    );
+}
+
+/// Settings of the simulations run alongside the equity, which draw every
+/// runout at random and play every iteration
+fn simulation_settings<'a>(
+    args: &Args,
+    progress: &'a (dyn Fn(u32) + Sync),
+) -> SimulationSettings<'a> {
+    SimulationSettings {
+        iterations: args.iterations(),
+        target_error: None,
+        threads: args.threads,
+        progress,
+        progress_interval: PROGRESS_INTERVAL,
+        evaluator: args.evaluator.evaluator(),
+        sampler: Sampler::Random,
+        runout_filter: None,
+        stop: Some(catch_interrupt()),
+        deadline: args.max_time.map(|time| Instant::now() + time),
+    }
+}
+
+/// Simulate every deal with the board run out `runs` times
+fn calculate_run_it(args: &Args, spot: Spot, runs: u32) -> Result<Vec<RunItResult>, Error> {
+    let Spot { hands, board, dead } = spot;
+
+    let bar = progress_bar(args);
+    let progress = |done| bar.set_position(done as u64);
+    let settings = simulation_settings(args, &progress);
+
+    let results = run_it_calculation(
+        args.game,
+        board,
+        dead,
+        hands,
+        runs,
+        &settings,
+        &mut rng(args),
+    );
+
+    bar.finish_and_clear();
+    results
+}
+
+/// Simulate every deal shown down on each street to come
+fn calculate_streets(args: &Args, spot: Spot) -> Result<Vec<StreetResult>, Error> {
+    let Spot { hands, board, dead } = spot;
+
+    let bar = progress_bar(args);
+    let progress = |done| bar.set_position(done as u64);
+    let settings = simulation_settings(args, &progress);
+
+    let results = run_street_calculation(args.game, board, dead, hands, &settings, &mut rng(args));
+
+    bar.finish_and_clear();
+    results
+}
+
+/// Simulate every deal, splitting the equities by the runout conditions
+fn calculate_conditions(args: &Args, spot: Spot) -> Result<Vec<ConditionResult>, Error> {
+    let Spot { hands, board, dead } = spot;
+
+    let bar = progress_bar(args);
+    let progress = |done| bar.set_position(done as u64);
+    let settings = simulation_settings(args, &progress);
+
+    let results = run_condition_calculation(
+        args.game,
+        board,
+        dead,
+        hands,
+        &args.condition,
+        &settings,
+        &mut rng(args),
+    );
+
+    bar.finish_and_clear();
+    results
+}
+
+/// Simulate every deal, counting the pots decided by a kicker and the
+/// hands playing the board
+fn calculate_kickers(args: &Args, spot: Spot) -> Result<Vec<KickerResult>, Error> {
+    let Spot { hands, board, dead } = spot;
+
+    let bar = progress_bar(args);
+    let progress = |done| bar.set_position(done as u64);
+    let settings = simulation_settings(args, &progress);
+
+    let results = run_kicker_calculation(args.game, board, dead, hands, &settings, &mut rng(args));
+
+    bar.finish_and_clear();
+    results
+}
+
+/// Simulate every deal shown down between all the hands and between every
+/// two of them
+fn calculate_pairwise(args: &Args, spot: Spot) -> Result<Vec<PairwiseResult>, Error> {
+    let Spot { hands, board, dead } = spot;
+
+    let bar = progress_bar(args);
+    let progress = |done| bar.set_position(done as u64);
+    let settings = simulation_settings(args, &progress);
+
+    let results =
+        run_pairwise_calculation(args.game, board, dead, hands, &settings, &mut rng(args));
+
+    bar.finish_and_clear();
+    results
+}
+
+/// Check there is a positive stack for every hand and split them into pots
+fn check_stacks(args: &Args, hands: usize) -> Result<Vec<Pot>, Error> {
+    let value = args
+        .stacks
+        .iter()
+        .map(|stack| stack.to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+    if args.stacks.len() != hands {
+        let error = Error::WrongStackCount {
+            stacks: args.stacks.len(),
+            hands,
+        };
+        return Err(error.input("--stacks", &value));
+    }
+    side_pots(&args.stacks).map_err(|e| e.input("--stacks", &value))
+}
+
+/// Simulate every deal with each hand all in for its stack, paying out the
+/// main pot and side pots
+fn calculate_side_pots(args: &Args, spot: Spot) -> Result<Vec<SidePotResult>, Error> {
+    let Spot { hands, board, dead } = spot;
+
+    let bar = progress_bar(args);
+    let progress = |done| bar.set_position(done as u64);
+    let settings = simulation_settings(args, &progress);
+
+    let results = run_side_pot_calculation(
+        args.game,
+        board,
+        dead,
+        hands,
+        &args.stacks,
+        &settings,
+        &mut rng(args),
+    );
+
+    bar.finish_and_clear();
+    results
+}
+
+/// Catch Ctrl+C so that a simulation stops and shows the results so far
+/// A second Ctrl+C quits straight away
+pub fn catch_interrupt() -> &'static AtomicBool {
+    static HANDLER: Once = Once::new();
+    HANDLER.call_once(|| {
+        // Without a handler Ctrl+C quits as it always has
+        let _ = ctrlc::set_handler(|| {
+            if INTERRUPTED.swap(true, Ordering::Relaxed) {
+                process::exit(130);
+            }
+        });
+    });
+    &INTERRUPTED
+}
+
+/// Whether Ctrl+C stopped a simulation early
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::Relaxed)
+}
+
+/// Progress bar over the iterations, hidden with --quiet
+fn progress_bar(args: &Args) -> ProgressBar {
+    if args.quiet {
+        return ProgressBar::hidden();
+    }
+    // Without an iteration count there is no telling how far along it is
+    if args.iterations() == u32::MAX {
+        return ProgressBar::new_spinner().with_style(
+            ProgressStyle::with_template("{spinner} {pos} iterations [{per_sec}]").unwrap(),
+        );
+    }
+
+    ProgressBar::new(args.iterations() as u64).with_style(
+        ProgressStyle::with_template("{bar:40} {pos}/{len} [{per_sec}, ETA {eta}]").unwrap(),
+    )
+}
+
+/// Random number generator from --seed, or from entropy without one
+fn rng(args: &Args) -> StdRng {
+    match args.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    }
+}
+
+/// Cache entry of a spot, keyed by its canonical form and how it is run so
+/// spots that only differ by their suits share results
+struct SpotCache {
+    key: String,
+
+    /// The ranges the results are for, without combos holding known cards
+    ranges: Vec<Range>,
+
+    /// Relabelling of the suits into the canonical form
+    suits: [Suit; 4],
+}
+
+/// Cache entry of the spot, None for seeded runs, which should give the same
+/// results as the seed, weighted ranges, which the canonical form leaves out,
+/// runs with another sampler, whose error would not match a random run, and
+/// runs against the clock
+fn spot_cache(args: &Args, spot: &Spot) -> Option<SpotCache> {
+    let cacheable = !args.no_cache
+        && args.seed.is_none()
+        && args.max_time.is_none()
+        && args.run_it.is_none()
+        && args.sampler == Sampler::Random
+        && args.runout_filter.is_none()
+        && !spot.hands.iter().any(Range::is_weighted);
+    if !cacheable {
+        return None;
+    }
+
+    let known = [spot.board.as_slice(), &spot.dead].concat();
+    let ranges = spot
+        .hands
+        .iter()
+        .map(|range| range.without_cards(&known))
+        .collect::<Vec<_>>();
+    let (form, suits) = canonicalize_spot(&ranges, &spot.board, &spot.dead);
+
+    let mode = match (args.exact, args.target_error) {
+        (true, _) => "exact".to_string(),
+        (false, None) => format!("{} iterations", args.iterations()),
+        (false, Some(target)) => format!("{} iterations to {}", args.iterations(), target),
+    };
+    let hands = form
+        .hands
+        .iter()
+        .map(|combos| {
+            combos
+                .iter()
+                .map(|c| cards_string(c))
+                .collect::<Vec<_>>()
+                .join(",")
+        })
+        .collect::<Vec<_>>()
+        .join(" vs ");
+    let key = format!(
+        "{} {}, {} on {} dead {}",
+        args.game,
+        mode,
+        hands,
+        cards_string(&form.board),
+        cards_string(&form.dead)
+    );
+
+    Some(SpotCache { key, ranges, suits })
+}
+
+/// Cache key of a single starting hand against random hands before the flop
+/// Every combo of a starting hand plays the same against random hands, so
+/// AhKh and AKs share results. Runs that need more than the totals, are
+/// seeded or stop at a target error are never cached
+fn cache_key(args: &Args, spot: &Spot) -> Option<String> {
+    let opponents = args.vs_random?;
+    let cacheable = !args.no_cache
+        && args.hands.len() == 1
+        && args.range_file.is_empty()
+        && args.game == Game::Holdem
+        && spot.board.is_empty()
+        && spot.dead.is_empty()
+        && !args.exact
+        && args.run_it.is_none()
+        && args.target_error.is_none()
+        && args.max_time.is_none()
+        && args.seed.is_none()
+        && args.sampler == Sampler::Random
+        && args.runout_filter.is_none()
+        && !args.combos
+        && !args.histogram
+        && !args.grid
+        && !args.categories;
+    let hand = args.hands[0]
+        .parse::<StartingHandClass>()
+        .ok()
+        .filter(|_| cacheable)?;
+
+    Some(format!(
+        "{} vs {} random, {} iterations",
+        hand,
+        opponents,
+        args.iterations()
+    ))
+}
+
+/// Cached results of every hand, as their wins, ties, losses and tie splits
+fn read_cached(key: &str) -> Option<Vec<HandResult>> {
+    let text = fs::read_to_string(cache_path(VS_RANDOM_CACHE)?).ok()?;
+    let cache = serde_json::from_str::<Value>(&text).ok()?;
+
+    cache
+        .get(key)?
+        .as_array()?
+        .iter()
+        .map(|result| {
+            let counts = serde_json::from_value::<(u64, u64, u64, Vec<u64>)>(result.clone());
+            let (wins, ties, losses, tie_splits) = counts.ok()?;
+            Some(HandResult {
+                wins,
+                ties,
+                losses,
+                tie_splits,
+                ..HandResult::default()
+            })
+        })
+        .collect()
+}
+
+/// Add results to the cache, which is only ever a shortcut so failing to
+/// write it is not an error
+fn write_cached(key: &str, results: &[HandResult]) {
+    let Some(path) = cache_path(VS_RANDOM_CACHE) else {
+        return;
+    };
+
+    let mut cache = fs::read_to_string(&path)
+        .ok()
+        .and_then(|text| serde_json::from_str::<Value>(&text).ok())
+        .filter(Value::is_object)
+        .unwrap_or_else(|| json!({}));
+    cache[key] = results
+        .iter()
+        .map(|r| json!([r.wins, r.ties, r.losses, r.tie_splits]))
+        .collect();
+
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    let _ = fs::write(&path, cache.to_string());
+}
+
+/// Print the results as a human readable table
+fn print_text(args: &Args, result: &EquityResult) {
+    let results = &result.per_hand;
+    if args.exact {
+        println!("Exact result over {} showdowns", result.iterations);
+    } else if let Some(target) = args.target_error {
+        let converged = results.iter().all(|r| r.std_error() <= target);
+        println!(
+            "{} after {} iterations",
+            if converged {
+                "Converged"
+            } else {
+                "Stopped without converging"
+            },
+            result.iterations
+        );
+    }
+
+    // Sampled equities come with a 95% margin of error
+    let margin = |text: String| {
+        if args.exact {
+            String::new()
+        } else {
+            format!(" {:>8}", text)
+        }
+    };
+
+    // Split pot games show the share won with each half and how often
+    // the whole pot was scooped
+    let hi_lo = args.game.is_hi_lo();
+    let columns = if hi_lo {
+        ["Hi", "Lo", "Scoop"]
+    } else {
+        ["Win", "Tie", "Lose"]
+    };
+
+    println!(
+        "{:<8} {:>8}{} {:>8} {:>8} {:>8}",
+        "",
+        "Equity",
+        margin("±95%".to_string()),
+        columns[0],
+        columns[1],
+        columns[2]
+    );
+    for (i, result) in results.iter().enumerate() {
+        let values = match (result.hi_equity(), result.lo_equity(), result.scoop()) {
+            (Some(hi), Some(lo), Some(scoop)) if hi_lo => [hi, lo, scoop],
+            _ => [result.win(), result.tie(), result.lose()],
+        };
+        println!(
+            "{:<8} {:>7.2}%{} {:>7.2}% {:>7.2}% {:>7.2}%",
+            format!("Hand {}:", i + 1),
+            result.equity() * 100.0,
+            margin(format!("±{:.2}%", Z_95 * result.std_error() * 100.0)),
+            values[0] * 100.0,
+            values[1] * 100.0,
+            values[2] * 100.0,
+        );
+    }
+
+    if args.combos {
+        for (i, result) in results.iter().enumerate() {
+            let combos = dealt_combos(result);
+            if combos.len() < 2 {
+                continue;
+            }
+
+            println!();
+            println!("Hand {} combos:", i + 1);
+            for combo in combos {
+                println!(
+                    "{:<8} {:>7.2}%",
+                    cards_string(&combo.cards),
+                    combo.equity() * 100.0
+                );
+            }
+        }
+    }
+
+    if args.grid {
+        for (i, result) in results.iter().enumerate() {
+            if dealt_combos(result).len() < 2 {
+                continue;
+            }
+
+            println!();
+            println!("Hand {} equity grid:", i + 1);
+            let grid = result.equity_grid();
+            print!(
+                "{}",
+                grid::render(&grid, Palette::Equity, Label::Percent, grid::use_color())
+            );
+        }
+    }
+
+    if args.histogram {
+        for (i, result) in results.iter().enumerate() {
+            println!();
+            println!("Hand {} equity distribution:", i + 1);
+            print_histogram(&result.equity_histogram(args.buckets as usize));
+        }
+    }
+
+    if args.categories {
+        for (i, result) in results.iter().enumerate() {
+            println!();
+            println!(
+                "{:<17} {:>8} {:>8} {:>8}",
+                format!("Hand {} made:", i + 1),
+                "Made",
+                "Win",
+                "Tie"
+            );
+            for category in HandCategory::ALL {
+                let counts = result.category(category);
+                if counts.made == 0 {
+                    continue;
+                }
+
+                let made = counts.made as f64;
+                println!(
+                    "{:<17} {:>7.2}% {:>7.2}% {:>7.2}%",
+                    category.name(),
+                    made / result.showdowns() as f64 * 100.0,
+                    counts.wins as f64 / made * 100.0,
+                    counts.ties as f64 / made * 100.0,
+                );
+            }
+        }
+    }
+}
+
+/// Print how the share of the pot won in a deal spreads out when the board
+/// is run out several times, against running it once
+fn print_run_it(results: &[RunItResult]) {
+    let runs = results[0].runs;
+
+    println!();
+    println!(
+        "{:<8} {:>8} {:>8} {:>8}",
+        format!("Run {}x", runs),
+        "Equity",
+        "Std dev",
+        "Run once"
+    );
+    for (i, result) in results.iter().enumerate() {
+        println!(
+            "{:<8} {:>7.2}% {:>7.2}% {:>7.2}%",
+            format!("Hand {}:", i + 1),
+            result.equity() * 100.0,
+            result.std_dev() * 100.0,
+            result.single_run_std_dev() * 100.0,
+        );
+    }
+
+    for (i, result) in results.iter().enumerate() {
+        println!();
+        println!("Hand {} runs won:", i + 1);
+        for won in 0..=runs as usize {
+            let fraction = result.runs_won(won);
+            if fraction > 0.0 {
+                println!("{:>3} of {:<3} {:>7.2}%", won, runs, fraction * 100.0);
+            }
+        }
+    }
+}
+
+/// Print a histogram as one bar per bucket, the largest bucket the full width
+fn print_histogram(histogram: &[f64]) {
+    const WIDTH: f64 = 40.0;
+
+    let largest = histogram.iter().cloned().fold(0.0, f64::max);
+    let step = 100.0 / histogram.len() as f64;
+    for (i, share) in histogram.iter().enumerate() {
+        let bar = (share / largest.max(f64::MIN_POSITIVE) * WIDTH).round() as usize;
+        let line = format!(
+            "{:>9} {:>7.2}% {}",
+            format!("{:.0}-{:.0}%", i as f64 * step, (i + 1) as f64 * step),
+            share * 100.0,
+            "#".repeat(bar)
+        );
+        println!("{}", line.trim_end());
+    }
+}
+
+/// Print the equity grid of every range as CSV, a block of 13 rows with the
+/// ranks along each side and the hand in the corner, blocks split by a blank line
+fn print_grid_csv(result: &EquityResult) -> Result<(), Error> {
+    let ranges = result
+        .hands
+        .iter()
+        .zip(&result.per_hand)
+        .filter(|(_, result)| dealt_combos(result).len() >= 2);
+
+    for (i, (hand, result)) in ranges.enumerate() {
+        if i > 0 {
+            println!();
+        }
+
+        let mut writer = csv::Writer::from_writer(std::io::stdout());
+        writer.write_record(std::iter::once(hand.clone()).chain((0..13).map(grid::rank_label)))?;
+        for (row, cells) in result.equity_grid().chunks(13).enumerate() {
+            let cells = cells
+                .iter()
+                .map(|cell| cell.map(|equity| equity.to_string()).unwrap_or_default());
+            writer.write_record(std::iter::once(grid::rank_label(row)).chain(cells))?;
+        }
+        writer.flush()?;
+    }
+
+    Ok(())
+}
+
+/// Cards written back out in the same form they are parsed, such as "AhKd"
+pub fn cards_string(cards: &[Card]) -> String {
+    cards.iter().map(|c| format!("{:?}", c)).collect()
+}
+
+/// A kicker as it is read out, such as "queen" in "wins with queen kicker"
+pub fn kicker_name(kicker: Rank) -> String {
+    kicker.name().to_lowercase()
+}
+
+/// Combos that reached a showdown, best first
+fn dealt_combos(result: &HandResult) -> Vec<&ComboResult> {
+    let mut combos = result
+        .combos
+        .iter()
+        .filter(|c| c.showdowns > 0)
+        .collect::<Vec<_>>();
+    combos.sort_by(|a, b| b.equity().total_cmp(&a.equity()));
+    combos
+}
+
+/// Columns of the CSV output, new columns are only ever added at the end
+const CSV_HEADER: [&str; 19] = [
+    "hand",
+    "equity",
+    "win",
+    "tie",
+    "lose",
+    "wins",
+    "ties",
+    "losses",
+    "showdowns",
+    "std_error",
+    "board",
+    "dead",
+    "game",
+    "exact",
+    "iterations",
+    "seed",
+    "hi_equity",
+    "lo_equity",
+    "scoop",
+];
+
+/// Write the results as CSV, one row per hand
+fn write_csv<W: std::io::Write>(out: W, args: &Args, result: &EquityResult) -> Result<(), Error> {
+    let mut writer = csv::Writer::from_writer(out);
+    if !args.no_header {
+        writer.write_record(CSV_HEADER)?;
+    }
+
+    let optional = |value: Option<String>| value.unwrap_or_default();
+    let (board, dead) = (cards_string(&result.board), cards_string(&result.dead));
+    let iterations = result.iterations;
+    for (hand, result) in result.hands.iter().zip(&result.per_hand) {
+        writer.write_record([
+            hand.clone(),
+            result.equity().to_string(),
+            result.win().to_string(),
+            result.tie().to_string(),
+            result.lose().to_string(),
+            result.wins.to_string(),
+            result.ties.to_string(),
+            result.losses.to_string(),
+            result.showdowns().to_string(),
+            optional((!args.exact).then(|| result.std_error().to_string())),
+            board.clone(),
+            dead.clone(),
+            args.game.to_string(),
+            args.exact.to_string(),
+            optional((!args.exact).then(|| iterations.to_string())),
+            optional(args.seed.map(|s| s.to_string())),
+            optional(result.hi_equity().map(|e| e.to_string())),
+            optional(result.lo_equity().map(|e| e.to_string())),
+            optional(result.scoop().map(|e| e.to_string())),
+        ])?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Print the share of the pot each hand wins shown down on every street
+fn print_streets(results: &[StreetResult]) {
+    println!();
+    print!("{:<8}", "Street");
+    for i in 0..results.len() {
+        print!(" {:>8}", format!("Hand {}", i + 1));
+    }
+    println!();
+
+    for (i, street) in results[0].streets.iter().enumerate() {
+        print!("{:<8}", street.name());
+        for result in results {
+            print!(
+                " {:>7.2}%",
+                result.shares[i] / result.deals.max(1) as f64 * 100.0
+            );
+        }
+        println!();
+    }
+}
+
+/// Print how often the runout meets each condition and every hand's equity
+/// when it does and when it does not
+fn print_conditions(conditions: &[RunoutFilter], results: &[ConditionResult]) {
+    let percent = |equity: Option<f64>| match equity {
+        Some(equity) => format!("{:.2}%", equity * 100.0),
+        None => "-".to_string(),
+    };
+
+    for (i, condition) in conditions.iter().enumerate() {
+        println!();
+        println!(
+            "Runouts with {}: {:.2}%",
+            condition,
+            results[0].frequency(i) * 100.0
+        );
+        println!("{:<8} {:>8} {:>8}", "", "Met", "Missed");
+        for (hand, result) in results.iter().enumerate() {
+            println!(
+                "{:<8} {:>8} {:>8}",
+                format!("Hand {}", hand + 1),
+                percent(result.equity_met(i)),
+                percent(result.equity_missed(i))
+            );
+        }
+    }
+}
+
+/// Print how often the pot is decided by a kicker and how often every hand
+/// wins or loses on one or plays the board
+fn print_kickers(results: &[KickerResult]) {
+    println!();
+    println!(
+        "Pots decided by a kicker: {:.2}%",
+        kicker_pot_frequency(results) * 100.0
+    );
+    println!(
+        "{:<8} {:>12} {:>12} {:>12}",
+        "", "Kicker wins", "Kicker loses", "Plays board"
+    );
+    for (hand, result) in results.iter().enumerate() {
+        println!(
+            "{:<8} {:>11.2}% {:>11.2}% {:>11.2}%",
+            format!("Hand {}", hand + 1),
+            result.kicker_win_frequency() * 100.0,
+            result.kicker_loss_frequency() * 100.0,
+            result.board_frequency() * 100.0
+        );
+    }
+}
+
+/// Print every example showdown, each hand's hole cards, its best five
+/// cards in the order the hand is read, what they make and the share of the pot it won
+fn print_examples(examples: &[ExampleShowdown]) {
+    for (i, example) in examples.iter().enumerate() {
+        println!();
+        println!("Example {}: {}", i + 1, cards_string(&example.board));
+        for (player, hand) in example.hands.iter().enumerate() {
+            let best = hand.best.map(|card| card.to_string()).join(" ");
+            let low = match hand.low {
+                Some(low) => format!("  low {}", low),
+                None => String::new(),
+            };
+            let result = match (example.share(player), example.kicker) {
+                (1.0, Some(kicker)) => format!("  wins with {} kicker", kicker_name(kicker)),
+                (1.0, None) => "  wins".to_string(),
+                (share, _) if share > 0.0 => format!("  wins {:.0}%", share * 100.0),
+                _ => String::new(),
+            };
+            let board = match hand.plays_the_board {
+                true => "  plays the board",
+                false => "",
+            };
+            println!(
+                "{:<8} {:<10} {}  {}{}{}{}",
+                format!("Hand {}", player + 1),
+                cards_string(&hand.hole),
+                best,
+                hand.rank,
+                low,
+                board,
+                result
+            );
+        }
+    }
+}
+
+/// Print the heads up equity of every hand against every other, the row
+/// hand against the column hand
+fn print_pairwise(results: &[PairwiseResult]) {
+    println!();
+    print!("{:<8}", "Heads up");
+    for i in 0..results.len() {
+        print!(" {:>8}", format!("Hand {}", i + 1));
+    }
+    println!();
+
+    for result in results {
+        print!("{:<8}", format!("Hand {}", result.hand + 1));
+        for other in 0..results.len() {
+            match result.equity_vs(other) {
+                Some(equity) => print!(" {:>7.2}%", equity * 100.0),
+                None => print!(" {:>8}", "-"),
+            }
+        }
+        println!();
+    }
+}
+
+/// Print every pot with the hands that can win it, and the chips each
+/// hand can expect back
+fn print_side_pots(pots: &[Pot], results: &[SidePotResult]) {
+    println!();
+    println!("{:<12} {:>10}  Hands", "Pot", "Chips");
+    for (i, pot) in pots.iter().enumerate() {
+        let name = match (i, pot.players.len()) {
+            (_, 1) => "Returned".to_string(),
+            (0, _) => "Main pot".to_string(),
+            (i, _) => format!("Side pot {}", i),
+        };
+        let hands = pot
+            .players
+            .iter()
+            .map(|player| (player + 1).to_string())
+            .collect::<Vec<_>>();
+        println!("{:<12} {:>10.0}  {}", name, pot.amount, hands.join(" "));
+    }
+
+    println!();
+    println!("{:<8} {:>10} {:>10} {:>10}", "", "Stack", "Expected", "EV");
+    for (i, result) in results.iter().enumerate() {
+        println!(
+            "{:<8} {:>10.0} {:>10.2} {:>+10.2}",
+            format!("Hand {}", i + 1),
+            result.stack,
+            result.expected_chips(),
+            result.ev()
+        );
+    }
+}
+
+/// Print the results as a single JSON document
+/// Results of running it more than once, of every street, of every heads up
+/// matchup, of the runout conditions, of the kickers and of the side pots
+/// are added to each hand, and the example showdowns after them
+#[allow(clippy::too_many_arguments)]
+fn print_json(
+    args: &Args,
+    result: &EquityResult,
+    run_it: Option<&[RunItResult]>,
+    streets: Option<&[StreetResult]>,
+    pairwise: Option<&[PairwiseResult]>,
+    conditions: Option<&[ConditionResult]>,
+    kickers: Option<&[KickerResult]>,
+    stacks: Option<(&[Pot], &[SidePotResult])>,
+    examples: Option<&[ExampleShowdown]>,
+) {
+    let mut output = json_output(args, result);
+    if let Some(examples) = examples {
+        output["examples"] = examples
+            .iter()
+            .map(|example| {
+                let hands = example
+                    .hands
+                    .iter()
+                    .enumerate()
+                    .map(|(player, hand)| {
+                        json!({
+                            "hole": cards_string(&hand.hole),
+                            "best": cards_string(&hand.best),
+                            "hand": hand.rank.to_string(),
+                            "low": hand.low.map(|low| low.to_string()),
+                            "plays_the_board": hand.plays_the_board,
+                            "share": example.share(player),
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                json!({
+                    "board": cards_string(&example.board),
+                    "hands": hands,
+                    "kicker": example.kicker.map(|kicker| kicker.name()),
+                })
+            })
+            .collect();
+    }
+    if let Some((pots, stacks)) = stacks {
+        output["pots"] = pots
+            .iter()
+            .map(|pot| json!({ "amount": pot.amount, "hands": pot.players }))
+            .collect();
+        for (hand, result) in stacks.iter().enumerate() {
+            output["hands"][hand]["stack"] = json!(result.stack);
+            output["hands"][hand]["expected_chips"] = json!(result.expected_chips());
+            output["hands"][hand]["ev"] = json!(result.ev());
+        }
+    }
+    for (hand, result) in pairwise.into_iter().flatten().enumerate() {
+        output["hands"][hand]["pairwise"] = (0..result.shares.len())
+            .map(|other| json!(result.equity_vs(other)))
+            .collect();
+    }
+    if let Some(conditions) = conditions {
+        output["conditions"] = args
+            .condition
+            .iter()
+            .enumerate()
+            .map(|(i, condition)| {
+                json!({
+                    "condition": condition.to_string(),
+                    "frequency": conditions[0].frequency(i),
+                })
+            })
+            .collect();
+        for (hand, result) in conditions.iter().enumerate() {
+            output["hands"][hand]["conditions"] = (0..args.condition.len())
+                .map(|i| json!({ "met": result.equity_met(i), "missed": result.equity_missed(i) }))
+                .collect();
+        }
+    }
+    if let Some(kickers) = kickers {
+        output["kicker_pots"] = json!(kicker_pot_frequency(kickers));
+        for (hand, result) in kickers.iter().enumerate() {
+            output["hands"][hand]["kickers"] = json!({
+                "wins": result.kicker_win_frequency(),
+                "losses": result.kicker_loss_frequency(),
+                "plays_the_board": result.board_frequency(),
+            });
+        }
+    }
+    for (hand, result) in streets.into_iter().flatten().enumerate() {
+        output["hands"][hand]["streets"] = result
+            .streets
+            .iter()
+            .map(|street| (street.name().to_lowercase(), json!(result.equity(*street))))
+            .collect::<serde_json::Map<_, _>>()
+            .into();
+    }
+    if let Some(run_it) = run_it {
+        output["runs"] = json!(run_it[0].runs);
+        for (hand, result) in run_it.iter().enumerate() {
+            output["hands"][hand]["run_it"] = json!({
+                "deals": result.deals,
+                "equity": result.equity(),
+                "std_dev": result.std_dev(),
+                "single_run_std_dev": result.single_run_std_dev(),
+                "runs_won": result.runs_won,
+            });
+        }
+    }
+    println!("{}", output);
+}
+
+/// The results and the settings they were run with as a JSON document
+pub fn json_output(args: &Args, output: &EquityResult) -> Value {
+    let hands = output
+        .hands
+        .iter()
+        .zip(&output.per_hand)
+        .map(|(hand, result)| {
+            let combos = args.combos.then(|| {
+                dealt_combos(result)
+                    .iter()
+                    .map(|combo| {
+                        json!({
+                            "cards": cards_string(&combo.cards),
+                            "equity": combo.equity(),
+                            "showdowns": combo.showdowns,
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            });
+
+            let categories = args.categories.then(|| {
+                HandCategory::ALL
+                    .iter()
+                    .map(|&category| {
+                        let counts = result.category(category);
+                        json!({
+                            "category": category.name(),
+                            "made": counts.made,
+                            "wins": counts.wins,
+                            "ties": counts.ties,
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            });
+
+            let histogram = args.histogram.then(|| {
+                let buckets = result.equity_histogram(args.buckets as usize);
+                let step = 1.0 / buckets.len() as f64;
+                buckets
+                    .iter()
+                    .enumerate()
+                    .map(|(i, share)| {
+                        json!({ "from": i as f64 * step, "to": (i + 1) as f64 * step, "share": share })
+                    })
+                    .collect::<Vec<_>>()
+            });
+
+            let grid = args.grid.then(|| {
+                result
+                    .equity_grid()
+                    .chunks(13)
+                    .map(|row| row.to_vec())
+                    .collect::<Vec<_>>()
+            });
+
+            json!({
+                "hand": hand,
+                "equity": result.equity(),
+                "std_error": (!args.exact).then(|| result.std_error()),
+                "confidence_interval": (!args.exact).then(|| {
+                    let (low, high) = result.confidence_interval();
+                    [low, high]
+                }),
+                "win": result.win(),
+                "tie": result.tie(),
+                "lose": result.lose(),
+                "wins": result.wins,
+                "ties": result.ties,
+                "losses": result.losses,
+                "tie_splits": result.tie_splits,
+                "hi_equity": result.hi_equity(),
+                "lo_equity": result.lo_equity(),
+                "scoop": result.scoop(),
+                "combos": combos,
+                "categories": categories,
+                "histogram": histogram,
+                "grid": grid,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "board": cards_string(&output.board),
+        "dead": cards_string(&output.dead),
+        "game": args.game.to_string(),
+        "exact": args.exact,
+        "iterations": if args.exact { None } else { Some(output.iterations) },
+        "target_error": args.target_error,
+        "showdowns": output.per_hand[0].showdowns(),
+        "threads": if args.exact { 1 } else { args.threads },
+        "seed": args.seed,
+        "version": env!("CARGO_PKG_VERSION"),
+        "hands": hands,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    #[command(no_binary_name = true)]
+    struct Cli {
+        #[command(flatten)]
+        args: Args,
+    }
+
+    fn key(argv: &str) -> Option<String> {
+        let args = Cli::parse_from(argv.split_whitespace()).args;
+        let spot = parse_spot(&args).unwrap();
+        cache_key(&args, &spot)
+    }
+
+    #[test]
+    fn test_vs_random() {
+        let args = Cli::parse_from(["AKs", "--vs-random", "2"]).args;
+        assert_eq!(args.hand_names(), ["AKs", "random", "random"]);
+        assert_eq!(parse_spot(&args).unwrap().hands[1].len(), 1326);
+
+        assert_eq!(
+            key("AhKh --vs-random 2 -i 5000").as_deref(),
+            Some("AKs vs 2 random, 5000 iterations")
+        );
+        assert_eq!(key("AhKh --vs-random 2 --no-cache"), None);
+        assert_eq!(key("AhKh --vs-random 2 --seed 3"), None);
+        assert_eq!(key("AhKh --vs-random 2 --board 2c3c4c"), None);
+        assert_eq!(key("AK --vs-random 2"), None);
+        assert_eq!(key("AhKh QQ --vs-random 2"), None);
+        assert_eq!(key("AhKh QQ"), None);
+    }
+
+    #[test]
+    fn test_range_file() {
+        let path = std::env::temp_dir().join(format!("villain-{}.txt", std::process::id()));
+        fs::write(&path, "[75.0]QQ+,AKs[/75.0]\nJJ\n").unwrap();
+        let path = path.to_str().unwrap();
+
+        let args = Cli::parse_from(["AhAs", "--range-file", path, "--vs-random", "1"]).args;
+        assert_eq!(args.hand_names(), ["AhAs", path, "random"]);
+        let spot = parse_spot(&args).unwrap();
+        assert_eq!(spot.hands[1].len(), 18 + 4 + 6);
+        assert!(spot.hands[1].is_weighted());
+        assert_eq!(cache_key(&args, &spot), None);
+
+        fs::remove_file(path).unwrap();
+        assert!(parse_spot(&args).is_err());
+    }
+
+    #[test]
+    fn test_stud_hands() {
+        let args = Cli::parse_from(["-g", "stud", "AsAhAd", "7c8c9cTc", "random"]).args;
+        let spot = parse_spot(&args).unwrap();
+        assert_eq!(spot.hands[1].combos()[0].len(), 4);
+
+        let board = Cli::parse_from(["-g", "stud", "AsAhAd", "7c8c9c", "-b", "2h3h4h"]).args;
+        assert!(matches!(
+            parse_spot(&board),
+            Err(Error::Input { source, .. }) if matches!(*source, Error::NoBoard(Game::Stud))
+        ));
+
+        let eight = Cli::parse_from(["-g", "stud", "AsAhAdKc2c3c4c5c", "7c8c9c"]).args;
+        assert!(parse_spot(&eight).is_err());
+        let players = ["-g", "stud", "--vs-random", "8", "AsAh"];
+        assert!(matches!(
+            parse_spot(&Cli::parse_from(players).args),
+            Err(Error::DeckTooSmall { players: 9, .. })
+        ));
+    }
+
+    #[test]
+    fn test_courchevel_board() {
+        let hands = ["AsAhKdQc2c", "7c8c9cTdJd"];
+        let args = Cli::parse_from([&["-g", "courchevel", "-b", "Kh"][..], &hands].concat()).args;
+        assert_eq!(parse_spot(&args).unwrap().board.len(), 1);
+
+        // Only Courchevel exposes a single card
+        let args = Cli::parse_from([&["-g", "omaha5", "-b", "Kh"][..], &hands].concat()).args;
+        assert!(parse_spot(&args).is_err());
+        let four = Cli::parse_from(["-g", "omaha5", "AsAhKdQc", "7c8c9cTd"]).args;
+        assert!(parse_spot(&four).is_err());
+    }
+
+    #[test]
+    fn test_board_streets() {
+        let board = |argv: &[&str]| parse_board(&Cli::parse_from(argv).args);
+        let streets = board(&["AsKs", "QQ", "--flop", "2c3c4c", "--turn", "5d"]).unwrap();
+        assert_eq!(streets.len(), 2);
+        assert_eq!(streets[1].0, "--turn");
+
+        // Each street needs the one before it
+        assert!(matches!(
+            board(&["AsKs", "QQ", "--turn", "5d"]),
+            Err(Error::MissingStreet { street, before }) if street == "--turn" && before == "--flop"
+        ));
+        assert!(matches!(
+            board(&["AsKs", "QQ", "--flop", "2c3c4c", "--river", "6d"]),
+            Err(Error::MissingStreet { street, before }) if street == "--river" && before == "--turn"
+        ));
+
+        // And the number of cards of that street
+        assert!(matches!(
+            board(&["AsKs", "QQ", "--flop", "2c3c"]),
+            Err(Error::Input { source, .. })
+                if matches!(*source, Error::WrongStreetSize { expected: 3, found: 2, .. })
+        ));
+        assert!(matches!(
+            board(&["AsKs", "QQ", "--flop", "2c3c4c", "--turn", "5d6d"]),
+            Err(Error::Input { source, .. })
+                if matches!(*source, Error::WrongStreetSize { expected: 1, found: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_refine() {
+        assert_eq!(parse_duration("2s"), Ok(Duration::from_secs(2)));
+        assert_eq!(parse_duration("250ms"), Ok(Duration::from_millis(250)));
+        assert_eq!(parse_duration("1.5"), Ok(Duration::from_millis(1500)));
+        assert!(parse_duration("0s").is_err());
+        assert!(parse_duration("soon").is_err());
+
+        let refine = |argv: &[&str]| Cli::parse_from(argv).args.refine;
+        assert_eq!(refine(&["AsKs", "QQ"]), None);
+        assert_eq!(
+            refine(&["AsKs", "QQ", "--refine"]),
+            Some(Refine::Every(Duration::from_secs(1)))
+        );
+        assert_eq!(
+            refine(&["--refine=50000", "AsKs", "QQ"]),
+            Some(Refine::Iterations(50_000))
+        );
+        assert!(Cli::try_parse_from(["--refine", "--exact", "AsKs", "QQ"]).is_err());
+
+        // The batches add up to every iteration
+        let args =
+            Cli::parse_from(["AsKs", "QQ", "-q", "-s", "7", "--refine=1000", "-i", "5000"]).args;
+        let results = run_spot(&args, parse_spot(&args).unwrap());
+        assert_eq!(results[0].showdowns(), 5000);
+    }
+
+    #[test]
+    fn test_max_time() {
+        let args = Cli::parse_from(["AsKs", "QQ", "--max-time", "100ms", "-q"]).args;
+        assert_eq!(args.max_time, Some(Duration::from_millis(100)));
+        assert_eq!(args.iterations(), u32::MAX);
+        assert!(Cli::try_parse_from(["--max-time", "1s", "--exact", "AsKs", "QQ"]).is_err());
+
+        // Stops at the deadline, or sooner with a set number of iterations
+        let results = run_spot(&args, parse_spot(&args).unwrap());
+        assert!(results[0].showdowns() > 0);
+        let args = Cli::parse_from(["AsKs", "QQ", "--max-time", "10s", "-i", "500", "-q"]).args;
+        let results = run_spot(&args, parse_spot(&args).unwrap());
+        assert_eq!(results[0].showdowns(), 500);
+    }
+
+    #[test]
+    fn test_run_it_needs_enough_cards() {
+        let args = Cli::parse_from(["AsKs", "QhQd", "--run-it", "10", "-q"]).args;
+        let error = run(&args).unwrap_err();
+        assert!(matches!(error, Error::TooManyRuns { runs: 10, most: 9 }));
+
+        assert_eq!(key("AhKh --vs-random 2 --run-it 2"), None);
+        assert!(Cli::try_parse_from(["AsKs", "QhQd", "--run-it", "1"]).is_err());
+    }
+
+    #[test]
+    fn test_streets_need_a_street_to_come() {
+        let args = Cli::parse_from(["AsKs", "QhQd", "-b", "2c3c4c5d6d", "--streets"]).args;
+        let error = run(&args).unwrap_err();
+        assert!(error.to_string().ends_with("there are no streets to come"));
+
+        assert!(Cli::try_parse_from(["AsKs", "QhQd", "--streets", "--exact"]).is_err());
+        assert!(Cli::try_parse_from(["AsKs", "QhQd", "--streets", "--run-it", "2"]).is_err());
+        assert!(Cli::try_parse_from(["AsKs", "QhQd", "--streets", "--sampler", "sobol"]).is_err());
+        assert!(Cli::try_parse_from(["AsKs", "QhQd", "--streets", "--refine"]).is_err());
+    }
+
+    #[test]
+    fn test_pairwise_needs_a_board_game() {
+        let args = Cli::parse_from(["AsKs", "QhQd", "7c7d", "-g", "stud", "--pairwise"]).args;
+        let error = run(&args).unwrap_err();
+        assert!(error
+            .to_string()
+            .ends_with("stud is played without a board"));
+
+        assert!(Cli::try_parse_from(["AsKs", "QhQd", "7c7d", "--pairwise", "--exact"]).is_err());
+        assert!(Cli::try_parse_from(["AsKs", "QhQd", "--pairwise", "--streets"]).is_err());
+        assert!(Cli::try_parse_from(["AsKs", "QhQd", "--pairwise", "--sampler", "sobol"]).is_err());
+        assert!(Cli::try_parse_from(["AsKs", "QhQd", "--pairwise", "--refine"]).is_err());
+    }
+
+    #[test]
+    fn test_conditions_need_a_board_game() {
+        let args =
+            Cli::parse_from(["AsKs", "QhQd", "7c7d", "-g", "stud", "--condition", "flush"]).args;
+        let error = run(&args).unwrap_err();
+        assert!(error
+            .to_string()
+            .ends_with("stud is played without a board"));
+
+        let args = Cli::parse_from([
+            "AsKs",
+            "QhQd",
+            "--condition",
+            "flush",
+            "--condition",
+            "turn:A",
+        ])
+        .args;
+        assert_eq!(args.condition.len(), 2);
+        assert!(Cli::try_parse_from(["AsKs", "QhQd", "--condition", "flush", "--exact"]).is_err());
+        assert!(Cli::try_parse_from(["AsKs", "QhQd", "--condition", "nothing"]).is_err());
+        assert!(Cli::try_parse_from([
+            "AsKs",
+            "QhQd",
+            "--condition",
+            "flush",
+            "--sampler",
+            "sobol"
+        ])
+        .is_err());
+        assert!(Cli::try_parse_from(["AsKs", "QhQd", "--condition", "flush", "--refine"]).is_err());
+    }
+
+    #[test]
+    fn test_kickers_need_a_board_game() {
+        let args = Cli::parse_from(["AsKs", "QhQd", "-g", "razz", "--kickers"]).args;
+        let error = run(&args).unwrap_err();
+        assert!(error
+            .to_string()
+            .ends_with("razz is played without a board"));
+
+        assert!(Cli::try_parse_from(["AsKs", "QhQd", "--kickers", "--exact"]).is_err());
+        assert!(Cli::try_parse_from(["AsKs", "QhQd", "--kickers", "--sampler", "sobol"]).is_err());
+        assert!(Cli::try_parse_from(["AsKs", "QhQd", "--kickers", "--refine"]).is_err());
+        assert!(
+            Cli::try_parse_from(["AsKs", "QhQd", "--kickers", "--condition", "flush"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_explain_needs_a_board_game() {
+        let args = Cli::parse_from(["AsKs", "QhQd", "-g", "stud", "--explain", "3"]).args;
+        assert!(run(&args).is_err());
+
+        let args = Cli::parse_from(["AsKs", "QhQd", "--explain", "3"]).args;
+        assert_eq!(args.explain, Some(3));
+        assert!(Cli::try_parse_from(["AsKs", "QhQd", "--explain", "many"]).is_err());
+    }
+
+    #[test]
+    fn test_stacks_for_every_hand() {
+        let args = Cli::parse_from(["AsKs", "QhQd", "7c7d", "--stacks", "2000,1500"]).args;
+        let error = run(&args).unwrap_err();
+        assert!(error.to_string().ends_with("2 stacks given for 3 hands"));
+
+        let args = Cli::parse_from(["AsKs", "QhQd", "--stacks", "2000,0"]).args;
+        assert!(run(&args).unwrap_err().to_string().ends_with("got 0"));
+        assert!(Cli::try_parse_from(["AsKs", "QhQd", "--stacks", "1,2", "--exact"]).is_err());
+        assert!(
+            Cli::try_parse_from(["AsKs", "QhQd", "--stacks", "1,2", "--sampler", "sobol"]).is_err()
+        );
+        assert!(Cli::try_parse_from(["AsKs", "QhQd", "--stacks", "1,2", "--refine"]).is_err());
+    }
+
+    #[test]
+    fn test_json_output() {
+        let args = Cli::parse_from(["AsAh", "KdKc", "-b", "Ac7d2h9s3c", "--exact", "--json"]).args;
+        assert_eq!(args.format(), Format::Json);
+        let spot = parse_spot(&args).unwrap();
+        let (board, dead) = (spot.board.clone(), spot.dead.clone());
+        let result = EquityResult {
+            hands: args.hand_names(),
+            board,
+            dead,
+            iterations: 1,
+            per_hand: calculate(&args, spot),
+        };
+
+        let output = json_output(&args, &result);
+        assert_eq!(output["board"], "Ac7d2h9s3c");
+        assert_eq!(output["exact"], true);
+        assert!(output["iterations"].is_null());
+        assert_eq!(output["showdowns"], 1);
+        assert_eq!(output["hands"][0]["hand"], "AsAh");
+        assert_eq!(output["hands"][0]["equity"], 1.0);
+        assert_eq!(output["hands"][1]["losses"], 1);
+        assert!(output["hands"][1]["std_error"].is_null());
+        assert!(output["hands"][1]["combos"].is_null());
+    }
+
+    #[test]
+    fn test_csv_rows() {
+        let csv = |argv: &[&str]| {
+            let args = Cli::parse_from(argv).args;
+            let spot = parse_spot(&args).unwrap();
+            let result = EquityResult {
+                hands: args.hand_names(),
+                board: spot.board.clone(),
+                dead: spot.dead.clone(),
+                iterations: 1,
+                per_hand: calculate(&args, spot),
+            };
+            let mut out = vec![];
+            write_csv(&mut out, &args, &result).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        let output = csv(&["AsAh", "KdKc", "-b", "Ac7d2h9s3c", "--exact"]);
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], CSV_HEADER.join(","));
+        assert!(lines[1].starts_with("AsAh,1,1,0,0,1,0,0,1,,Ac7d2h9s3c,,holdem,true,"));
+        assert!(lines[2].starts_with("KdKc,0,0,0,1,0,0,1,1,"));
+
+        let rows = csv(&["AsAh", "KdKc", "-b", "Ac7d2h9s3c", "--exact", "--no-header"]);
+        assert_eq!(rows.lines().count(), 2);
+        assert!(rows.starts_with("AsAh,"));
+    }
+}
@@ -0,0 +1,188 @@
+//! `ev` subcommand, the expected value of folding, calling or moving all in
+
+use equity_cli::{call_ev, required_equity, shove_ev, Bounty, Error};
+
+use super::equity::{self, Format};
+
+/// Arguments of the ev subcommand
+#[derive(clap::Args, Debug)]
+#[group(skip)]
+pub struct Args {
+    /// Pot before the bet being faced
+    #[arg(long)]
+    pot: f64,
+
+    /// Amount to call, the same as the bet being faced
+    #[arg(long, alias = "call")]
+    to_call: f64,
+
+    /// Stack behind before calling, to compare moving all in
+    #[arg(long)]
+    stack: Option<f64>,
+
+    /// Chance the bettor folds to an all in, as a percentage or a fraction
+    #[arg(long, default_value = "0", value_parser = parse_chance)]
+    fold_equity: f64,
+
+    /// Bounty on the bettor in a knockout tournament, in the same units as
+    /// the pot, won by knocking them out
+    /// In a progressive knockout half is paid at once and half goes onto
+    /// your own bounty, so count what that half is worth to you
+    #[arg(long)]
+    bounty: Option<f64>,
+
+    /// Bettor's chips in the hand, their bet and everything behind it, to
+    /// tell whether a call or a shove knocks them out
+    /// The bettor is taken to be all in when it is not given
+    #[arg(long, requires = "bounty")]
+    villain_stack: Option<f64>,
+
+    /// Size of the big blind, to show the values in big blinds
+    #[arg(long)]
+    big_blind: Option<f64>,
+
+    /// Equity of the hand, as a percentage or a fraction, instead of
+    /// working it out from hands
+    #[arg(long, value_parser = parse_chance)]
+    equity: Option<f64>,
+
+    /// The first hand's equity is used when hands are given
+    #[command(flatten, next_help_heading = "Equity")]
+    hands: equity::Args,
+}
+
+/// Parse a chance such as "35%" or "0.35"
+pub(super) fn parse_chance(s: &str) -> Result<f64, String> {
+    let (number, scale) = match s.strip_suffix('%') {
+        Some(percent) => (percent, 100.0),
+        None => (s, 1.0),
+    };
+
+    match number.trim().parse::<f64>() {
+        Ok(value) if (0.0..=1.0).contains(&(value / scale)) => Ok(value / scale),
+        _ => Err(format!(
+            "expected a percentage such as 35% or a fraction such as 0.35, got \"{}\"",
+            s
+        )),
+    }
+}
+
+/// Print the value of every option and the best one
+pub fn run(args: &Args) -> Result<(), Error> {
+    args.hands.check_equity_only(&[Format::Text])?;
+    let equity = match args.equity {
+        Some(equity) => equity,
+        None => {
+            let spot = equity::parse_spot(&args.hands)?;
+            equity::calculate(&args.hands, spot)[0].equity()
+        }
+    };
+
+    let bounty = args
+        .bounty
+        .map(|value| Bounty::new(value, args.villain_stack.unwrap_or(args.to_call)))
+        .transpose()?;
+    let call_bounty = match bounty {
+        Some(bounty) => bounty.call_ev(args.to_call, equity)?,
+        None => 0.0,
+    };
+    let shove_bounty = match (bounty, args.stack) {
+        (Some(bounty), Some(stack)) => bounty.shove_ev(stack, equity, args.fold_equity)?,
+        _ => 0.0,
+    };
+
+    let call = call_ev(args.pot, args.to_call, equity)? + call_bounty;
+    // No more than the bettor has can be won when they call the shove, and
+    // there is nothing to shove over a bettor who is already all in
+    let villain_stack = bounty.map_or(f64::INFINITY, |bounty| bounty.stack);
+    let effective = args
+        .stack
+        .filter(|_| villain_stack > args.to_call)
+        .map(|stack| stack.min(villain_stack));
+    let shove = effective
+        .map(|stack| shove_ev(args.pot, args.to_call, stack, equity, args.fold_equity))
+        .transpose()?
+        .map(|shove| shove + shove_bounty);
+
+    // Values in big blinds when one is given
+    let (scale, unit) = match args.big_blind {
+        Some(bb) if bb > 0.0 => (bb, " bb"),
+        Some(bb) => return Err(Error::InvalidAmount(bb)),
+        None => (1.0, ""),
+    };
+    let value = |ev: f64| format!("{:+.2}{}", ev / scale, unit);
+
+    println!("Equity: {:.2}%", equity * 100.0);
+    // A bounty won by calling pays like more chips in the pot
+    let bounty_pot = match bounty {
+        Some(bounty) if bounty.covered_by(args.to_call) => bounty.value,
+        _ => 0.0,
+    };
+    println!(
+        "Needed to call: {:.2}%",
+        required_equity(args.pot + bounty_pot, args.to_call)? * 100.0
+    );
+    println!();
+    let bounty_note = |ev: f64| bounty.map(|_| format!("{} bounty", value(ev)));
+    println!("{:<7} {:>12}", "Fold", value(0.0));
+    match bounty_note(call_bounty) {
+        Some(note) => println!("{:<7} {:>12}  ({})", "Call", value(call), note),
+        None => println!("{:<7} {:>12}", "Call", value(call)),
+    }
+    if let Some(shove) = shove {
+        let mut notes = format!("{:.0}% fold equity", args.fold_equity * 100.0);
+        if let Some(note) = bounty_note(shove_bounty) {
+            notes = format!("{}, {}", notes, note);
+        }
+        println!("{:<7} {:>12}  ({})", "Shove", value(shove), notes);
+    }
+
+    let options = [("fold", Some(0.0)), ("call", Some(call)), ("shove", shove)];
+    let best = options
+        .iter()
+        .filter_map(|(name, ev)| Some((name, (*ev)?)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .unwrap();
+    println!();
+    println!("Best: {}", best.0);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    #[command(no_binary_name = true)]
+    struct Cli {
+        #[command(flatten)]
+        args: Args,
+    }
+
+    #[test]
+    fn test_parse_chance() {
+        assert_eq!(parse_chance("35%"), Ok(0.35));
+        assert_eq!(parse_chance("0.2"), Ok(0.2));
+        assert_eq!(parse_chance("0"), Ok(0.0));
+        assert!(parse_chance("120%").is_err());
+        assert!(parse_chance("-0.1").is_err());
+    }
+
+    #[test]
+    fn test_rejects_unused_equity_flags() {
+        let run = |flags: &[&str]| {
+            let argv = [&["--pot", "100", "--to-call", "50", "AKs", "QQ"], flags].concat();
+            run(&Cli::parse_from(argv).args)
+        };
+        assert!(matches!(
+            run(&["--stacks", "100,200"]),
+            Err(Error::UnusedFlag("--stacks"))
+        ));
+        assert!(matches!(
+            run(&["--histogram"]),
+            Err(Error::UnusedFlag("--histogram"))
+        ));
+    }
+}
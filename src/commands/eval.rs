@@ -0,0 +1,65 @@
+//! `eval` subcommand, the best five card hand out of five to seven cards
+
+use equity_cli::{
+    cards_from_str, cards_to_hand, check_duplicate_cards, get_best_hand_cards, Card, Error,
+};
+
+use super::equity::kicker_name;
+
+/// Arguments of the eval subcommand
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Cards to evaluate, five to seven in total
+    /// They can be split over several arguments
+    /// Example: AhKhQhJhTh 2c
+    #[arg(required = true)]
+    cards: Vec<String>,
+
+    /// Board cards played with the cards, to tell when the hand is
+    /// simply the board
+    /// Example: AsKdQcJhTs
+    #[arg(short, long, default_value = "")]
+    board: String,
+}
+
+/// Print the best hand that can be made from the cards
+pub fn run(args: &Args) -> Result<(), Error> {
+    let value = args.cards.concat();
+    let hole = cards_from_str(&value).map_err(|e| Error::from(e).input("cards", &value))?;
+    let board =
+        cards_from_str(&args.board).map_err(|e| Error::from(e).input("--board", &args.board))?;
+    let cards = [hole.as_slice(), &board].concat();
+
+    if !(5..=7).contains(&cards.len()) {
+        return Err(Error::InvalidHandSize(cards.len()).input("cards", &value));
+    }
+    check_duplicate_cards(&[
+        ("cards".to_string(), hole.clone()),
+        ("--board".to_string(), board.clone()),
+    ])?;
+
+    let (best, rank) = get_best_hand_cards(&cards);
+    let unused = cards
+        .iter()
+        .filter(|card| !best.contains(card))
+        .collect::<Vec<_>>();
+
+    println!("Best hand: {:?}", best);
+    if !unused.is_empty() {
+        println!("Unused: {:?}", unused);
+    }
+    println!("Category: {}", rank.category());
+    println!("{}", rank);
+    let kickers = rank.kickers();
+    if !kickers.is_empty() {
+        let names = kickers.into_iter().map(kicker_name).collect::<Vec<_>>();
+        println!("Kickers: {}", names.join(", "));
+    }
+    if let Ok(board) = <[Card; 5]>::try_from(board) {
+        if cards_to_hand(board) == rank {
+            println!("Plays the board");
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,297 @@
+//! `flops` subcommand, the equity of ranges on every strategically distinct
+//! flop together with aggregates by texture, like a solver's flop report
+
+use std::cmp::Reverse;
+
+use equity_cli::{
+    cards_from_str, default_threads, distinct_flops, run_flop_report, Error, EvaluatorKind, Flop,
+    FlopReport, Game, HandCategory, Pairing, Range, Rank, Sampler, SimulationSettings, Suitedness,
+    PROGRESS_INTERVAL,
+};
+use indicatif::{ProgressBar, ProgressStyle};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use serde_json::json;
+
+use super::equity::{cards_string, catch_interrupt, check_known_cards};
+
+/// Flop textures that can be picked out with --texture
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Texture {
+    Rainbow,
+    TwoTone,
+    Monotone,
+    Unpaired,
+    Paired,
+    Trips,
+}
+
+/// Arguments of the flops subcommand
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Hands or ranges to deal on every flop, such as "QQ+,AKs" "random"
+    #[arg(required = true, num_args = 2..)]
+    hands: Vec<String>,
+
+    /// Iterations on each flop
+    #[arg(short, long, default_value_t = 10_000)]
+    iterations: u32,
+
+    /// Only play flops with these textures, all of them when given more than once
+    #[arg(long, value_enum)]
+    texture: Vec<Texture>,
+
+    /// Play this many flops picked at random instead of all of them
+    #[arg(long)]
+    sample: Option<usize>,
+
+    /// Dead cards that have been exposed or folded
+    #[arg(short, long, default_value = "")]
+    dead: String,
+
+    /// Game to deal: holdem, omaha or omaha8
+    #[arg(short, long, default_value_t = Game::Holdem)]
+    game: Game,
+
+    /// Order the flops by the first hand's equity, best first
+    #[arg(long)]
+    sort: bool,
+
+    /// Number of worker threads
+    #[arg(short, long, default_value_t = default_threads())]
+    threads: usize,
+
+    /// Seed for the random number generator
+    #[arg(short, long)]
+    seed: Option<u64>,
+
+    /// Hide the progress bar
+    #[arg(short, long)]
+    quiet: bool,
+
+    /// Print every flop and the aggregates as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+///////////////////////////////////////////////
+/// Texture Implementations
+///////////////////////////////////////////////
+impl Texture {
+    /// Whether the flop has this texture
+    fn matches(self, flop: &Flop) -> bool {
+        match self {
+            Texture::Rainbow => flop.suitedness() == Suitedness::Rainbow,
+            Texture::TwoTone => flop.suitedness() == Suitedness::TwoTone,
+            Texture::Monotone => flop.suitedness() == Suitedness::Monotone,
+            Texture::Unpaired => flop.pairing() == Pairing::Unpaired,
+            Texture::Paired => flop.pairing() == Pairing::Paired,
+            Texture::Trips => flop.pairing() == Pairing::Trips,
+        }
+    }
+}
+
+/// Play the ranges on every flop and print each flop and the aggregates
+pub fn run(args: &Args) -> Result<(), Error> {
+    if !args.game.has_board() {
+        return Err(Error::NoBoard(args.game).input("--game", &args.game.to_string()));
+    }
+    let ranges = args
+        .hands
+        .iter()
+        .enumerate()
+        .map(|(i, hand)| {
+            let name = format!("hand {}", i + 1);
+            let range = Range::parse(hand).map_err(|e| Error::from(e).input(&name, hand))?;
+            for combo in range.combos() {
+                args.game
+                    .check_hole_cards(combo.len())
+                    .map_err(|e| e.input(&name, hand))?;
+            }
+            Ok(range)
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    let dead =
+        cards_from_str(&args.dead).map_err(|e| Error::from(e).input("--dead", &args.dead))?;
+    check_known_cards(&ranges, vec![("--dead".to_string(), dead.clone())])?;
+
+    let mut rng = match args.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let mut flops = distinct_flops(&ranges, &dead)
+        .into_iter()
+        .filter(|flop| args.texture.iter().all(|texture| texture.matches(flop)))
+        .collect::<Vec<_>>();
+    if let Some(sample) = args.sample {
+        flops.shuffle(&mut rng);
+        flops.truncate(sample);
+        flops.sort_by_key(|flop| Reverse(flop.cards));
+    }
+
+    let bar = if args.quiet {
+        ProgressBar::hidden()
+    } else {
+        ProgressBar::new(flops.len() as u64 * args.iterations as u64).with_style(
+            ProgressStyle::with_template("{bar:40} {pos}/{len} [{per_sec}, ETA {eta}]").unwrap(),
+        )
+    };
+    let progress = |done| bar.set_position(done as u64);
+    let settings = SimulationSettings {
+        iterations: args.iterations,
+        target_error: None,
+        threads: args.threads,
+        progress: &progress,
+        progress_interval: PROGRESS_INTERVAL,
+        evaluator: EvaluatorKind::default().evaluator(),
+        sampler: Sampler::Random,
+        runout_filter: None,
+        stop: Some(catch_interrupt()),
+        deadline: None,
+    };
+    let mut report = run_flop_report(args.game, ranges, dead, &flops, &settings, &mut rng)?;
+    bar.finish_and_clear();
+
+    if args.sort {
+        report
+            .flops
+            .sort_by(|a, b| b.equities()[0].total_cmp(&a.equities()[0]));
+    }
+
+    if args.json {
+        print_json(args, &report);
+    } else {
+        print_text(args, &report);
+    }
+    Ok(())
+}
+
+/// Print every flop, then the equities by texture and overall, and how
+/// often each hand finishes with each category
+fn print_text(args: &Args, report: &FlopReport) {
+    let header = (1..=args.hands.len())
+        .map(|i| format!("{:>8}", format!("Hand {}", i)))
+        .collect::<String>();
+    let equities = |equities: Vec<f64>| {
+        equities
+            .iter()
+            .map(|equity| format!("{:>7.2}%", equity * 100.0))
+            .collect::<String>()
+    };
+
+    println!("{:<10} {:>6}{}", "Flop", "Weight", header);
+    for result in &report.flops {
+        println!(
+            "{:<10} {:>6}{}",
+            cards_string(&result.flop.cards),
+            result.flop.weight,
+            equities(result.equities())
+        );
+    }
+
+    println!();
+    println!("{:<10} {:>6}{}", "Texture", "Share", header);
+    for (name, subset) in textures(report) {
+        if !subset.flops.is_empty() {
+            println!(
+                "{:<10} {:>5.1}%{}",
+                name,
+                subset.weight() as f64 / report.weight().max(1) as f64 * 100.0,
+                equities(subset.equities())
+            );
+        }
+    }
+    println!(
+        "{:<10} {:>5.1}%{}",
+        "All",
+        100.0,
+        equities(report.equities())
+    );
+
+    println!();
+    println!("{:<16}{}", "Category", header);
+    let categories = (0..args.hands.len())
+        .map(|player| report.categories(player))
+        .collect::<Vec<_>>();
+    for category in HandCategory::ALL {
+        let shares = categories.iter().map(|c| c[category as usize]).collect();
+        println!("{:<16}{}", category.name(), equities(shares));
+    }
+}
+
+/// Print every flop and the aggregates as JSON
+fn print_json(args: &Args, report: &FlopReport) {
+    let categories = |shares: Vec<f64>| {
+        HandCategory::ALL
+            .iter()
+            .map(|&category| json!({ "category": category.name(), "share": shares[category as usize] }))
+            .collect::<Vec<_>>()
+    };
+    let flops = report
+        .flops
+        .iter()
+        .map(|result| {
+            let single = FlopReport {
+                flops: vec![result.clone()],
+            };
+            json!({
+                "flop": cards_string(&result.flop.cards),
+                "weight": result.flop.weight,
+                "equities": result.equities(),
+                "categories": (0..args.hands.len())
+                    .map(|player| categories(single.categories(player)))
+                    .collect::<Vec<_>>(),
+            })
+        })
+        .collect::<Vec<_>>();
+    let textures = textures(report)
+        .into_iter()
+        .filter(|(_, subset)| !subset.flops.is_empty())
+        .map(|(name, subset)| {
+            json!({
+                "texture": name,
+                "weight": subset.weight(),
+                "equities": subset.equities(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    println!(
+        "{}",
+        json!({
+            "hands": args.hands,
+            "game": args.game.to_string(),
+            "iterations": args.iterations,
+            "weight": report.weight(),
+            "equities": report.equities(),
+            "categories": (0..args.hands.len())
+                .map(|player| categories(report.categories(player)))
+                .collect::<Vec<_>>(),
+            "textures": textures,
+            "flops": flops,
+        })
+    );
+}
+
+/// The flops of the report split by suits, by pairing and by high card
+fn textures(report: &FlopReport) -> Vec<(String, FlopReport)> {
+    let suits = Suitedness::ALL.iter().map(|&suits| {
+        (
+            suits.name().to_string(),
+            report.subset(|f| f.suitedness() == suits),
+        )
+    });
+    let pairing = Pairing::ALL.iter().map(|&pairing| {
+        (
+            pairing.name().to_string(),
+            report.subset(|f| f.pairing() == pairing),
+        )
+    });
+    let high = Rank::ALL.iter().rev().map(|&rank| {
+        let name = format!("{} high", rank.as_str());
+        (name, report.subset(|f| f.high_card() == rank))
+    });
+
+    suits.chain(pairing).chain(high).collect()
+}
@@ -0,0 +1,125 @@
+//! Colored 13x13 grid of starting hands for the terminal, AA in the top
+//! left, suited hands above the pairs and offsuit below
+
+use std::env;
+use std::io::{stdout, IsTerminal};
+
+use equity_cli::class_name;
+
+/// How the value of a cell turns into its color
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Palette {
+    /// Red at 0 through yellow to green at 1, for equities
+    Equity,
+
+    /// Darker to brighter blue, for how much of a hand is in a range
+    Frequency,
+}
+
+/// What is written in each cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Label {
+    /// The starting hand, such as "AKs"
+    Hand,
+
+    /// The value in whole percent, with the ranks along the sides
+    Percent,
+}
+
+/// Whether stdout is a terminal that wants color, which NO_COLOR turns off
+pub fn use_color() -> bool {
+    stdout().is_terminal() && env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+}
+
+/// Render a grid of 169 cells indexed like `starting_hands`, one line per row
+/// Cells with no value are dimmed, or left as a dot without color
+pub fn render(cells: &[Option<f64>], palette: Palette, label: Label, color: bool) -> String {
+    let mut out = String::new();
+    if label == Label::Percent {
+        let header = (0..13)
+            .map(|i| format!("{:>4}", rank_label(i)))
+            .collect::<String>();
+        out.push_str(&format!("  {}\n", header));
+    }
+
+    for (row, cells) in cells.chunks(13).enumerate() {
+        if label == Label::Percent {
+            out.push_str(&format!("{} ", rank_label(row)));
+        }
+
+        for (col, cell) in cells.iter().enumerate() {
+            let text = match (label, cell) {
+                (Label::Hand, Some(_)) => format!(" {:<3}", class_name(row, col)),
+                (Label::Percent, Some(value)) => format!("{:>4.0}", value * 100.0),
+                (Label::Hand, None) if color => format!(" {:<3}", class_name(row, col)),
+                (_, None) => format!("{:>4}", "."),
+            };
+
+            match (color, cell) {
+                (false, _) => out.push_str(&text),
+                (true, Some(value)) => {
+                    let (r, g, b) = cell_color(palette, *value);
+                    out.push_str(&format!("\x1b[30;48;2;{};{};{}m{}\x1b[0m", r, g, b, text));
+                }
+                (true, None) => out.push_str(&format!("\x1b[90m{}\x1b[0m", text)),
+            }
+        }
+        out.push('\n');
+    }
+
+    out
+}
+
+/// Background color of a value between 0 and 1
+pub fn cell_color(palette: Palette, value: f64) -> (u8, u8, u8) {
+    let value = value.clamp(0.0, 1.0);
+    let channel =
+        |from: u8, to: u8, t: f64| (from as f64 + (to as f64 - from as f64) * t).round() as u8;
+
+    match palette {
+        Palette::Equity if value < 0.5 => {
+            let t = value * 2.0;
+            (230, channel(80, 210, t), 80)
+        }
+        Palette::Equity => {
+            let t = (value - 0.5) * 2.0;
+            (channel(230, 90, t), channel(210, 200, t), 80)
+        }
+        Palette::Frequency => (
+            channel(70, 120, value),
+            channel(90, 180, value),
+            channel(140, 255, value),
+        ),
+    }
+}
+
+/// Rank at the start of a row or column of the grid, such as "A"
+pub fn rank_label(i: usize) -> String {
+    class_name(i, i)[..1].to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_render_without_color() {
+        let mut cells = vec![None; 169];
+        cells[0] = Some(0.85);
+        cells[1] = Some(0.5);
+
+        let percent = render(&cells, Palette::Equity, Label::Percent, false);
+        let lines = percent.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 14);
+        assert!(lines[0].trim_start().starts_with("A   K   Q"));
+        assert!(lines[1].starts_with("A   85  50   ."));
+
+        let hands = render(&cells, Palette::Frequency, Label::Hand, false);
+        assert_eq!(hands.lines().count(), 13);
+        assert!(hands.starts_with(" AA  AKs   ."));
+        assert!(!hands.contains('\x1b'));
+
+        let colored = render(&cells, Palette::Frequency, Label::Hand, true);
+        assert!(colored.contains("\x1b[90m 32o"));
+    }
+}
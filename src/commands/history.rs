@@ -0,0 +1,454 @@
+//! `history` subcommand, the expected value of every all in found in
+//! PokerStars and GGPoker hand histories against what was actually won
+//! With --report it sums whole folders of histories into a luck report
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use equity_cli::{parse_hand_history, split_hand_histories, Error, HandHistory};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde_json::json;
+
+use super::equity::cards_string;
+
+/// Arguments of the history subcommand
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Player to report on, defaults to the player dealt the hole cards in each hand
+    #[arg(short, long)]
+    player: Option<String>,
+
+    /// Iterations to simulate for all ins before the flop,
+    /// later all ins are enumerated exactly
+    #[arg(short, long, default_value_t = 20_000)]
+    iterations: u32,
+
+    /// Seed for the random number generator
+    #[arg(short, long)]
+    seed: Option<u64>,
+
+    /// Totals of each file and how far the all ins ran from their
+    /// expected value, instead of every all in
+    #[arg(long)]
+    report: bool,
+
+    /// Print the hands and totals as JSON
+    #[arg(long)]
+    json: bool,
+
+    /// Hand history files, each holding any number of hands,
+    /// or folders searched for .txt files
+    #[arg(required = true)]
+    paths: Vec<PathBuf>,
+}
+
+/// An all in the player was part of
+#[derive(Debug, Clone, PartialEq)]
+pub struct AllIn {
+    pub hand: String,
+    pub street: &'static str,
+    pub cards: String,
+    pub against: Vec<String>,
+    pub pot: f64,
+
+    /// Chips in the pots the player could win, less the rake
+    pub stake: f64,
+
+    pub equity: f64,
+    pub won: f64,
+    pub ev: f64,
+}
+
+/// Totals of the player across every hand read
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Session {
+    /// Hands the player was dealt into
+    pub hands: usize,
+
+    /// Hands that could not be read
+    pub skipped: usize,
+
+    /// Chips won or lost in every hand
+    pub won: f64,
+
+    pub all_ins: Vec<AllIn>,
+}
+
+///////////////////////////////////////////////
+/// Session Implementations
+///////////////////////////////////////////////
+impl Session {
+    /// Chips won or lost in the all ins
+    pub fn all_in_won(&self) -> f64 {
+        self.all_ins.iter().map(|a| a.won).sum()
+    }
+
+    /// Chips expected to be won or lost in the all ins
+    pub fn all_in_ev(&self) -> f64 {
+        self.all_ins.iter().map(|a| a.ev).sum()
+    }
+
+    /// Chips won above the expected value of the all ins
+    pub fn luck(&self) -> f64 {
+        self.all_in_won() - self.all_in_ev()
+    }
+
+    /// What would have been won with every all in paying its expected value
+    pub fn adjusted(&self) -> f64 {
+        self.won - self.luck()
+    }
+
+    /// All ins expected to be won, counting each by its equity
+    pub fn expected_wins(&self) -> f64 {
+        self.all_ins.iter().map(|a| a.equity).sum()
+    }
+
+    /// All ins won outright or in part
+    pub fn wins(&self) -> usize {
+        self.all_ins.iter().filter(|a| a.won > 0.0).count()
+    }
+
+    /// Standard deviation of the luck, treating each all in as winning its
+    /// whole stake with its equity and nothing otherwise
+    pub fn luck_std_dev(&self) -> f64 {
+        self.all_ins
+            .iter()
+            .map(|a| a.equity * (1.0 - a.equity) * a.stake * a.stake)
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    /// Add up the hands of another session
+    pub fn merge(&mut self, other: &Session) {
+        self.hands += other.hands;
+        self.skipped += other.skipped;
+        self.won += other.won;
+        self.all_ins.extend(other.all_ins.iter().cloned());
+    }
+
+    /// Add every hand in the text of a hand history file, printing
+    /// the hands that could not be read
+    pub fn add_file<R: rand::Rng>(
+        &mut self,
+        text: &str,
+        player: Option<&str>,
+        iterations: u32,
+        rng: &mut R,
+    ) {
+        for text in split_hand_histories(text) {
+            match parse_hand_history(text) {
+                Ok(hand) => self.add_hand(&hand, player, iterations, rng),
+                Err(e) => {
+                    eprintln!("skipped {}", e);
+                    self.skipped += 1;
+                }
+            }
+        }
+    }
+
+    /// Add a hand the player was dealt into
+    pub fn add_hand<R: rand::Rng>(
+        &mut self,
+        hand: &HandHistory,
+        player: Option<&str>,
+        iterations: u32,
+        rng: &mut R,
+    ) {
+        let Some(i) = player.or(hand.hero.as_deref()).and_then(|p| hand.player(p)) else {
+            return;
+        };
+        self.hands += 1;
+        self.won += hand.players[i].net();
+
+        let Some(ev) = hand.all_in_ev(iterations, rng) else {
+            return;
+        };
+        let me = &hand.players[i];
+        if me.folded || me.cards.is_none() {
+            return;
+        }
+
+        let pot = hand.players.iter().map(|p| p.invested).sum::<f64>();
+        let stake = hand
+            .pots()
+            .iter()
+            .filter(|(_, eligible)| eligible.contains(&i))
+            .map(|(chips, _)| chips * hand.paid_out())
+            .sum::<f64>();
+        let against = hand
+            .players
+            .iter()
+            .enumerate()
+            .filter(|(j, p)| *j != i && !p.folded && p.invested > 0.0)
+            .filter_map(|(_, p)| p.cards.as_deref().map(cards_string))
+            .collect();
+
+        self.all_ins.push(AllIn {
+            hand: hand.id.clone(),
+            street: street_name(hand.betting_board),
+            cards: me.cards.as_deref().map(cards_string).unwrap_or_default(),
+            against,
+            pot,
+            stake,
+            equity: (ev[i] + me.invested) / stake.max(f64::MIN_POSITIVE),
+            won: me.net(),
+            ev: ev[i],
+        });
+    }
+}
+
+/// Print every all in and the session totals
+pub fn run(args: &Args) -> Result<(), Error> {
+    let mut rng = match args.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+
+    let mut files = vec![];
+    for path in &args.paths {
+        history_files(path, &mut files)
+            .map_err(|e| Error::from(e).input("path", &path.display().to_string()))?;
+    }
+
+    let mut sessions = vec![];
+    for path in files {
+        let text = fs::read_to_string(&path)
+            .map_err(|e| Error::from(e).input("file", &path.display().to_string()))?;
+        let mut session = Session::default();
+        session.add_file(&text, args.player.as_deref(), args.iterations, &mut rng);
+        sessions.push((path, session));
+    }
+
+    let mut total = Session::default();
+    for (_, session) in &sessions {
+        total.merge(session);
+    }
+
+    match (args.json, args.report) {
+        (true, false) => println!("{}", session_json(&total)),
+        (true, true) => {
+            let files = sessions
+                .iter()
+                .map(|(path, session)| {
+                    let mut json = totals_json(session);
+                    json["file"] = json!(path.display().to_string());
+                    json
+                })
+                .collect::<Vec<_>>();
+            let mut json = totals_json(&total);
+            json["files"] = json!(files);
+            println!("{}", json);
+        }
+        (false, false) => {
+            print_all_ins(&total.all_ins);
+            print_totals(&total);
+        }
+        (false, true) => {
+            print_files(&sessions);
+            print_totals(&total);
+            print_variance(&total);
+        }
+    }
+    Ok(())
+}
+
+/// The path if it is a file, or every .txt file in the folder and the
+/// folders inside it in name order
+fn history_files(path: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    if !path.is_dir() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+
+    let mut entries = fs::read_dir(path)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    entries.sort();
+
+    for entry in entries {
+        let text = entry
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("txt"));
+        if entry.is_dir() || text {
+            history_files(&entry, files)?;
+        }
+    }
+    Ok(())
+}
+
+/// Table of the all ins, one line each
+pub fn print_all_ins(all_ins: &[AllIn]) {
+    if all_ins.is_empty() {
+        println!("No all ins with the cards shown");
+        return;
+    }
+
+    println!(
+        "{:<14} {:<8} {:<10} {:<18} {:>7} {:>9} {:>9} {:>9} {:>9}",
+        "Hand", "Street", "Cards", "Against", "Equity", "Pot", "Won", "EV", "Luck"
+    );
+    for all_in in all_ins {
+        println!(
+            "{:<14} {:<8} {:<10} {:<18} {:>6.1}% {:>9.2} {:>9.2} {:>9.2} {:>9.2}",
+            all_in.hand,
+            all_in.street,
+            all_in.cards,
+            all_in.against.join(" "),
+            all_in.equity * 100.0,
+            all_in.pot,
+            all_in.won,
+            all_in.ev,
+            all_in.won - all_in.ev,
+        );
+    }
+}
+
+/// Totals of a session
+pub fn print_totals(session: &Session) {
+    println!();
+    println!("{:<16} {:>10}", "Hands:", session.hands);
+    if session.skipped > 0 {
+        println!("{:<16} {:>10}", "Skipped:", session.skipped);
+    }
+    println!("{:<16} {:>10}", "All ins:", session.all_ins.len());
+    println!("{:<16} {:>10.2}", "Won:", session.won);
+    println!("{:<16} {:>10.2}", "All in won:", session.all_in_won());
+    println!("{:<16} {:>10.2}", "All in EV:", session.all_in_ev());
+    println!("{:<16} {:>10.2}", "Luck:", session.luck());
+    println!("{:<16} {:>10.2}", "EV adjusted:", session.adjusted());
+}
+
+/// Table of the totals of each file
+fn print_files(sessions: &[(PathBuf, Session)]) {
+    println!(
+        "{:<30} {:>7} {:>7} {:>10} {:>10} {:>10}",
+        "File", "Hands", "All ins", "Won", "All in EV", "Luck"
+    );
+    for (path, session) in sessions {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        println!(
+            "{:<30} {:>7} {:>7} {:>10.2} {:>10.2} {:>10.2}",
+            name,
+            session.hands,
+            session.all_ins.len(),
+            session.won,
+            session.all_in_ev(),
+            session.luck(),
+        );
+    }
+}
+
+/// How far the all ins ran from their expected value
+fn print_variance(session: &Session) {
+    let std_dev = session.luck_std_dev();
+
+    println!();
+    println!(
+        "{:<16} {:>10} of {:.1} expected",
+        "All ins won:",
+        session.wins(),
+        session.expected_wins()
+    );
+    println!("{:<16} {:>10.2}", "Luck std dev:", std_dev);
+    if std_dev > 0.0 {
+        println!("{:<16} {:>+10.2}", "Std devs:", session.luck() / std_dev);
+    }
+    if session.hands > 0 {
+        println!(
+            "{:<16} {:>10.2}",
+            "Luck / 100:",
+            session.luck() / session.hands as f64 * 100.0
+        );
+    }
+}
+
+/// The totals and variance of a session as JSON
+fn totals_json(session: &Session) -> serde_json::Value {
+    json!({
+        "hands": session.hands,
+        "skipped": session.skipped,
+        "won": session.won,
+        "all_ins": session.all_ins.len(),
+        "all_ins_won": session.wins(),
+        "expected_wins": session.expected_wins(),
+        "all_in_won": session.all_in_won(),
+        "all_in_ev": session.all_in_ev(),
+        "luck": session.luck(),
+        "luck_std_dev": session.luck_std_dev(),
+        "adjusted": session.adjusted(),
+    })
+}
+
+/// A session and its all ins as JSON
+pub fn session_json(session: &Session) -> serde_json::Value {
+    let all_ins = session
+        .all_ins
+        .iter()
+        .map(|a| {
+            json!({
+                "hand": a.hand,
+                "street": a.street,
+                "cards": a.cards,
+                "against": a.against,
+                "equity": a.equity,
+                "pot": a.pot,
+                "stake": a.stake,
+                "won": a.won,
+                "ev": a.ev,
+                "luck": a.won - a.ev,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "hands": session.hands,
+        "skipped": session.skipped,
+        "won": session.won,
+        "all_in_won": session.all_in_won(),
+        "all_in_ev": session.all_in_ev(),
+        "luck": session.luck(),
+        "adjusted": session.adjusted(),
+        "all_ins": all_ins,
+    })
+}
+
+/// Name of the street with this many board cards
+fn street_name(board: usize) -> &'static str {
+    match board {
+        0 => "Preflop",
+        3 => "Flop",
+        4 => "Turn",
+        _ => "River",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_history_files() {
+        let dir = std::env::temp_dir().join(format!("histories-{}", std::process::id()));
+        fs::create_dir_all(dir.join("2024")).unwrap();
+        for name in ["b.txt", "a.TXT", "notes.md", "2024/c.txt"] {
+            fs::write(dir.join(name), "").unwrap();
+        }
+
+        let mut files = vec![];
+        history_files(&dir, &mut files).unwrap();
+        let names = files
+            .iter()
+            .map(|f| f.strip_prefix(&dir).unwrap().to_path_buf())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            ["2024/c.txt", "a.TXT", "b.txt"].map(PathBuf::from).to_vec()
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
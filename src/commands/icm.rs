@@ -0,0 +1,43 @@
+//! `icm` subcommand, tournament prize equity of each stack
+
+use equity_cli::{icm, Error};
+
+/// Arguments of the icm subcommand
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Chip stacks of every player, comma separated
+    /// Example: 5000,3000,1500
+    #[arg(short, long, value_delimiter = ',', required = true)]
+    stacks: Vec<f64>,
+
+    /// Payouts from first place down, comma separated
+    /// Either amounts or percentages of the prize pool
+    /// Example: 50,30,20
+    #[arg(short, long, value_delimiter = ',', required = true)]
+    payouts: Vec<f64>,
+}
+
+/// Print the prize equity of every stack
+pub fn run(args: &Args) -> Result<(), Error> {
+    let equity = icm(&args.stacks, &args.payouts)?;
+
+    let chips = args.stacks.iter().sum::<f64>();
+    let pool = args.payouts.iter().take(args.stacks.len()).sum::<f64>();
+
+    println!(
+        "{:<10} {:>10} {:>8} {:>10} {:>8}",
+        "", "Stack", "Chips", "Equity", "Prizes"
+    );
+    for (i, (stack, equity)) in args.stacks.iter().zip(&equity).enumerate() {
+        println!(
+            "{:<10} {:>10} {:>7.2}% {:>10.2} {:>7.2}%",
+            format!("Player {}:", i + 1),
+            stack,
+            stack / chips * 100.0,
+            equity,
+            equity / pool.max(f64::MIN_POSITIVE) * 100.0,
+        );
+    }
+
+    Ok(())
+}
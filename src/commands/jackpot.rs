@@ -0,0 +1,153 @@
+//! `jackpot` subcommand, how often a bad beat jackpot is hit at every table
+//! size, simulated with every player going to showdown
+
+use equity_cli::{
+    cards_from_str, check_duplicate_cards, default_threads, get_best_hand, run_jackpot_simulation,
+    Error, Game, HandValue, JackpotResult, JackpotRule, SimulationSettings,
+};
+use indicatif::{ProgressBar, ProgressStyle};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde_json::json;
+
+use super::equity::{catch_interrupt, interrupted};
+
+/// Arguments of the jackpot subcommand
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Weakest losing hand that qualifies, as five cards
+    /// Example: AcAdAhJcJd for aces full of jacks
+    #[arg(long)]
+    losing: String,
+
+    /// Both hole cards have to play in the losing and the winning hand
+    #[arg(long)]
+    both_cards_play: bool,
+
+    /// Most players at the table, every size from heads up is shown
+    #[arg(short, long, default_value_t = 9)]
+    players: usize,
+
+    /// Game to deal: holdem, omaha, omaha5, courchevel or pineapple
+    #[arg(short, long, default_value_t = Game::Holdem)]
+    game: Game,
+
+    /// Number of deals to simulate
+    #[arg(short, long, default_value_t = 1_000_000)]
+    iterations: u32,
+
+    /// Number of worker threads
+    #[arg(short, long, default_value_t = default_threads())]
+    threads: usize,
+
+    /// Seed for the random number generator
+    #[arg(short, long)]
+    seed: Option<u64>,
+
+    /// Hide the progress bar
+    #[arg(short, long)]
+    quiet: bool,
+
+    /// Print the frequencies as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+/// Simulate the deals and print how often the jackpot is hit
+pub fn run(args: &Args) -> Result<(), Error> {
+    let cards =
+        cards_from_str(&args.losing).map_err(|e| Error::from(e).input("--losing", &args.losing))?;
+    if cards.len() != 5 {
+        return Err(Error::InvalidHandSize(cards.len()).input("--losing", &args.losing));
+    }
+    check_duplicate_cards(&[("--losing".to_string(), cards.clone())])?;
+    let rule = JackpotRule {
+        losing: HandValue::from(get_best_hand(&cards)),
+        both_cards_play: args.both_cards_play,
+    };
+
+    let bar = if args.quiet {
+        ProgressBar::hidden()
+    } else {
+        ProgressBar::new(args.iterations as u64).with_style(
+            ProgressStyle::with_template("{bar:40} {pos}/{len} [{per_sec}, ETA {eta}]").unwrap(),
+        )
+    };
+    let progress = |done| bar.set_position(done as u64);
+    let settings = SimulationSettings {
+        threads: args.threads,
+        progress: &progress,
+        stop: Some(catch_interrupt()),
+        ..SimulationSettings::new(args.iterations)
+    };
+    let mut rng = match args.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+
+    let results = run_jackpot_simulation(args.game, &rule, args.players, &settings, &mut rng)
+        .map_err(|e| e.input("--players", &args.players.to_string()))?;
+    bar.finish_and_clear();
+    if interrupted() {
+        eprintln!(
+            "Interrupted after {} of {} deals",
+            results[0].deals, args.iterations
+        );
+    }
+
+    if args.json {
+        let tables = results
+            .iter()
+            .map(|result| {
+                json!({
+                    "players": result.players,
+                    "deals": result.deals,
+                    "hits": result.hits,
+                    "frequency": result.frequency(),
+                    "seat_hits": result.seat_hits,
+                    "seat_frequency": result.seat_frequency(),
+                })
+            })
+            .collect::<Vec<_>>();
+        println!(
+            "{}",
+            json!({
+                "losing": rule.losing.to_string(),
+                "both_cards_play": rule.both_cards_play,
+                "game": args.game.to_string(),
+                "tables": tables,
+            })
+        );
+        return Ok(());
+    }
+
+    print_text(&rule, &results);
+    Ok(())
+}
+
+/// Print the rule and a row for every table size
+fn print_text(rule: &JackpotRule, results: &[JackpotResult]) {
+    println!("Losing hand: {} or better", rule.losing);
+    if rule.both_cards_play {
+        println!("Both hole cards play in both hands");
+    }
+    println!("Deals: {}", results[0].deals);
+    println!();
+    println!("{:<8} {:>22} {:>22}", "Players", "Table", "Your seat");
+    for result in results {
+        println!(
+            "{:<8} {:>22} {:>22}",
+            result.players,
+            one_in(result.frequency()),
+            one_in(result.seat_frequency())
+        );
+    }
+}
+
+/// A small chance as a percentage and as one deal in so many
+fn one_in(chance: f64) -> String {
+    if chance == 0.0 {
+        return "never".to_string();
+    }
+    format!("{:.4}% (1 in {:.0})", chance * 100.0, 1.0 / chance)
+}
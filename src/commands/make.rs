@@ -0,0 +1,97 @@
+//! `make` subcommand, the chance of a hand making a category such as a
+//! flush by the turn and by the river, with every card to come enumerated
+
+use equity_cli::{chance_to_make, check_duplicate_cards, Error, Game, HandCategory, Street};
+use serde_json::json;
+
+use super::equity::cards_string;
+use super::parse_cards;
+
+/// Arguments of the make subcommand
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Hole cards to complete
+    /// Example: 9h8h
+    #[arg(long)]
+    hand: String,
+
+    /// Cards on the board so far, none up to the turn
+    /// Example: 7h2c
+    #[arg(short, long, default_value = "")]
+    board: String,
+
+    /// Dead cards that have been exposed or folded
+    #[arg(short, long, default_value = "")]
+    dead: String,
+
+    /// Hand to make, counting anything better as made: pair, two-pair,
+    /// trips, straight, flush, full-house, quads or straight-flush
+    /// Example: two-pair-or-better
+    #[arg(short, long)]
+    target: HandCategory,
+
+    /// Game to make the hand in: holdem, omaha or omaha8
+    /// Only the high hand is counted in omaha8
+    #[arg(short, long, default_value_t = Game::Holdem)]
+    game: Game,
+
+    /// Print the chances as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+/// Work out and print the chance of making the target on every street
+pub fn run(args: &Args) -> Result<(), Error> {
+    if !args.game.has_board() {
+        return Err(Error::NoBoard(args.game).input("--game", &args.game.to_string()));
+    }
+    let hand = parse_cards("--hand", &args.hand)?;
+    args.game
+        .check_hole_cards(hand.len())
+        .map_err(|e| e.input("--hand", &args.hand))?;
+
+    let board = parse_cards("--board", &args.board)?;
+    if board.len() >= Street::River.cards() {
+        return Err(Error::NoStreetsLeft.input("--board", &args.board));
+    }
+    let dead = parse_cards("--dead", &args.dead)?;
+    check_duplicate_cards(&[
+        ("--hand".to_string(), hand.clone()),
+        ("--board".to_string(), board.clone()),
+        ("--dead".to_string(), dead.clone()),
+    ])?;
+
+    let chances = chance_to_make(args.game, &hand, &board, &dead, args.target)?;
+
+    if args.json {
+        let streets = chances
+            .iter()
+            .map(|(street, chance)| (street.name().to_lowercase(), json!(chance)))
+            .collect::<serde_json::Map<_, _>>();
+        println!(
+            "{}",
+            json!({
+                "hand": cards_string(&hand),
+                "board": cards_string(&board),
+                "game": args.game.to_string(),
+                "target": args.target.name(),
+                "streets": streets,
+            })
+        );
+        return Ok(());
+    }
+
+    println!("Hand: {:?}", hand);
+    println!("Board: {:?}", board);
+    println!("Target: {} or better", args.target);
+    println!();
+    for (street, chance) in chances {
+        println!(
+            "By the {:<6} {:>7.2}%",
+            street.name().to_lowercase(),
+            chance * 100.0
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,107 @@
+//! `mdf` subcommand, the minimum defense frequency against a bet and how
+//! often a bluff has to work
+
+use equity_cli::{bluff_break_even, minimum_defense_frequency, required_equity, Error};
+
+use super::equity::{self, Format};
+
+/// Arguments of the mdf subcommand
+#[derive(clap::Args, Debug)]
+#[group(skip)]
+pub struct Args {
+    /// Pot before the bet
+    #[arg(long)]
+    pot: f64,
+
+    /// Size of the bet
+    #[arg(long)]
+    bet: f64,
+
+    /// Hands can be given to check how much of the first range has the
+    /// equity to call
+    #[command(flatten, next_help_heading = "Equity")]
+    equity: equity::Args,
+}
+
+/// Print the minimum defense frequency and the bluff break even, and how
+/// much of the first range can call when hands are given
+pub fn run(args: &Args) -> Result<(), Error> {
+    args.equity.check_equity_only(&[Format::Text])?;
+    let named = |e: Error| match e {
+        Error::InvalidPot(_) => e.input("--pot", &args.pot.to_string()),
+        Error::InvalidBet(_) => e.input("--bet", &args.bet.to_string()),
+        e => e,
+    };
+    let mdf = minimum_defense_frequency(args.pot, args.bet).map_err(named)?;
+    let bluff = bluff_break_even(args.pot, args.bet)?;
+    let required = required_equity(args.pot, args.bet)?;
+
+    println!("Minimum defense frequency: {:.2}%", mdf * 100.0);
+    println!("Bluff break even: {:.2}%", bluff * 100.0);
+    println!("Required equity: {:.2}%", required * 100.0);
+
+    if args.equity.hand_names().is_empty() {
+        return Ok(());
+    }
+
+    let spot = equity::parse_spot(&args.equity)?;
+    let results = equity::calculate(&args.equity, spot);
+    let defends = results[0].share_at_least(required);
+
+    println!(
+        "{:.2}% of the range has at least {:.2}% - {}",
+        defends * 100.0,
+        required * 100.0,
+        if defends >= mdf {
+            "enough to defend"
+        } else {
+            "folding too much"
+        }
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    #[command(no_binary_name = true)]
+    struct Cli {
+        #[command(flatten)]
+        args: Args,
+    }
+
+    #[test]
+    fn test_names_the_invalid_amount() {
+        let error = |argv: &[&str]| run(&Cli::parse_from(argv).args).unwrap_err().to_string();
+        assert_eq!(
+            error(&["--pot", "100", "--bet", "0"]),
+            "--bet \"0\": a bet or call must be more than 0, got 0"
+        );
+        assert_eq!(
+            error(&["--pot=-5", "--bet", "10"]),
+            "--pot \"-5\": the pot can not be negative, got -5"
+        );
+    }
+
+    #[test]
+    fn test_rejects_unused_equity_flags() {
+        let run = |argv: &[&str]| run(&Cli::parse_from(argv).args);
+        let base = ["--pot", "100", "--bet", "50", "AKs", "QQ"];
+        for flag in ["--streets", "--combos", "--grid", "--json"] {
+            let argv = [&base[..], &[flag]].concat();
+            assert!(matches!(run(&argv), Err(Error::UnusedFlag(f)) if f == flag));
+        }
+        assert!(matches!(
+            run(&[&base[..], &["--format", "csv"]].concat()),
+            Err(Error::UnusedFlag("--format"))
+        ));
+        assert!(matches!(
+            run(&[&base[..], &["--run-it", "2"]].concat()),
+            Err(Error::UnusedFlag("--run-it"))
+        ));
+    }
+}
@@ -0,0 +1,88 @@
+//! Subcommands of the command line tool, one module each
+
+use std::fs;
+use std::ops;
+
+use equity_cli::{cards_from_str, Card, Error, Game, Range};
+
+pub mod above;
+pub mod batch;
+pub mod beats;
+pub mod blockers;
+pub mod breakdown;
+pub mod bubble;
+pub mod cache;
+pub mod compare;
+pub mod coolers;
+pub mod equity;
+pub mod ev;
+pub mod eval;
+pub mod flops;
+pub mod grid;
+pub mod history;
+pub mod icm;
+pub mod jackpot;
+pub mod make;
+pub mod mdf;
+pub mod multiway;
+pub mod next_card;
+pub mod odds;
+pub mod outs;
+#[cfg(feature = "plot")]
+pub mod plot;
+pub mod potential;
+pub mod preflop_matrix;
+pub mod push_fold;
+pub mod range;
+pub mod ranges;
+#[cfg(feature = "serve")]
+pub mod serve;
+pub mod split;
+pub mod stream;
+#[cfg(feature = "tui")]
+pub mod tui;
+
+/// Parse a list of cards from the named argument
+pub(crate) fn parse_cards(name: &str, value: &str) -> Result<Vec<Card>, Error> {
+    cards_from_str(value).map_err(|e| Error::from(e).input(name, value))
+}
+
+/// Parse the hand arguments, checking each has the right number of hole cards
+/// The hands at the indexes in `files` are paths to ranges exported from a
+/// solver
+pub(crate) fn parse_hands(
+    game: Game,
+    hands: &[String],
+    files: ops::Range<usize>,
+) -> Result<Vec<Range>, Error> {
+    if hands.len() < 2 {
+        return Err(Error::NotEnoughHands(hands.len()));
+    }
+
+    hands
+        .iter()
+        .enumerate()
+        .map(|(i, hand)| {
+            let name = format!("hand {}", i + 1);
+            let range = if files.contains(&i) {
+                read_range_file(hand)?
+            } else {
+                Range::parse(hand).map_err(|e| Error::from(e).input(&name, hand))?
+            };
+
+            // Every hand needs the right number of hole cards for the game
+            for combo in range.combos() {
+                game.check_hole_cards(combo.len())
+                    .map_err(|e| e.input(&name, hand))?;
+            }
+
+            Ok(range)
+        })
+        .collect()
+}
+
+/// Read a range exported from a solver
+fn read_range_file(path: &str) -> Result<Range, Error> {
+    let text = fs::read_to_string(path).map_err(|e| Error::from(e).input("--range-file", path))?;
+    Range::parse_solver(&text).map_err(|e| Error::from(e).input("--range-file", path))
+}
@@ -0,0 +1,173 @@
+//! `multiway` subcommand, how a hand's equity falls against one random
+//! opponent, two and so on, from the same deals
+
+use equity_cli::{
+    cards_from_str, default_threads, run_multiway_simulation, Board, Card, Error, Game,
+    MultiwayResult, Range, SimulationSettings, Z_95,
+};
+use indicatif::{ProgressBar, ProgressStyle};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde_json::json;
+
+use super::equity::{cards_string, catch_interrupt, check_known_cards, interrupted};
+
+/// Arguments of the multiway subcommand
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Hand or range to play, such as AhKh or "QQ+,AKs"
+    hand: String,
+
+    /// Most random opponents, every number from one is shown
+    #[arg(short, long, default_value_t = 8, value_parser = clap::value_parser!(u8).range(1..))]
+    opponents: u8,
+
+    /// Board cards, the flop, turn or river
+    #[arg(short, long, default_value = "")]
+    board: String,
+
+    /// Dead cards that have been exposed or folded
+    #[arg(short, long, default_value = "")]
+    dead: String,
+
+    /// Game to deal: holdem, omaha, omaha8, omaha5, courchevel or pineapple
+    #[arg(short, long, default_value_t = Game::Holdem)]
+    game: Game,
+
+    /// Number of deals to simulate
+    #[arg(short, long, default_value_t = 100_000)]
+    iterations: u32,
+
+    /// Number of worker threads
+    #[arg(short, long, default_value_t = default_threads())]
+    threads: usize,
+
+    /// Seed for the random number generator
+    #[arg(short, long)]
+    seed: Option<u64>,
+
+    /// Hide the progress bar
+    #[arg(short, long)]
+    quiet: bool,
+
+    /// Print the curve as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+/// Simulate the deals and print the equity against every number of
+/// opponents
+pub fn run(args: &Args) -> Result<(), Error> {
+    if !args.game.has_board() {
+        return Err(Error::NoBoard(args.game).input("--game", &args.game.to_string()));
+    }
+    let range = Range::parse(&args.hand).map_err(|e| Error::from(e).input("hand", &args.hand))?;
+    for combo in range.combos() {
+        args.game
+            .check_hole_cards(combo.len())
+            .map_err(|e| e.input("hand", &args.hand))?;
+    }
+    let board =
+        cards_from_str(&args.board).map_err(|e| Error::from(e).input("--board", &args.board))?;
+    Board::new(args.game, board.clone()).map_err(|e| e.input("--board", &args.board))?;
+    let dead =
+        cards_from_str(&args.dead).map_err(|e| Error::from(e).input("--dead", &args.dead))?;
+    check_known_cards(
+        std::slice::from_ref(&range),
+        vec![
+            ("--board".to_string(), board.clone()),
+            ("--dead".to_string(), dead.clone()),
+        ],
+    )?;
+
+    let bar = if args.quiet {
+        ProgressBar::hidden()
+    } else {
+        ProgressBar::new(args.iterations as u64).with_style(
+            ProgressStyle::with_template("{bar:40} {pos}/{len} [{per_sec}, ETA {eta}]").unwrap(),
+        )
+    };
+    let progress = |done| bar.set_position(done as u64);
+    let settings = SimulationSettings {
+        threads: args.threads,
+        progress: &progress,
+        stop: Some(catch_interrupt()),
+        ..SimulationSettings::new(args.iterations)
+    };
+    let mut rng = match args.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+
+    let opponents = args.opponents as usize;
+    let results = run_multiway_simulation(
+        args.game,
+        range,
+        board.clone(),
+        dead,
+        opponents,
+        &settings,
+        &mut rng,
+    )
+    .map_err(|e| e.input("--opponents", &args.opponents.to_string()))?;
+    bar.finish_and_clear();
+    if interrupted() {
+        eprintln!(
+            "Interrupted after {} of {} deals",
+            results[0].deals, args.iterations
+        );
+    }
+
+    if args.json {
+        let curve = results
+            .iter()
+            .map(|result| {
+                json!({
+                    "opponents": result.opponents,
+                    "deals": result.deals,
+                    "equity": result.equity(),
+                    "std_error": result.std_error(),
+                    "fair_share": result.fair_share(),
+                })
+            })
+            .collect::<Vec<_>>();
+        println!(
+            "{}",
+            json!({
+                "hand": args.hand,
+                "board": cards_string(&board),
+                "game": args.game.to_string(),
+                "curve": curve,
+            })
+        );
+        return Ok(());
+    }
+
+    print_text(args, &board, &results);
+    Ok(())
+}
+
+/// Print the hand and a row for every number of opponents
+fn print_text(args: &Args, board: &[Card], results: &[MultiwayResult]) {
+    println!("Hand: {}", args.hand);
+    if !board.is_empty() {
+        println!("Board: {}", cards_string(board));
+    }
+    println!("Deals: {}", results[0].deals);
+    println!();
+    println!(
+        "{:<10} {:>8} {:>8} {:>8}  Curve",
+        "Opponents", "Equity", "±95%", "Fair"
+    );
+    for result in results {
+        let equity = result.equity();
+        println!(
+            "{:<10} {:>7.2}% {:>7.2}% {:>7.2}%  {}",
+            result.opponents,
+            equity * 100.0,
+            Z_95 * result.std_error() * 100.0,
+            result.fair_share() * 100.0,
+            "#".repeat((equity * 40.0).round() as usize)
+        );
+    }
+}
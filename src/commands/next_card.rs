@@ -0,0 +1,182 @@
+//! `next-card` subcommand, the equity of every hand after each card that
+//! can come next on a flop or turn, biggest swings first
+
+use equity_cli::{
+    default_threads, next_cards, run_exact_calculation, run_range_calculation, Card, Error, Game,
+    Range, SimulationSettings,
+};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde_json::json;
+
+use super::equity::{cards_string, check_known_cards};
+use super::{parse_cards, parse_hands};
+
+/// Arguments of the next-card subcommand
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Flop or turn
+    /// Example: 7h2hKs
+    #[arg(short, long)]
+    board: String,
+
+    /// Dead cards that have been exposed or folded
+    #[arg(short, long, default_value = "")]
+    dead: String,
+
+    /// Game to simulate: holdem, omaha or omaha8
+    #[arg(short, long, default_value_t = Game::Holdem)]
+    game: Game,
+
+    /// Simulate this many iterations after each card instead of
+    /// enumerating every runout, for wide ranges
+    #[arg(short, long)]
+    iterations: Option<u32>,
+
+    /// Number of worker threads when simulating
+    #[arg(short, long, default_value_t = default_threads())]
+    threads: usize,
+
+    /// Seed for the random number generator when simulating
+    #[arg(short, long)]
+    seed: Option<u64>,
+
+    /// Print the equities as JSON
+    #[arg(long)]
+    json: bool,
+
+    /// Hands or ranges to compare, such as AhAs KdKh or "QQ+,AKs"
+    #[arg(required = true)]
+    hands: Vec<String>,
+}
+
+/// Equities now and after a single next card
+struct NextCard {
+    card: Card,
+    equity: Vec<f64>,
+}
+
+/// Print the equity of every hand after each possible next card
+pub fn run(args: &Args) -> Result<(), Error> {
+    if !args.game.has_board() {
+        return Err(Error::NoBoard(args.game).input("--game", &args.game.to_string()));
+    }
+    let hands = parse_hands(args.game, &args.hands, 0..0)?;
+    let board = parse_cards("--board", &args.board)?;
+    if !matches!(board.len(), 3 | 4) {
+        return Err(Error::NotFlopOrTurn(board.len()).input("--board", &args.board));
+    }
+    let dead = parse_cards("--dead", &args.dead)?;
+    check_known_cards(
+        &hands,
+        vec![
+            ("--board".to_string(), board.clone()),
+            ("--dead".to_string(), dead.clone()),
+        ],
+    )?;
+    if args.iterations.is_none() && hands.iter().any(Range::is_weighted) {
+        return Err(Error::WeightedExact);
+    }
+
+    let now = equities(args, &hands, board.clone(), &dead, 0);
+    let mut cards = next_cards(&board, &dead, &hands)
+        .into_iter()
+        .enumerate()
+        .map(|(i, card)| {
+            let mut next = board.clone();
+            next.push(card);
+            NextCard {
+                card,
+                equity: equities(args, &hands, next, &dead, i as u64 + 1),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // Biggest change to any hand first, keeping deck order otherwise
+    let swing = |next: &NextCard| {
+        next.equity
+            .iter()
+            .zip(&now)
+            .map(|(after, before)| (after - before).abs())
+            .fold(0.0, f64::max)
+    };
+    cards.sort_by(|a, b| swing(b).total_cmp(&swing(a)));
+
+    if args.json {
+        let cards = cards
+            .iter()
+            .map(|next| {
+                json!({
+                    "card": format!("{:?}", next.card),
+                    "equity": next.equity,
+                    "change": next.equity.iter().zip(&now).map(|(a, b)| a - b).collect::<Vec<_>>(),
+                })
+            })
+            .collect::<Vec<_>>();
+        println!(
+            "{}",
+            json!({
+                "hands": args.hands,
+                "board": cards_string(&board),
+                "dead": cards_string(&dead),
+                "game": args.game.to_string(),
+                "exact": args.iterations.is_none(),
+                "equity": now,
+                "cards": cards,
+            })
+        );
+        return Ok(());
+    }
+
+    print!("{:<6}", "Card");
+    for i in 0..hands.len() {
+        print!(" {:>8}", format!("Hand {}", i + 1));
+    }
+    println!(" {:>8}", "Change");
+
+    print_row("Now", &now, None);
+    for next in &cards {
+        print_row(
+            &format!("{:?}", next.card),
+            &next.equity,
+            Some(next.equity[0] - now[0]),
+        );
+    }
+
+    Ok(())
+}
+
+/// Print one row of the table, with the change in the first hand's equity
+fn print_row(name: &str, equity: &[f64], change: Option<f64>) {
+    print!("{:<6}", name);
+    for equity in equity {
+        print!(" {:>7.2}%", equity * 100.0);
+    }
+    match change {
+        Some(change) => println!(" {:>+7.2}%", change * 100.0),
+        None => println!(),
+    }
+}
+
+/// Equity of every hand on a board, enumerated or simulated with --iterations
+/// Each board gets its own seed so the order of the cards does not matter
+fn equities(args: &Args, hands: &[Range], board: Vec<Card>, dead: &[Card], n: u64) -> Vec<f64> {
+    let hands = hands.to_vec();
+    let results = match args.iterations {
+        None => run_exact_calculation(args.game, board, dead.to_vec(), hands)
+            .expect("weighted ranges are rejected without --iterations"),
+        Some(iterations) => {
+            let settings = SimulationSettings {
+                threads: args.threads,
+                ..SimulationSettings::new(iterations)
+            };
+            let mut rng = match args.seed {
+                Some(seed) => StdRng::seed_from_u64(seed.wrapping_add(n)),
+                None => StdRng::from_entropy(),
+            };
+            run_range_calculation(args.game, board, dead.to_vec(), hands, &settings, &mut rng)
+        }
+    };
+
+    results.iter().map(|r| r.equity()).collect()
+}
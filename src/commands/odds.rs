@@ -0,0 +1,96 @@
+//! `odds` subcommand, pot odds and the equity needed to call
+
+use equity_cli::{pot_odds, required_equity, Error};
+
+use super::equity::{self, Format};
+
+/// Arguments of the odds subcommand
+#[derive(clap::Args, Debug)]
+#[group(skip)]
+pub struct Args {
+    /// Pot before the bet being faced
+    #[arg(long)]
+    pot: f64,
+
+    /// Amount to call, the same as the bet being faced
+    #[arg(long)]
+    call: f64,
+
+    /// Hands can be given to check the first hand's equity against the price
+    #[command(flatten, next_help_heading = "Equity")]
+    equity: equity::Args,
+}
+
+/// Print the pot odds, and whether to call when hands are given
+pub fn run(args: &Args) -> Result<(), Error> {
+    args.equity.check_equity_only(&[Format::Text])?;
+    let named = |e: Error| match e {
+        Error::InvalidPot(_) => e.input("--pot", &args.pot.to_string()),
+        Error::InvalidBet(_) => e.input("--call", &args.call.to_string()),
+        e => e,
+    };
+    let odds = pot_odds(args.pot, args.call).map_err(named)?;
+    let required = required_equity(args.pot, args.call)?;
+
+    println!("Pot odds: {:.2} to 1", odds);
+    println!("Required equity: {:.2}%", required * 100.0);
+
+    if args.equity.hand_names().is_empty() {
+        return Ok(());
+    }
+
+    let spot = equity::parse_spot(&args.equity)?;
+    let results = equity::calculate(&args.equity, spot);
+    let have = results[0].equity();
+
+    println!(
+        "You need {:.2}%, you have {:.2}% - {}",
+        required * 100.0,
+        have * 100.0,
+        if have >= required { "call" } else { "fold" }
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    #[command(no_binary_name = true)]
+    struct Cli {
+        #[command(flatten)]
+        args: Args,
+    }
+
+    #[test]
+    fn test_names_the_invalid_amount() {
+        let error = |argv: &[&str]| run(&Cli::parse_from(argv).args).unwrap_err().to_string();
+        assert_eq!(
+            error(&["--pot", "100", "--call", "0"]),
+            "--call \"0\": a bet or call must be more than 0, got 0"
+        );
+        assert_eq!(
+            error(&["--pot=-5", "--call", "10"]),
+            "--pot \"-5\": the pot can not be negative, got -5"
+        );
+    }
+
+    #[test]
+    fn test_rejects_unused_equity_flags() {
+        let run = |flags: &[&str]| {
+            let argv = [&["--pot", "100", "--call", "50", "AKs", "QQ"], flags].concat();
+            run(&Cli::parse_from(argv).args)
+        };
+        assert!(matches!(
+            run(&["--pairwise"]),
+            Err(Error::UnusedFlag("--pairwise"))
+        ));
+        assert!(matches!(
+            run(&["--explain", "3"]),
+            Err(Error::UnusedFlag("--explain"))
+        ));
+    }
+}
@@ -0,0 +1,174 @@
+//! `outs` subcommand, which cards still to come make a hand the best hand
+
+use equity_cli::{
+    check_duplicate_cards, find_outs, made_hand, rule_of_thumb, run_exact_calculation, Card, Error,
+    Game, Outs, Range,
+};
+use serde_json::json;
+
+use super::equity::cards_string;
+use super::parse_cards;
+
+/// Arguments of the outs subcommand
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Hole cards to count the outs of
+    /// Example: 9h8h
+    #[arg(long)]
+    hand: String,
+
+    /// Hole cards of each opponent
+    /// Example: AcAd
+    #[arg(long, required = true, num_args = 1..)]
+    vs: Vec<String>,
+
+    /// Flop or turn
+    /// Example: 7h2hKs
+    #[arg(short, long)]
+    board: String,
+
+    /// Dead cards that have been exposed or folded
+    #[arg(short, long, default_value = "")]
+    dead: String,
+
+    /// Game to count the outs in: holdem, omaha or omaha8
+    /// Only the high hand is counted in omaha8
+    #[arg(short, long, default_value_t = Game::Holdem)]
+    game: Game,
+
+    /// Print the outs as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+/// Count and print the outs of the hand
+pub fn run(args: &Args) -> Result<(), Error> {
+    if !args.game.has_board() {
+        return Err(Error::NoBoard(args.game).input("--game", &args.game.to_string()));
+    }
+    let hand = parse_hole_cards("--hand", &args.hand, args.game)?;
+    let opponents = args
+        .vs
+        .iter()
+        .map(|vs| parse_hole_cards("--vs", vs, args.game))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let board = parse_cards("--board", &args.board)?;
+    if !matches!(board.len(), 3 | 4) {
+        return Err(Error::NotFlopOrTurn(board.len()).input("--board", &args.board));
+    }
+    let dead = parse_cards("--dead", &args.dead)?;
+
+    let mut known = vec![("--hand".to_string(), hand.clone())];
+    known.extend(
+        opponents
+            .iter()
+            .enumerate()
+            .map(|(i, cards)| (format!("opponent {}", i + 1), cards.clone())),
+    );
+    known.push(("--board".to_string(), board.clone()));
+    known.push(("--dead".to_string(), dead.clone()));
+    check_duplicate_cards(&known)?;
+
+    let outs = find_outs(args.game, &hand, &opponents, &board, &dead)?;
+    let cards_to_come = 5 - board.len();
+    let estimate = rule_of_thumb(outs.clean.len(), cards_to_come);
+
+    let ranges = std::iter::once(&hand)
+        .chain(&opponents)
+        .map(|cards| Range::from_cards(cards.clone()))
+        .collect();
+    let equity = run_exact_calculation(args.game, board.clone(), dead, ranges)?[0].equity();
+
+    if args.json {
+        println!(
+            "{}",
+            json!({
+                "hand": cards_string(&hand),
+                "vs": opponents.iter().map(|o| cards_string(o)).collect::<Vec<_>>(),
+                "board": cards_string(&board),
+                "game": args.game.to_string(),
+                "clean": card_names(&outs.clean),
+                "tainted": card_names(&outs.tainted),
+                "splits": card_names(&outs.splits),
+                "unseen": outs.unseen,
+                "next_card": outs.next_card(),
+                "rule_of_thumb": estimate,
+                "equity": equity,
+            })
+        );
+        return Ok(());
+    }
+
+    print_text(args.game, &hand, &opponents, &board, &outs);
+    println!();
+    println!(
+        "Rule of {}:      {:>7.2}%",
+        cards_to_come * 2,
+        estimate.min(1.0) * 100.0
+    );
+    println!("Actual equity:  {:>7.2}%", equity * 100.0);
+
+    Ok(())
+}
+
+/// Print where the hand stands and every kind of out
+fn print_text(game: Game, hand: &[Card], opponents: &[Vec<Card>], board: &[Card], outs: &Outs) {
+    println!("Hand: {:?}", hand);
+    for (i, opponent) in opponents.iter().enumerate() {
+        println!("Opponent {}: {:?}", i + 1, opponent);
+    }
+    println!("Board: {:?}", board);
+
+    let mine = made_hand(game, hand, board);
+    let best = opponents
+        .iter()
+        .map(|opponent| made_hand(game, opponent, board))
+        .max()
+        .unwrap();
+    println!(
+        "You are {} with {}",
+        match mine.cmp(&best) {
+            std::cmp::Ordering::Greater => "ahead",
+            std::cmp::Ordering::Equal => "tied",
+            std::cmp::Ordering::Less => "behind",
+        },
+        mine
+    );
+
+    println!();
+    for (name, cards) in [
+        ("Clean outs", &outs.clean),
+        ("Tainted outs", &outs.tainted),
+        ("Split outs", &outs.splits),
+    ] {
+        let line = format!(
+            "{:<13} {:>2}: {}",
+            name,
+            cards.len(),
+            card_names(cards).join(" ")
+        );
+        println!("{}", line.trim_end());
+    }
+
+    println!();
+    println!(
+        "Next card:      {:>7.2}% ({} of {} cards)",
+        outs.next_card() * 100.0,
+        outs.clean.len(),
+        outs.unseen
+    );
+}
+
+/// Parse the hole cards of a player, checking the number of cards for the game
+fn parse_hole_cards(name: &str, value: &str, game: Game) -> Result<Vec<Card>, Error> {
+    let cards = parse_cards(name, value)?;
+    game.check_hole_cards(cards.len())
+        .map_err(|e| e.input(name, value))?;
+    Ok(cards)
+}
+
+/// Every card on its own, such as ["Ah", "Kd"]
+fn card_names(cards: &[Card]) -> Vec<String> {
+    cards.iter().map(|c| format!("{:?}", c)).collect()
+}
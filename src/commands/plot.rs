@@ -0,0 +1,281 @@
+//! Charts written to PNG or SVG files with --plot, picked by the file extension
+//! Text needs a TrueType font, read from $EQUITY_CLI_FONT or a few common
+//! system font paths
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use equity_cli::{class_name, Error};
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use plotters::style::register_font;
+
+use super::grid::{self, cell_color};
+
+/// Fonts tried when $EQUITY_CLI_FONT is not set
+const FONT_PATHS: [&str; 6] = [
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/TTF/DejaVuSans.ttf",
+    "/usr/share/fonts/dejavu/DejaVuSans.ttf",
+    "/System/Library/Fonts/Supplemental/Arial.ttf",
+    "/Library/Fonts/Arial.ttf",
+    "C:\\Windows\\Fonts\\arial.ttf",
+];
+
+/// Side of a cell of an equity grid in pixels
+const CELL: i32 = 44;
+
+/// Draw the equity grid of each named range side by side
+pub fn equity_grids(path: &Path, grids: &[(String, Vec<Option<f64>>)]) -> Result<(), Error> {
+    let panel = (CELL * 13 + 40) as u32;
+    let size = (panel * grids.len().max(1) as u32, panel + 30);
+
+    match image_format(path)? {
+        Format::Png => draw_grids(BitMapBackend::new(path, size).into_drawing_area(), grids),
+        Format::Svg => draw_grids(SVGBackend::new(path, size).into_drawing_area(), grids),
+    }
+}
+
+/// Draw a line for each hand through its equity on every street
+/// `equities[street][hand]` is the equity of a hand on that street
+pub fn street_equities(
+    path: &Path,
+    streets: &[&str],
+    hands: &[String],
+    equities: &[Vec<f64>],
+) -> Result<(), Error> {
+    let size = (800, 500);
+
+    match image_format(path)? {
+        Format::Png => draw_streets(
+            BitMapBackend::new(path, size).into_drawing_area(),
+            streets,
+            hands,
+            equities,
+        ),
+        Format::Svg => draw_streets(
+            SVGBackend::new(path, size).into_drawing_area(),
+            streets,
+            hands,
+            equities,
+        ),
+    }
+}
+
+/// Check a plot can be written to the path before any calculation is run
+pub fn check_path(path: &Path) -> Result<(), Error> {
+    image_format(path).map(|_| ())
+}
+
+/// Image formats a plot can be written as
+enum Format {
+    Png,
+    Svg,
+}
+
+/// Image format from the extension of the path, and load the font text needs
+fn image_format(path: &Path) -> Result<Format, Error> {
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_ascii_lowercase());
+    let format = match extension.as_deref() {
+        Some("png") => Format::Png,
+        Some("svg") => Format::Svg,
+        _ => return Err(Error::PlotFormat(path.display().to_string())),
+    };
+
+    load_font()?;
+    Ok(format)
+}
+
+/// Register the font for all text once, an error if none can be read
+fn load_font() -> Result<(), Error> {
+    static FONT: OnceLock<Result<(), String>> = OnceLock::new();
+
+    let loaded = FONT.get_or_init(|| {
+        let paths = match env::var_os("EQUITY_CLI_FONT") {
+            Some(path) => vec![PathBuf::from(path)],
+            None => FONT_PATHS.iter().map(PathBuf::from).collect(),
+        };
+        let bytes = paths
+            .iter()
+            .find_map(|path| fs::read(path).ok())
+            .ok_or("no font found, set EQUITY_CLI_FONT to a .ttf file")?;
+
+        // Plotters keeps the font for the rest of the program
+        register_font(
+            "sans-serif",
+            FontStyle::Normal,
+            Box::leak(bytes.into_boxed_slice()),
+        )
+        .map_err(|_| "the font could not be read, set EQUITY_CLI_FONT to a .ttf file".to_string())
+    });
+
+    loaded.clone().map_err(Error::Plot)
+}
+
+/// Turn an error from plotters into a plot error
+fn plot_error<E: std::error::Error + Send + Sync>(error: DrawingAreaErrorKind<E>) -> Error {
+    Error::Plot(error.to_string())
+}
+
+/// Draw the grids on the drawing area
+fn draw_grids<DB: DrawingBackend>(
+    root: DrawingArea<DB, Shift>,
+    grids: &[(String, Vec<Option<f64>>)],
+) -> Result<(), Error>
+where
+    DB::ErrorType: 'static,
+{
+    root.fill(&WHITE).map_err(plot_error)?;
+    let panels = root.split_evenly((1, grids.len().max(1)));
+
+    for (panel, (name, grid)) in panels.iter().zip(grids) {
+        let title = ("sans-serif", 20).into_font();
+        panel
+            .draw(&Text::new(name.clone(), (20, 12), title))
+            .map_err(plot_error)?;
+
+        for (i, cell) in grid.iter().enumerate() {
+            let (row, col) = ((i / 13) as i32, (i % 13) as i32);
+            let (x, y) = (20 + col * CELL, 45 + row * CELL);
+            let fill = match cell {
+                Some(equity) => {
+                    let (r, g, b) = cell_color(grid::Palette::Equity, *equity);
+                    RGBColor(r, g, b)
+                }
+                None => RGBColor(235, 235, 235),
+            };
+            panel
+                .draw(&Rectangle::new(
+                    [(x, y), (x + CELL - 1, y + CELL - 1)],
+                    fill.filled(),
+                ))
+                .map_err(plot_error)?;
+
+            let color = if cell.is_some() {
+                BLACK
+            } else {
+                RGBColor(160, 160, 160)
+            };
+            let label = ("sans-serif", 13).into_font().color(&color);
+            panel
+                .draw(&Text::new(
+                    class_name(i / 13, i % 13),
+                    (x + 4, y + 5),
+                    label.clone(),
+                ))
+                .map_err(plot_error)?;
+            if let Some(equity) = cell {
+                panel
+                    .draw(&Text::new(
+                        format!("{:.0}%", equity * 100.0),
+                        (x + 4, y + 24),
+                        label,
+                    ))
+                    .map_err(plot_error)?;
+            }
+        }
+    }
+
+    root.present().map_err(plot_error)
+}
+
+/// Draw the street chart on the drawing area
+fn draw_streets<DB: DrawingBackend>(
+    root: DrawingArea<DB, Shift>,
+    streets: &[&str],
+    hands: &[String],
+    equities: &[Vec<f64>],
+) -> Result<(), Error>
+where
+    DB::ErrorType: 'static,
+{
+    root.fill(&WHITE).map_err(plot_error)?;
+
+    let last = streets.len().saturating_sub(1).max(1) as f64;
+    let mut chart = ChartBuilder::on(&root)
+        .caption("Equity by street", ("sans-serif", 24))
+        .margin(20)
+        .x_label_area_size(35)
+        .y_label_area_size(50)
+        .build_cartesian_2d(-0.2..last + 0.2, 0.0..100.0)
+        .map_err(plot_error)?;
+
+    chart
+        .configure_mesh()
+        .x_labels(streets.len())
+        .x_label_formatter(&|x| {
+            let i = x.round();
+            match streets.get(i as usize) {
+                Some(street) if (x - i).abs() < 1e-6 => street.to_string(),
+                _ => String::new(),
+            }
+        })
+        .y_desc("Equity %")
+        .draw()
+        .map_err(plot_error)?;
+
+    for (hand, name) in hands.iter().enumerate() {
+        let color = Palette99::pick(hand).to_rgba();
+        let points = equities
+            .iter()
+            .enumerate()
+            .map(|(street, equity)| (street as f64, equity[hand] * 100.0))
+            .collect::<Vec<_>>();
+
+        chart
+            .draw_series(LineSeries::new(points.clone(), color.stroke_width(3)))
+            .map_err(plot_error)?
+            .label(name.clone())
+            .legend(move |(x, y)| PathElement::new([(x, y), (x + 20, y)], color.stroke_width(3)));
+        chart
+            .draw_series(
+                points
+                    .into_iter()
+                    .map(|p| Circle::new(p, 4, color.filled())),
+            )
+            .map_err(plot_error)?;
+    }
+
+    chart
+        .configure_series_labels()
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()
+        .map_err(plot_error)?;
+
+    root.present().map_err(plot_error)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_plot_formats() {
+        assert!(matches!(
+            check_path(Path::new("chart.jpg")),
+            Err(Error::PlotFormat(_))
+        ));
+        assert!(matches!(
+            check_path(Path::new("chart")),
+            Err(Error::PlotFormat(_))
+        ));
+
+        // Drawing needs a font, which not every machine has
+        if load_font().is_err() {
+            return;
+        }
+        let path = env::temp_dir().join(format!("streets-{}.SVG", std::process::id()));
+        let hands = ["AsKs".to_string(), "QhQd".to_string()];
+        let equities = [vec![0.46, 0.54], vec![0.3, 0.7], vec![1.0, 0.0]];
+        street_equities(&path, &["Preflop", "Flop", "River"], &hands, &equities).unwrap();
+        let svg = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("QhQd"));
+    }
+}
@@ -0,0 +1,127 @@
+//! `potential` subcommand, the hand strength of a hand against a range on
+//! the flop or turn, its chances of improving or being outdrawn, and the
+//! effective hand strength they make next to its equity
+
+use equity_cli::{
+    check_duplicate_cards, hand_potential, made_hand, Card, Error, Game, HandPotential, Range,
+};
+use serde_json::json;
+
+use super::equity::cards_string;
+use super::parse_cards;
+
+/// Arguments of the potential subcommand
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Hole cards to measure
+    /// Example: 9h8h
+    #[arg(long)]
+    hand: String,
+
+    /// Range of the opponent, such as "QQ+,AKs,T9s"
+    #[arg(long)]
+    range: String,
+
+    /// Flop or turn
+    /// Example: 7h2hKs
+    #[arg(short, long)]
+    board: String,
+
+    /// Dead cards that have been exposed or folded
+    #[arg(short, long, default_value = "")]
+    dead: String,
+
+    /// Game to measure the hand in: holdem, omaha or omaha8
+    /// Only the high hand is looked at in omaha8
+    #[arg(short, long, default_value_t = Game::Holdem)]
+    game: Game,
+
+    /// Print the measures as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+/// Measure and print the strength and potential of the hand
+pub fn run(args: &Args) -> Result<(), Error> {
+    if !args.game.has_board() {
+        return Err(Error::NoBoard(args.game).input("--game", &args.game.to_string()));
+    }
+    let hand = parse_cards("--hand", &args.hand)?;
+    args.game
+        .check_hole_cards(hand.len())
+        .map_err(|e| e.input("--hand", &args.hand))?;
+
+    let range =
+        Range::parse(&args.range).map_err(|e| Error::from(e).input("--range", &args.range))?;
+    for combo in range.combos() {
+        args.game
+            .check_hole_cards(combo.len())
+            .map_err(|e| e.input("--range", &args.range))?;
+    }
+
+    let board = parse_cards("--board", &args.board)?;
+    if !matches!(board.len(), 3 | 4) {
+        return Err(Error::NotFlopOrTurn(board.len()).input("--board", &args.board));
+    }
+    let dead = parse_cards("--dead", &args.dead)?;
+    check_duplicate_cards(&[
+        ("--hand".to_string(), hand.clone()),
+        ("--board".to_string(), board.clone()),
+        ("--dead".to_string(), dead.clone()),
+    ])?;
+
+    let potential = hand_potential(args.game, &hand, &range, &board, &dead)
+        .map_err(|e| e.input("--range", &args.range))?;
+
+    if args.json {
+        println!(
+            "{}",
+            json!({
+                "hand": cards_string(&hand),
+                "range": args.range,
+                "board": cards_string(&board),
+                "game": args.game.to_string(),
+                "hand_strength": potential.strength,
+                "positive_potential": potential.positive,
+                "negative_potential": potential.negative,
+                "effective_strength": potential.effective_strength(),
+                "equity": potential.equity,
+            })
+        );
+        return Ok(());
+    }
+
+    print_text(args, &hand, &board, &potential);
+    Ok(())
+}
+
+/// Print the hand, what it makes now and every measure
+fn print_text(args: &Args, hand: &[Card], board: &[Card], potential: &HandPotential) {
+    println!("Hand: {:?}", hand);
+    println!("Range: {}", args.range);
+    println!("Board: {:?}", board);
+    println!("You have {}", made_hand(args.game, hand, board));
+
+    println!();
+    for (name, value, meaning) in [
+        ("Hand strength", potential.strength, "ahead now"),
+        (
+            "Positive potential",
+            potential.positive,
+            "ahead at the river when behind now",
+        ),
+        (
+            "Negative potential",
+            potential.negative,
+            "behind at the river when ahead now",
+        ),
+        (
+            "Effective strength",
+            potential.effective_strength(),
+            "EHS, HS + (1 - HS) x PPOT",
+        ),
+        ("Equity", potential.equity, "ahead at the river"),
+    ] {
+        println!("{:<19} {:>7.2}%  {}", name, value * 100.0, meaning);
+    }
+}
@@ -0,0 +1,214 @@
+//! `preflop-matrix` subcommand, heads up equity of every starting hand
+//! against every other, cached on disk after the first run
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use equity_cli::{
+    default_threads, preflop_matrix, starting_hands, Error, StartingHandClass, STARTING_HANDS,
+};
+use indicatif::{ProgressBar, ProgressStyle};
+use serde_json::json;
+
+use super::cache::cache_path;
+use super::grid::{self, Label, Palette};
+
+/// Arguments of the preflop-matrix subcommand
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    #[command(flatten)]
+    matrix: MatrixArgs,
+
+    /// Print the matrix as JSON instead of CSV
+    #[arg(long)]
+    json: bool,
+
+    /// Two starting hands such as AKs QQ to look up a single matchup
+    /// instead of printing the whole matrix, or one to show its equity
+    /// against every starting hand on a 13x13 grid
+    #[arg(num_args = 0..=2)]
+    hands: Vec<String>,
+}
+
+/// How the matrix is calculated and cached, shared with the subcommands
+/// built on it
+#[derive(clap::Args, Debug)]
+pub struct MatrixArgs {
+    /// Iterations of each matchup
+    #[arg(short, long, default_value_t = 10_000)]
+    iterations: u32,
+
+    /// Number of worker threads
+    #[arg(short, long, default_value_t = default_threads())]
+    threads: usize,
+
+    /// Seed for the random number generator
+    #[arg(short, long, default_value_t = 1)]
+    seed: u64,
+
+    /// Cache file to read the matrix from and write it to
+    /// Defaults to a file named after the iterations and seed under ~/.cache/equity-cli
+    #[arg(long, conflicts_with = "no_cache")]
+    cache: Option<PathBuf>,
+
+    /// Neither read nor write the cache
+    #[arg(long)]
+    no_cache: bool,
+
+    /// Calculate the matrix again even if it is cached
+    #[arg(long)]
+    refresh: bool,
+
+    /// Hide the progress bar
+    #[arg(short, long)]
+    quiet: bool,
+}
+
+/// Print the matrix or a single matchup out of it
+pub fn run(args: &Args) -> Result<(), Error> {
+    let lookup = args
+        .hands
+        .iter()
+        .map(|hand| starting_hand(hand))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let matrix = load_matrix(&args.matrix)?;
+    let hands = starting_hands();
+
+    match (lookup.as_slice(), args.json) {
+        (&[a, b], false) => {
+            println!("{} vs {}: {:.2}%", hands[a], hands[b], matrix[a][b] * 100.0)
+        }
+        (&[a, b], true) => println!(
+            "{}",
+            json!({ "hand": hands[a], "other": hands[b], "equity": matrix[a][b] })
+        ),
+        (&[a], false) => {
+            println!("{} against every starting hand:", hands[a]);
+            let cells = matrix[a].iter().map(|e| Some(*e)).collect::<Vec<_>>();
+            print!(
+                "{}",
+                grid::render(&cells, Palette::Equity, Label::Percent, grid::use_color())
+            );
+        }
+        (&[a], true) => {
+            let grid = matrix[a].chunks(13).collect::<Vec<_>>();
+            println!("{}", json!({ "hand": hands[a], "equity": grid }))
+        }
+        (_, false) => print!("{}", matrix_csv(&matrix)?),
+        (_, true) => println!("{}", json!({ "hands": hands, "equity": matrix })),
+    }
+
+    Ok(())
+}
+
+/// Index of a starting hand argument
+fn starting_hand(name: &str) -> Result<usize, Error> {
+    name.parse::<StartingHandClass>()
+        .map(StartingHandClass::index)
+}
+
+/// Read the matrix from the cache, or calculate and cache it
+pub(super) fn load_matrix(args: &MatrixArgs) -> Result<Vec<Vec<f64>>, Error> {
+    let path = match (&args.cache, args.no_cache) {
+        (_, true) => None,
+        (Some(path), false) => Some(path.clone()),
+        (None, false) => cache_path(&format!(
+            "preflop-matrix-{}-{}.csv",
+            args.iterations, args.seed
+        )),
+    };
+
+    // A cache that can not be read is calculated again
+    if let (Some(path), false) = (&path, args.refresh) {
+        if let Some(matrix) = read_matrix(path) {
+            return Ok(matrix);
+        }
+    }
+
+    let bar = if args.quiet {
+        ProgressBar::hidden()
+    } else {
+        let matchups = STARTING_HANDS * (STARTING_HANDS - 1) / 2;
+        ProgressBar::new(matchups as u64).with_style(
+            ProgressStyle::with_template("{bar:40} {pos}/{len} matchups [ETA {eta}]").unwrap(),
+        )
+    };
+    let matrix = preflop_matrix(args.iterations, args.threads, args.seed, &|done| {
+        bar.set_position(done as u64)
+    });
+    bar.finish_and_clear();
+
+    if let Some(path) = path {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, matrix_csv(&matrix)?)?;
+    }
+
+    Ok(matrix)
+}
+
+/// The matrix as CSV, a header row of every hand then one row per hand
+fn matrix_csv(matrix: &[Vec<f64>]) -> Result<String, Error> {
+    let hands = starting_hands();
+    let mut writer = csv::Writer::from_writer(vec![]);
+
+    writer.write_record(std::iter::once("hand").chain(hands.iter().map(String::as_str)))?;
+    for (hand, row) in hands.iter().zip(matrix) {
+        let equities = row.iter().map(|equity| format!("{:.6}", equity));
+        writer.write_record(std::iter::once(hand.clone()).chain(equities))?;
+    }
+
+    let bytes = writer.into_inner().map_err(|e| e.into_error())?;
+    Ok(String::from_utf8(bytes).expect("CSV of ASCII names and numbers"))
+}
+
+/// Matrix from a CSV file written by `matrix_csv`, None if it is missing
+/// or not a full matrix
+fn read_matrix(path: &Path) -> Option<Vec<Vec<f64>>> {
+    let mut reader = csv::Reader::from_path(path).ok()?;
+    let hands = starting_hands();
+    if reader.headers().ok()?.iter().skip(1).ne(hands.iter()) {
+        return None;
+    }
+
+    let matrix = reader
+        .records()
+        .map(|record| {
+            let record = record.ok()?;
+            record
+                .iter()
+                .skip(1)
+                .map(|value| value.parse().ok())
+                .collect::<Option<Vec<f64>>>()
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let full = matrix.len() == STARTING_HANDS && matrix.iter().all(|r| r.len() == STARTING_HANDS);
+    full.then_some(matrix)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_matrix_csv_round_trip() {
+        let matrix = (0..STARTING_HANDS)
+            .map(|a| {
+                (0..STARTING_HANDS)
+                    .map(|b| (a * STARTING_HANDS + b) as f64 / 1e5)
+                    .collect()
+            })
+            .collect::<Vec<Vec<f64>>>();
+
+        let path = std::env::temp_dir().join(format!("preflop-matrix-{}.csv", std::process::id()));
+        fs::write(&path, matrix_csv(&matrix).unwrap()).unwrap();
+        assert_eq!(read_matrix(&path), Some(matrix));
+
+        fs::write(&path, "hand,AA\nAA,0.5\n").unwrap();
+        assert_eq!(read_matrix(&path), None);
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,77 @@
+//! `push-fold` subcommand, heads up push or fold ranges at equilibrium
+
+use equity_cli::{push_fold_chart, Error};
+use serde_json::json;
+
+use super::grid::{self, Label, Palette};
+use super::preflop_matrix::{load_matrix, MatrixArgs};
+
+/// Arguments of the push-fold subcommand
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Effective stack in big blinds, before the blinds and antes are posted
+    #[arg(long, default_value_t = 10.0)]
+    stack: f64,
+
+    /// Ante each player posts, in big blinds
+    #[arg(long, default_value_t = 0.0)]
+    ante: f64,
+
+    /// Rounds of fictitious play, more get closer to the equilibrium
+    #[arg(long, default_value_t = 2_000)]
+    rounds: u32,
+
+    /// Print how often each starting hand pushes and calls as JSON
+    #[arg(long)]
+    json: bool,
+
+    /// Equities come from the preflop matrix, cached after the first run
+    #[command(flatten, next_help_heading = "Preflop matrix")]
+    matrix: MatrixArgs,
+}
+
+/// Print the small blind's pushing range and the big blind's calling range
+pub fn run(args: &Args) -> Result<(), Error> {
+    let matrix = load_matrix(&args.matrix)?;
+    let chart = push_fold_chart(&matrix, args.stack, args.ante, args.rounds)?;
+
+    if args.json {
+        let grid = |frequencies: &[f64]| {
+            frequencies
+                .chunks(13)
+                .map(|row| row.to_vec())
+                .collect::<Vec<_>>()
+        };
+        println!(
+            "{}",
+            json!({
+                "stack": chart.stack,
+                "ante": chart.ante,
+                "push": { "share": chart.push_share(), "grid": grid(&chart.push) },
+                "call": { "share": chart.call_share(), "grid": grid(&chart.call) },
+            })
+        );
+        return Ok(());
+    }
+
+    let color = grid::use_color();
+    let render = |frequencies: &[f64]| {
+        let cells = frequencies
+            .iter()
+            .map(|f| (*f >= 0.005).then_some(*f))
+            .collect::<Vec<_>>();
+        grid::render(&cells, Palette::Frequency, Label::Hand, color)
+    };
+
+    println!(
+        "Small blind pushes {:.2}% of hands with {} bb",
+        chart.push_share() * 100.0,
+        chart.stack
+    );
+    print!("{}", render(&chart.push));
+    println!();
+    println!("Big blind calls {:.2}% of hands", chart.call_share() * 100.0);
+    print!("{}", render(&chart.call));
+
+    Ok(())
+}
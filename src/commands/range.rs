@@ -0,0 +1,67 @@
+//! `range` subcommand, the starting hands a range holds on a 13x13 grid
+
+use equity_cli::{Error, Range};
+use serde_json::json;
+
+use super::grid::{self, Label, Palette};
+
+/// Arguments of the range subcommand
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Range to show, in the same notation as the equity subcommand
+    /// Example: "QQ+,AKs,AQs:0.5" or "@co-open"
+    range: String,
+
+    /// Print how much of each starting hand is in the range as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+/// Print the grid of the range and how many combos it holds
+pub fn run(args: &Args) -> Result<(), Error> {
+    let range =
+        Range::parse(&args.range).map_err(|e| Error::from(e).input("range", &args.range))?;
+    let frequencies = range.class_frequencies();
+    let share = range.total_weight() / 1326.0;
+
+    if args.json {
+        let grid = frequencies
+            .chunks(13)
+            .map(|row| row.to_vec())
+            .collect::<Vec<_>>();
+        println!(
+            "{}",
+            json!({
+                "range": args.range,
+                "combos": range.len(),
+                "weight": range.total_weight(),
+                "share": share,
+                "grid": grid,
+            })
+        );
+        return Ok(());
+    }
+
+    let cells = frequencies
+        .iter()
+        .map(|f| (*f > 0.0).then_some(*f))
+        .collect::<Vec<_>>();
+    print!(
+        "{}",
+        grid::render(&cells, Palette::Frequency, Label::Hand, grid::use_color())
+    );
+
+    println!();
+    if range.is_weighted() {
+        println!(
+            "{} combos, {:.2} weighted, {:.2}% of all hands",
+            range.len(),
+            range.total_weight(),
+            share * 100.0
+        );
+    } else {
+        println!("{} combos, {:.2}% of all hands", range.len(), share * 100.0);
+    }
+
+    Ok(())
+}
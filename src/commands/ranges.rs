@@ -0,0 +1,25 @@
+//! `--list-ranges`, every named range that can be used as @name
+
+use equity_cli::{presets, ranges_path, Range};
+
+/// Print each named range with its number of combos and notation
+pub fn list() {
+    for preset in presets() {
+        let combos = Range::parse(&preset.range)
+            .map(|range| format!("{:>4} combos", range.len()))
+            .unwrap_or_else(|e| format!("invalid: {}", e));
+        println!("@{:<12} {}  {}", preset.name, combos, preset.description);
+        println!("    {}", preset.range);
+    }
+
+    println!("@topN%        The best N% of starting hands against a random hand, such as @top10%");
+    match ranges_path() {
+        Some(path) => println!(
+            "\nAdd or replace ranges with name = range lines in {}",
+            path.display()
+        ),
+        None => println!(
+            "\nSet EQUITY_CLI_RANGES to a file of name = range lines to add or replace ranges"
+        ),
+    }
+}
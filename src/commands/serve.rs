@@ -0,0 +1,145 @@
+//! `serve` subcommand, a small HTTP JSON API for the equity engine
+//! `POST /equity` takes a scenario object like the ones `batch` reads
+//! and answers with the same document as `equity --json`
+
+use std::io::Read;
+use std::thread;
+
+use equity_cli::{cards_from_str, default_threads, get_best_hand, EquityResult, Error};
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use super::batch::{check_scenario, json_scenario, parse_scenario};
+use super::equity::{calculate, json_output, parse_spot};
+
+/// Largest request body that is read
+const MAX_BODY: u64 = 64 * 1024;
+
+/// Arguments of the serve subcommand
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1")]
+    host: String,
+
+    /// Port to listen on
+    #[arg(short, long, default_value_t = 8080)]
+    port: u16,
+
+    /// Number of requests handled at the same time
+    #[arg(short, long, default_value_t = 4)]
+    workers: usize,
+
+    /// Worker threads of each calculation
+    #[arg(short, long, default_value_t = default_threads())]
+    threads: usize,
+
+    /// Most iterations a single request can ask for
+    #[arg(long, default_value_t = 10_000_000)]
+    max_iterations: u32,
+}
+
+/// Serve requests until the process is stopped
+pub fn run(args: &Args) -> Result<(), Error> {
+    let address = format!("{}:{}", args.host, args.port);
+    let server = Server::http(&address).map_err(std::io::Error::other)?;
+
+    // Build any evaluator tables now rather than on the first request
+    get_best_hand(&cards_from_str("AhKhQhJhTh").unwrap());
+
+    eprintln!("Listening on http://{}", address);
+    thread::scope(|scope| {
+        for _ in 0..args.workers.max(1) {
+            scope.spawn(|| loop {
+                match server.recv() {
+                    Ok(request) => handle(request, args),
+                    Err(e) => eprintln!("error: {}", e),
+                }
+            });
+        }
+    });
+
+    Ok(())
+}
+
+/// Answer a single request, failures to respond only mean the client went away
+fn handle(mut request: Request, args: &Args) {
+    let (status, body) = match (request.method(), request.url()) {
+        (Method::Post, "/equity") => {
+            let mut body = String::new();
+            let read = request.as_reader().take(MAX_BODY).read_to_string(&mut body);
+            match read {
+                Ok(_) => match equity(&body, args) {
+                    Ok(output) => (200, output),
+                    Err(message) => (400, json!({ "error": message })),
+                },
+                Err(e) => (400, json!({ "error": e.to_string() })),
+            }
+        }
+        (_, "/equity") => (405, json!({ "error": "use POST" })),
+        _ => (404, json!({ "error": "not found" })),
+    };
+
+    let header = Header::from_bytes("Content-Type", "application/json").unwrap();
+    let response = Response::from_string(body.to_string())
+        .with_status_code(status)
+        .with_header(header);
+    let _ = request.respond(response);
+}
+
+/// Run the scenario in a request body
+fn equity(body: &str, args: &Args) -> Result<Value, String> {
+    let value = serde_json::from_str(body).map_err(|e| e.to_string())?;
+    let scenario = json_scenario(1, value).map_err(|e| e.to_string())?;
+    check_scenario(1, &scenario).map_err(|e| e.to_string())?;
+
+    let equity = parse_scenario(&scenario, args.threads, None)?;
+    if !equity.exact && equity.iterations() > args.max_iterations {
+        return Err(format!(
+            "at most {} iterations can be run, got {}",
+            args.max_iterations,
+            equity.iterations()
+        ));
+    }
+
+    let spot = parse_spot(&equity).map_err(|e| e.to_string())?;
+    let (board, dead) = (spot.board.clone(), spot.dead.clone());
+    let results = calculate(&equity, spot);
+    let result = EquityResult::new(equity.hand_names(), board, dead, results);
+
+    Ok(json_output(&equity, &result))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    #[command(no_binary_name = true)]
+    struct Cli {
+        #[command(flatten)]
+        args: Args,
+    }
+
+    #[test]
+    fn test_equity_request() {
+        let args = Cli::parse_from(["--threads", "1", "--max-iterations", "5000"]).args;
+
+        let body = r#"{"hands": ["AhAs", "KdKh"], "board": "AdKsQc2c", "exact": true}"#;
+        let output = equity(body, &args).unwrap();
+        assert_eq!(output["showdowns"], 44);
+        assert_eq!(output["hands"][0]["hand"], "AhAs");
+
+        let body = r#"{"hands": "AhAs KdKh", "iterations": 1000, "seed": 3}"#;
+        let output = equity(body, &args).unwrap();
+        assert_eq!(output["showdowns"], 1000);
+
+        let body = r#"{"hands": "AhAs KdKh", "iterations": 6000}"#;
+        assert!(equity(body, &args).unwrap_err().contains("at most 5000"));
+
+        assert!(equity("not json", &args).is_err());
+        assert!(equity(r#"{"hands": "AhAs AhKh"}"#, &args).is_err());
+        assert!(equity(r#"{"hand": "AhAs KdKh"}"#, &args).is_err());
+    }
+}
@@ -0,0 +1,116 @@
+//! `split` subcommand, the combos of a range split by their equity into
+//! value, marginal hands and air
+
+use equity_cli::{split_range, Bucket, Error, RangeSplit};
+use serde_json::json;
+
+use super::equity::{self, cards_string, Format};
+use super::ev::parse_chance;
+
+/// Arguments of the split subcommand
+#[derive(clap::Args, Debug)]
+#[group(skip)]
+pub struct Args {
+    /// Equity a combo needs to count as value, as a percentage or a fraction
+    #[arg(long, default_value = "66%", value_parser = parse_chance)]
+    value: f64,
+
+    /// Equity below which a combo counts as air, as a percentage or a fraction
+    #[arg(long, default_value = "33%", value_parser = parse_chance)]
+    air: f64,
+
+    /// The first range is split by its equity against the other hands, and
+    /// --json prints the buckets with their combos
+    #[command(flatten, next_help_heading = "Equity")]
+    equity: equity::Args,
+}
+
+/// Print how many combos of the first range fall in each bucket
+pub fn run(args: &Args) -> Result<(), Error> {
+    args.equity
+        .check_equity_only(&[Format::Text, Format::Json])?;
+    let spot = equity::parse_spot(&args.equity)?;
+    let results = equity::calculate(&args.equity, spot);
+    let split = split_range(&results[0], args.air, args.value)?;
+
+    if args.equity.format() == Format::Json {
+        let buckets = Bucket::ALL
+            .iter()
+            .map(|bucket| {
+                let result = split.bucket(*bucket);
+                json!({
+                    "bucket": bucket.name().to_lowercase(),
+                    "combos": result.combos.iter().map(|c| cards_string(c)).collect::<Vec<_>>(),
+                    "share": result.share,
+                    "equity": result.equity,
+                })
+            })
+            .collect::<Vec<_>>();
+        println!(
+            "{}",
+            json!({
+                "range": args.equity.hand_names()[0],
+                "value": split.value_threshold,
+                "air": split.air_threshold,
+                "buckets": buckets,
+            })
+        );
+        return Ok(());
+    }
+
+    println!("Range: {}", args.equity.hand_names()[0]);
+    println!();
+    println!(
+        "{:<24} {:>7} {:>8} {:>8}",
+        "Bucket", "Combos", "Share", "Equity"
+    );
+    for bucket in Bucket::ALL {
+        let result = split.bucket(bucket);
+        println!(
+            "{:<24} {:>7} {:>7.2}% {:>7.2}%",
+            label(&split, bucket),
+            result.combos.len(),
+            result.share * 100.0,
+            result.equity * 100.0
+        );
+    }
+
+    Ok(())
+}
+
+/// Name of a bucket together with the equities it covers
+fn label(split: &RangeSplit, bucket: Bucket) -> String {
+    let (air, value) = (split.air_threshold * 100.0, split.value_threshold * 100.0);
+    let range = match bucket {
+        Bucket::Value => format!("{:.0}%+", value),
+        Bucket::Marginal => format!("{:.0}-{:.0}%", air, value),
+        Bucket::Air => format!("<{:.0}%", air),
+    };
+    format!("{} ({})", bucket.name(), range)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    #[command(no_binary_name = true)]
+    struct Cli {
+        #[command(flatten)]
+        args: Args,
+    }
+
+    #[test]
+    fn test_rejects_unused_equity_flags() {
+        let run = |flags: &[&str]| {
+            let argv = [&["QQ+,AKs", "JhTh", "-b", "Kh7h2c", "-q"], flags].concat();
+            run(&Cli::parse_from(argv).args)
+        };
+        assert!(matches!(run(&["--grid"]), Err(Error::UnusedFlag("--grid"))));
+        assert!(matches!(
+            run(&["--format", "csv"]),
+            Err(Error::UnusedFlag("--format"))
+        ));
+    }
+}
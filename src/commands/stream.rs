@@ -0,0 +1,139 @@
+//! `stream` subcommand, reads scenarios from stdin one per line
+//! and answers each with a result line as soon as it is done, so
+//! other programs can keep a single process running
+
+use std::io::{self, BufRead, Write};
+
+use equity_cli::{default_threads, Error, Game};
+
+use super::batch::{result_row, Scenario, HEADER};
+use super::equity::parse_error_target;
+
+/// Arguments of the stream subcommand
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Number of iterations of every scenario
+    #[arg(short, long)]
+    iterations: Option<u32>,
+
+    /// Keep simulating until the standard error of every equity is below this
+    #[arg(long, value_parser = parse_error_target)]
+    target_error: Option<f64>,
+
+    /// Game to simulate: holdem, omaha, omaha8, omaha5, courchevel, pineapple, stud, razz, deuce7 or badugi
+    #[arg(short, long, default_value_t = Game::Holdem)]
+    game: Game,
+
+    /// Enumerate every possible runout instead of sampling
+    #[arg(short, long)]
+    exact: bool,
+
+    /// Number of worker threads
+    #[arg(short, long, default_value_t = default_threads())]
+    threads: usize,
+
+    /// Seed for the random number generator of every scenario
+    #[arg(short, long)]
+    seed: Option<u64>,
+
+    /// Print the header row before reading any input
+    #[arg(long)]
+    header: bool,
+}
+
+/// Answer every line of stdin until it is closed
+/// Lines are hands separated by spaces, then optionally `|` and the board
+/// and another `|` and the dead cards, such as `AhAs KdKh | 7c8c9c`
+/// Blank lines and lines starting with # are skipped
+pub fn run(args: &Args) -> Result<(), Error> {
+    let mut writer = csv::Writer::from_writer(io::stdout());
+    if args.header {
+        writer.write_record(HEADER)?;
+        writer.flush()?;
+    }
+
+    for (i, line) in io::stdin().lock().lines().enumerate() {
+        let line = line?;
+        let Some(scenario) = parse_line(&line, args) else {
+            continue;
+        };
+
+        writer.write_record(result_row(i + 1, &scenario, args.threads, args.seed))?;
+        if let Err(e) = writer.flush() {
+            // The reading program went away
+            if e.kind() == io::ErrorKind::BrokenPipe {
+                return Ok(());
+            }
+            return Err(e.into());
+        }
+    }
+
+    io::stdout().flush()?;
+    Ok(())
+}
+
+/// Scenario of an input line with the shared options, None for lines to skip
+fn parse_line(line: &str, args: &Args) -> Option<Scenario> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+
+    let mut parts = line.splitn(3, '|').map(str::trim);
+    let mut scenario = vec![
+        ("hands", parts.next().unwrap_or_default().to_string()),
+        ("board", parts.next().unwrap_or_default().to_string()),
+        ("dead", parts.next().unwrap_or_default().to_string()),
+        ("game", args.game.to_string()),
+        ("exact", args.exact.to_string()),
+    ];
+    if let Some(iterations) = args.iterations {
+        scenario.push(("iterations", iterations.to_string()));
+    }
+    if let Some(target) = args.target_error {
+        scenario.push(("target_error", target.to_string()));
+    }
+
+    Some(
+        scenario
+            .into_iter()
+            .map(|(column, value)| (column.to_string(), value))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    #[command(no_binary_name = true)]
+    struct Cli {
+        #[command(flatten)]
+        args: Args,
+    }
+
+    #[test]
+    fn test_parse_line() {
+        let args = Cli::parse_from(["-i", "500", "-t", "1"]).args;
+
+        assert_eq!(parse_line("  ", &args), None);
+        assert_eq!(parse_line("# comment", &args), None);
+
+        let scenario = parse_line("AhAs KdKh | 7c8c9c", &args).unwrap();
+        let column = |name: &str| &scenario.iter().find(|(c, _)| c == name).unwrap().1;
+        assert_eq!(column("hands"), "AhAs KdKh");
+        assert_eq!(column("board"), "7c8c9c");
+        assert_eq!(column("dead"), "");
+        assert_eq!(column("iterations"), "500");
+
+        let row = result_row(1, &scenario, args.threads, Some(3));
+        assert_eq!(row[4], "500");
+        assert_eq!(row[7], "");
+
+        let scenario = parse_line("AhAs KdKh | | 7c", &args).unwrap();
+        let row = result_row(2, &scenario, args.threads, Some(3));
+        assert_eq!(row[3], "7c");
+    }
+}
@@ -0,0 +1,313 @@
+//! `tui` subcommand, a full screen explorer with live equity updates
+//! Hands and the board are edited in place, and the simulation restarts
+//! in small batches whenever they change
+
+use std::time::Duration;
+
+use equity_cli::{
+    cards_from_str, class_cell, class_name, run_range_calculation, Card, Error, Game, HandResult,
+    Range, SimulationSettings,
+};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Gauge, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+
+use super::equity::check_known_cards;
+
+/// Iterations run between redraws
+const BATCH: u32 = 2_000;
+
+/// Stop simulating once this many iterations have been run
+const MAX_ITERATIONS: u64 = 1_000_000;
+
+/// Arguments of the tui subcommand
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Board to start with
+    #[arg(short, long, default_value = "")]
+    board: String,
+
+    /// Hands or ranges to start with
+    #[arg()]
+    hands: Vec<String>,
+}
+
+/// Editable inputs and the results so far
+struct App {
+    /// Text of every hand followed by the board
+    fields: Vec<String>,
+    selected: usize,
+    spot: Result<(Vec<Range>, Vec<Card>), Error>,
+    results: Vec<HandResult>,
+    rng: StdRng,
+}
+
+/// Run the explorer until the user quits
+pub fn run(args: &Args) -> Result<(), Error> {
+    let mut fields = args.hands.clone();
+    while fields.len() < 2 {
+        fields.push(String::new());
+    }
+    fields.push(args.board.clone());
+
+    let mut app = App {
+        fields,
+        selected: 0,
+        spot: Err(Error::NotEnoughHands(0)),
+        results: vec![],
+        rng: StdRng::from_entropy(),
+    };
+    app.restart();
+
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &mut app);
+    ratatui::restore();
+
+    result
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, app: &mut App) -> Result<(), Error> {
+    loop {
+        terminal.draw(|frame| draw(frame, app))?;
+
+        // Only block on input once there is nothing left to simulate
+        let wait = if app.is_running() {
+            Duration::ZERO
+        } else {
+            Duration::from_millis(250)
+        };
+        if event::poll(wait)? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && !app.handle_key(key) {
+                    return Ok(());
+                }
+            }
+        }
+
+        app.step();
+    }
+}
+
+///////////////////////////////////////////////
+/// App Implementations
+///////////////////////////////////////////////
+impl App {
+    fn board_field(&self) -> usize {
+        self.fields.len() - 1
+    }
+
+    fn is_running(&self) -> bool {
+        self.spot.is_ok() && self.iterations() < MAX_ITERATIONS
+    }
+
+    fn iterations(&self) -> u64 {
+        self.results.first().map_or(0, |r| r.showdowns())
+    }
+
+    /// Handle a key press, returning false to quit
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let control = key.modifiers.contains(KeyModifiers::CONTROL);
+
+        match key.code {
+            KeyCode::Esc => return false,
+            KeyCode::Char('c') if control => return false,
+            KeyCode::Char('a') if control => {
+                self.fields.insert(self.board_field(), String::new());
+                self.selected = self.board_field() - 1;
+            }
+            KeyCode::Char('d') if control => {
+                if self.selected < self.board_field() && self.board_field() > 2 {
+                    self.fields.remove(self.selected);
+                    self.selected = self.selected.min(self.board_field() - 1);
+                }
+            }
+            KeyCode::Down | KeyCode::Tab => {
+                self.selected = (self.selected + 1) % self.fields.len();
+                return true;
+            }
+            KeyCode::Up | KeyCode::BackTab => {
+                self.selected = (self.selected + self.fields.len() - 1) % self.fields.len();
+                return true;
+            }
+            KeyCode::Backspace => {
+                self.fields[self.selected].pop();
+            }
+            KeyCode::Char(c) if !control && (c.is_ascii_alphanumeric() || ",+-".contains(c)) => {
+                self.fields[self.selected].push(c);
+            }
+            _ => return true,
+        }
+
+        self.restart();
+        true
+    }
+
+    /// Parse the fields again and throw away the results so far
+    fn restart(&mut self) {
+        self.spot = self.parse();
+        self.results = match &self.spot {
+            Ok((ranges, _)) => vec![HandResult::default(); ranges.len()],
+            Err(_) => vec![],
+        };
+    }
+
+    fn parse(&self) -> Result<(Vec<Range>, Vec<Card>), Error> {
+        let hands = &self.fields[..self.board_field()];
+        let ranges = hands
+            .iter()
+            .enumerate()
+            .map(|(i, hand)| {
+                let name = format!("hand {}", i + 1);
+                Range::parse(hand).map_err(|e| Error::from(e).input(&name, hand))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if let Some(range) = ranges.iter().find(|r| r.combos()[0].len() != 2) {
+            return Err(Error::WrongHoleCards {
+                game: Game::Holdem,
+                expected: 2,
+                found: range.combos()[0].len(),
+            });
+        }
+
+        let value = &self.fields[self.board_field()];
+        let board = cards_from_str(value).map_err(|e| Error::from(e).input("board", value))?;
+        if matches!(board.len(), 1 | 2 | 6..) {
+            return Err(Error::InvalidBoard(board.len()).input("board", value));
+        }
+
+        check_known_cards(&ranges, vec![("board".to_string(), board.clone())])?;
+        Ok((ranges, board))
+    }
+
+    /// Run one more batch of iterations and add it to the results
+    fn step(&mut self) {
+        if !self.is_running() {
+            return;
+        }
+        let Ok((ranges, board)) = &self.spot else {
+            return;
+        };
+
+        let batch = run_range_calculation(
+            Game::Holdem,
+            board.clone(),
+            vec![],
+            ranges.clone(),
+            &SimulationSettings::new(BATCH),
+            &mut self.rng,
+        );
+        for (total, result) in self.results.iter_mut().zip(&batch) {
+            total.merge(result);
+        }
+    }
+}
+
+///////////////////////////////////////////////
+/// Drawing
+///////////////////////////////////////////////
+fn draw(frame: &mut Frame, app: &App) {
+    let hands = app.board_field();
+    let [top, equity, status] = Layout::vertical([
+        Constraint::Length(15),
+        Constraint::Length(hands as u16 + 2),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let [inputs, grid] =
+        Layout::horizontal([Constraint::Min(30), Constraint::Length(13 * 4 + 2)]).areas(top);
+
+    // Inputs, the selected one is highlighted
+    let lines = app
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(i, text)| {
+            let label = if i == hands {
+                "Board:  ".to_string()
+            } else {
+                format!("Hand {}: ", i + 1)
+            };
+            let style = if i == app.selected {
+                Style::new().add_modifier(Modifier::REVERSED)
+            } else {
+                Style::new()
+            };
+            Line::from(vec![
+                Span::raw(label),
+                Span::styled(format!("{} ", text), style),
+            ])
+        })
+        .collect::<Vec<_>>();
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::bordered().title("Hands")),
+        inputs,
+    );
+
+    // Range grid of the selected hand
+    let selected = app.selected.min(hands - 1);
+    let parsed;
+    let range = match &app.spot {
+        Ok((ranges, _)) => Some(&ranges[selected]),
+        Err(_) => {
+            parsed = Range::parse(&app.fields[selected]).ok();
+            parsed.as_ref()
+        }
+    };
+    let title = format!("Range of hand {}", selected + 1);
+    frame.render_widget(
+        Paragraph::new(range_grid(range)).block(Block::bordered().title(title)),
+        grid,
+    );
+
+    // Live equity bars
+    let title = format!("Equity over {} iterations", app.iterations());
+    let block = Block::bordered().title(title);
+    let rows = Layout::vertical(vec![Constraint::Length(1); hands]).split(block.inner(equity));
+    frame.render_widget(block, equity);
+    for (i, result) in app.results.iter().enumerate() {
+        let gauge = Gauge::default()
+            .gauge_style(Style::new().fg(Color::Green))
+            .ratio(result.equity())
+            .label(format!("Hand {}: {:.2}%", i + 1, result.equity() * 100.0));
+        frame.render_widget(gauge, rows[i]);
+    }
+
+    let help = match &app.spot {
+        Ok(_) => "Tab/arrows move, type to edit, Ctrl+A add hand, Ctrl+D remove hand, Esc quit"
+            .to_string(),
+        Err(e) => format!("error: {}", e),
+    };
+    frame.render_widget(Paragraph::new(help), status);
+}
+
+/// 13x13 grid of hand classes, pairs on the diagonal, suited above and offsuit below
+/// Classes with any combo in the range are highlighted
+fn range_grid(range: Option<&Range>) -> Vec<Line<'static>> {
+    let mut held = [[false; 13]; 13];
+    for combo in range.map_or(&[][..], |r| r.combos()) {
+        let (row, col) = class_cell(combo[0], combo[1]);
+        held[row][col] = true;
+    }
+
+    (0..13)
+        .map(|row| {
+            let spans = (0..13)
+                .map(|col| {
+                    let style = if held[row][col] {
+                        Style::new().bg(Color::Yellow).fg(Color::Black)
+                    } else {
+                        Style::new().fg(Color::DarkGray)
+                    };
+                    Span::styled(format!("{:<4}", class_name(row, col)), style)
+                })
+                .collect::<Vec<_>>();
+            Line::from(spans)
+        })
+        .collect()
+}
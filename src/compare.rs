@@ -0,0 +1,333 @@
+//! Comparing the equity of a hand in two scenarios, such as on two boards
+//! or against two ranges, with common random numbers
+//! Both scenarios are dealt from the same random numbers every iteration,
+//! so the luck of the runout mostly cancels out and the change in equity
+//! is known far more precisely than from two separate simulations
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::board::Board;
+use crate::card::Card;
+use crate::deck::Deck;
+use crate::equity::{
+    check_duplicate_cards, deal_hands, deal_runout, dealt_hands, deviation, empty_results,
+    pot_share, record_showdown, remove_known_cards, run_workers, HandResult, Progress,
+    SimulationSettings, Z_95,
+};
+use crate::error::Error;
+use crate::evaluator::Evaluator;
+use crate::game::Game;
+use crate::poker_utils::{determine_low_winners_into, determine_winner_into};
+use crate::range::Range;
+
+/// One side of a comparison
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scenario {
+    pub board: Vec<Card>,
+    pub dead: Vec<Card>,
+
+    /// Range of every hand, the first being the hand compared
+    pub ranges: Vec<Range>,
+}
+
+/// Results of two scenarios played from the same random numbers
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Comparison {
+    /// Results of every hand in each scenario
+    pub results: [Vec<HandResult>; 2],
+
+    /// Number of deals played in both scenarios
+    pub deals: u64,
+
+    /// Total of the first hand's share of the pot in the second scenario
+    /// minus its share in the first, over every deal
+    pub delta: f64,
+
+    /// Sum of the squared difference in every deal
+    pub delta_squares: f64,
+}
+
+///////////////////////////////////////////////
+/// Scenario Implementations
+///////////////////////////////////////////////
+impl Scenario {
+    /// Check the scenario can be played in `game`: at least two hands with
+    /// the right number of hole cards, a valid board and no card in two places
+    pub fn check(&self, game: Game) -> Result<(), Error> {
+        if self.ranges.len() < 2 {
+            return Err(Error::NotEnoughHands(self.ranges.len()));
+        }
+        for combo in self.ranges.iter().flat_map(Range::combos) {
+            game.check_hole_cards(combo.len())?;
+        }
+        Board::new(game, self.board.clone())?;
+        if !game.has_board() && self.ranges.len() * game.hole_cards() + self.dead.len() > 52 {
+            return Err(Error::DeckTooSmall {
+                cards: game.hole_cards(),
+                players: self.ranges.len(),
+                dead: self.dead.len(),
+            });
+        }
+
+        let mut known = self
+            .ranges
+            .iter()
+            .enumerate()
+            .filter(|(_, range)| range.len() == 1)
+            .map(|(i, range)| (format!("hand {}", i + 1), range.combos()[0].clone()))
+            .collect::<Vec<_>>();
+        known.push(("board".to_string(), self.board.clone()));
+        known.push(("dead".to_string(), self.dead.clone()));
+        check_duplicate_cards(&known)
+    }
+}
+
+///////////////////////////////////////////////
+/// Comparison Implementations
+///////////////////////////////////////////////
+impl Comparison {
+    /// Empty comparison of the scenarios
+    fn new(scenarios: &[Scenario; 2]) -> Self {
+        Comparison {
+            results: scenarios.each_ref().map(|s| empty_results(&s.ranges)),
+            ..Default::default()
+        }
+    }
+
+    /// Equity of the first hand in each scenario
+    pub fn equities(&self) -> [f64; 2] {
+        self.results.each_ref().map(|results| results[0].equity())
+    }
+
+    /// Change in the first hand's equity from the first scenario to the second
+    pub fn delta(&self) -> f64 {
+        self.delta / self.deals.max(1) as f64
+    }
+
+    /// Standard error of the change in equity
+    pub fn delta_std_error(&self) -> f64 {
+        let n = self.deals.max(1) as f64;
+        deviation(self.delta, self.delta_squares, self.deals) / n.sqrt()
+    }
+
+    /// 95% confidence interval of the change in equity
+    pub fn confidence_interval(&self) -> (f64, f64) {
+        let margin = Z_95 * self.delta_std_error();
+        (self.delta() - margin, self.delta() + margin)
+    }
+
+    /// Standard error the change in equity would have had the scenarios
+    /// been simulated separately with as many deals each
+    pub fn independent_std_error(&self) -> f64 {
+        let [a, b] = self.results.each_ref().map(|r| r[0].std_error());
+        a.hypot(b)
+    }
+
+    /// Add the counts of another comparison of the same scenarios into this one
+    pub fn merge(&mut self, other: &Comparison) {
+        for (totals, results) in self.results.iter_mut().zip(&other.results) {
+            for (total, result) in totals.iter_mut().zip(results) {
+                total.merge(result);
+            }
+        }
+        self.deals += other.deals;
+        self.delta += other.delta;
+        self.delta_squares += other.delta_squares;
+    }
+}
+
+/// Simulate both scenarios from the same random numbers
+/// Every iteration deals each scenario its hands from one seed and its
+/// runout from another, so a hand in both scenarios is dealt the same cards
+/// and the boards are filled from the same shuffle
+/// Games without a board can not be compared, and `settings` can not set a
+/// target error, a sampler or a runout filter
+pub fn run_comparison<R: Rng>(
+    game: Game,
+    scenarios: [Scenario; 2],
+    settings: &SimulationSettings,
+    rng: &mut R,
+) -> Result<Comparison, Error> {
+    if !game.has_board() {
+        return Err(Error::NoBoard(game));
+    }
+    settings.check_random_runouts()?;
+
+    let scenarios = scenarios.map(|scenario| {
+        let (_, ranges) = remove_known_cards(&scenario.board, &scenario.dead, scenario.ranges);
+        Scenario { ranges, ..scenario }
+    });
+    let progress = Progress::new(settings);
+    let run = |share: u32, rng: StdRng| {
+        let evaluator = settings.evaluator;
+        simulate(game, &scenarios, share, &progress, evaluator, rng)
+    };
+    Ok(run_workers(settings, rng, run, Comparison::merge))
+}
+
+/// Run `iterations` paired deals on the current thread
+fn simulate(
+    game: Game,
+    scenarios: &[Scenario; 2],
+    iterations: u32,
+    progress: &Progress,
+    evaluator: &dyn Evaluator,
+    mut rng: StdRng,
+) -> Comparison {
+    let mut comparison = Comparison::new(scenarios);
+
+    let full_deck = Deck::new();
+    let full_deck = full_deck.remaining();
+    let mut deck = full_deck.to_vec();
+    let mut dealt = [vec![], vec![]];
+    let mut best = vec![];
+    let mut winners = vec![];
+    let mut lows = vec![];
+    let mut low_winners = vec![];
+
+    for _ in 0..iterations {
+        if progress.stopped() {
+            break;
+        }
+        progress.tick();
+
+        let hand_seed = rng.gen();
+        let board_seed = rng.gen();
+
+        // A deal only counts when it can be made in both scenarios
+        let dealt_both = scenarios.iter().zip(&mut dealt).all(|(scenario, dealt)| {
+            deal_hands(
+                &scenario.ranges,
+                &mut StdRng::seed_from_u64(hand_seed),
+                dealt,
+            )
+        });
+        if !dealt_both {
+            continue;
+        }
+
+        let mut shares = [0.0; 2];
+        for (i, scenario) in scenarios.iter().enumerate() {
+            let mut known = dealt_hands(&scenario.ranges, &dealt[i]);
+            let hands = known.len();
+            known.extend([scenario.board.as_slice(), scenario.dead.as_slice()]);
+
+            // Each scenario shuffles the same full deck and skips its own
+            // known cards, so the boards share every card they can
+            deck.copy_from_slice(full_deck);
+            let mut board_rng = StdRng::seed_from_u64(board_seed);
+            let board = deal_runout(&mut deck, &scenario.board, &known, &mut board_rng);
+
+            let known = &known[..hands];
+            determine_winner_into(game, known, board, evaluator, &mut best, &mut winners);
+            if game.is_hi_lo() {
+                determine_low_winners_into(game, known, board, &mut lows, &mut low_winners);
+            }
+            let low_winners = game.is_hi_lo().then_some(low_winners.as_slice());
+            record_showdown(
+                &mut comparison.results[i],
+                &dealt[i],
+                &best,
+                &winners,
+                low_winners,
+            );
+            shares[i] = pot_share(0, &winners, low_winners);
+        }
+
+        let delta = shares[1] - shares[0];
+        comparison.deals += 1;
+        comparison.delta += delta;
+        comparison.delta_squares += delta * delta;
+    }
+
+    comparison
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::card::cards_from_str;
+
+    fn scenario(hands: &[&str], board: &str) -> Scenario {
+        Scenario {
+            board: cards_from_str(board).unwrap(),
+            dead: vec![],
+            ranges: hands.iter().map(|h| Range::parse(h).unwrap()).collect(),
+        }
+    }
+
+    fn settings(iterations: u32) -> SimulationSettings<'static> {
+        SimulationSettings {
+            threads: 2,
+            ..SimulationSettings::new(iterations)
+        }
+    }
+
+    #[test]
+    fn test_common_random_numbers() {
+        // Against queens instead of jacks
+        let scenarios = [
+            scenario(&["AhKh", "JJ"], "7c2d5s"),
+            scenario(&["AhKh", "QQ"], "7c2d5s"),
+        ];
+        let comparison = run_comparison(
+            Game::Holdem,
+            scenarios,
+            &settings(20_000),
+            &mut StdRng::seed_from_u64(3),
+        )
+        .unwrap();
+
+        let [before, after] = comparison.equities();
+        assert_eq!(comparison.deals, 20_000);
+        assert!((comparison.delta() - (after - before)).abs() < 1e-9);
+
+        // The shared runouts make the change far more precise
+        let paired = comparison.delta_std_error();
+        assert!(paired > 0.0);
+        assert!(paired < comparison.independent_std_error() / 2.0);
+        let (low, high) = comparison.confidence_interval();
+        assert!(low < comparison.delta() && comparison.delta() < high);
+
+        let stud = [scenario(&["AhKh7c"], ""), scenario(&["AhKh7c"], "")];
+        let comparison = run_comparison(
+            Game::Stud,
+            stud,
+            &settings(100),
+            &mut StdRng::seed_from_u64(3),
+        );
+        assert!(matches!(comparison, Err(Error::NoBoard(Game::Stud))));
+
+        let settings = SimulationSettings {
+            target_error: Some(0.01),
+            ..settings(100)
+        };
+        let spot = scenario(&["AhKh", "QQ"], "");
+        let comparison = run_comparison(
+            Game::Holdem,
+            [spot.clone(), spot],
+            &settings,
+            &mut StdRng::seed_from_u64(3),
+        );
+        assert!(matches!(
+            comparison,
+            Err(Error::UnsupportedSetting("target_error"))
+        ));
+    }
+
+    #[test]
+    fn test_same_scenario_has_no_delta() {
+        let spot = scenario(&["AhKh", "QQ,JJ"], "7c2d5s");
+        let comparison = run_comparison(
+            Game::Holdem,
+            [spot.clone(), spot],
+            &settings(5_000),
+            &mut StdRng::seed_from_u64(4),
+        )
+        .unwrap();
+        assert_eq!(comparison.results[0], comparison.results[1]);
+        assert_eq!(comparison.delta(), 0.0);
+        assert_eq!(comparison.delta_std_error(), 0.0);
+    }
+}
@@ -0,0 +1,235 @@
+//! Equity split by whether the runout meets each of a set of conditions
+//! Every deal is tagged with the conditions its runout meets, so the
+//! equity when a flush comes and when it does not come from the same
+//! deals in a single pass
+
+use rand::rngs::StdRng;
+use rand::Rng;
+
+use crate::card::Card;
+use crate::equity::{
+    merge_each, remove_known_cards, run_workers, simulate_showdowns, DealShowdown, DealtResult,
+    HandResult, Progress, SimulationSettings, Stratum,
+};
+use crate::error::Error;
+use crate::game::Game;
+use crate::range::Range;
+use crate::runout::RunoutFilter;
+
+/// Shares of the pot one player wins on the runouts that meet each
+/// condition and on the ones that miss it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConditionResult {
+    /// Number of deals played
+    pub deals: u64,
+
+    /// Pot shares on the runouts meeting each condition, in the order the
+    /// conditions were given
+    pub met: Vec<Stratum>,
+
+    /// Pot shares on the runouts missing each condition
+    pub missed: Vec<Stratum>,
+
+    /// The showdown of every deal
+    pub showdowns: HandResult,
+}
+
+///////////////////////////////////////////////
+/// ConditionResult Implementations
+///////////////////////////////////////////////
+impl ConditionResult {
+    /// Empty result of a range
+    fn new(conditions: usize, showdowns: HandResult) -> Self {
+        ConditionResult {
+            met: vec![Stratum::default(); conditions],
+            missed: vec![Stratum::default(); conditions],
+            showdowns,
+            ..Default::default()
+        }
+    }
+
+    /// How often the runout met condition `i`
+    pub fn frequency(&self, i: usize) -> f64 {
+        self.met[i].showdowns as f64 / self.deals.max(1) as f64
+    }
+
+    /// Share of the pot won on average when the runout met condition `i`,
+    /// None when it never did
+    pub fn equity_met(&self, i: usize) -> Option<f64> {
+        average(&self.met[i])
+    }
+
+    /// Share of the pot won on average when the runout missed condition
+    /// `i`, None when it never did
+    pub fn equity_missed(&self, i: usize) -> Option<f64> {
+        average(&self.missed[i])
+    }
+
+    /// Add the counts of another result into this one
+    pub fn merge(&mut self, other: &ConditionResult) {
+        self.deals += other.deals;
+        for (total, stratum) in self
+            .met
+            .iter_mut()
+            .zip(&other.met)
+            .chain(self.missed.iter_mut().zip(&other.missed))
+        {
+            total.merge(stratum);
+        }
+        self.showdowns.merge(&other.showdowns);
+    }
+}
+
+impl DealtResult for ConditionResult {
+    fn showdowns(&mut self) -> &mut HandResult {
+        &mut self.showdowns
+    }
+
+    fn add_deal(&mut self) {
+        self.deals += 1;
+    }
+}
+
+fn average(stratum: &Stratum) -> Option<f64> {
+    (stratum.showdowns > 0).then(|| stratum.share / stratum.showdowns as f64)
+}
+
+/// Simulate deals from the ranges, splitting every hand's pot shares by
+/// whether the runout met each of `conditions`
+/// Games without a board have no runout to meet a condition, and `settings`
+/// can not set a target error, a sampler or a runout filter
+pub fn run_condition_calculation<R: Rng>(
+    game: Game,
+    board: Vec<Card>,
+    dead: Vec<Card>,
+    ranges: Vec<Range>,
+    conditions: &[RunoutFilter],
+    settings: &SimulationSettings,
+    rng: &mut R,
+) -> Result<Vec<ConditionResult>, Error> {
+    if !game.has_board() {
+        return Err(Error::NoBoard(game));
+    }
+    settings.check_random_runouts()?;
+    let (deck, ranges) = remove_known_cards(&board, &dead, ranges);
+    let progress = Progress::new(settings);
+    let run = |share: u32, rng: StdRng| {
+        let deal = DealShowdown::new(game, deck.remaining(), &ranges, &board, settings.evaluator);
+        let new = |_, showdowns| ConditionResult::new(conditions.len(), showdowns);
+        let mut met = vec![false; conditions.len()];
+        simulate_showdowns(deal, share, &progress, rng, new, |deal, results| {
+            // Tag the deal with every condition its runout meets
+            for (met, condition) in met.iter_mut().zip(conditions) {
+                *met = condition.matches(&deal.board, board.len());
+            }
+
+            for (player, result) in results.iter_mut().enumerate() {
+                let share = deal.pot_share(player);
+                for (i, met) in met.iter().enumerate() {
+                    match met {
+                        true => result.met[i].record(share),
+                        false => result.missed[i].record(share),
+                    }
+                }
+            }
+        })
+    };
+    Ok(run_workers(
+        settings,
+        rng,
+        run,
+        merge_each(ConditionResult::merge),
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::card::cards_from_str;
+    use crate::range::hand_ranges;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_condition_equities() {
+        let settings = SimulationSettings {
+            threads: 2,
+            ..SimulationSettings::new(20_000)
+        };
+        let conditions = ["runout:h".parse().unwrap(), "river:A".parse().unwrap()];
+        let mut rng = StdRng::seed_from_u64(29);
+        let results = run_condition_calculation(
+            Game::Holdem,
+            cards_from_str("9h6h2c").unwrap(),
+            vec![],
+            hand_ranges(&["AhKh", "QsQd"]),
+            &conditions,
+            &settings,
+            &mut rng,
+        )
+        .unwrap();
+
+        let draw = &results[0];
+        assert_eq!(draw.deals, 20_000);
+        assert_eq!(draw.showdowns.showdowns(), 20_000);
+        for i in 0..conditions.len() {
+            assert_eq!(draw.met[i].showdowns + draw.missed[i].showdowns, 20_000);
+        }
+
+        // Nine of the 45 cards to come are hearts, so a little over a third
+        // of the runouts bring the flush, which the draw nearly always wins
+        let flush = draw.frequency(0);
+        assert!((0.34..0.39).contains(&flush), "{}", flush);
+        let met = draw.equity_met(0).unwrap();
+        assert!(met > 0.95, "{}", met);
+        let missed = draw.equity_missed(0).unwrap();
+        assert!((0.25..0.35).contains(&missed), "{}", missed);
+
+        // Splitting the runouts in two keeps the overall equity
+        let overall = draw.showdowns.equity();
+        let split = flush * met + (1.0 - flush) * missed;
+        assert!((overall - split).abs() < 1e-9);
+
+        // The queens win the runouts that miss the flush, and lose more
+        // often when an ace comes on the river
+        let queens = &results[1];
+        assert!(queens.equity_met(0).unwrap() < 0.05);
+        assert!(queens.equity_met(1).unwrap() < queens.equity_missed(1).unwrap());
+    }
+
+    #[test]
+    fn test_conditions_without_a_board() {
+        let settings = SimulationSettings::new(100);
+        let mut rng = StdRng::seed_from_u64(3);
+        assert!(matches!(
+            run_condition_calculation(
+                Game::Stud,
+                vec![],
+                vec![],
+                hand_ranges(&["AhKhQh", "2c2d2s"]),
+                &["flush".parse().unwrap()],
+                &settings,
+                &mut rng,
+            ),
+            Err(Error::NoBoard(Game::Stud))
+        ));
+
+        // The conditions split the runouts, so they are never filtered
+        let filter = "no-flush".parse().unwrap();
+        let settings = SimulationSettings {
+            runout_filter: Some(&filter),
+            ..settings
+        };
+        assert!(matches!(
+            run_condition_calculation(
+                Game::Holdem,
+                vec![],
+                vec![],
+                hand_ranges(&["AsKs", "QhQd"]),
+                &["flush".parse().unwrap()],
+                &settings,
+                &mut rng,
+            ),
+            Err(Error::UnsupportedSetting("runout_filter"))
+        ));
+    }
+}
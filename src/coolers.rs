@@ -0,0 +1,285 @@
+//! How often two ranges run into the classic coolers, such as set over set
+//! or flush over flush, where both hands make the same strong category and
+//! one of them loses a big pot
+//! Every board is enumerated, so the frequencies are exact
+
+use crate::board::Street;
+use crate::card::Card;
+use crate::deck::Deck;
+use crate::error::Error;
+use crate::evaluator::EvaluatorKind;
+use crate::game::Game;
+use crate::poker_hand::{HandCategory, HandValue};
+use crate::poker_utils::{binomial, for_each_combination};
+use crate::range::Range;
+
+/// Two hands making the same strong category, one beating the other
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Cooler {
+    /// Both hands make a set, a pocket pair matching a board card
+    SetOverSet,
+    StraightOverStraight,
+    FlushOverFlush,
+    FullHouseOverFullHouse,
+}
+
+/// Frequencies of every cooler between two ranges
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Coolers {
+    /// Weight of every deal of a combo from each range and a board
+    pub deals: f64,
+
+    /// Weight of the deals with each cooler, indexed by `Cooler`
+    pub coolers: [f64; 4],
+
+    /// Weight of the deals with each cooler where the first range is on top
+    pub first_on_top: [f64; 4],
+}
+
+/// What a combo makes on one board, kept while looking for coolers
+struct Made<'a> {
+    combo: &'a [Card],
+    weight: f64,
+    value: HandValue,
+    set: bool,
+}
+
+///////////////////////////////////////////////
+/// Cooler Implementations
+///////////////////////////////////////////////
+impl Cooler {
+    /// Every cooler, weakest first
+    pub const ALL: [Cooler; 4] = [
+        Cooler::SetOverSet,
+        Cooler::StraightOverStraight,
+        Cooler::FlushOverFlush,
+        Cooler::FullHouseOverFullHouse,
+    ];
+
+    /// Name of the cooler, such as "Set over set"
+    pub fn name(self) -> &'static str {
+        match self {
+            Cooler::SetOverSet => "Set over set",
+            Cooler::StraightOverStraight => "Straight over straight",
+            Cooler::FlushOverFlush => "Flush over flush",
+            Cooler::FullHouseOverFullHouse => "Full house over full house",
+        }
+    }
+
+    /// The cooler between two different hands, if they make one
+    fn between(first: &Made, second: &Made) -> Option<Cooler> {
+        let category = first.value.category();
+        if first.value == second.value || category != second.value.category() {
+            return None;
+        }
+        match category {
+            HandCategory::ThreeOfAKind if first.set && second.set => Some(Cooler::SetOverSet),
+            HandCategory::Straight => Some(Cooler::StraightOverStraight),
+            HandCategory::Flush => Some(Cooler::FlushOverFlush),
+            HandCategory::FullHouse => Some(Cooler::FullHouseOverFullHouse),
+            _ => None,
+        }
+    }
+}
+
+///////////////////////////////////////////////
+/// Coolers Implementations
+///////////////////////////////////////////////
+impl Coolers {
+    /// Chance of a deal having the cooler
+    pub fn frequency(&self, cooler: Cooler) -> f64 {
+        self.coolers[cooler as usize] / self.deals.max(f64::MIN_POSITIVE)
+    }
+
+    /// Share of the coolers of one kind the first range wins
+    pub fn first_share(&self, cooler: Cooler) -> f64 {
+        let coolers = self.coolers[cooler as usize];
+        if coolers > 0.0 {
+            self.first_on_top[cooler as usize] / coolers
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Frequencies of the coolers between `first` and `second` with the hands
+/// shown down on `street`, over every board that can come after `board`
+/// An empty board looks at every flop, turn or river, and a flop at every
+/// runout from it
+/// Combos count by their weight in the ranges, and cards on the board or
+/// `dead` are removed from the ranges first
+pub fn find_coolers(
+    game: Game,
+    first: &Range,
+    second: &Range,
+    board: &[Card],
+    dead: &[Card],
+    street: Street,
+) -> Result<Coolers, Error> {
+    if !game.has_board() {
+        return Err(Error::NoBoard(game));
+    }
+    if !game.is_valid_board(board.len()) {
+        return Err(Error::InvalidBoard(board.len()));
+    }
+    if board.len() > street.cards() || street == Street::Preflop {
+        return Err(Error::BoardPastStreet {
+            street,
+            cards: board.len(),
+        });
+    }
+    let known = [board, dead].concat();
+    let ranges = [first.without_cards(&known), second.without_cards(&known)];
+    for (range, name) in ranges.iter().zip(["the first range", "the second range"]) {
+        if range.is_empty() {
+            return Err(Error::EmptyRange(name.to_string()));
+        }
+    }
+
+    // Every pair of combos without a card in common is dealt the same
+    // number of boards, so the deals are counted without going through them
+    let deck = Deck::without(&known);
+    let to_come = street.cards() - board.len();
+    let mut result = Coolers::default();
+    for (a, first_weight) in ranges[0].combos().iter().zip(ranges[0].weights()) {
+        for (b, second_weight) in ranges[1].combos().iter().zip(ranges[1].weights()) {
+            if !shares_a_card(a, b) {
+                let boards = binomial(deck.len() - a.len() - b.len(), to_come);
+                result.deals += first_weight * second_weight * boards as f64;
+            }
+        }
+    }
+
+    let evaluator = EvaluatorKind::default().evaluator();
+    let mut dealt = board.to_vec();
+    for_each_combination(deck.remaining(), to_come, |cards| {
+        dealt.truncate(board.len());
+        dealt.extend_from_slice(cards);
+
+        // Only hands of three of a kind or better can be in a cooler
+        let made = ranges.each_ref().map(|range| {
+            range
+                .combos()
+                .iter()
+                .zip(range.weights())
+                .filter(|(combo, _)| !shares_a_card(combo, cards))
+                .map(|(combo, weight)| Made {
+                    combo,
+                    weight: *weight,
+                    value: game.rules().best_hand(combo, &dealt, evaluator),
+                    set: is_set(combo, &dealt),
+                })
+                .filter(|made| made.value.category() >= HandCategory::ThreeOfAKind)
+                .collect::<Vec<_>>()
+        });
+
+        for a in &made[0] {
+            for b in made[1].iter().filter(|b| !shares_a_card(a.combo, b.combo)) {
+                if let Some(cooler) = Cooler::between(a, b) {
+                    let weight = a.weight * b.weight;
+                    result.coolers[cooler as usize] += weight;
+                    if a.value > b.value {
+                        result.first_on_top[cooler as usize] += weight;
+                    }
+                }
+            }
+        }
+    });
+
+    Ok(result)
+}
+
+/// Whether two sets of cards have a card in common
+fn shares_a_card(a: &[Card], b: &[Card]) -> bool {
+    a.iter().any(|card| b.contains(card))
+}
+
+/// Whether the hand holds a pocket pair of a rank on the board
+fn is_set(hand: &[Card], board: &[Card]) -> bool {
+    hand.iter().enumerate().any(|(i, a)| {
+        hand[i + 1..].iter().any(|b| b.rank == a.rank) && board.iter().any(|c| c.rank == a.rank)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::card::cards_from_str;
+
+    fn range(s: &str) -> Range {
+        Range::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_set_over_set() {
+        let flop = |board: &str| {
+            let board = cards_from_str(board).unwrap();
+            find_coolers(
+                Game::Holdem,
+                &range("KK"),
+                &range("77"),
+                &board,
+                &[],
+                Street::Flop,
+            )
+            .unwrap()
+        };
+
+        // Every flop, set over set is when the flop has a king and a seven
+        // but no other king or seven
+        let coolers = flop("");
+        let sets = (2.0 * 2.0 * 44.0) / binomial(48, 3) as f64;
+        assert!((coolers.frequency(Cooler::SetOverSet) - sets).abs() < 1e-9);
+        assert_eq!(coolers.first_share(Cooler::SetOverSet), 1.0);
+        assert_eq!(coolers.frequency(Cooler::FlushOverFlush), 0.0);
+
+        // A given flop is the only board on the flop
+        let given = flop("Kh7c2d");
+        assert_eq!(given.frequency(Cooler::SetOverSet), 1.0);
+        assert_eq!(flop("Kh8c2d").frequency(Cooler::SetOverSet), 0.0);
+    }
+
+    #[test]
+    fn test_coolers_by_the_river() {
+        let board = cards_from_str("Ah7h2h").unwrap();
+        let coolers = find_coolers(
+            Game::Holdem,
+            &range("KhQh"),
+            &range("Th9h,8h6h"),
+            &board,
+            &[],
+            Street::River,
+        )
+        .unwrap();
+
+        // Both made a flush on the flop, and it is only not a cooler when a
+        // straight flush comes or trips on the board give both a full house
+        let flushes = coolers.frequency(Cooler::FlushOverFlush);
+        assert!((0.99..1.0).contains(&flushes), "{:?}", coolers);
+        assert_eq!(coolers.first_share(Cooler::FlushOverFlush), 1.0);
+        assert!(coolers.frequency(Cooler::FullHouseOverFullHouse) == 0.0);
+
+        assert!(matches!(
+            find_coolers(
+                Game::Holdem,
+                &range("KK"),
+                &range("77"),
+                &board,
+                &[],
+                Street::Preflop
+            ),
+            Err(Error::BoardPastStreet { .. })
+        ));
+        assert!(matches!(
+            find_coolers(
+                Game::Holdem,
+                &range("AhAs"),
+                &range("77"),
+                &board,
+                &[],
+                Street::Turn
+            ),
+            Err(Error::EmptyRange(_))
+        ));
+    }
+}
@@ -0,0 +1,183 @@
+//! Every simulated deal one at a time, for statistics the simulator does
+//! not keep, such as how often a hand flops top pair
+
+use rand::Rng;
+
+use crate::card::Card;
+use crate::compare::Scenario;
+use crate::equity::{pot_share, remove_known_cards, DealShowdown};
+use crate::error::Error;
+use crate::evaluator::{Evaluator, EvaluatorKind};
+use crate::game::Game;
+use crate::poker_hand::HandValue;
+use crate::range::Range;
+
+/// One deal of a simulation, with its showdown
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulatedDeal {
+    /// Hole cards dealt to every hand, in the order of the ranges
+    pub hands: Vec<Vec<Card>>,
+
+    /// The whole board, the known cards followed by the runout
+    pub board: [Card; 5],
+
+    /// Best high hand of every hand
+    pub best: Vec<HandValue>,
+
+    /// Hands with the best high hand, more than one when they tie
+    pub winners: Vec<usize>,
+
+    /// Hands with the best qualifying low in hi-lo games, empty when no
+    /// low qualifies, and None in other games
+    pub low_winners: Option<Vec<usize>>,
+}
+
+/// Iterator dealing a scenario over and over without end, see `deals`
+pub struct Deals<R> {
+    game: Game,
+    deck: Vec<Card>,
+    ranges: Vec<Range>,
+    community: Vec<Card>,
+    evaluator: &'static dyn Evaluator,
+    rng: R,
+}
+
+///////////////////////////////////////////////
+/// SimulatedDeal Implementations
+///////////////////////////////////////////////
+impl SimulatedDeal {
+    /// Share of the pot won by a hand, split between the high and the low
+    /// in hi-lo games
+    pub fn pot_share(&self, hand: usize) -> f64 {
+        pot_share(hand, &self.winners, self.low_winners.as_deref())
+    }
+}
+
+///////////////////////////////////////////////
+/// Deals Implementations
+///////////////////////////////////////////////
+impl<R: Rng> Iterator for Deals<R> {
+    type Item = SimulatedDeal;
+
+    /// Deal the next hands and runout, None once the ranges can no longer
+    /// be dealt without sharing a card
+    fn next(&mut self) -> Option<SimulatedDeal> {
+        // Every deal is handed out as a value of its own, so its buffers are
+        // not kept for the next one
+        let mut deal = DealShowdown::new(
+            self.game,
+            &self.deck,
+            &self.ranges,
+            &self.community,
+            self.evaluator,
+        );
+        if !deal.deal(&mut self.rng) {
+            return None;
+        }
+
+        Some(SimulatedDeal {
+            hands: deal.hands.iter().map(|hand| hand.to_vec()).collect(),
+            board: deal.board,
+            low_winners: deal.low_winners().map(<[usize]>::to_vec),
+            best: deal.best,
+            winners: deal.winners,
+        })
+    }
+}
+
+/// Deal a scenario of a game with a board over and over, each time dealing
+/// every hand a random combo of its range and running out the board like
+/// `run_range_calculation`
+/// The iterator never ends on its own, so take as many deals as needed
+pub fn deals<R: Rng>(game: Game, scenario: Scenario, rng: R) -> Result<Deals<R>, Error> {
+    if !game.has_board() {
+        return Err(Error::NoBoard(game));
+    }
+    scenario.check(game)?;
+
+    let (deck, ranges) = remove_known_cards(&scenario.board, &scenario.dead, scenario.ranges);
+    Ok(Deals {
+        game,
+        deck: deck.into(),
+        ranges,
+        community: scenario.board,
+        evaluator: EvaluatorKind::default().evaluator(),
+        rng,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::card::cards_from_str;
+    use crate::hole;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn scenario(ranges: Vec<Range>) -> Scenario {
+        Scenario {
+            board: vec![],
+            dead: vec![],
+            ranges,
+        }
+    }
+
+    #[test]
+    fn test_deals() {
+        let scenario = scenario(vec![
+            Range::parse("AK").unwrap(),
+            Range::from_combo(hole!("QdQs")),
+        ]);
+        let mut top_pair = 0;
+        let mut share = 0.0;
+        for deal in deals(Game::Holdem, scenario, StdRng::seed_from_u64(1))
+            .unwrap()
+            .take(2_000)
+        {
+            let hand = &deal.hands[0];
+            assert!(hand.iter().all(|c| !deal.board.contains(c)));
+            assert!(hand.iter().all(|c| !deal.hands[1].contains(c)));
+            assert_eq!(deal.low_winners, None);
+
+            let flop_high = deal.board[..3].iter().map(|c| c.rank).max().unwrap();
+            if hand.iter().any(|c| c.rank == flop_high) {
+                top_pair += 1;
+            }
+            share += deal.pot_share(0);
+        }
+
+        // AK flops an ace or a king about a third of the time and is a
+        // small underdog to queens
+        assert!((570..780).contains(&top_pair), "{}", top_pair);
+        assert!((0.38..0.5).contains(&(share / 2_000.0)));
+    }
+
+    #[test]
+    fn test_deals_checks_the_scenario() {
+        let aces = scenario(vec![
+            Range::from_combo(hole!("AhAs")),
+            Range::from_combo(hole!("KdKh")),
+        ]);
+        let rng = || StdRng::seed_from_u64(2);
+        assert!(matches!(
+            deals(Game::Stud, aces.clone(), rng()),
+            Err(Error::NoBoard(Game::Stud))
+        ));
+        let board = Scenario {
+            board: cards_from_str("Kc7c").unwrap(),
+            ..aces.clone()
+        };
+        assert!(matches!(
+            deals(Game::Holdem, board, rng()),
+            Err(Error::InvalidBoard(2))
+        ));
+
+        let omaha = scenario(vec![
+            Range::parse("As2s3d4d").unwrap(),
+            Range::parse("KhKdQhQd").unwrap(),
+        ]);
+        let deal = deals(Game::Omaha8, omaha, rng()).unwrap().next().unwrap();
+        assert!(deal.low_winners.is_some());
+        assert_eq!(deal.hands[0].len(), 4);
+    }
+}
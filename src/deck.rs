@@ -0,0 +1,148 @@
+//! A deck of cards to draw from, used by the simulator and open to
+//! anything else that deals cards
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::card::{Card, Rank, Suit};
+
+/// The cards left to deal, drawn from the front
+/// A new deck holds all 52 cards ordered by rank and then by suit
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Deck {
+    cards: Vec<Card>,
+
+    /// Index of the next card to draw
+    next: usize,
+}
+
+///////////////////////////////////////////////
+/// Deck Implementations
+///////////////////////////////////////////////
+impl Deck {
+    /// A full deck of 52 cards
+    pub fn new() -> Self {
+        let cards = Rank::ALL
+            .into_iter()
+            .flat_map(|rank| Suit::ALL.map(|suit| Card::new(rank, suit)))
+            .collect();
+        Deck { cards, next: 0 }
+    }
+
+    /// A full deck without the given cards, such as the board and the
+    /// cards already held
+    pub fn without(cards: &[Card]) -> Self {
+        let mut deck = Deck::new();
+        deck.remove(cards);
+        deck
+    }
+
+    /// Take cards out of the cards left, keeping the rest in order
+    /// Cards that are not left in the deck are ignored
+    pub fn remove(&mut self, cards: &[Card]) {
+        let mut index = 0;
+        let next = self.next;
+        self.cards.retain(|card| {
+            index += 1;
+            index <= next || !cards.contains(card)
+        });
+    }
+
+    /// Shuffle the cards that are left
+    pub fn shuffle<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        self.cards[self.next..].shuffle(rng);
+    }
+
+    /// Draw the next card, None once the deck is empty
+    pub fn draw(&mut self) -> Option<Card> {
+        let card = self.cards.get(self.next).copied()?;
+        self.next += 1;
+        Some(card)
+    }
+
+    /// Draw the next `n` cards, None without drawing any when fewer are left
+    pub fn draw_n(&mut self, n: usize) -> Option<Vec<Card>> {
+        let end = self.next.checked_add(n)?;
+        let cards = self.cards.get(self.next..end)?.to_vec();
+        self.next = end;
+        Some(cards)
+    }
+
+    /// Throw away the next card unseen, as before dealing each street
+    pub fn burn(&mut self) {
+        self.draw();
+    }
+
+    /// The cards left, in the order they will be drawn
+    pub fn remaining(&self) -> &[Card] {
+        &self.cards[self.next..]
+    }
+
+    /// Number of cards left
+    pub fn len(&self) -> usize {
+        self.cards.len() - self.next
+    }
+
+    /// Whether every card has been drawn
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for Deck {
+    fn default() -> Self {
+        Deck::new()
+    }
+}
+
+impl From<Deck> for Vec<Card> {
+    /// The cards left in the deck
+    fn from(mut deck: Deck) -> Self {
+        deck.cards.split_off(deck.next)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::card::cards_from_str;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_draw_and_remove() {
+        let mut deck = Deck::new();
+        assert_eq!(deck.len(), 52);
+        assert_eq!(deck.remaining()[..3], cards_from_str("2s2h2c").unwrap());
+
+        assert_eq!(deck.draw(), Some(Card::new(Rank::Two, Suit::Spades)));
+        deck.burn();
+        assert_eq!(deck.draw_n(2), Some(cards_from_str("2c2d").unwrap()));
+        assert_eq!(deck.len(), 48);
+
+        // Cards already drawn are not in the deck to remove
+        let aces = cards_from_str("AsAhAcAd2s").unwrap();
+        deck.remove(&aces);
+        assert_eq!(deck.len(), 44);
+        assert!(deck.remaining().iter().all(|card| card.rank != Rank::Ace));
+        assert_eq!(Deck::without(&aces).len(), 47);
+
+        assert_eq!(deck.draw_n(45), None);
+        assert_eq!(deck.len(), 44);
+        assert_eq!(deck.draw_n(44).map(|cards| cards.len()), Some(44));
+        assert!(deck.is_empty());
+        assert_eq!(deck.draw(), None);
+    }
+
+    #[test]
+    fn test_shuffle_keeps_every_card() {
+        let mut deck = Deck::new();
+        let first = deck.draw().unwrap();
+        deck.shuffle(&mut StdRng::seed_from_u64(8));
+
+        let mut cards = Vec::from(deck.clone());
+        assert_ne!(cards, Deck::without(&[first]).remaining());
+        cards.sort();
+        assert_eq!(cards, Deck::without(&[first]).remaining());
+    }
+}
@@ -0,0 +1,129 @@
+//! The chance of a hand making a category, such as a flush, by each street
+//! Every card still to come is enumerated, so the chances are exact
+//! Only the high hand is looked at, in split pot games as well
+
+use crate::board::Street;
+use crate::card::Card;
+use crate::deck::Deck;
+use crate::error::Error;
+use crate::evaluator::EvaluatorKind;
+use crate::game::Game;
+use crate::poker_hand::HandCategory;
+use crate::poker_utils::for_each_combination;
+use crate::streets::streets_after;
+
+/// Chance of `hand` holding `target` or a better category by every street
+/// after `board`, which can have any number of cards up to the turn
+/// The cards by a street are any of the unseen cards, so the chance is the
+/// share of every set of them that makes the hand
+/// Cards held, on the board or `dead` can not come
+pub fn chance_to_make(
+    game: Game,
+    hand: &[Card],
+    board: &[Card],
+    dead: &[Card],
+    target: HandCategory,
+) -> Result<Vec<(Street, f64)>, Error> {
+    if !game.has_board() {
+        return Err(Error::NoBoard(game));
+    }
+    if board.len() >= Street::River.cards() {
+        return Err(Error::NoStreetsLeft);
+    }
+    let evaluator = EvaluatorKind::default().evaluator();
+    let unseen = Deck::without(&[hand, board, dead].concat());
+    let mut dealt = board.to_vec();
+
+    let chances = streets_after(game, board.len())
+        .into_iter()
+        .map(|street| {
+            let (mut made, mut total) = (0u64, 0u64);
+            for_each_combination(unseen.remaining(), street.cards() - board.len(), |cards| {
+                dealt.truncate(board.len());
+                dealt.extend_from_slice(cards);
+                let category = game.rules().best_hand(hand, &dealt, evaluator).category();
+                made += u64::from(category >= target);
+                total += 1;
+            });
+            (street, made as f64 / total.max(1) as f64)
+        })
+        .collect();
+    Ok(chances)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::card::cards_from_str;
+
+    fn cards(s: &str) -> Vec<Card> {
+        cards_from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_flush_draw() {
+        let chances = chance_to_make(
+            Game::Holdem,
+            &cards("9h8h"),
+            &cards("7h2hKs"),
+            &[],
+            HandCategory::Flush,
+        )
+        .unwrap();
+
+        // Nine hearts left of 47 unseen cards, one card or two to come
+        let turn = 9.0 / 47.0;
+        let river = 1.0 - (38.0 * 37.0) / (47.0 * 46.0);
+        assert_eq!(chances.len(), 2);
+        assert_eq!(chances[0].0, Street::Turn);
+        assert!((chances[0].1 - turn).abs() < 1e-9);
+        assert_eq!(chances[1].0, Street::River);
+        assert!((chances[1].1 - river).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_made_or_better() {
+        // A pair is already made, so every runout has a pair or better
+        let board = cards("9c2d");
+        let pair = chance_to_make(
+            Game::Holdem,
+            &cards("9h8h"),
+            &board,
+            &[],
+            HandCategory::Pair,
+        )
+        .unwrap();
+        assert_eq!(
+            pair.iter().map(|(street, _)| *street).collect::<Vec<_>>(),
+            Street::ALL[1..]
+        );
+        assert!(pair.iter().all(|(_, chance)| *chance == 1.0));
+
+        let quads = chance_to_make(
+            Game::Holdem,
+            &cards("9h9s"),
+            &cards("9c2d5s"),
+            &cards("9d"),
+            HandCategory::FourOfAKind,
+        )
+        .unwrap();
+        assert!(quads.iter().all(|(_, chance)| *chance == 0.0));
+        assert_eq!("two-pair".parse(), Ok(HandCategory::TwoPair));
+        assert_eq!("Straight_Flush".parse(), Ok(HandCategory::StraightFlush));
+        assert_eq!("trips".parse(), Ok(HandCategory::ThreeOfAKind));
+        assert_eq!("two-pair-or-better".parse(), Ok(HandCategory::TwoPair));
+        assert!("pairs".parse::<HandCategory>().is_err());
+
+        let river = cards("9c2d5s7hKd");
+        assert!(matches!(
+            chance_to_make(
+                Game::Holdem,
+                &cards("9h8h"),
+                &river,
+                &[],
+                HandCategory::Flush
+            ),
+            Err(Error::NoStreetsLeft)
+        ));
+    }
+}
@@ -0,0 +1,2313 @@
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering as AtomicOrdering};
+use std::thread;
+use std::time::Instant;
+
+use rand::rngs::StdRng;
+use rand::{thread_rng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::board::Board;
+use crate::canonical;
+use crate::card::Card;
+use crate::deck::Deck;
+use crate::error::Error;
+use crate::evaluator::{Evaluator, EvaluatorKind};
+use crate::game::Game;
+use crate::poker_hand::{HandCategory, HandValue, LowHand};
+use crate::poker_utils::{
+    determine_low_winners, determine_low_winners_into, determine_winner, determine_winner_into,
+    for_each_combination,
+};
+use crate::preflop::{StartingHandClass, STARTING_HANDS};
+use crate::range::Range;
+use crate::runout::RunoutFilter;
+use crate::sampler::{Sampler, Sobol, Strata, SOBOL_DIMENSIONS};
+use crate::stud;
+
+/// How many times to try dealing non-conflicting hands from the ranges
+/// before giving up on an iteration
+const MAX_DEAL_ATTEMPTS: u32 = 1000;
+
+/// How many iterations between calls to the progress callback by default
+pub const PROGRESS_INTERVAL: u32 = 1000;
+
+/// How many iterations between looking at the clock when there is a deadline
+const DEADLINE_INTERVAL: u32 = 64;
+
+/// Iterations between convergence checks when running to a target error
+pub const CONVERGENCE_BATCH: u32 = 10_000;
+
+/// Standard normal quantile for a two sided 95% confidence interval
+pub const Z_95: f64 = 1.96;
+
+/// Results of a calculation together with the spot they are for
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EquityResult {
+    /// Every hand or range as it was given, such as "AhKh" or "QQ+,AKs"
+    pub hands: Vec<String>,
+
+    pub board: Vec<Card>,
+    pub dead: Vec<Card>,
+
+    /// Iterations run, or every deal and runout of an exact enumeration
+    pub iterations: u64,
+
+    /// Results of each hand, in the same order as `hands`
+    pub per_hand: Vec<HandResult>,
+}
+
+/// Showdown outcomes for a single player
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HandResult {
+    /// Showdowns won outright
+    pub wins: u64,
+
+    /// Showdowns where the pot was split
+    pub ties: u64,
+
+    /// Showdowns lost
+    pub losses: u64,
+
+    /// Number of ties by how many players split the pot
+    /// `tie_splits[3]` is how often the pot was split three ways
+    /// Only counted in games with a single pot
+    pub tie_splits: Vec<u64>,
+
+    /// Breakdown of the results by each combo in the player's range
+    pub combos: Vec<ComboResult>,
+
+    /// Breakdown of the results by the category of the player's final hand
+    /// indexed by `HandCategory as usize`
+    pub categories: [CategoryResult; 9],
+
+    /// Shares of the high and low halves in split pot games such as Omaha hi-lo
+    /// In these games a win is a scoop of the whole pot and a tie is any
+    /// part of it
+    pub hi_lo: Option<HiLoResult>,
+
+    /// Pot shares by the stratum of the first runout card, indexed by
+    /// stratum, when simulated with `Sampler::Stratified`
+    pub strata: Vec<Stratum>,
+}
+
+/// Pot shares of a player in a split pot game
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct HiLoResult {
+    /// Total share of the pots won with the high hand
+    pub hi_share: f64,
+
+    /// Total share of the pots won with the low hand
+    pub lo_share: f64,
+
+    /// Sum of the squared share of the pot won in every showdown
+    pub share_squares: f64,
+
+    /// Showdowns where the player won the whole pot
+    pub scoops: u64,
+}
+
+/// Pot shares of a player in one stratum of a stratified simulation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Stratum {
+    /// Showdowns played in the stratum
+    pub showdowns: u64,
+
+    /// Total share of the pots won
+    pub share: f64,
+
+    /// Sum of the squared share of the pot won in every showdown
+    pub share_squares: f64,
+}
+
+/// What a single showdown meant for one player
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Outcome {
+    Win,
+    Tie,
+    Loss,
+}
+
+/// How often a player finished with one hand category and how it fared
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct CategoryResult {
+    /// Showdowns where the player's best hand was in this category
+    pub made: u64,
+
+    /// Of those, showdowns won outright
+    pub wins: u64,
+
+    /// Of those, showdowns where the pot was split
+    pub ties: u64,
+}
+
+/// Showdown results while holding one particular combo of a range
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComboResult {
+    /// The hole cards of the combo
+    pub cards: Vec<Card>,
+
+    /// Showdowns played holding this combo
+    pub showdowns: u64,
+
+    /// Total share of the pots won, a two way tie counts as half
+    pub pot_share: f64,
+}
+
+impl ComboResult {
+    /// Share of the pot won on average holding this combo
+    pub fn equity(&self) -> f64 {
+        self.pot_share / self.showdowns.max(1) as f64
+    }
+}
+
+impl Stratum {
+    /// Add the pot share of a showdown
+    pub(crate) fn record(&mut self, share: f64) {
+        self.showdowns += 1;
+        self.share += share;
+        self.share_squares += share * share;
+    }
+
+    /// Add the counts of another stratum into this one
+    pub(crate) fn merge(&mut self, other: &Stratum) {
+        self.showdowns += other.showdowns;
+        self.share += other.share;
+        self.share_squares += other.share_squares;
+    }
+}
+
+///////////////////////////////////////////////
+/// EquityResult Implementations
+///////////////////////////////////////////////
+impl EquityResult {
+    /// Results of the hands in a spot, counting the iterations from the
+    /// showdowns of the first hand
+    pub fn new(
+        hands: Vec<String>,
+        board: Vec<Card>,
+        dead: Vec<Card>,
+        per_hand: Vec<HandResult>,
+    ) -> Self {
+        EquityResult {
+            hands,
+            board,
+            dead,
+            iterations: per_hand.first().map_or(0, HandResult::showdowns),
+            per_hand,
+        }
+    }
+
+    /// Equity of every hand
+    pub fn equities(&self) -> Vec<f64> {
+        self.per_hand.iter().map(HandResult::equity).collect()
+    }
+}
+
+///////////////////////////////////////////////
+/// HandResult Implementations
+///////////////////////////////////////////////
+impl HandResult {
+    /// Empty result with a combo breakdown for every combo in the range
+    fn for_range(range: &Range) -> Self {
+        HandResult {
+            combos: range
+                .combos()
+                .iter()
+                .map(|cards| ComboResult {
+                    cards: cards.clone(),
+                    showdowns: 0,
+                    pot_share: 0.0,
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Total number of showdowns played
+    pub fn showdowns(&self) -> u64 {
+        self.wins + self.ties + self.losses
+    }
+
+    /// Fraction of showdowns won outright
+    pub fn win(&self) -> f64 {
+        self.fraction(self.wins as f64)
+    }
+
+    /// Fraction of showdowns that were split
+    pub fn tie(&self) -> f64 {
+        self.fraction(self.ties as f64)
+    }
+
+    /// Fraction of showdowns lost
+    pub fn lose(&self) -> f64 {
+        self.fraction(self.losses as f64)
+    }
+
+    /// Results for the showdowns the player finished with a category
+    pub fn category(&self, category: HandCategory) -> &CategoryResult {
+        &self.categories[category as usize]
+    }
+
+    /// Share of the pot won on average, ties count as a partial win
+    pub fn equity(&self) -> f64 {
+        if let Some(hi_lo) = &self.hi_lo {
+            return self.fraction(hi_lo.hi_share + hi_lo.lo_share);
+        }
+
+        let tie_share = self
+            .tie_splits
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(n, count)| *count as f64 / n as f64)
+            .sum::<f64>();
+
+        self.fraction(self.wins as f64 + tie_share)
+    }
+
+    /// Standard error of the equity estimate
+    /// Each showdown is one sample of the pot share won, 1 for a win,
+    /// 1/n for an n way tie and 0 for a loss
+    /// Stratified simulations only count the spread inside each stratum
+    pub fn std_error(&self) -> f64 {
+        let n = self.showdowns() as f64;
+        if n < 2.0 {
+            return 0.0;
+        }
+        if !self.strata.is_empty() {
+            let variance = self
+                .strata
+                .iter()
+                .map(|s| {
+                    s.showdowns as f64 * deviation(s.share, s.share_squares, s.showdowns).powi(2)
+                })
+                .sum::<f64>();
+            return variance.sqrt() / n;
+        }
+
+        let tie_squares = self
+            .tie_splits
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(split, count)| *count as f64 / (split * split) as f64)
+            .sum::<f64>();
+
+        let mean = self.equity();
+        let mean_square = match &self.hi_lo {
+            Some(hi_lo) => hi_lo.share_squares / n,
+            None => (self.wins as f64 + tie_squares) / n,
+        };
+        let variance = (mean_square - mean * mean).max(0.0) * n / (n - 1.0);
+
+        (variance / n).sqrt()
+    }
+
+    /// Share of the pot won on average with the high hand, in split pot games
+    pub fn hi_equity(&self) -> Option<f64> {
+        self.hi_lo.map(|hi_lo| self.fraction(hi_lo.hi_share))
+    }
+
+    /// Share of the pot won on average with the low hand, in split pot games
+    pub fn lo_equity(&self) -> Option<f64> {
+        self.hi_lo.map(|hi_lo| self.fraction(hi_lo.lo_share))
+    }
+
+    /// Fraction of showdowns where the whole pot was won, in split pot games
+    pub fn scoop(&self) -> Option<f64> {
+        self.hi_lo.map(|hi_lo| self.fraction(hi_lo.scoops as f64))
+    }
+
+    /// Spread of the equity over the combos of the range, as the share of
+    /// showdowns played with a combo whose equity falls in each of `buckets`
+    /// equal buckets from 0 to 100%
+    /// A single hand puts every showdown in one bucket
+    pub fn equity_histogram(&self, buckets: usize) -> Vec<f64> {
+        let mut histogram = vec![0.0; buckets];
+        let total = self.combos.iter().map(|c| c.showdowns).sum::<u64>();
+
+        for combo in self.combos.iter().filter(|c| c.showdowns > 0) {
+            let bucket = ((combo.equity() * buckets as f64) as usize).min(buckets - 1);
+            histogram[bucket] += combo.showdowns as f64 / total as f64;
+        }
+
+        histogram
+    }
+
+    /// Share of showdowns played with a combo whose equity is at least
+    /// `threshold`, weighing each combo of the range by how often it was dealt
+    pub fn share_at_least(&self, threshold: f64) -> f64 {
+        let total = self.combos.iter().map(|c| c.showdowns).sum::<u64>();
+        let above = self
+            .combos
+            .iter()
+            .filter(|c| c.showdowns > 0 && c.equity() >= threshold)
+            .map(|c| c.showdowns)
+            .sum::<u64>();
+
+        above as f64 / total.max(1) as f64
+    }
+
+    /// Combos whose equity is at least `threshold`, best first
+    /// Combos that never reached a showdown are left out
+    pub fn combos_at_least(&self, threshold: f64) -> Vec<&ComboResult> {
+        let mut combos = self
+            .combos
+            .iter()
+            .filter(|c| c.showdowns > 0 && c.equity() >= threshold)
+            .collect::<Vec<_>>();
+        combos.sort_by(|a, b| b.equity().total_cmp(&a.equity()));
+        combos
+    }
+
+    /// Equity of each starting hand of the range, indexed like `starting_hands`
+    /// The combos of a starting hand are added up by their showdowns
+    /// None for starting hands where no two card combo reached a showdown
+    pub fn equity_grid(&self) -> Vec<Option<f64>> {
+        let mut totals = vec![(0.0, 0); STARTING_HANDS];
+        for combo in self.combos.iter().filter(|c| c.cards.len() == 2) {
+            let class = StartingHandClass::of(combo.cards[0], combo.cards[1]);
+            let (share, showdowns) = &mut totals[class.index()];
+            *share += combo.pot_share;
+            *showdowns += combo.showdowns;
+        }
+
+        totals
+            .into_iter()
+            .map(|(share, showdowns)| (showdowns > 0).then(|| share / showdowns as f64))
+            .collect()
+    }
+
+    /// 95% confidence interval of the equity, clamped to between 0 and 1
+    pub fn confidence_interval(&self) -> (f64, f64) {
+        let margin = Z_95 * self.std_error();
+        let equity = self.equity();
+        ((equity - margin).max(0.0), (equity + margin).min(1.0))
+    }
+
+    /// Record a single showdown given the combo the player held,
+    /// the category of the hand they made and the indexes of every winner
+    /// `low_winners` is given in split pot games, empty when no low qualified
+    fn record(
+        &mut self,
+        player: usize,
+        combo: usize,
+        category: HandCategory,
+        winners: &[usize],
+        low_winners: Option<&[usize]>,
+    ) {
+        let share = |winners: &[usize]| {
+            if winners.contains(&player) {
+                1.0 / winners.len() as f64
+            } else {
+                0.0
+            }
+        };
+
+        let (outcome, pot_share) = match low_winners {
+            Some(low_winners) => {
+                let lo = (!low_winners.is_empty()).then(|| share(low_winners));
+                let scoop =
+                    winners == [player] && (low_winners.is_empty() || low_winners == [player]);
+                self.record_hi_lo(share(winners), lo, scoop)
+            }
+            None if !winners.contains(&player) => (Outcome::Loss, 0.0),
+            None if winners.len() == 1 => (Outcome::Win, 1.0),
+            None => {
+                if self.tie_splits.len() <= winners.len() {
+                    self.tie_splits.resize(winners.len() + 1, 0);
+                }
+                self.tie_splits[winners.len()] += 1;
+                (Outcome::Tie, share(winners))
+            }
+        };
+
+        let category = &mut self.categories[category as usize];
+        category.made += 1;
+        match outcome {
+            Outcome::Win => category.wins += 1,
+            Outcome::Tie => category.ties += 1,
+            Outcome::Loss => {}
+        }
+
+        if let Some(combo) = self.combos.get_mut(combo) {
+            combo.showdowns += 1;
+            combo.pot_share += pot_share;
+        }
+
+        match outcome {
+            Outcome::Win => self.wins += 1,
+            Outcome::Tie => self.ties += 1,
+            Outcome::Loss => self.losses += 1,
+        }
+    }
+
+    /// Add the pot share of a showdown to its stratum, out of `count`
+    fn record_stratum(&mut self, stratum: usize, count: usize, share: f64) {
+        if self.strata.len() < count {
+            self.strata.resize(count, Stratum::default());
+        }
+        self.strata[stratum].record(share);
+    }
+
+    /// Add the shares of both halves of a split pot, where `hi` and `lo` are
+    /// the shares of each half won, giving the outcome and the share of the
+    /// whole pot
+    fn record_hi_lo(&mut self, hi: f64, lo: Option<f64>, scoop: bool) -> (Outcome, f64) {
+        // Without a qualifying low the high hand takes the whole pot
+        let (hi, lo) = match lo {
+            Some(lo) => (hi / 2.0, lo / 2.0),
+            None => (hi, 0.0),
+        };
+        let pot_share = hi + lo;
+
+        let hi_lo = self.hi_lo.get_or_insert_with(HiLoResult::default);
+        hi_lo.hi_share += hi;
+        hi_lo.lo_share += lo;
+        hi_lo.share_squares += pot_share * pot_share;
+
+        let outcome = if scoop {
+            hi_lo.scoops += 1;
+            Outcome::Win
+        } else if pot_share > 0.0 {
+            Outcome::Tie
+        } else {
+            Outcome::Loss
+        };
+        (outcome, pot_share)
+    }
+
+    /// Add the counts of another result into this one
+    pub fn merge(&mut self, other: &HandResult) {
+        self.wins += other.wins;
+        self.ties += other.ties;
+        self.losses += other.losses;
+
+        if self.tie_splits.len() < other.tie_splits.len() {
+            self.tie_splits.resize(other.tie_splits.len(), 0);
+        }
+        for (total, count) in self.tie_splits.iter_mut().zip(&other.tie_splits) {
+            *total += count;
+        }
+
+        for (total, combo) in self.combos.iter_mut().zip(&other.combos) {
+            total.showdowns += combo.showdowns;
+            total.pot_share += combo.pot_share;
+        }
+
+        for (total, category) in self.categories.iter_mut().zip(&other.categories) {
+            total.made += category.made;
+            total.wins += category.wins;
+            total.ties += category.ties;
+        }
+
+        if let Some(other) = &other.hi_lo {
+            let total = self.hi_lo.get_or_insert_with(HiLoResult::default);
+            total.hi_share += other.hi_share;
+            total.lo_share += other.lo_share;
+            total.share_squares += other.share_squares;
+            total.scoops += other.scoops;
+        }
+
+        if self.strata.len() < other.strata.len() {
+            self.strata.resize(other.strata.len(), Stratum::default());
+        }
+        for (total, stratum) in self.strata.iter_mut().zip(&other.strata) {
+            total.merge(stratum);
+        }
+    }
+
+    fn fraction(&self, count: f64) -> f64 {
+        count / self.showdowns().max(1) as f64
+    }
+}
+
+/// Empty results for every player
+pub(crate) fn empty_results(ranges: &[Range]) -> Vec<HandResult> {
+    ranges.iter().map(HandResult::for_range).collect()
+}
+
+/// Record one showdown for every player
+/// `dealt` is the index of the combo each player held in their range
+/// and `hands` the best hand each player made, high or razz
+/// `low_winners` share the low half of the pot in split pot games
+pub(crate) fn record_showdown<H: Copy + Into<HandCategory>>(
+    results: &mut [HandResult],
+    dealt: &[usize],
+    hands: &[H],
+    winners: &[usize],
+    low_winners: Option<&[usize]>,
+) {
+    for (player, result) in results.iter_mut().enumerate() {
+        result.record(
+            player,
+            dealt[player],
+            hands[player].into(),
+            winners,
+            low_winners,
+        );
+    }
+}
+
+/// Standard deviation of samples given their sum and sum of squares
+pub(crate) fn deviation(sum: f64, squares: f64, n: u64) -> f64 {
+    if n < 2 {
+        return 0.0;
+    }
+
+    let n = n as f64;
+    let mean = sum / n;
+    ((squares / n - mean * mean).max(0.0) * n / (n - 1.0)).sqrt()
+}
+
+/// Share of a single pot won by a player, splitting it with the best low
+/// in split pot games
+pub(crate) fn pot_share(player: usize, winners: &[usize], low_winners: Option<&[usize]>) -> f64 {
+    let share = |winners: &[usize]| {
+        if winners.contains(&player) {
+            1.0 / winners.len() as f64
+        } else {
+            0.0
+        }
+    };
+
+    match low_winners {
+        Some(low_winners) if !low_winners.is_empty() => (share(winners) + share(low_winners)) / 2.0,
+        _ => share(winners),
+    }
+}
+
+/// Look up the hole cards of each player from the dealt combo indexes
+pub(crate) fn dealt_hands<'a>(ranges: &'a [Range], dealt: &[usize]) -> Vec<&'a [Card]> {
+    ranges
+        .iter()
+        .zip(dealt)
+        .map(|(range, i)| range.combos()[*i].as_slice())
+        .collect()
+}
+
+/// One deal of a simulation and its showdown, in buffers kept from one
+/// deal to the next so the loop never allocates
+/// Simulations keeping counts of their own deal and show down with it and
+/// only add their counting
+pub(crate) struct DealShowdown<'a> {
+    game: Game,
+    ranges: &'a [Range],
+    community: &'a [Card],
+    evaluator: &'a dyn Evaluator,
+
+    /// Cards left to deal the runout from
+    pub deck: Vec<Card>,
+
+    /// Combo of its range dealt to every player
+    pub dealt: Vec<usize>,
+
+    /// Hole cards of every player
+    pub hands: Vec<&'a [Card]>,
+
+    /// The whole board, the community cards and the runout
+    pub board: [Card; 5],
+
+    /// Best high hand of every player
+    pub best: Vec<HandValue>,
+
+    /// Players with the best high hand
+    pub winners: Vec<usize>,
+
+    lows: Vec<Option<LowHand>>,
+    low_winners: Vec<usize>,
+}
+
+///////////////////////////////////////////////
+/// DealShowdown Implementations
+///////////////////////////////////////////////
+impl<'a> DealShowdown<'a> {
+    /// Empty buffers for dealing the ranges from `deck`
+    pub(crate) fn new(
+        game: Game,
+        deck: &[Card],
+        ranges: &'a [Range],
+        community: &'a [Card],
+        evaluator: &'a dyn Evaluator,
+    ) -> Self {
+        let players = ranges.len();
+        DealShowdown {
+            game,
+            ranges,
+            community,
+            evaluator,
+            deck: deck.to_vec(),
+            dealt: Vec::with_capacity(players),
+            hands: Vec::with_capacity(players),
+            board: [deck[0]; 5],
+            best: Vec::with_capacity(players),
+            winners: Vec::with_capacity(players),
+            lows: Vec::with_capacity(players),
+            low_winners: Vec::with_capacity(players),
+        }
+    }
+
+    /// Deal every range a combo and a random runout, and show them down
+    /// False when the ranges can not be dealt without sharing a card
+    pub(crate) fn deal<R: Rng>(&mut self, rng: &mut R) -> bool {
+        if !self.deal_hands(rng) {
+            return false;
+        }
+        let board = deal_runout(&mut self.deck, self.community, &self.hands, rng);
+        self.show_down(board);
+        true
+    }
+
+    /// Deal every range a combo, leaving the runout to the caller
+    /// False when the ranges can not be dealt without sharing a card
+    pub(crate) fn deal_hands<R: Rng>(&mut self, rng: &mut R) -> bool {
+        if !deal_hands(self.ranges, rng, &mut self.dealt) {
+            return false;
+        }
+        self.hands.clear();
+        self.hands.extend(
+            self.ranges
+                .iter()
+                .zip(&self.dealt)
+                .map(|(range, i)| range.combos()[*i].as_slice()),
+        );
+        true
+    }
+
+    /// Deal hands and a runout until the runout meets `filter`, leaving the
+    /// showdown to the caller, and give up after `MAX_DEAL_ATTEMPTS` tries
+    /// Hands are dealt again each time, so the hands played are the ones
+    /// that go with the runouts that meet the filter
+    pub(crate) fn deal_filtered<R: Rng>(
+        &mut self,
+        filter: &RunoutFilter,
+        rng: &mut R,
+    ) -> Option<[Card; 5]> {
+        for _ in 0..MAX_DEAL_ATTEMPTS {
+            if !self.deal_hands(rng) {
+                return None;
+            }
+            let board = deal_runout(&mut self.deck, self.community, &self.hands, rng);
+            if filter.matches(&board, self.community.len()) {
+                return Some(board);
+            }
+        }
+
+        None
+    }
+
+    /// Show the dealt hands down on `board`
+    pub(crate) fn show_down(&mut self, board: [Card; 5]) {
+        self.board = board;
+        determine_winner_into(
+            self.game,
+            &self.hands,
+            board,
+            self.evaluator,
+            &mut self.best,
+            &mut self.winners,
+        );
+        if self.game.is_hi_lo() {
+            determine_low_winners_into(
+                self.game,
+                &self.hands,
+                board,
+                &mut self.lows,
+                &mut self.low_winners,
+            );
+        }
+    }
+
+    /// Best qualifying low of every player in hi-lo games, None in others
+    pub(crate) fn lows(&self) -> Option<&[Option<LowHand>]> {
+        self.game.is_hi_lo().then_some(self.lows.as_slice())
+    }
+
+    /// Players with the best low in hi-lo games, None in others
+    pub(crate) fn low_winners(&self) -> Option<&[usize]> {
+        self.game.is_hi_lo().then_some(self.low_winners.as_slice())
+    }
+
+    /// Share of the pot won by a player
+    pub(crate) fn pot_share(&self, player: usize) -> f64 {
+        pot_share(player, &self.winners, self.low_winners())
+    }
+
+    /// Add the showdown to the results of every player
+    pub(crate) fn record(&self, results: &mut [HandResult]) {
+        record_showdown(
+            results,
+            &self.dealt,
+            &self.best,
+            &self.winners,
+            self.low_winners(),
+        );
+    }
+}
+
+/// One player's result of a simulation keeping counts of its own next to
+/// the showdowns of its deals
+pub(crate) trait DealtResult {
+    /// The showdowns of every deal
+    fn showdowns(&mut self) -> &mut HandResult;
+
+    /// Count one more deal played
+    fn add_deal(&mut self);
+}
+
+/// Play `iterations` deals on the current thread with `deal`, each dealt
+/// and shown down into `showdowns` by `play`, which is false when the
+/// ranges could not be dealt
+/// The results of every player are made by `new` from their index and
+/// their empty showdowns, and `play` adds its counts to them
+pub(crate) fn simulate_deals<'a, T, N, P>(
+    mut deal: DealShowdown<'a>,
+    iterations: u32,
+    progress: &Progress,
+    mut rng: StdRng,
+    new: N,
+    mut play: P,
+) -> Vec<T>
+where
+    T: DealtResult,
+    N: Fn(usize, HandResult) -> T,
+    P: FnMut(&mut DealShowdown<'a>, &mut StdRng, &mut [HandResult], &mut [T]) -> bool,
+{
+    let mut results = empty_results(deal.ranges)
+        .into_iter()
+        .enumerate()
+        .map(|(player, showdowns)| new(player, showdowns))
+        .collect::<Vec<_>>();
+    let mut showdowns = results
+        .iter_mut()
+        .map(|result| std::mem::take(result.showdowns()))
+        .collect::<Vec<_>>();
+
+    for _ in 0..iterations {
+        if progress.stopped() {
+            break;
+        }
+        progress.tick();
+
+        if !play(&mut deal, &mut rng, &mut showdowns, &mut results) {
+            continue;
+        }
+        for result in &mut results {
+            result.add_deal();
+        }
+    }
+
+    for (result, showdowns) in results.iter_mut().zip(showdowns) {
+        *result.showdowns() = showdowns;
+    }
+    results
+}
+
+/// `simulate_deals` where every deal gets a random runout and one showdown,
+/// which `count` adds its counts for
+pub(crate) fn simulate_showdowns<T, N, C>(
+    deal: DealShowdown,
+    iterations: u32,
+    progress: &Progress,
+    rng: StdRng,
+    new: N,
+    mut count: C,
+) -> Vec<T>
+where
+    T: DealtResult,
+    N: Fn(usize, HandResult) -> T,
+    C: FnMut(&DealShowdown, &mut [T]),
+{
+    let play = |deal: &mut DealShowdown,
+                rng: &mut StdRng,
+                showdowns: &mut [HandResult],
+                results: &mut [T]| {
+        if !deal.deal(rng) {
+            return false;
+        }
+        deal.record(showdowns);
+        count(deal, results);
+        true
+    };
+    simulate_deals(deal, iterations, progress, rng, new, play)
+}
+
+/// Settings for a Monte Carlo simulation
+#[derive(Clone, Copy)]
+pub struct SimulationSettings<'a> {
+    /// Number of deals to simulate
+    /// With a `target_error` this is the most that will be run
+    pub iterations: u32,
+
+    /// Stop once the standard error of every hand's equity is at most this
+    pub target_error: Option<f64>,
+
+    /// Number of worker threads to split the iterations across
+    pub threads: usize,
+
+    /// Called with the number of completed iterations as the simulation runs
+    pub progress: &'a (dyn Fn(u32) + Sync),
+
+    /// How many iterations between calls to `progress`, never calling it
+    /// when 0
+    pub progress_interval: u32,
+
+    /// Ranks the hands at every showdown
+    pub evaluator: &'a dyn Evaluator,
+
+    /// How the runout of every deal is drawn
+    pub sampler: Sampler,
+
+    /// Only play the deals whose runout meets this filter, dealing again
+    /// until one does, with the runout always drawn at random
+    pub runout_filter: Option<&'a RunoutFilter>,
+
+    /// Stop early once this is set, such as by a Ctrl+C handler or another
+    /// thread holding it in an `Arc`, leaving the results of the iterations
+    /// run so far
+    pub stop: Option<&'a AtomicBool>,
+
+    /// Stop early at this time, like `stop`
+    pub deadline: Option<Instant>,
+}
+
+impl SimulationSettings<'_> {
+    /// Run `iterations` deals on every logical CPU without reporting progress
+    pub fn new(iterations: u32) -> Self {
+        SimulationSettings {
+            iterations,
+            target_error: None,
+            threads: default_threads(),
+            progress: &no_progress,
+            progress_interval: PROGRESS_INTERVAL,
+            evaluator: EvaluatorKind::default().evaluator(),
+            sampler: Sampler::Random,
+            runout_filter: None,
+            stop: None,
+            deadline: None,
+        }
+    }
+
+    /// An error naming `target_error`, `sampler` or `runout_filter` when it
+    /// is set, for the calculations that play every iteration with a random
+    /// runout
+    pub(crate) fn check_random_runouts(&self) -> Result<(), Error> {
+        if self.target_error.is_some() {
+            return Err(Error::UnsupportedSetting("target_error"));
+        }
+        if self.sampler != Sampler::Random {
+            return Err(Error::UnsupportedSetting("sampler"));
+        }
+        if self.runout_filter.is_some() {
+            return Err(Error::UnsupportedSetting("runout_filter"));
+        }
+        Ok(())
+    }
+}
+
+fn no_progress(_: u32) {}
+
+/// Counts iterations across worker threads and reports them to a callback
+pub(crate) struct Progress<'a> {
+    done: AtomicU32,
+    callback: &'a (dyn Fn(u32) + Sync),
+    interval: u32,
+    stop: Option<&'a AtomicBool>,
+    deadline: Option<Instant>,
+
+    /// Set once the deadline has passed
+    expired: AtomicBool,
+}
+
+impl<'a> Progress<'a> {
+    /// Start counting from zero
+    pub(crate) fn new(settings: &SimulationSettings<'a>) -> Self {
+        Progress {
+            done: AtomicU32::new(0),
+            callback: settings.progress,
+            interval: settings.progress_interval,
+            stop: settings.stop,
+            deadline: settings.deadline,
+            expired: AtomicBool::new(false),
+        }
+    }
+
+    /// Whether the simulation was asked to stop early or ran out of time
+    pub(crate) fn stopped(&self) -> bool {
+        self.expired.load(AtomicOrdering::Relaxed)
+            || self
+                .stop
+                .is_some_and(|stop| stop.load(AtomicOrdering::Relaxed))
+    }
+
+    /// Count one iteration, calling back every `interval` iterations
+    pub(crate) fn tick(&self) {
+        let done = self.done.fetch_add(1, AtomicOrdering::Relaxed) + 1;
+        if done.is_multiple_of(self.interval) {
+            (self.callback)(done);
+        }
+        if done.is_multiple_of(DEADLINE_INTERVAL)
+            && self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            self.expired.store(true, AtomicOrdering::Relaxed);
+        }
+    }
+}
+
+/// Given a game state, run simulations to determine the frequencies of winning
+/// Each iteration deals every player a random combo from their range
+/// The iterations are split evenly across worker threads, each seeded from `rng`
+/// so the same seed and thread count always give the same results
+/// With a `target_error` the iterations run in batches, stopping early once
+/// every hand's equity has converged
+/// In stud games every player is dealt the rest of their own cards instead
+/// of sharing a board
+pub fn run_out<R: Rng>(
+    game: Game,
+    deck: Deck,
+    ranges: Vec<Range>,
+    board: Board,
+    settings: &SimulationSettings,
+    rng: &mut R,
+) -> Vec<HandResult> {
+    let progress = Progress::new(settings);
+
+    let Some(target) = settings.target_error else {
+        return run_batch(
+            game,
+            deck.remaining(),
+            &ranges,
+            board.cards(),
+            settings,
+            &progress,
+            rng,
+        );
+    };
+
+    let mut results = empty_results(&ranges);
+    let mut done = 0;
+    while done < settings.iterations {
+        let batch = SimulationSettings {
+            iterations: CONVERGENCE_BATCH.min(settings.iterations - done),
+            ..*settings
+        };
+        let totals = run_batch(
+            game,
+            deck.remaining(),
+            &ranges,
+            board.cards(),
+            &batch,
+            &progress,
+            rng,
+        );
+        for (total, result) in results.iter_mut().zip(&totals) {
+            total.merge(result);
+        }
+        done += batch.iterations;
+
+        if results.iter().all(|r| r.std_error() <= target) || progress.stopped() {
+            break;
+        }
+    }
+
+    results
+}
+
+/// Split the iterations across worker threads and sum their results
+fn run_batch<R: Rng>(
+    game: Game,
+    deck: &[Card],
+    ranges: &[Range],
+    community: &[Card],
+    settings: &SimulationSettings,
+    progress: &Progress,
+    rng: &mut R,
+) -> Vec<HandResult> {
+    let work = |share: u32, rng: StdRng| {
+        simulate(
+            game, deck, ranges, community, share, progress, settings, rng,
+        )
+    };
+    run_workers(settings, rng, work, merge_each(HandResult::merge))
+}
+
+/// Split the iterations of `settings` across its worker threads, run `work`
+/// on every share with a generator seeded from `rng`, and add up what the
+/// workers return with `merge`
+/// The workers are seeded in turn, so a seed gives the same results for the
+/// same number of threads
+pub(crate) fn run_workers<T, R, W, M>(
+    settings: &SimulationSettings,
+    rng: &mut R,
+    work: W,
+    merge: M,
+) -> T
+where
+    T: Send,
+    R: Rng,
+    W: Fn(u32, StdRng) -> T + Sync,
+    M: Fn(&mut T, &T),
+{
+    let iterations = settings.iterations;
+    let threads = settings.threads.clamp(1, iterations.max(1) as usize);
+
+    // A single worker runs in place, which also keeps targets without
+    // threads such as wasm32 working
+    if threads == 1 {
+        return work(iterations, StdRng::seed_from_u64(rng.gen()));
+    }
+
+    let totals = thread::scope(|scope| {
+        let workers = (0..threads)
+            .map(|t| {
+                // Spread the remainder over the first few workers
+                let share = iterations / threads as u32
+                    + u32::from((t as u32) < iterations % threads as u32);
+                let worker_rng = StdRng::seed_from_u64(rng.gen());
+                let work = &work;
+                scope.spawn(move || work(share, worker_rng))
+            })
+            .collect::<Vec<_>>();
+
+        workers
+            .into_iter()
+            .map(|w| w.join().unwrap())
+            .collect::<Vec<_>>()
+    });
+
+    let mut totals = totals.into_iter();
+    let mut total = totals.next().unwrap();
+    for worker in totals {
+        merge(&mut total, &worker);
+    }
+    total
+}
+
+/// Merge for `run_workers` of one result per player, adding every
+/// player's result with `merge`
+pub(crate) fn merge_each<T>(merge: fn(&mut T, &T)) -> impl Fn(&mut Vec<T>, &Vec<T>) {
+    move |totals, results| {
+        for (total, result) in totals.iter_mut().zip(results) {
+            merge(total, result);
+        }
+    }
+}
+
+/// Run `iterations` simulations on the current thread
+/// Every buffer is allocated once up front so the loop itself never allocates
+#[allow(clippy::too_many_arguments)]
+fn simulate(
+    game: Game,
+    deck: &[Card],
+    ranges: &[Range],
+    community: &[Card],
+    iterations: u32,
+    progress: &Progress,
+    settings: &SimulationSettings,
+    mut rng: StdRng,
+) -> Vec<HandResult> {
+    if !game.has_board() {
+        return stud::simulate(game, deck, ranges, iterations, progress, rng);
+    }
+    let mut results = empty_results(ranges);
+    let (evaluator, sampler, filter) =
+        (settings.evaluator, settings.sampler, settings.runout_filter);
+
+    let order = deck;
+    let mut deal = DealShowdown::new(game, deck, ranges, community, evaluator);
+
+    // One stratum for every card the first runout card can be
+    let held = ranges
+        .iter()
+        .map(|range| range.combos().first().map_or(0, Vec::len))
+        .sum::<usize>();
+    // Filtered runouts are always drawn at random
+    let sampler = match filter {
+        Some(_) => Sampler::Random,
+        None => sampler,
+    };
+    let mut strata = (sampler == Sampler::Stratified && community.len() < 5)
+        .then(|| Strata::new(deal.deck.len().saturating_sub(held).max(1)));
+    let mut sobol = (sampler == Sampler::Sobol).then(|| Sobol::new(&mut rng));
+
+    // The GPU queue records showdowns without their strata
+    #[cfg(feature = "gpu")]
+    let mut queue = crate::gpu::queue(game).filter(|_| strata.is_none());
+
+    for _ in 0..iterations {
+        if progress.stopped() {
+            break;
+        }
+        progress.tick();
+
+        let (board, stratum) = if let Some(filter) = filter {
+            let Some(board) = deal.deal_filtered(filter, &mut rng) else {
+                continue;
+            };
+            (board, None)
+        } else {
+            if !deal.deal_hands(&mut rng) {
+                continue;
+            }
+
+            match (&mut strata, &mut sobol) {
+                (Some(strata), _) => {
+                    let (stratum, position) = strata.next(&mut rng);
+                    let board = deal_stratified_runout(
+                        &mut deal.deck,
+                        order,
+                        community,
+                        &deal.hands,
+                        position,
+                        &mut rng,
+                    );
+                    (board, Some((stratum, strata.len())))
+                }
+                (None, Some(sobol)) => (
+                    deal_quasi_runout(order, community, &deal.hands, sobol.next()),
+                    None,
+                ),
+                (None, None) => (
+                    deal_runout(&mut deal.deck, community, &deal.hands, &mut rng),
+                    None,
+                ),
+            }
+        };
+        #[cfg(feature = "gpu")]
+        if let Some(queue) = &mut queue {
+            queue.push(&mut results, &deal.dealt, &deal.hands, board);
+            continue;
+        }
+        deal.show_down(board);
+        deal.record(&mut results);
+
+        if let Some((stratum, count)) = stratum {
+            for (player, result) in results.iter_mut().enumerate() {
+                result.record_stratum(stratum, count, deal.pot_share(player));
+            }
+        }
+    }
+
+    #[cfg(feature = "gpu")]
+    if let Some(queue) = &mut queue {
+        queue.flush(&mut results);
+    }
+
+    results
+}
+
+/// Fill the board up to five cards from the deck, skipping any held card
+/// The deck is partially shuffled in place with Fisher-Yates, only drawing
+/// as many cards as the board needs
+pub(crate) fn deal_runout<R: Rng>(
+    deck: &mut [Card],
+    community: &[Card],
+    hands: &[&[Card]],
+    rng: &mut R,
+) -> [Card; 5] {
+    // Every slot past the community cards is overwritten below
+    let mut board = [deck[0]; 5];
+    board[..community.len()].copy_from_slice(community);
+
+    let mut filled = community.len();
+    let mut next = 0;
+    while filled < 5 {
+        let pick = rng.gen_range(next..deck.len());
+        deck.swap(next, pick);
+        let card = deck[next];
+        next += 1;
+
+        if !hands.iter().any(|hand| hand.contains(&card)) {
+            board[filled] = card;
+            filled += 1;
+        }
+    }
+
+    board
+}
+
+/// Fill the board like `deal_runout`, with the first card to come picked
+/// at `position`, from 0 to 1, along the cards of `order` no hand holds
+/// `order` is the deck before any shuffling, so a position is always the
+/// same card
+fn deal_stratified_runout<R: Rng>(
+    deck: &mut [Card],
+    order: &[Card],
+    community: &[Card],
+    hands: &[&[Card]],
+    position: f64,
+    rng: &mut R,
+) -> [Card; 5] {
+    let open = |card: &&Card| !hands.iter().any(|hand| hand.contains(card));
+    let count = order.iter().filter(open).count();
+    let index = ((position * count as f64) as usize).min(count - 1);
+    let first = order.iter().filter(open).nth(index).unwrap();
+
+    // Moved to the end of the deck, out of reach of the rest of the runout
+    let pick = deck.iter().position(|card| card == first).unwrap();
+    let last = deck.len() - 1;
+    deck.swap(pick, last);
+
+    let mut start = [deck[last]; 5];
+    start[..community.len()].copy_from_slice(community);
+    deal_runout(&mut deck[..last], &start[..=community.len()], hands, rng)
+}
+
+/// Fill the board up to five cards, picking each card at the next coordinate
+/// of `point`, from 0 to 1, along the cards of `order` not held or dealt yet
+fn deal_quasi_runout(
+    order: &[Card],
+    community: &[Card],
+    hands: &[&[Card]],
+    point: [f64; SOBOL_DIMENSIONS],
+) -> [Card; 5] {
+    let mut board = [order[0]; 5];
+    board[..community.len()].copy_from_slice(community);
+
+    for (filled, position) in (community.len()..5).zip(point) {
+        let open = |card: &&Card| {
+            !hands.iter().any(|hand| hand.contains(card)) && !board[..filled].contains(card)
+        };
+        let count = order.iter().filter(open).count();
+        let index = ((position * count as f64) as usize).min(count - 1);
+        board[filled] = *order.iter().filter(open).nth(index).unwrap();
+    }
+
+    board
+}
+
+/// Number of worker threads to use when none is given
+pub fn default_threads() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// Pick one combo from each range so that no card is used twice
+/// Conflicting deals are thrown away and redrawn so that no player is favoured
+/// Fills `dealt` with the index of the combo dealt from each range,
+/// returning false if no deal without conflicts was found
+pub(crate) fn deal_hands<R: Rng>(ranges: &[Range], rng: &mut R, dealt: &mut Vec<usize>) -> bool {
+    for _ in 0..MAX_DEAL_ATTEMPTS {
+        dealt.clear();
+        for range in ranges {
+            match range.sample_index(rng) {
+                Some(i) => dealt.push(i),
+                None => return false,
+            }
+        }
+
+        let combo = |player: usize| &ranges[player].combos()[dealt[player]];
+        let conflict = (0..ranges.len())
+            .any(|a| (a + 1..ranges.len()).any(|b| combo(a).iter().any(|c| combo(b).contains(c))));
+
+        if !conflict {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Enumerate every deal of the ranges and every runout of the board
+/// In stud games every player is dealt the rest of their own cards instead
+/// Every combo is dealt once, so weighted ranges are rejected
+pub fn run_exact(
+    game: Game,
+    deck: Deck,
+    ranges: Vec<Range>,
+    board: Board,
+) -> Result<Vec<HandResult>, Error> {
+    check_unweighted(&ranges)?;
+    Ok(enumerate(game, deck, ranges, board, None))
+}
+
+/// Check that no range is weighted, for the enumerations that deal every
+/// combo once
+fn check_unweighted(ranges: &[Range]) -> Result<(), Error> {
+    if ranges.iter().any(Range::is_weighted) {
+        return Err(Error::WeightedExact);
+    }
+    Ok(())
+}
+
+/// Enumerate like `run_exact`, skipping the runouts that do not meet `filter`
+fn enumerate(
+    game: Game,
+    deck: Deck,
+    ranges: Vec<Range>,
+    board: Board,
+    filter: Option<&RunoutFilter>,
+) -> Vec<HandResult> {
+    if !game.has_board() {
+        return stud::run_exact(game, deck, ranges);
+    }
+    let community = board.cards();
+    let mut results = empty_results(&ranges);
+    #[cfg(feature = "gpu")]
+    let mut queue = crate::gpu::queue(game);
+
+    for_each_deal(&ranges, &mut vec![], &mut |dealt| {
+        let hands = dealt_hands(&ranges, dealt);
+        let dead_cards = hands.iter().copied().flatten().cloned().collect::<Vec<_>>();
+        let mut deck = deck.clone();
+        deck.remove(&dead_cards);
+        let missing = 5 - community.len();
+
+        for_each_combination(deck.remaining(), missing, |runout| {
+            let mut board = community.to_vec();
+            board.extend_from_slice(runout);
+
+            let board = board.try_into().unwrap();
+            if filter.is_some_and(|filter| !filter.matches(&board, community.len())) {
+                return;
+            }
+            #[cfg(feature = "gpu")]
+            if let Some(queue) = &mut queue {
+                queue.push(&mut results, dealt, &hands, board);
+                return;
+            }
+            let (idx, best) = determine_winner(game, &hands, board);
+            let low = game
+                .is_hi_lo()
+                .then(|| determine_low_winners(game, &hands, board));
+            record_showdown(&mut results, dealt, &best, &idx, low.as_deref());
+        });
+    });
+
+    #[cfg(feature = "gpu")]
+    if let Some(queue) = &mut queue {
+        queue.flush(&mut results);
+    }
+
+    results
+}
+
+/// Call `f` with every assignment of one combo per range that shares no cards
+/// Each assignment is given as the index of the combo in each range
+pub(crate) fn for_each_deal<F: FnMut(&[usize])>(
+    ranges: &[Range],
+    dealt: &mut Vec<usize>,
+    f: &mut F,
+) {
+    if dealt.len() == ranges.len() {
+        f(dealt);
+        return;
+    }
+
+    for (i, combo) in ranges[dealt.len()].combos().iter().enumerate() {
+        let conflict = dealt_hands(ranges, dealt)
+            .into_iter()
+            .flatten()
+            .any(|c| combo.contains(c));
+        if conflict {
+            continue;
+        }
+
+        dealt.push(i);
+        for_each_deal(ranges, dealt, f);
+        dealt.pop();
+    }
+}
+
+/// Run the actual Caculation
+pub fn run_calculation(board: Vec<Card>, hands: Vec<[Card; 2]>, iterations: u32) -> EquityResult {
+    run_calculation_with(board, hands, &SimulationSettings::new(iterations))
+}
+
+/// Run the calculation with settings, such as a progress callback and a flag
+/// to cancel it from another thread
+/// A cancelled run keeps the iterations done so far in its results
+pub fn run_calculation_with(
+    board: Vec<Card>,
+    hands: Vec<[Card; 2]>,
+    settings: &SimulationSettings,
+) -> EquityResult {
+    let names = hands
+        .iter()
+        .map(|[a, b]| format!("{:?}{:?}", a, b))
+        .collect();
+    let ranges = hands.into_iter().map(Range::from_combo).collect();
+    let results = run_range_calculation(
+        Game::Holdem,
+        board.clone(),
+        vec![],
+        ranges,
+        settings,
+        &mut thread_rng(),
+    );
+    EquityResult::new(names, board, vec![], results)
+}
+
+/// Run the calculation where each player holds a range of hands
+/// `dead` cards are removed from the deck and can never be dealt
+pub fn run_range_calculation<R: Rng>(
+    game: Game,
+    board: Vec<Card>,
+    dead: Vec<Card>,
+    ranges: Vec<Range>,
+    settings: &SimulationSettings,
+    rng: &mut R,
+) -> Vec<HandResult> {
+    let (deck, ranges) = remove_known_cards(&board, &dead, ranges);
+    run_out(
+        game,
+        deck,
+        ranges,
+        Board::from_checked(board),
+        settings,
+        rng,
+    )
+}
+
+/// Run an exact enumeration of every runout
+/// Results are memoized, so a spot that only differs from an earlier one
+/// by its suits is not enumerated again
+/// Weighted ranges can not be enumerated and return `Error::WeightedExact`
+pub fn run_exact_calculation(
+    game: Game,
+    board: Vec<Card>,
+    dead: Vec<Card>,
+    ranges: Vec<Range>,
+) -> Result<Vec<HandResult>, Error> {
+    check_unweighted(&ranges)?;
+    let (deck, ranges) = remove_known_cards(&board, &dead, ranges);
+    Ok(canonical::memoize(game, &ranges, &board, &dead, || {
+        enumerate(
+            game,
+            deck,
+            ranges.clone(),
+            Board::from_checked(board.clone()),
+            None,
+        )
+    }))
+}
+
+/// Run an exact enumeration of only the runouts that meet `filter`
+/// Filtered results are not memoized, as the filter tells the suits apart
+pub fn run_filtered_exact_calculation(
+    game: Game,
+    board: Vec<Card>,
+    dead: Vec<Card>,
+    ranges: Vec<Range>,
+    filter: &RunoutFilter,
+) -> Result<Vec<HandResult>, Error> {
+    check_unweighted(&ranges)?;
+    let (deck, ranges) = remove_known_cards(&board, &dead, ranges);
+    Ok(enumerate(
+        game,
+        deck,
+        ranges,
+        Board::from_checked(board),
+        Some(filter),
+    ))
+}
+
+/// Check that no card is used twice across named groups of known cards
+/// such as each player's hole cards, the board and the dead cards
+pub fn check_duplicate_cards(groups: &[(String, Vec<Card>)]) -> Result<(), Error> {
+    let mut seen: Vec<(Card, &str)> = vec![];
+
+    for (name, cards) in groups {
+        for card in cards {
+            if let Some((_, first)) = seen.iter().find(|(c, _)| c == card) {
+                return Err(Error::DuplicateCard {
+                    card: *card,
+                    first: first.to_string(),
+                    second: name.clone(),
+                });
+            }
+            seen.push((*card, name));
+        }
+    }
+
+    Ok(())
+}
+
+/// Build the deck without the board and dead cards
+/// and drop every combo that uses one of them
+pub(crate) fn remove_known_cards(
+    board: &[Card],
+    dead: &[Card],
+    ranges: Vec<Range>,
+) -> (Deck, Vec<Range>) {
+    let known = board.iter().chain(dead).cloned().collect::<Vec<_>>();
+
+    let deck = Deck::without(&known);
+    let ranges = ranges.iter().map(|r| r.without_cards(&known)).collect();
+
+    (deck, ranges)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::card::{cards_from_str, Rank};
+    use crate::hole;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_aces_vs_kings() {
+        let result = run_calculation(vec![], vec![hole!("AhAs"), hole!("KdKh")], 10_000);
+        assert_eq!(result.hands, ["AhAs", "KdKh"]);
+        assert_eq!(result.iterations, 10_000);
+
+        // AA should be ~ 2
+        let [aces, kings] = result.per_hand.as_slice() else {
+            panic!("expected two hands, got {:?}", result.per_hand);
+        };
+        assert!(aces.equity() > 0.80, "actual: {:?}", aces);
+        assert!(kings.equity() < 0.20, "actual: {:?}", kings);
+        assert_eq!(aces.showdowns(), 10_000);
+    }
+
+    #[test]
+    fn test_equity_result_round_trip() {
+        let board = cards_from_str("Qh7h2c4d").unwrap();
+        let ranges = vec![
+            Range::parse("AhKh").unwrap(),
+            Range::parse("QQ,JJ").unwrap(),
+        ];
+        let results = run_exact_calculation(Game::Holdem, board.clone(), vec![], ranges).unwrap();
+        let result = EquityResult::new(
+            vec!["AhKh".to_string(), "QQ,JJ".to_string()],
+            board,
+            vec![],
+            results,
+        );
+        assert_eq!(result.iterations, result.per_hand[1].showdowns());
+
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["board"], serde_json::json!(["Qh", "7h", "2c", "4d"]));
+        assert_eq!(json["per_hand"][0]["combos"][0]["cards"][0], "Ah");
+        assert_eq!(
+            serde_json::from_value::<EquityResult>(json).unwrap(),
+            result
+        );
+        assert!(serde_json::from_str::<Card>("\"Ax\"").is_err());
+    }
+
+    #[test]
+    fn test_aces_vs_range() {
+        let ranges = vec![
+            Range::from_combo(hole!("AhAs")),
+            Range::parse("QQ+,AKs").unwrap(),
+        ];
+        let settings = SimulationSettings {
+            threads: 2,
+            ..SimulationSettings::new(10_000)
+        };
+        let result = run_range_calculation(
+            Game::Holdem,
+            vec![],
+            vec![],
+            ranges,
+            &settings,
+            &mut thread_rng(),
+        );
+
+        // AA is well ahead of a range it mostly dominates
+        assert!(result[0].equity() > 0.70, "actual: {:?}", result[0]);
+    }
+
+    #[test]
+    fn test_threads_play_every_iteration() {
+        let run = |threads| {
+            let ranges = vec![
+                Range::from_combo(hole!("AhAs")),
+                Range::from_combo(hole!("KdKc")),
+            ];
+            let settings = SimulationSettings {
+                threads,
+                ..SimulationSettings::new(4_001)
+            };
+            let mut rng = StdRng::seed_from_u64(3);
+            run_range_calculation(Game::Holdem, vec![], vec![], ranges, &settings, &mut rng)
+        };
+
+        // An uneven split still plays every iteration, and the workers agree
+        // with a single thread on the equity
+        let one = run(1);
+        let four = run(4);
+        assert_eq!(one[0].showdowns(), 4_001);
+        assert_eq!(four[0].showdowns(), 4_001);
+        assert!((one[0].equity() - four[0].equity()).abs() < 0.04);
+        assert!((four[0].equity() + four[1].equity() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_seeded_runs_are_reproducible() {
+        let run = |seed| {
+            let ranges = vec![
+                Range::from_combo(hole!("AhAs")),
+                Range::parse("QQ+,AKs").unwrap(),
+            ];
+            let settings = SimulationSettings {
+                threads: 3,
+                ..SimulationSettings::new(3_000)
+            };
+            let mut rng = StdRng::seed_from_u64(seed);
+            run_range_calculation(Game::Holdem, vec![], vec![], ranges, &settings, &mut rng)
+        };
+
+        assert_eq!(run(7), run(7));
+        assert_ne!(run(7), run(8));
+    }
+
+    #[test]
+    fn test_workers_share_the_iterations() {
+        let settings = SimulationSettings {
+            threads: 3,
+            ..SimulationSettings::new(10)
+        };
+        let mut rng = StdRng::seed_from_u64(1);
+        let shares = run_workers(
+            &settings,
+            &mut rng,
+            |share, _| vec![share],
+            |a, b| a.extend(b),
+        );
+        assert_eq!(shares, [4, 3, 3]);
+
+        let one = SimulationSettings::new(10);
+        assert_eq!(
+            run_workers(&one, &mut rng, |share, _| share, |a, b| *a += b),
+            10
+        );
+    }
+
+    #[test]
+    fn test_combo_breakdown() {
+        let board = cards_from_str("Kh7d2c9s").unwrap();
+        let ranges = vec![
+            Range::from_combo(hole!("AhKd")),
+            Range::parse("KK,77,AQs").unwrap(),
+        ];
+        let result = run_exact_calculation(Game::Holdem, board, vec![], ranges).unwrap();
+
+        // Combos using hero's cards are never dealt
+        // KsKc, three sevens and three suited AQ remain
+        let combos = &result[1].combos;
+        let dealt = combos
+            .iter()
+            .filter(|c| c.showdowns > 0)
+            .collect::<Vec<_>>();
+        assert_eq!(dealt.len(), 1 + 3 + 3);
+        assert_eq!(
+            combos.iter().map(|c| c.showdowns).sum::<u64>(),
+            result[1].showdowns()
+        );
+
+        for combo in dealt {
+            let expected = match combo.cards[0].rank {
+                // Sets never lose, a king fills up the sevens
+                Rank::King | Rank::Seven => 1.0,
+                // AQ is drawing dead against top pair
+                _ => 0.0,
+            };
+            assert_eq!(combo.showdowns, 44);
+            assert!((combo.equity() - expected).abs() < 1e-9, "{:?}", combo);
+        }
+    }
+
+    #[test]
+    fn test_category_frequencies() {
+        let board = cards_from_str("Kh7d2c9s").unwrap();
+        let result = run_exact_calculation(
+            Game::Holdem,
+            board,
+            vec![],
+            vec![
+                Range::from_combo(hole!("AhKd")),
+                Range::from_combo(hole!("7s7h")),
+            ],
+        )
+        .unwrap();
+
+        // Top pair makes two pair with an ace or a paired board, trips with a king
+        let kings = &result[0];
+        assert_eq!(kings.category(HandCategory::Pair).made, 44 - 10 - 2);
+        assert_eq!(kings.category(HandCategory::TwoPair).made, 3 + 3 + 3 + 1);
+        assert_eq!(kings.category(HandCategory::ThreeOfAKind).made, 2);
+        assert_eq!(kings.category(HandCategory::ThreeOfAKind).wins, 0);
+
+        // The set fills up whenever the board pairs
+        let sevens = &result[1];
+        assert_eq!(sevens.category(HandCategory::FullHouse).made, 2 + 3 + 3);
+        assert_eq!(sevens.category(HandCategory::FourOfAKind).made, 1);
+        assert_eq!(
+            sevens.categories.iter().map(|c| c.wins).sum::<u64>(),
+            sevens.wins
+        );
+    }
+
+    #[test]
+    fn test_std_error() {
+        // Half wins and half losses, each sample is 0 or 1
+        let result = HandResult {
+            wins: 50,
+            losses: 50,
+            ..Default::default()
+        };
+        let expected = (0.25 * 100.0 / 99.0 / 100.0_f64).sqrt();
+        assert!((result.std_error() - expected).abs() < 1e-12);
+
+        let (low, high) = result.confidence_interval();
+        assert!((high - low - 2.0 * Z_95 * expected).abs() < 1e-12);
+
+        // Every pot split the same way has no spread at all
+        let result = HandResult {
+            ties: 30,
+            tie_splits: vec![0, 0, 30],
+            ..Default::default()
+        };
+        assert_eq!(result.equity(), 0.5);
+        assert!(result.std_error() < 1e-12);
+
+        // More iterations tighten the estimate
+        let mut rng = StdRng::seed_from_u64(23);
+        let ranges = vec![
+            Range::from_combo(hole!("AhAs")),
+            Range::from_combo(hole!("KdKh")),
+        ];
+        let small = run_range_calculation(
+            Game::Holdem,
+            vec![],
+            vec![],
+            ranges.clone(),
+            &SimulationSettings::new(1_000),
+            &mut rng,
+        );
+        let large = run_range_calculation(
+            Game::Holdem,
+            vec![],
+            vec![],
+            ranges,
+            &SimulationSettings::new(16_000),
+            &mut rng,
+        );
+        assert!(large[0].std_error() < small[0].std_error() / 2.0);
+    }
+
+    #[test]
+    fn test_stop_early() {
+        let stop = AtomicBool::new(false);
+        let settings = SimulationSettings {
+            threads: 2,
+            stop: Some(&stop),
+            ..SimulationSettings::new(20_000)
+        };
+        let ranges = vec![
+            Range::from_combo(hole!("AhAs")),
+            Range::from_combo(hole!("KdKh")),
+        ];
+        let mut rng = StdRng::seed_from_u64(32);
+        let run = |rng: &mut StdRng| {
+            run_range_calculation(Game::Holdem, vec![], vec![], ranges.clone(), &settings, rng)
+        };
+        assert_eq!(run(&mut rng)[0].showdowns(), 20_000);
+
+        // Stopped before it starts, every hand is still there with no showdowns
+        stop.store(true, AtomicOrdering::Relaxed);
+        let results = run(&mut rng);
+        assert_eq!(results[0].showdowns(), 0);
+        assert_eq!(results.len(), 2);
+    }
+
+    #[test]
+    fn test_deadline() {
+        let settings = SimulationSettings {
+            threads: 2,
+            deadline: Some(Instant::now() + std::time::Duration::from_millis(100)),
+            ..SimulationSettings::new(u32::MAX)
+        };
+        let ranges = vec![
+            Range::from_combo(hole!("AhAs")),
+            Range::from_combo(hole!("KdKh")),
+        ];
+        let start = Instant::now();
+        let results = run_range_calculation(
+            Game::Holdem,
+            vec![],
+            vec![],
+            ranges,
+            &settings,
+            &mut StdRng::seed_from_u64(33),
+        );
+
+        assert!(start.elapsed().as_secs() < 5);
+        assert!(results[0].showdowns() > 0);
+        assert!(results[0].std_error() > 0.0);
+    }
+
+    #[test]
+    fn test_stratified_sampling() {
+        let run = |sampler| {
+            let settings = SimulationSettings {
+                threads: 2,
+                sampler,
+                ..SimulationSettings::new(50_000)
+            };
+            let ranges = vec![
+                Range::from_combo(hole!("AhKh")),
+                Range::parse("QQ").unwrap(),
+            ];
+            let board = cards_from_str("7c2d5s").unwrap();
+            let mut rng = StdRng::seed_from_u64(31);
+            run_range_calculation(Game::Holdem, board, vec![], ranges, &settings, &mut rng)
+        };
+        let random = run(Sampler::Random);
+        let stratified = run(Sampler::Stratified);
+
+        // Every turn card comes up as often as the others
+        assert!(random[0].strata.is_empty());
+        assert_eq!(stratified[0].strata.len(), 45);
+        let counts = stratified[0].strata.iter().map(|s| s.showdowns);
+        assert!(counts.clone().max().unwrap() - counts.min().unwrap() <= 2);
+
+        // The same equity with less error, as hitting the turn no longer adds to it
+        assert_eq!(stratified[0].showdowns(), 50_000);
+        assert!((stratified[0].equity() - random[0].equity()).abs() < 0.01);
+        assert!(stratified[0].std_error() < random[0].std_error() * 0.9);
+    }
+
+    #[test]
+    fn test_sobol_sampling() {
+        let ranges = vec![
+            Range::from_combo(hole!("AhKh")),
+            Range::from_combo(hole!("QsQd")),
+        ];
+        let board = cards_from_str("7c2d5s").unwrap();
+        let exact =
+            run_exact_calculation(Game::Holdem, board.clone(), vec![], ranges.clone()).unwrap();
+
+        // Averaged over a few runs so one lucky run does not decide it
+        let error = |sampler| {
+            (0..8)
+                .map(|seed| {
+                    let settings = SimulationSettings {
+                        threads: 1,
+                        sampler,
+                        ..SimulationSettings::new(4_096)
+                    };
+                    let mut rng = StdRng::seed_from_u64(seed);
+                    let results = run_range_calculation(
+                        Game::Holdem,
+                        board.clone(),
+                        vec![],
+                        ranges.clone(),
+                        &settings,
+                        &mut rng,
+                    );
+                    (results[0].equity() - exact[0].equity()).abs()
+                })
+                .sum::<f64>()
+                / 8.0
+        };
+
+        let (random, sobol) = (error(Sampler::Random), error(Sampler::Sobol));
+        assert!(sobol < random / 4.0, "{} {}", sobol, random);
+    }
+
+    #[test]
+    fn test_target_error_stops_early() {
+        let settings = SimulationSettings {
+            target_error: Some(0.005),
+            ..SimulationSettings::new(1_000_000)
+        };
+        let results = run_range_calculation(
+            Game::Holdem,
+            vec![],
+            vec![],
+            vec![
+                Range::from_combo(hole!("AhAs")),
+                Range::from_combo(hole!("KdKh")),
+            ],
+            &settings,
+            &mut StdRng::seed_from_u64(24),
+        );
+
+        // Around 6000 iterations are needed, so one batch is enough
+        assert_eq!(results[0].showdowns(), CONVERGENCE_BATCH as u64);
+        assert!(results.iter().all(|r| r.std_error() <= 0.005));
+
+        // The iteration count still caps a target that is never reached
+        let settings = SimulationSettings {
+            target_error: Some(0.0),
+            ..SimulationSettings::new(25_000)
+        };
+        let results = run_range_calculation(
+            Game::Holdem,
+            vec![],
+            vec![],
+            vec![
+                Range::from_combo(hole!("AhAs")),
+                Range::from_combo(hole!("KdKh")),
+            ],
+            &settings,
+            &mut StdRng::seed_from_u64(24),
+        );
+        assert_eq!(results[0].showdowns(), 25_000);
+    }
+
+    #[test]
+    fn test_deal_runout_skips_held_cards() {
+        let community = cards_from_str("2c8d").unwrap();
+        let held = cards_from_str("AhAsKdKh").unwrap();
+        let hands = [&held[..2], &held[2..]];
+        let mut deck = Vec::from(Deck::without(&community));
+        let mut rng = StdRng::seed_from_u64(25);
+
+        let mut seen = vec![];
+        for _ in 0..2000 {
+            let board = deal_runout(&mut deck, &community, &hands, &mut rng);
+            assert_eq!(board[..2], community[..]);
+            for card in &board[2..] {
+                assert!(!held.contains(card) && !community.contains(card));
+                assert_eq!(board.iter().filter(|c| *c == card).count(), 1);
+                if !seen.contains(card) {
+                    seen.push(*card);
+                }
+            }
+        }
+
+        // Every other card turns up somewhere
+        assert_eq!(seen.len(), 52 - 2 - 4);
+    }
+
+    #[test]
+    fn test_check_duplicate_cards() {
+        let groups = vec![
+            ("hand 1".to_string(), cards_from_str("AhAs").unwrap()),
+            ("hand 2".to_string(), cards_from_str("KdKh").unwrap()),
+            ("board".to_string(), cards_from_str("2c3c4c").unwrap()),
+        ];
+        assert!(check_duplicate_cards(&groups).is_ok());
+
+        let groups = vec![
+            ("hand 1".to_string(), cards_from_str("AhAs").unwrap()),
+            ("hand 2".to_string(), cards_from_str("AhKd").unwrap()),
+        ];
+        let error = check_duplicate_cards(&groups).unwrap_err();
+        assert_eq!(error.to_string(), "card Ah is in both hand 1 and hand 2");
+
+        let groups = vec![("board".to_string(), cards_from_str("2c3c2c").unwrap())];
+        let error = check_duplicate_cards(&groups).unwrap_err();
+        assert_eq!(error.to_string(), "card 2c is listed twice in board");
+    }
+
+    #[test]
+    fn test_progress_callback() {
+        let calls = AtomicU32::new(0);
+        let ranges = vec![
+            Range::from_combo(hole!("AhAs")),
+            Range::from_combo(hole!("KdKh")),
+        ];
+        let progress = |done| {
+            assert!(done <= 5_000);
+            calls.fetch_add(1, AtomicOrdering::Relaxed);
+        };
+        let settings = SimulationSettings {
+            threads: 2,
+            progress: &progress,
+            ..SimulationSettings::new(5_000)
+        };
+        run_range_calculation(
+            Game::Holdem,
+            vec![],
+            vec![],
+            ranges,
+            &settings,
+            &mut thread_rng(),
+        );
+
+        assert_eq!(
+            calls.load(AtomicOrdering::Relaxed),
+            5_000 / PROGRESS_INTERVAL
+        );
+    }
+
+    #[test]
+    fn test_run_calculation_with_hooks() {
+        let calls = AtomicU32::new(0);
+        let stop = Arc::new(AtomicBool::new(false));
+        let progress = |done| {
+            calls.fetch_add(1, AtomicOrdering::Relaxed);
+            if done >= 300 {
+                stop.store(true, AtomicOrdering::Relaxed);
+            }
+        };
+        let settings = SimulationSettings {
+            threads: 1,
+            progress: &progress,
+            progress_interval: 100,
+            stop: Some(&stop),
+            ..SimulationSettings::new(1_000_000)
+        };
+
+        // Cancelled by the callback at the third call
+        let result = run_calculation_with(vec![], vec![hole!("AhAs"), hole!("KdKh")], &settings);
+        assert_eq!(calls.load(AtomicOrdering::Relaxed), 3);
+        assert_eq!(result.iterations, 300);
+        assert!(result.per_hand[0].equity() > 0.0);
+
+        let quiet = SimulationSettings {
+            iterations: 1_000,
+            progress_interval: 0,
+            stop: None,
+            ..settings
+        };
+        run_calculation_with(vec![], vec![hole!("AhAs"), hole!("KdKh")], &quiet);
+        assert_eq!(calls.load(AtomicOrdering::Relaxed), 3);
+    }
+
+    #[test]
+    fn test_exact_on_the_turn() {
+        let board = cards_from_str("2c8dJd3s").unwrap();
+        let ranges = vec![
+            Range::from_combo(hole!("JcJh")),
+            Range::from_combo(hole!("AdKd")),
+        ];
+        let result = run_exact_calculation(Game::Holdem, board, vec![], ranges).unwrap();
+
+        // 44 rivers, the flush draw hits with 9 diamonds
+        // but 2d and 3d also fill up the set
+        assert_eq!(result[1].showdowns(), 44);
+        assert_eq!(result[1].wins, 7);
+        assert_eq!(result[0].wins, 37);
+        assert_eq!(result[0].ties, 0);
+    }
+
+    #[test]
+    fn test_exact_rejects_weighted_ranges() {
+        let board = cards_from_str("2c8dJd3s").unwrap();
+        let ranges = || {
+            vec![
+                Range::parse("AA,KK:0.5").unwrap(),
+                Range::parse("QQ").unwrap(),
+            ]
+        };
+        assert!(matches!(
+            run_exact_calculation(Game::Holdem, board.clone(), vec![], ranges()),
+            Err(Error::WeightedExact)
+        ));
+
+        let hearts = RunoutFilter::parse("runout:h").unwrap();
+        assert!(matches!(
+            run_filtered_exact_calculation(Game::Holdem, board, vec![], ranges(), &hearts),
+            Err(Error::WeightedExact)
+        ));
+    }
+
+    #[test]
+    fn test_ties_are_split() {
+        // Every player plays the royal flush on the board
+        let board = cards_from_str("AsKsQsJsTs").unwrap();
+        let ranges = vec![
+            Range::from_combo(hole!("2c3c")),
+            Range::from_combo(hole!("2d3d")),
+            Range::from_combo(hole!("4h5h")),
+        ];
+        let result = run_exact_calculation(Game::Holdem, board, vec![], ranges).unwrap();
+
+        assert_eq!(result[0].ties, 1);
+        assert_eq!(result[0].tie_splits[3], 1);
+        assert_eq!(result[0].win(), 0.0);
+        assert_eq!(result[0].tie(), 1.0);
+        assert!((result[0].equity() - 1.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_omaha_exact_on_the_turn() {
+        let board = cards_from_str("AsKs7h2c").unwrap();
+        let ranges = vec![
+            // Top two pair
+            Range::from_cards(cards_from_str("AhKh3d4c").unwrap()),
+            // A single spade, which can never make a flush in Omaha
+            Range::from_cards(cards_from_str("Js8d9d5c").unwrap()),
+        ];
+        let result = run_exact_calculation(Game::Omaha, board, vec![], ranges).unwrap();
+
+        // The second hand can only ever make one pair
+        assert_eq!(result[0].showdowns(), 40);
+        assert_eq!(result[1].wins, 0);
+    }
+
+    #[test]
+    fn test_crazy_pineapple_discard_is_dead() {
+        // Aces kept after the flop with the king of clubs discarded, against
+        // a player still holding three cards
+        let board = cards_from_str("Kh8d3c2s").unwrap();
+        let dead = cards_from_str("Kc").unwrap();
+        let ranges = vec![
+            Range::from_combo(hole!("AhAd")),
+            Range::from_cards(cards_from_str("Ks7c7d").unwrap()),
+        ];
+        let result = run_exact_calculation(Game::Pineapple, board, dead, ranges).unwrap();
+
+        // Only the last king and the two sevens left make trips
+        assert_eq!(result[1].showdowns(), 42);
+        assert_eq!(result[1].wins, 3);
+    }
+
+    #[test]
+    fn test_omaha5_exact_on_the_turn() {
+        // The fifth card adds a flush draw to the wrap
+        let board = cards_from_str("AsKs7h2c").unwrap();
+        let ranges = vec![
+            Range::from_cards(cards_from_str("AhKh3d4c").unwrap()),
+            Range::from_cards(cards_from_str("Js8d9d5cTs").unwrap()),
+        ];
+        let result = run_exact_calculation(Game::Omaha5, board, vec![], ranges).unwrap();
+
+        // Any of the nine spades left makes the flush, and the other three
+        // queens make Broadway
+        assert_eq!(result[0].showdowns(), 39);
+        assert_eq!(result[1].wins, 12);
+    }
+
+    #[test]
+    fn test_omaha8_split_pot() {
+        let hands = |hands: [&str; 2]| {
+            hands
+                .map(|h| Range::from_cards(cards_from_str(h).unwrap()))
+                .to_vec()
+        };
+
+        // Quad kings take the high half and the only low takes the other
+        let board = cards_from_str("2c3d7hKcKs").unwrap();
+        let result =
+            run_exact_calculation(Game::Omaha8, board, vec![], hands(["Ah4sQdQh", "KdKh9s9d"]))
+                .unwrap();
+        assert_eq!(result[0].equity(), 0.5);
+        assert_eq!(result[0].lo_equity(), Some(0.5));
+        assert_eq!(result[1].hi_equity(), Some(0.5));
+        assert_eq!(result[1].scoop(), Some(0.0));
+        assert_eq!(result[0].ties, 1);
+
+        // Trips and the only low scoop the whole pot
+        let board = cards_from_str("2c3d7hKc9s").unwrap();
+        let result =
+            run_exact_calculation(Game::Omaha8, board, vec![], hands(["Ah4sKdKh", "QdQhJcTc"]))
+                .unwrap();
+        assert_eq!(result[0].equity(), 1.0);
+        assert_eq!(result[0].scoop(), Some(1.0));
+        assert_eq!(result[0].wins, 1);
+        assert_eq!(result[1].losses, 1);
+
+        // Without a low the high hand takes the whole pot, and the shares
+        // of every runout add up to one pot
+        let board = cards_from_str("2c3d7hKc").unwrap();
+        let result =
+            run_exact_calculation(Game::Omaha8, board, vec![], hands(["Ah4sKdKh", "As5sQdQh"]))
+                .unwrap();
+        assert_eq!(result[0].showdowns(), 40);
+        assert!((result[0].equity() + result[1].equity() - 1.0).abs() < 1e-9);
+        assert!(result[0].scoop().unwrap() > 0.0);
+        assert!(result[0].std_error() > 0.0);
+    }
+
+    #[test]
+    fn test_runout_filter() {
+        let board = cards_from_str("Kh7h2c").unwrap();
+        let ranges = || vec![Range::parse("AsAd").unwrap(), Range::parse("9h8h").unwrap()];
+        let all = run_exact_calculation(Game::Holdem, board.clone(), vec![], ranges()).unwrap();
+
+        // The runouts with a heart and the ones without split every runout
+        let hearts = RunoutFilter::parse("runout:h").unwrap();
+        let no_hearts = RunoutFilter::parse("!runout:h").unwrap();
+        let with =
+            run_filtered_exact_calculation(Game::Holdem, board.clone(), vec![], ranges(), &hearts)
+                .unwrap();
+        let without = run_filtered_exact_calculation(
+            Game::Holdem,
+            board.clone(),
+            vec![],
+            ranges(),
+            &no_hearts,
+        )
+        .unwrap();
+        assert_eq!(
+            with[0].showdowns() + without[0].showdowns(),
+            all[0].showdowns()
+        );
+        assert!(with[1].equity() > 0.9);
+        assert!(without[1].equity() < all[1].equity());
+
+        // Simulations only play the runouts that meet the filter
+        let settings = SimulationSettings {
+            runout_filter: Some(&hearts),
+            ..SimulationSettings::new(20_000)
+        };
+        let mut rng = StdRng::seed_from_u64(7);
+        let simulated =
+            run_range_calculation(Game::Holdem, board, vec![], ranges(), &settings, &mut rng);
+        assert_eq!(simulated[0].showdowns(), 20_000);
+        assert!((simulated[1].equity() - with[1].equity()).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_equity_histogram() {
+        let aces = Range::parse("AA").unwrap();
+        let mixed = Range::parse("KK,T9o").unwrap();
+        let board = cards_from_str("Kc7h2s").unwrap();
+        let result = run_exact_calculation(Game::Holdem, board, vec![], vec![mixed, aces]).unwrap();
+
+        // Sets of kings are near certain winners and ten nine is drawing thin
+        let histogram = result[0].equity_histogram(10);
+        assert_eq!(histogram.len(), 10);
+        assert!((histogram.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(histogram[9] > 0.0);
+        assert!(histogram[0] > 0.0);
+        assert_eq!(histogram[1..9].iter().sum::<f64>(), 0.0);
+        assert!((result[0].share_at_least(0.5) - histogram[9]).abs() < 1e-9);
+        assert_eq!(result[0].share_at_least(0.0), 1.0);
+
+        // Only the sets of kings are ahead, best first
+        let ahead = result[0].combos_at_least(0.5);
+        assert_eq!(ahead.len(), 3);
+        assert!(ahead.iter().all(|c| c.cards[0].rank == Rank::King));
+        assert!(ahead.windows(2).all(|w| w[0].equity() >= w[1].equity()));
+
+        assert_eq!(HandResult::default().equity_histogram(4), vec![0.0; 4]);
+        assert_eq!(HandResult::default().share_at_least(0.5), 0.0);
+    }
+
+    #[test]
+    fn test_equity_grid() {
+        let board = cards_from_str("Kc7h2s").unwrap();
+        let mixed = Range::parse("KK,AKs,T9o").unwrap();
+        let queens = Range::from_combo(hole!("QhQd"));
+        let result =
+            run_exact_calculation(Game::Holdem, board, vec![], vec![mixed, queens]).unwrap();
+
+        let grid = result[0].equity_grid();
+        let filled = grid.iter().filter(|cell| cell.is_some()).count();
+        assert_eq!(filled, 3);
+        // Rows are the higher rank when suited and the lower when offsuit
+        let kings = grid[13 + 1].unwrap();
+        let ace_king = grid[1].unwrap();
+        let ten_nine = grid[5 * 13 + 4].unwrap();
+        assert!(kings > 0.99 && ace_king > 0.8 && ten_nine < 0.2);
+
+        // A single combo fills its own cell
+        let grid = result[1].equity_grid();
+        assert_eq!(grid.iter().filter(|cell| cell.is_some()).count(), 1);
+        assert!((grid[2 * 13 + 2].unwrap() - result[1].equity()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_dead_cards_are_not_dealt() {
+        let board = cards_from_str("2c8dJd3s").unwrap();
+        let dead = cards_from_str("2d3d4d5d6d").unwrap();
+        let ranges = vec![
+            Range::from_combo(hole!("JcJh")),
+            Range::from_combo(hole!("AdKd")),
+        ];
+        let result = run_exact_calculation(Game::Holdem, board, dead, ranges).unwrap();
+
+        // Only 7d, 9d, Td and Qd are left to make the flush
+        assert_eq!(result[1].showdowns(), 39);
+        assert_eq!(result[1].wins, 4);
+    }
+}
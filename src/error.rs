@@ -0,0 +1,181 @@
+//! Crate level error type
+
+use crate::board::Street;
+use crate::card::{Card, ParseCardError};
+use crate::game::Game;
+use crate::range::ParseRangeError;
+
+/// Everything that can go wrong setting up a calculation
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Card(#[from] ParseCardError),
+
+    #[error(transparent)]
+    Range(#[from] ParseRangeError),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error(transparent)]
+    Csv(#[from] csv::Error),
+
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+
+    #[error("at least 2 hands are needed to compare, got {0}")]
+    NotEnoughHands(usize),
+
+    #[error("{game} needs {expected} hole cards per hand, got {found}")]
+    WrongHoleCards {
+        game: Game,
+        expected: usize,
+        found: usize,
+    },
+
+    #[error("a {game} hand has at most {} cards, got {found}", game.hole_cards())]
+    TooManyCards { game: Game, found: usize },
+
+    #[error("{0} is played without a board")]
+    NoBoard(Game),
+
+    #[error("the deck runs out dealing {cards} cards to each of {players} players with {dead} dead cards")]
+    DeckTooSmall {
+        cards: usize,
+        players: usize,
+        dead: usize,
+    },
+
+    #[error("a board has 0, 3, 4 or 5 cards, got {0}")]
+    InvalidBoard(usize),
+
+    #[error("the {street} is {expected} card{}, got {found}", if *expected == 1 { "" } else { "s" })]
+    WrongStreetSize {
+        street: String,
+        expected: usize,
+        found: usize,
+    },
+
+    #[error("{street} needs {before} to be given as well")]
+    MissingStreet { street: String, before: String },
+
+    #[error("a flop or a turn is needed, got {0} board cards")]
+    NotFlopOrTurn(usize),
+
+    #[error("the board is dealt to the river, there are no streets to come")]
+    NoStreetsLeft,
+
+    #[error("the board has {cards} cards, more than the {} has", street.name().to_lowercase())]
+    BoardPastStreet { street: Street, cards: usize },
+
+    #[error("a hand to evaluate has 5, 6 or 7 cards, got {0}")]
+    InvalidHandSize(usize),
+
+    #[error("card {card:?} {}", duplicate_location(first, second))]
+    DuplicateCard {
+        card: Card,
+        first: String,
+        second: String,
+    },
+
+    #[error("{0} has no possible hands left once the known cards are removed")]
+    EmptyRange(String),
+
+    #[error("the deck can only run the board out {most} times, got {runs}")]
+    TooManyRuns { runs: u32, most: u32 },
+
+    #[error("{0} can not be used with this calculation, which plays every iteration with a random runout")]
+    UnsupportedSetting(&'static str),
+
+    #[error("{0} has no effect on this command")]
+    UnusedFlag(&'static str),
+
+    #[error("\"{0}\" is not a single starting hand such as AKs, T9o or 77")]
+    InvalidStartingHand(String),
+
+    #[error("stacks must be positive, got {0}")]
+    InvalidStack(f64),
+
+    #[error("{stacks} stacks given for {hands} hands")]
+    WrongStackCount { stacks: usize, hands: usize },
+
+    #[error("there is no player {} at a table of {players}", .player + 1)]
+    NoSuchPlayer { player: usize, players: usize },
+
+    #[error("player {} can not call their own all in", .0 + 1)]
+    OwnAllIn(usize),
+
+    #[error("payouts can not be negative, got {0}")]
+    InvalidPayout(f64),
+
+    #[error("the pot can not be negative and the call must be positive, got {0}")]
+    InvalidAmount(f64),
+
+    #[error("the pot can not be negative, got {0}")]
+    InvalidPot(f64),
+
+    #[error("a bet or call must be more than 0, got {0}")]
+    InvalidBet(f64),
+
+    #[error("a chance is between 0 and 1, got {0}")]
+    InvalidChance(f64),
+
+    #[error("the air threshold of {air} is above the value threshold of {value}")]
+    BucketOrder { air: f64, value: f64 },
+
+    #[error("a stack of {stack} can not cover the call of {call}")]
+    StackBelowCall { stack: f64, call: f64 },
+
+    #[error("\"{0}\" is not a runout condition such as flush, no-paired, turn:h or !runout:A")]
+    InvalidRunoutFilter(String),
+
+    #[error("no runout meets the filter {0}")]
+    NoMatchingRunout(String),
+
+    #[error("weighted ranges can not be enumerated exactly, simulate them instead")]
+    WeightedExact,
+
+    #[error("weighted ranges can not be counted combo by combo")]
+    WeightedCombos,
+
+    #[error("plots are written as .png or .svg, got {0}")]
+    PlotFormat(String),
+
+    #[error("could not draw the plot: {0}")]
+    Plot(String),
+
+    #[error("hand {hand}: {reason}")]
+    HandHistory { hand: String, reason: String },
+
+    #[error("scenario {index}: {reason}")]
+    InvalidScenario { index: usize, reason: String },
+
+    /// Wraps another error with the name and value of the input that caused it
+    #[error("{name} \"{value}\": {source}")]
+    Input {
+        name: String,
+        value: String,
+        #[source]
+        source: Box<Error>,
+    },
+}
+
+/// Describe where a duplicated card was found
+fn duplicate_location(first: &str, second: &str) -> String {
+    if first == second {
+        format!("is listed twice in {}", first)
+    } else {
+        format!("is in both {} and {}", first, second)
+    }
+}
+
+impl Error {
+    /// Attach the name and value of the input that caused the error
+    pub fn input(self, name: &str, value: &str) -> Self {
+        Error::Input {
+            name: name.to_string(),
+            value: value.to_string(),
+            source: Box::new(self),
+        }
+    }
+}
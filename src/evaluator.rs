@@ -0,0 +1,159 @@
+//! Hand evaluators behind one trait, so simulations can be run and
+//! benchmarked with any of them
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use crate::card::Card;
+use crate::lookup;
+use crate::poker_hand::HandValue;
+use crate::poker_utils::get_best_five_cards;
+
+/// Most seven card hands a showdown hands to `evaluate_batch` at once
+pub const BATCH: usize = 8;
+
+/// Ranks the best five card hand out of five to seven cards
+pub trait Evaluator: Sync {
+    fn evaluate(&self, cards: &[Card]) -> HandValue;
+
+    /// Rank several seven card hands, adding their values to `values`
+    /// Evaluators that can rank hands together override this
+    fn evaluate_batch(&self, hands: &[[Card; 7]], values: &mut Vec<HandValue>) {
+        values.extend(hands.iter().map(|hand| self.evaluate(hand)));
+    }
+}
+
+/// Scores every five card combination and keeps the best
+pub struct Naive;
+
+/// Ranks all the cards in one pass with rank bitmasks and lookup tables
+pub struct Lookup;
+
+/// The lookup evaluator, ranking batches of hands with `std::simd`
+#[cfg(feature = "simd")]
+pub struct Simd;
+
+/// Evaluators that can be picked by name, such as with `--evaluator`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvaluatorKind {
+    Naive,
+    Lookup,
+    #[cfg(feature = "simd")]
+    Simd,
+}
+
+///////////////////////////////////////////////
+/// Evaluator Implementations
+///////////////////////////////////////////////
+impl Evaluator for Naive {
+    fn evaluate(&self, cards: &[Card]) -> HandValue {
+        get_best_five_cards(cards).1.into()
+    }
+}
+
+impl Evaluator for Lookup {
+    fn evaluate(&self, cards: &[Card]) -> HandValue {
+        lookup::evaluate(cards).into()
+    }
+}
+
+#[cfg(feature = "simd")]
+impl Evaluator for Simd {
+    fn evaluate(&self, cards: &[Card]) -> HandValue {
+        lookup::evaluate(cards).into()
+    }
+
+    fn evaluate_batch(&self, hands: &[[Card; 7]], values: &mut Vec<HandValue>) {
+        crate::simd::evaluate_batch(hands, values);
+    }
+}
+
+///////////////////////////////////////////////
+/// EvaluatorKind Implementations
+///////////////////////////////////////////////
+impl EvaluatorKind {
+    /// The evaluator of this kind
+    pub fn evaluator(self) -> &'static dyn Evaluator {
+        match self {
+            EvaluatorKind::Naive => &Naive,
+            EvaluatorKind::Lookup => &Lookup,
+            #[cfg(feature = "simd")]
+            EvaluatorKind::Simd => &Simd,
+        }
+    }
+}
+
+/// The lookup tables with the `fast-eval` feature and combinations otherwise
+impl Default for EvaluatorKind {
+    fn default() -> Self {
+        match cfg!(feature = "fast-eval") {
+            true => EvaluatorKind::Lookup,
+            false => EvaluatorKind::Naive,
+        }
+    }
+}
+
+impl Display for EvaluatorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            EvaluatorKind::Naive => "naive",
+            EvaluatorKind::Lookup => "lookup",
+            #[cfg(feature = "simd")]
+            EvaluatorKind::Simd => "simd",
+        })
+    }
+}
+
+impl FromStr for EvaluatorKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "naive" => Ok(EvaluatorKind::Naive),
+            "lookup" => Ok(EvaluatorKind::Lookup),
+            #[cfg(feature = "simd")]
+            "simd" => Ok(EvaluatorKind::Simd),
+            _ => Err(format!(
+                "Unknown evaluator \"{}\", expected naive or lookup",
+                s
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::card::cards_from_str;
+    use crate::equity::{run_range_calculation, SimulationSettings};
+    use crate::game::Game;
+    use crate::range::Range;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_evaluators_agree() {
+        let ranges = ["AhKh", "QQ+,AKo"]
+            .map(|r| Range::parse(r).unwrap())
+            .to_vec();
+        let board = cards_from_str("Qh7c2h").unwrap();
+
+        let results = [EvaluatorKind::Naive, EvaluatorKind::Lookup].map(|kind| {
+            let settings = SimulationSettings {
+                threads: 1,
+                evaluator: kind.evaluator(),
+                ..SimulationSettings::new(5_000)
+            };
+            let mut rng = StdRng::seed_from_u64(3);
+            run_range_calculation(
+                Game::Holdem,
+                board.clone(),
+                vec![],
+                ranges.clone(),
+                &settings,
+                &mut rng,
+            )
+        });
+        assert_eq!(results[0], results[1]);
+    }
+}
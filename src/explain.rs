@@ -0,0 +1,245 @@
+//! Worked example showdowns, a few deals played out in full with every
+//! player's best five cards, for checking results by eye or for teaching
+
+use rand::Rng;
+
+use crate::card::{Card, Rank};
+use crate::equity::{deal_runout, pot_share, remove_known_cards, DealShowdown};
+use crate::evaluator::EvaluatorKind;
+use crate::game::Game;
+use crate::poker_hand::{LowHand, PokerHandRank};
+use crate::poker_utils::{determine_low_winners, determine_winner};
+use crate::range::Range;
+use crate::runout::RunoutFilter;
+
+/// One player's hand in an example showdown
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExampleHand {
+    /// Hole cards dealt from the player's range
+    pub hole: Vec<Card>,
+
+    /// The five cards of the best high hand
+    pub best: [Card; 5],
+
+    /// What the five cards make, such as a flush
+    pub rank: PokerHandRank,
+
+    /// Best qualifying low in split pot games
+    pub low: Option<LowHand>,
+
+    /// Whether the best high hand is the board alone
+    pub plays_the_board: bool,
+}
+
+/// A deal played out to the river
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExampleShowdown {
+    /// The whole board, the known cards and the runout
+    pub board: [Card; 5],
+
+    /// Every player's hand in the order the ranges were given
+    pub hands: Vec<ExampleHand>,
+
+    /// Players with the best high hand
+    pub winners: Vec<usize>,
+
+    /// Players sharing the low half of the pot, empty when nobody has a low
+    pub low_winners: Vec<usize>,
+
+    /// The kicker the high hand won by, when it beat the next best hand
+    /// only on a kicker
+    pub kicker: Option<Rank>,
+}
+
+///////////////////////////////////////////////
+/// ExampleShowdown Implementations
+///////////////////////////////////////////////
+impl ExampleShowdown {
+    /// Share of the pot a player won
+    pub fn share(&self, player: usize) -> f64 {
+        pot_share(player, &self.winners, Some(&self.low_winners))
+    }
+}
+
+/// Deal `count` example showdowns from the ranges, each with a random
+/// runout that meets `filter` when there is one
+/// Games without a board have no examples, and fewer are returned when the
+/// ranges or the filter can not be dealt
+pub fn example_showdowns<R: Rng>(
+    game: Game,
+    board: &[Card],
+    dead: &[Card],
+    ranges: Vec<Range>,
+    filter: Option<&RunoutFilter>,
+    count: usize,
+    rng: &mut R,
+) -> Vec<ExampleShowdown> {
+    if !game.has_board() {
+        return vec![];
+    }
+    let (deck, ranges) = remove_known_cards(board, dead, ranges);
+    let evaluator = EvaluatorKind::default().evaluator();
+    let mut deal = DealShowdown::new(game, deck.remaining(), &ranges, board, evaluator);
+    let mut examples = Vec::with_capacity(count);
+
+    while examples.len() < count {
+        let runout = match filter {
+            Some(filter) => deal.deal_filtered(filter, rng),
+            None => deal
+                .deal_hands(rng)
+                .then(|| deal_runout(&mut deal.deck, board, &deal.hands, rng)),
+        };
+        let Some(runout) = runout else {
+            break;
+        };
+        let hands = &deal.hands;
+
+        let (winners, _) = determine_winner(game, hands, runout);
+        let low_winners = determine_low_winners(game, hands, runout);
+        let hands = hands
+            .iter()
+            .map(|hole| {
+                let (best, rank) = game.best_five_cards(hole, runout);
+                ExampleHand {
+                    hole: hole.to_vec(),
+                    best,
+                    rank,
+                    low: game.best_low(hole, runout),
+                    plays_the_board: game.plays_the_board(hole, runout),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        examples.push(ExampleShowdown {
+            board: runout,
+            kicker: winning_kicker(&hands, &winners),
+            hands,
+            winners,
+            low_winners,
+        });
+    }
+
+    examples
+}
+
+/// The kicker a lone winner beat the next best hand with
+fn winning_kicker(hands: &[ExampleHand], winners: &[usize]) -> Option<Rank> {
+    let [winner] = winners else {
+        return None;
+    };
+    let runner_up = hands
+        .iter()
+        .enumerate()
+        .filter(|(player, _)| player != winner)
+        .map(|(_, hand)| hand.rank)
+        .max()?;
+    hands[*winner].rank.kicker_over(&runner_up)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::card::cards_from_str;
+    use crate::range::hand_ranges;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_example_showdowns() {
+        let mut rng = StdRng::seed_from_u64(17);
+        let board = cards_from_str("Kh7h2c").unwrap();
+        let examples = example_showdowns(
+            Game::Holdem,
+            &board,
+            &[],
+            hand_ranges(&["AsAd", "9h8h"]),
+            None,
+            20,
+            &mut rng,
+        );
+        assert_eq!(examples.len(), 20);
+
+        for example in &examples {
+            assert_eq!(example.board[..3], board[..]);
+            for hand in &example.hands {
+                let known = |card: &Card| hand.hole.contains(card) || example.board.contains(card);
+                assert!(hand.best.iter().all(known));
+                assert_eq!(Game::Holdem.best_hand(&hand.hole, example.board), hand.rank);
+            }
+
+            // The winner holds the best hand
+            let best = example.hands.iter().map(|hand| hand.rank).max().unwrap();
+            assert!(example
+                .winners
+                .iter()
+                .all(|w| example.hands[*w].rank == best));
+            if example.hands[1].rank > example.hands[0].rank {
+                assert_eq!(example.share(1), 1.0);
+            }
+            assert!(example.low_winners.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_example_kickers() {
+        let mut rng = StdRng::seed_from_u64(8);
+        let board = cards_from_str("AhJc7d3s2c").unwrap();
+        let examples = example_showdowns(
+            Game::Holdem,
+            &board,
+            &[],
+            hand_ranges(&["AsQd", "AdTc", "Jh9h"]),
+            None,
+            1,
+            &mut rng,
+        );
+        assert_eq!(examples[0].winners, [0]);
+        assert_eq!(examples[0].kicker, Some(Rank::Queen));
+
+        // Both hands play the broadway board and split the pot
+        let board = cards_from_str("AhKcQdJsTc").unwrap();
+        let examples = example_showdowns(
+            Game::Holdem,
+            &board,
+            &[],
+            hand_ranges(&["2s2d", "9h8h"]),
+            None,
+            1,
+            &mut rng,
+        );
+        assert!(examples[0].hands.iter().all(|hand| hand.plays_the_board));
+        assert_eq!(examples[0].winners, [0, 1]);
+        assert_eq!(examples[0].kicker, None);
+    }
+
+    #[test]
+    fn test_filtered_examples() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let filter = "runout:h".parse::<RunoutFilter>().unwrap();
+        let examples = example_showdowns(
+            Game::Omaha8,
+            &cards_from_str("Kh7h2c").unwrap(),
+            &[],
+            hand_ranges(&["As2s3d4d", "9h8hQdQs"]),
+            Some(&filter),
+            5,
+            &mut rng,
+        );
+        assert_eq!(examples.len(), 5);
+        assert!(examples.iter().all(|e| filter.matches(&e.board, 3)));
+
+        let shares = examples.iter().map(|e| e.share(0) + e.share(1));
+        assert!(shares.into_iter().all(|total| (total - 1.0).abs() < 1e-9));
+
+        assert!(example_showdowns(
+            Game::Stud,
+            &[],
+            &[],
+            hand_ranges(&["AsAdKc", "9h8h7h"]),
+            None,
+            3,
+            &mut rng
+        )
+        .is_empty());
+    }
+}
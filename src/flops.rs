@@ -0,0 +1,402 @@
+//! Equity of ranges across every flop, like the aggregate flop reports of
+//! solvers
+//! Flops that only differ by their suits play the same when the ranges
+//! treat every suit alike, so the 22100 flops come down to 1755
+//! strategically distinct ones, each weighed by how many flops it stands for
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use rand::Rng;
+
+use crate::canonical::{relabel, relabellings};
+use crate::card::{Card, Rank, Suit};
+use crate::deck::Deck;
+use crate::equity::{run_range_calculation, HandResult, SimulationSettings};
+use crate::error::Error;
+use crate::game::Game;
+use crate::poker_hand::HandCategory;
+use crate::poker_utils::for_each_combination;
+use crate::range::Range;
+
+/// A flop standing for every flop that plays the same
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Flop {
+    /// Cards of the flop, highest first
+    pub cards: [Card; 3],
+
+    /// Number of flops this one stands for
+    pub weight: u32,
+}
+
+/// How many suits a flop has
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Suitedness {
+    Rainbow,
+    TwoTone,
+    Monotone,
+}
+
+/// How many cards of a flop share a rank
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pairing {
+    Unpaired,
+    Paired,
+    Trips,
+}
+
+/// Relabelling that leaves every suit as it is
+const IDENTITY: [Suit; 4] = Suit::ALL;
+
+/// Results of the hands on one flop
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlopResult {
+    pub flop: Flop,
+
+    /// Results of each hand, in the order of the ranges
+    pub per_hand: Vec<HandResult>,
+}
+
+/// Results of the hands on a set of flops
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FlopReport {
+    /// Every flop played, leaving out the ones that left a range empty
+    pub flops: Vec<FlopResult>,
+}
+
+///////////////////////////////////////////////
+/// Flop Implementations
+///////////////////////////////////////////////
+impl Flop {
+    /// How many suits the flop has
+    pub fn suitedness(&self) -> Suitedness {
+        let [a, b, c] = self.cards.map(|card| card.suit);
+        match (a == b) as u8 + (b == c) as u8 + (a == c) as u8 {
+            0 => Suitedness::Rainbow,
+            1 => Suitedness::TwoTone,
+            _ => Suitedness::Monotone,
+        }
+    }
+
+    /// How many cards share a rank
+    pub fn pairing(&self) -> Pairing {
+        let [a, b, c] = self.cards.map(|card| card.rank);
+        match (a == b) as u8 + (b == c) as u8 + (a == c) as u8 {
+            0 => Pairing::Unpaired,
+            1 => Pairing::Paired,
+            _ => Pairing::Trips,
+        }
+    }
+
+    /// Rank of the highest card
+    pub fn high_card(&self) -> Rank {
+        self.cards[0].rank
+    }
+}
+
+///////////////////////////////////////////////
+/// Suitedness Implementations
+///////////////////////////////////////////////
+impl Suitedness {
+    pub const ALL: [Suitedness; 3] = [
+        Suitedness::Rainbow,
+        Suitedness::TwoTone,
+        Suitedness::Monotone,
+    ];
+
+    /// Name of the texture
+    pub fn name(self) -> &'static str {
+        match self {
+            Suitedness::Rainbow => "Rainbow",
+            Suitedness::TwoTone => "Two tone",
+            Suitedness::Monotone => "Monotone",
+        }
+    }
+}
+
+///////////////////////////////////////////////
+/// Pairing Implementations
+///////////////////////////////////////////////
+impl Pairing {
+    pub const ALL: [Pairing; 3] = [Pairing::Unpaired, Pairing::Paired, Pairing::Trips];
+
+    /// Name of the texture
+    pub fn name(self) -> &'static str {
+        match self {
+            Pairing::Unpaired => "Unpaired",
+            Pairing::Paired => "Paired",
+            Pairing::Trips => "Trips",
+        }
+    }
+}
+
+///////////////////////////////////////////////
+/// FlopResult Implementations
+///////////////////////////////////////////////
+impl FlopResult {
+    /// Equity of every hand on the flop
+    pub fn equities(&self) -> Vec<f64> {
+        self.per_hand.iter().map(HandResult::equity).collect()
+    }
+}
+
+///////////////////////////////////////////////
+/// FlopReport Implementations
+///////////////////////////////////////////////
+impl FlopReport {
+    /// Number of flops the report stands for
+    pub fn weight(&self) -> u32 {
+        self.flops.iter().map(|result| result.flop.weight).sum()
+    }
+
+    /// Equity of every hand over all the flops, each flop counting by its
+    /// weight, and empty without any flops
+    pub fn equities(&self) -> Vec<f64> {
+        self.weighted(|result| result.equities())
+    }
+
+    /// How often `player` finishes with each hand category, indexed by
+    /// `HandCategory as usize`, each flop counting by its weight
+    pub fn categories(&self, player: usize) -> Vec<f64> {
+        self.weighted(|result| {
+            let hand = &result.per_hand[player];
+            HandCategory::ALL
+                .iter()
+                .map(|&category| {
+                    hand.category(category).made as f64 / hand.showdowns().max(1) as f64
+                })
+                .collect()
+        })
+    }
+
+    /// Results of the flops that `keep` returns true for
+    pub fn subset(&self, keep: impl Fn(&Flop) -> bool) -> FlopReport {
+        FlopReport {
+            flops: self
+                .flops
+                .iter()
+                .filter(|result| keep(&result.flop))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Average of a value of every flop, weighed by the flop's weight
+    fn weighted(&self, value: impl Fn(&FlopResult) -> Vec<f64>) -> Vec<f64> {
+        let mut totals: Vec<f64> = vec![];
+        for result in &self.flops {
+            let values = value(result);
+            totals.resize(values.len(), 0.0);
+            for (total, value) in totals.iter_mut().zip(values) {
+                *total += value * result.flop.weight as f64;
+            }
+        }
+
+        let weight = self.weight().max(1) as f64;
+        totals.iter().map(|total| total / weight).collect()
+    }
+}
+
+/// Every flop that plays differently for the ranges, with how many flops
+/// each stands for, leaving out flops with a dead card
+/// Flops are put together when relabelling the suits turns one into the
+/// other and leaves the ranges and dead cards the same, so ranges that
+/// treat every suit alike give the 1755 strategically distinct flops
+pub fn distinct_flops(ranges: &[Range], dead: &[Card]) -> Vec<Flop> {
+    let mut dead_cards = dead.to_vec();
+    dead_cards.sort();
+    let forms = ranges
+        .iter()
+        .map(|range| relabel_range(range, IDENTITY))
+        .collect::<Vec<_>>();
+    let symmetries = relabellings()
+        .filter(|&suits| {
+            relabel(dead, suits) == dead_cards
+                && ranges
+                    .iter()
+                    .zip(&forms)
+                    .all(|(range, form)| relabel_range(range, suits) == *form)
+        })
+        .collect::<Vec<_>>();
+
+    let mut flops: Vec<Flop> = vec![];
+    let mut seen = HashMap::new();
+    for_each_combination(Deck::without(dead).remaining(), 3, |cards| {
+        let form = symmetries
+            .iter()
+            .map(|&suits| relabel(cards, suits))
+            .min()
+            .unwrap();
+        let i = *seen.entry(form).or_insert_with(|| {
+            let mut cards = <[Card; 3]>::try_from(cards).unwrap();
+            cards.sort_by(|a, b| b.cmp(a));
+            flops.push(Flop { cards, weight: 0 });
+            flops.len() - 1
+        });
+        flops[i].weight += 1;
+    });
+
+    flops.sort_by_key(|flop| Reverse(flop.cards));
+    flops
+}
+
+/// Simulate the ranges on every flop, running `settings.iterations` deals
+/// on each one, and put the results together in a report
+/// `settings.progress` is called with the iterations done over all flops,
+/// and flops that leave a range without a combo are skipped
+pub fn run_flop_report<R: Rng>(
+    game: Game,
+    ranges: Vec<Range>,
+    dead: Vec<Card>,
+    flops: &[Flop],
+    settings: &SimulationSettings,
+    rng: &mut R,
+) -> Result<FlopReport, Error> {
+    if !game.has_board() {
+        return Err(Error::NoBoard(game));
+    }
+
+    let mut report = FlopReport::default();
+    let mut done = 0u32;
+    for flop in flops {
+        let stopped = settings
+            .stop
+            .is_some_and(|stop| stop.load(std::sync::atomic::Ordering::Relaxed));
+        if stopped
+            || settings
+                .deadline
+                .is_some_and(|d| std::time::Instant::now() >= d)
+        {
+            break;
+        }
+
+        let known = [&flop.cards[..], &dead].concat();
+        if ranges
+            .iter()
+            .any(|range| range.without_cards(&known).is_empty())
+        {
+            continue;
+        }
+
+        let progress = |iterations| (settings.progress)(done.saturating_add(iterations));
+        let flop_settings = SimulationSettings {
+            progress: &progress,
+            ..*settings
+        };
+        let per_hand = run_range_calculation(
+            game,
+            flop.cards.to_vec(),
+            dead.clone(),
+            ranges.clone(),
+            &flop_settings,
+            rng,
+        );
+        done = done.saturating_add(settings.iterations);
+        report.flops.push(FlopResult {
+            flop: *flop,
+            per_hand,
+        });
+    }
+
+    Ok(report)
+}
+
+/// Every combo of the range with its suits relabelled and its weight,
+/// sorted so that ranges holding the same weighted combos compare equal
+fn relabel_range(range: &Range, suits: [Suit; 4]) -> Vec<(Vec<Card>, u64)> {
+    let mut combos = range
+        .combos()
+        .iter()
+        .zip(range.weights())
+        .map(|(combo, weight)| (relabel(combo, suits), weight.to_bits()))
+        .collect::<Vec<_>>();
+    combos.sort();
+    combos
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::card::cards_from_str;
+
+    fn flop(cards: &str) -> Flop {
+        let cards = cards_from_str(cards).unwrap().try_into().unwrap();
+        Flop { cards, weight: 1 }
+    }
+
+    #[test]
+    fn test_distinct_flops() {
+        let ranges = [
+            Range::parse("QQ+,AKs").unwrap(),
+            Range::parse("random").unwrap(),
+        ];
+        let flops = distinct_flops(&ranges, &[]);
+        assert_eq!(flops.len(), 1755);
+        assert_eq!(flops.iter().map(|f| f.weight).sum::<u32>(), 22100);
+
+        // Three of a rank can only be dealt four ways, and a rainbow flop of
+        // three ranks twenty four ways
+        let trips = flops
+            .iter()
+            .find(|f| f.pairing() == Pairing::Trips)
+            .unwrap();
+        assert_eq!(trips.weight, 4);
+        let rainbow = flops
+            .iter()
+            .filter(|f| f.suitedness() == Suitedness::Rainbow && f.pairing() == Pairing::Unpaired)
+            .map(|f| f.weight)
+            .collect::<Vec<_>>();
+        assert!(rainbow.iter().all(|weight| *weight == 24));
+
+        // A range holding one suit tells the suits apart, and dead cards
+        // take their flops away
+        let suited = [
+            Range::parse("AhKh").unwrap(),
+            Range::parse("random").unwrap(),
+        ];
+        let flops = distinct_flops(&suited, &[]);
+        assert!(flops.len() > 1755);
+        assert_eq!(flops.iter().map(|f| f.weight).sum::<u32>(), 22100);
+        let dead = cards_from_str("2c").unwrap();
+        let flops = distinct_flops(&ranges, &dead);
+        assert_eq!(flops.iter().map(|f| f.weight).sum::<u32>(), 20825);
+    }
+
+    #[test]
+    fn test_textures() {
+        assert_eq!(flop("AhKh2h").suitedness(), Suitedness::Monotone);
+        assert_eq!(flop("AhKh2c").suitedness(), Suitedness::TwoTone);
+        assert_eq!(flop("AhKs2c").suitedness(), Suitedness::Rainbow);
+        assert_eq!(flop("7h7s2c").pairing(), Pairing::Paired);
+        assert_eq!(flop("7h7s7c").pairing(), Pairing::Trips);
+        assert_eq!(flop("Th7s2c").high_card(), Rank::Ten);
+    }
+
+    #[test]
+    fn test_flop_report() {
+        let ranges = vec![Range::parse("AA").unwrap(), Range::parse("KK").unwrap()];
+        let flops = [flop("AcKd2s"), flop("Kh7c2d"), flop("AsAhAd")];
+        let settings = SimulationSettings::new(2_000);
+        let mut rng = rand::thread_rng();
+        let report =
+            run_flop_report(Game::Holdem, ranges, vec![], &flops, &settings, &mut rng).unwrap();
+
+        // Aces are left without a combo on a flop of three aces
+        assert_eq!(report.flops.len(), 2);
+        assert_eq!(report.weight(), 2);
+        let equities = report.equities();
+        assert!((equities[0] + equities[1] - 1.0).abs() < 1e-9);
+        assert!(report.flops[0].equities()[0] > 0.9);
+        assert!(report.flops[1].equities()[0] < 0.2);
+
+        let categories = report.categories(0);
+        assert!((categories.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        let set = report.subset(|flop| flop.high_card() == Rank::Ace);
+        assert_eq!(set.flops.len(), 1);
+
+        assert!(matches!(
+            run_flop_report(Game::Stud, vec![], vec![], &flops, &settings, &mut rng),
+            Err(Error::NoBoard(_))
+        ));
+    }
+}
@@ -0,0 +1,496 @@
+//! Poker variants and the rules for building a showdown hand
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use crate::card::Card;
+use crate::error::Error;
+use crate::evaluator::{Evaluator, EvaluatorKind, BATCH};
+use crate::poker_hand::{cards_to_hand, HandValue, LowHand, PokerHandRank};
+use crate::poker_utils::{
+    get_best_five_cards, get_best_omaha_five_cards, get_best_omaha_hand, get_best_omaha_low,
+    get_best_pineapple_five_cards, get_best_pineapple_hand, in_hand_order,
+};
+
+/// Supported poker variants
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Game {
+    /// Two hole cards, any five of the seven cards play
+    #[default]
+    Holdem,
+
+    /// Four hole cards, exactly two from the hand and three from the board
+    Omaha,
+
+    /// Omaha hi-lo, the pot is split with the best eight or better low
+    Omaha8,
+
+    /// Five card Omaha, still exactly two from the hand
+    Omaha5,
+
+    /// Five card Omaha with the first flop card dealt face up before the
+    /// preflop betting
+    Courchevel,
+
+    /// Three hole cards, one of them discarded before the showdown so at
+    /// most two play
+    Pineapple,
+
+    /// Seven card stud, every player gets seven cards of their own and
+    /// there is no board
+    Stud,
+
+    /// Razz, seven card stud won by the lowest ace to five hand
+    Razz,
+
+    /// Deuce to seven single draw, five cards each with one draw to the
+    /// lowest hand where straights and flushes count
+    Deuce7,
+
+    /// Four cards each with one draw, the most cards of different ranks
+    /// and suits wins and then the lowest
+    Badugi,
+}
+
+/// The rules of a variant that the equity calculations depend on
+/// Each game is an implementation, so a new variant does not need another
+/// arm in every match on `Game`
+pub trait GameRules: Sync {
+    /// Number of hole cards dealt to each player
+    /// In stud this is every card a player is dealt, up and down
+    fn hole_cards(&self) -> usize;
+
+    /// Whether the players share community cards
+    fn has_board(&self) -> bool {
+        true
+    }
+
+    /// Whether a player's best hand can be the five board cards alone
+    fn can_play_the_board(&self) -> bool {
+        self.has_board()
+    }
+
+    /// Whether a board of `cards` cards can be played, which is preflop or
+    /// any street after the flop
+    fn is_valid_board(&self, cards: usize) -> bool {
+        matches!(cards, 0 | 3..=5)
+    }
+
+    /// Whether a hand of `cards` cards can be played, the full deal in
+    /// games with a board and any number dealt so far without one
+    fn is_valid_hand(&self, cards: usize) -> bool {
+        match self.has_board() {
+            true => cards == self.hole_cards(),
+            false => cards <= self.hole_cards(),
+        }
+    }
+
+    /// Best five card hand out of the hole cards and the board, ranking any
+    /// five to seven cards with `evaluator`
+    fn best_hand(&self, hole: &[Card], board: &[Card], evaluator: &dyn Evaluator) -> HandValue;
+
+    /// The five cards of the high hand `best_hand` ranks, along with its
+    /// rank, for showing which cards play
+    fn best_five_cards(&self, hole: &[Card], board: &[Card]) -> ([Card; 5], PokerHandRank) {
+        let cards = hole.iter().chain(board).copied().collect::<Vec<_>>();
+        get_best_five_cards(&cards)
+    }
+
+    /// Best hand of every player, added to `values`
+    /// Games whose hands are seven cards rank them in batches
+    fn best_hands(
+        &self,
+        hands: &[&[Card]],
+        board: &[Card],
+        evaluator: &dyn Evaluator,
+        values: &mut Vec<HandValue>,
+    ) {
+        values.extend(
+            hands
+                .iter()
+                .map(|hole| self.best_hand(hole, board, evaluator)),
+        );
+    }
+
+    /// Whether the pot is split between the best high and the best low hand
+    fn is_hi_lo(&self) -> bool {
+        false
+    }
+
+    /// Best qualifying low a player can make, None in games without a low
+    fn best_low(&self, _hole: &[Card], _board: &[Card]) -> Option<LowHand> {
+        None
+    }
+
+    /// Whether the lowest hand wins the whole pot, as in razz
+    fn is_lowball(&self) -> bool {
+        false
+    }
+}
+
+/// Any five of the hole cards and the board
+pub struct Holdem;
+
+/// Exactly two hole cards and three from the board
+pub struct Omaha {
+    /// Cards dealt to each player
+    pub hole_cards: usize,
+    /// Whether the pot is split with the best eight or better low
+    pub hi_lo: bool,
+    /// Whether the first flop card is dealt before the preflop betting
+    pub exposed_card: bool,
+}
+
+/// Three hole cards, of which at most two play
+pub struct Pineapple;
+
+/// Games without a board, where every player's cards are their own
+/// Lowball games are ranked by `RazzHand`, `DeuceSevenHand` or `BadugiHand`
+pub struct OwnCards {
+    /// Cards a player ends up with
+    pub hole_cards: usize,
+    /// Whether the lowest hand wins
+    pub lowball: bool,
+}
+
+impl Game {
+    /// The rules of the game
+    pub fn rules(self) -> &'static dyn GameRules {
+        match self {
+            Game::Holdem => &Holdem,
+            Game::Omaha => &Omaha::PLO,
+            Game::Omaha8 => &Omaha::PLO8,
+            Game::Omaha5 => &Omaha::PLO5,
+            Game::Courchevel => &Omaha::COURCHEVEL,
+            Game::Pineapple => &Pineapple,
+            Game::Stud => &OwnCards::STUD,
+            Game::Razz => &OwnCards::RAZZ,
+            Game::Deuce7 => &OwnCards::DEUCE_SEVEN,
+            Game::Badugi => &OwnCards::BADUGI,
+        }
+    }
+
+    /// Number of hole cards dealt to each player
+    pub fn hole_cards(self) -> usize {
+        self.rules().hole_cards()
+    }
+
+    /// Whether the players share community cards
+    pub fn has_board(self) -> bool {
+        self.rules().has_board()
+    }
+
+    /// Whether a board of `cards` cards can be played
+    pub fn is_valid_board(self, cards: usize) -> bool {
+        self.rules().is_valid_board(cards)
+    }
+
+    /// Whether the lowest hand wins the whole pot
+    pub fn is_lowball(self) -> bool {
+        self.rules().is_lowball()
+    }
+
+    /// Check a hand holds as many hole cards as the game deals, or in games
+    /// without a board any number of them dealt so far
+    pub fn check_hole_cards(self, found: usize) -> Result<(), Error> {
+        if self.rules().is_valid_hand(found) {
+            return Ok(());
+        }
+        let expected = self.hole_cards();
+        if !self.has_board() && found > expected {
+            return Err(Error::TooManyCards { game: self, found });
+        }
+        Err(Error::WrongHoleCards {
+            game: self,
+            expected,
+            found,
+        })
+    }
+
+    /// Best five card hand a player can make with their hole cards and the board
+    /// Games without a board use the hole cards alone and ignore `board`
+    pub fn best_hand(self, hole: &[Card], board: [Card; 5]) -> PokerHandRank {
+        let evaluator = EvaluatorKind::default().evaluator();
+        self.rules().best_hand(hole, &board, evaluator).rank()
+    }
+
+    /// Whether the pot is split between the best high and the best low hand
+    pub fn is_hi_lo(self) -> bool {
+        self.rules().is_hi_lo()
+    }
+
+    /// The five cards of the best high hand a player can make in the order
+    /// the hand is read, along with its rank
+    /// Games without a board use the hole cards alone and ignore `board`
+    pub fn best_five_cards(self, hole: &[Card], board: [Card; 5]) -> ([Card; 5], PokerHandRank) {
+        let (cards, rank) = match self.has_board() {
+            true => self.rules().best_five_cards(hole, &board),
+            false => self.rules().best_five_cards(hole, &[]),
+        };
+        (in_hand_order(cards, rank), rank)
+    }
+
+    /// Best qualifying low a player can make, None in games without a low
+    pub fn best_low(self, hole: &[Card], board: [Card; 5]) -> Option<LowHand> {
+        self.rules().best_low(hole, &board)
+    }
+
+    /// Whether the hole cards add nothing to the board, so the player's best
+    /// hand is the five board cards
+    pub fn plays_the_board(self, hole: &[Card], board: [Card; 5]) -> bool {
+        self.rules().can_play_the_board() && self.best_hand(hole, board) == cards_to_hand(board)
+    }
+}
+
+///////////////////////////////////////////////
+/// GameRules Implementations
+///////////////////////////////////////////////
+impl GameRules for Holdem {
+    fn hole_cards(&self) -> usize {
+        2
+    }
+
+    fn best_hand(&self, hole: &[Card], board: &[Card], evaluator: &dyn Evaluator) -> HandValue {
+        // The river showdown is the hot path
+        if let ([a, b], [c, d, e, f, g]) = (hole, board) {
+            return evaluator.evaluate(&[*a, *b, *c, *d, *e, *f, *g]);
+        }
+        let mut cards = [hole[0]; 7];
+        let count = hole.len() + board.len();
+        cards[..hole.len()].copy_from_slice(hole);
+        cards[hole.len()..count].copy_from_slice(board);
+        evaluator.evaluate(&cards[..count])
+    }
+
+    fn best_hands(
+        &self,
+        hands: &[&[Card]],
+        board: &[Card],
+        evaluator: &dyn Evaluator,
+        values: &mut Vec<HandValue>,
+    ) {
+        let Ok(&[c, d, e, f, g]) = <&[Card; 5]>::try_from(board) else {
+            values.extend(
+                hands
+                    .iter()
+                    .map(|hole| self.best_hand(hole, board, evaluator)),
+            );
+            return;
+        };
+
+        for chunk in hands.chunks(BATCH) {
+            let mut sevens = [[c; 7]; BATCH];
+            for (seven, hole) in sevens.iter_mut().zip(chunk) {
+                *seven = [hole[0], hole[1], c, d, e, f, g];
+            }
+            evaluator.evaluate_batch(&sevens[..chunk.len()], values);
+        }
+    }
+}
+
+impl Omaha {
+    /// Four card Omaha
+    pub const PLO: Omaha = Omaha {
+        hole_cards: 4,
+        hi_lo: false,
+        exposed_card: false,
+    };
+    /// Omaha hi-lo, eight or better
+    pub const PLO8: Omaha = Omaha {
+        hi_lo: true,
+        ..Omaha::PLO
+    };
+    /// Five card Omaha
+    pub const PLO5: Omaha = Omaha {
+        hole_cards: 5,
+        ..Omaha::PLO
+    };
+    /// Five card Omaha with the first flop card exposed preflop
+    pub const COURCHEVEL: Omaha = Omaha {
+        exposed_card: true,
+        ..Omaha::PLO5
+    };
+}
+
+impl GameRules for Omaha {
+    fn hole_cards(&self) -> usize {
+        self.hole_cards
+    }
+
+    /// Two hole cards always play
+    fn can_play_the_board(&self) -> bool {
+        false
+    }
+
+    fn is_valid_board(&self, cards: usize) -> bool {
+        matches!(cards, 0 | 3..=5) || (self.exposed_card && cards == 1)
+    }
+
+    /// Only five card hands are ranked, so any evaluator gives the same
+    fn best_hand(&self, hole: &[Card], board: &[Card], _: &dyn Evaluator) -> HandValue {
+        get_best_omaha_hand(hole, board).into()
+    }
+
+    fn best_five_cards(&self, hole: &[Card], board: &[Card]) -> ([Card; 5], PokerHandRank) {
+        get_best_omaha_five_cards(hole, board)
+    }
+
+    fn is_hi_lo(&self) -> bool {
+        self.hi_lo
+    }
+
+    fn best_low(&self, hole: &[Card], board: &[Card]) -> Option<LowHand> {
+        self.hi_lo
+            .then(|| get_best_omaha_low(hole, board))
+            .flatten()
+    }
+}
+
+impl GameRules for Pineapple {
+    fn hole_cards(&self) -> usize {
+        3
+    }
+
+    /// Two cards once the player has discarded
+    fn is_valid_hand(&self, cards: usize) -> bool {
+        matches!(cards, 2 | 3)
+    }
+
+    fn best_hand(&self, hole: &[Card], board: &[Card], _: &dyn Evaluator) -> HandValue {
+        get_best_pineapple_hand(hole, board).into()
+    }
+
+    fn best_five_cards(&self, hole: &[Card], board: &[Card]) -> ([Card; 5], PokerHandRank) {
+        get_best_pineapple_five_cards(hole, board)
+    }
+}
+
+impl OwnCards {
+    /// Seven card stud
+    pub const STUD: OwnCards = OwnCards {
+        hole_cards: 7,
+        lowball: false,
+    };
+    /// Seven card stud won by the lowest ace to five hand
+    pub const RAZZ: OwnCards = OwnCards {
+        lowball: true,
+        ..OwnCards::STUD
+    };
+    /// Deuce to seven single draw
+    pub const DEUCE_SEVEN: OwnCards = OwnCards {
+        hole_cards: 5,
+        lowball: true,
+    };
+    /// Badugi, four cards with one draw
+    pub const BADUGI: OwnCards = OwnCards {
+        hole_cards: 4,
+        lowball: true,
+    };
+}
+
+impl GameRules for OwnCards {
+    fn hole_cards(&self) -> usize {
+        self.hole_cards
+    }
+
+    fn has_board(&self) -> bool {
+        false
+    }
+
+    /// The high hand out of the player's own cards, which needs at least five
+    fn best_hand(&self, hole: &[Card], _: &[Card], evaluator: &dyn Evaluator) -> HandValue {
+        evaluator.evaluate(hole)
+    }
+
+    fn is_lowball(&self) -> bool {
+        self.lowball
+    }
+}
+
+impl Display for Game {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Game::Holdem => "holdem",
+            Game::Omaha => "omaha",
+            Game::Omaha8 => "omaha8",
+            Game::Omaha5 => "omaha5",
+            Game::Courchevel => "courchevel",
+            Game::Pineapple => "pineapple",
+            Game::Stud => "stud",
+            Game::Razz => "razz",
+            Game::Deuce7 => "deuce7",
+            Game::Badugi => "badugi",
+        })
+    }
+}
+
+impl FromStr for Game {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "holdem" | "nlhe" => Ok(Game::Holdem),
+            "omaha" | "plo" => Ok(Game::Omaha),
+            "omaha8" | "plo8" | "o8" => Ok(Game::Omaha8),
+            "omaha5" | "plo5" => Ok(Game::Omaha5),
+            "courchevel" => Ok(Game::Courchevel),
+            "pineapple" | "crazy-pineapple" => Ok(Game::Pineapple),
+            "stud" | "7stud" | "stud7" => Ok(Game::Stud),
+            "razz" => Ok(Game::Razz),
+            "deuce7" | "2-7" | "27" => Ok(Game::Deuce7),
+            "badugi" => Ok(Game::Badugi),
+            _ => Err(format!(
+                "Unknown game \"{}\", expected holdem, omaha, omaha8, omaha5, courchevel, pineapple, stud, razz, deuce7 or badugi",
+                s
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::card::cards_from_str;
+
+    #[test]
+    fn test_game_rules() {
+        assert!(Game::Pineapple.check_hole_cards(2).is_ok());
+        assert!(Game::Holdem.check_hole_cards(3).is_err());
+        assert!(Game::Stud.check_hole_cards(3).is_ok());
+        assert!(matches!(
+            Game::Razz.check_hole_cards(8),
+            Err(Error::TooManyCards { found: 8, .. })
+        ));
+
+        assert!(Game::Courchevel.is_valid_board(1));
+        assert!(!Game::Omaha5.is_valid_board(1));
+        assert!(!Game::Holdem.is_valid_board(6));
+
+        // Omaha plays exactly two hole cards on any street
+        let turn = cards_from_str("2h3h4h9h").unwrap();
+        let hole = cards_from_str("AhKsQsJc").unwrap();
+        let evaluator = EvaluatorKind::Naive.evaluator();
+        let omaha = Game::Omaha.rules().best_hand(&hole, &turn, evaluator);
+        assert!(omaha < Game::Holdem.rules().best_hand(&hole[..2], &turn, evaluator));
+
+        // The five cards shown are the ones the hand is ranked by
+        let board = <[Card; 5]>::try_from(cards_from_str("2h3h4h9hTd").unwrap()).unwrap();
+        for (game, hole) in [(Game::Holdem, &hole[..2]), (Game::Omaha, &hole[..])] {
+            let (five, rank) = game.best_five_cards(hole, board);
+            assert_eq!(rank, game.best_hand(hole, board));
+            let from_hole = five.iter().filter(|card| hole.contains(card)).count();
+            assert!(five
+                .iter()
+                .all(|card| hole.contains(card) || board.contains(card)));
+            assert!(game != Game::Omaha || from_hole == 2, "{:?}", five);
+        }
+
+        // A broadway board plays for anyone without a flush, except in Omaha
+        let broadway = <[Card; 5]>::try_from(cards_from_str("AsKdQcJhTs").unwrap()).unwrap();
+        let hole = cards_from_str("2c2d3h4h").unwrap();
+        assert!(Game::Holdem.plays_the_board(&hole[..2], broadway));
+        assert!(!Game::Omaha.plays_the_board(&hole, broadway));
+        assert!(!Game::Holdem.plays_the_board(&hole[..2], board));
+        assert!(!Game::Stud.plays_the_board(&hole, broadway));
+    }
+}
@@ -0,0 +1,273 @@
+//! Hold'em showdowns ranked on the GPU with a wgpu compute shader
+//! Enabled with the `gpu` feature. Exact enumerations and simulations of
+//! hold'em queue up their showdowns and rank them in large batches, and
+//! run on the CPU as before when no adapter is found
+
+use std::sync::OnceLock;
+
+use wgpu::util::DeviceExt;
+
+use crate::card::Card;
+use crate::equity::{record_showdown, HandResult};
+use crate::game::Game;
+use crate::poker_hand::HandValue;
+
+/// Seven card hands ranked in one dispatch
+const DISPATCH: usize = 1 << 18;
+
+/// Threads in each workgroup of the shader
+const WORKGROUP: usize = 64;
+
+/// Seven card hands queued up before ranking them
+const QUEUED: usize = 1 << 16;
+
+/// A GPU ready to rank seven card hands
+pub struct Gpu {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    pipeline: wgpu::ComputePipeline,
+}
+
+/// Hold'em showdowns waiting to be ranked on the GPU together
+pub(crate) struct Queue {
+    gpu: &'static Gpu,
+    dealt: Vec<usize>,
+    hands: Vec<[Card; 7]>,
+    values: Vec<HandValue>,
+    winners: Vec<usize>,
+}
+
+/// The first GPU found, connected to once and shared from then on
+pub fn gpu() -> Option<&'static Gpu> {
+    static GPU: OnceLock<Option<Gpu>> = OnceLock::new();
+    GPU.get_or_init(Gpu::new).as_ref()
+}
+
+/// Queue for the showdowns of the game, when it is hold'em and a GPU was found
+pub(crate) fn queue(game: Game) -> Option<Queue> {
+    (game == Game::Holdem).then(gpu).flatten().map(Queue::new)
+}
+
+/// One byte a card with the suit above the rank, two words a hand
+fn pack(hand: &[Card; 7]) -> [u32; 2] {
+    let mut words = [0; 2];
+    for (i, card) in hand.iter().enumerate() {
+        let byte = (card.suit as u32) << 4 | (card.rank.value() - 2);
+        words[i / 4] |= byte << (i % 4 * 8);
+    }
+    words
+}
+
+///////////////////////////////////////////////
+/// Gpu Implementations
+///////////////////////////////////////////////
+impl Gpu {
+    /// Connect to the first GPU found and build the shader, None without one
+    pub fn new() -> Option<Gpu> {
+        pollster::block_on(async {
+            let instance = wgpu::Instance::default();
+            let options = wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::HighPerformance,
+                ..Default::default()
+            };
+            let adapter = instance.request_adapter(&options).await.ok()?;
+            let (device, queue) = adapter
+                .request_device(&wgpu::DeviceDescriptor::default())
+                .await
+                .ok()?;
+
+            let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some("evaluate"),
+                source: wgpu::ShaderSource::Wgsl(include_str!("gpu.wgsl").into()),
+            });
+            let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some("evaluate"),
+                layout: None,
+                module: &module,
+                entry_point: Some("main"),
+                compilation_options: Default::default(),
+                cache: None,
+            });
+
+            Some(Gpu {
+                device,
+                queue,
+                pipeline,
+            })
+        })
+    }
+
+    /// Rank the best five card hand of every seven card hand, adding their
+    /// values to `values`
+    pub fn evaluate_batch(&self, hands: &[[Card; 7]], values: &mut Vec<HandValue>) {
+        for chunk in hands.chunks(DISPATCH) {
+            self.dispatch(chunk, values);
+        }
+    }
+
+    /// Rank at most `DISPATCH` hands in one go
+    fn dispatch(&self, hands: &[[Card; 7]], values: &mut Vec<HandValue>) {
+        let packed = hands.iter().map(pack).collect::<Vec<_>>();
+        let size = (hands.len() * size_of::<u32>()) as wgpu::BufferAddress;
+
+        let input = self
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("hands"),
+                contents: bytemuck::cast_slice(&packed),
+                usage: wgpu::BufferUsages::STORAGE,
+            });
+        let output = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("values"),
+            size,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let readback = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("readback"),
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("evaluate"),
+            layout: &self.pipeline.get_bind_group_layout(0),
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: input.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: output.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut encoder = self.device.create_command_encoder(&Default::default());
+        {
+            let mut pass = encoder.begin_compute_pass(&Default::default());
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.dispatch_workgroups(hands.len().div_ceil(WORKGROUP) as u32, 1, 1);
+        }
+        encoder.copy_buffer_to_buffer(&output, 0, &readback, 0, size);
+        let submission = self.queue.submit([encoder.finish()]);
+
+        let slice = readback.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |_| {});
+        self.device
+            .poll(wgpu::PollType::WaitForSubmissionIndex(submission))
+            .expect("GPU stopped responding");
+
+        let mapped = slice.get_mapped_range();
+        values.extend(
+            bytemuck::cast_slice::<u8, u32>(&mapped)
+                .iter()
+                .map(|&value| HandValue(value)),
+        );
+    }
+}
+
+///////////////////////////////////////////////
+/// Queue Implementations
+///////////////////////////////////////////////
+impl Queue {
+    fn new(gpu: &'static Gpu) -> Self {
+        Queue {
+            gpu,
+            dealt: Vec::with_capacity(QUEUED),
+            hands: Vec::with_capacity(QUEUED),
+            values: Vec::with_capacity(QUEUED),
+            winners: vec![],
+        }
+    }
+
+    /// Queue a showdown of two card hands on a full board, ranking and
+    /// recording everything queued once it is full
+    pub(crate) fn push(
+        &mut self,
+        results: &mut [HandResult],
+        dealt: &[usize],
+        hands: &[&[Card]],
+        board: [Card; 5],
+    ) {
+        self.dealt.extend_from_slice(dealt);
+        self.hands.extend(hands.iter().map(|hole| {
+            let mut cards = [board[0]; 7];
+            cards[..2].copy_from_slice(hole);
+            cards[2..].copy_from_slice(&board);
+            cards
+        }));
+
+        if self.hands.len() >= QUEUED {
+            self.flush(results);
+        }
+    }
+
+    /// Rank and record every queued showdown
+    pub(crate) fn flush(&mut self, results: &mut [HandResult]) {
+        if self.hands.is_empty() {
+            return;
+        }
+        self.values.clear();
+        self.gpu.evaluate_batch(&self.hands, &mut self.values);
+
+        let players = results.len();
+        let showdowns = self.dealt.chunks(players).zip(self.values.chunks(players));
+        for (dealt, values) in showdowns {
+            let best = values.iter().max().unwrap();
+            self.winners.clear();
+            self.winners
+                .extend((0..players).filter(|&player| values[player] == *best));
+            record_showdown(results, dealt, values, &self.winners, None);
+        }
+
+        self.dealt.clear();
+        self.hands.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::deck::Deck;
+    use crate::lookup::evaluate;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::array;
+
+    #[test]
+    fn test_shader_is_valid() {
+        use wgpu::naga::{front::wgsl, valid};
+
+        let module = wgsl::parse_str(include_str!("gpu.wgsl")).unwrap();
+        valid::Validator::new(valid::ValidationFlags::all(), valid::Capabilities::empty())
+            .validate(&module)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_matches_lookup_evaluator() {
+        // Everything runs on the CPU without a GPU
+        let Some(gpu) = gpu() else {
+            return;
+        };
+
+        let mut rng = StdRng::seed_from_u64(29);
+        let mut deck = Deck::new();
+        let hands = (0..100_000)
+            .map(|_| {
+                deck.shuffle(&mut rng);
+                array::from_fn(|i| deck.remaining()[i])
+            })
+            .collect::<Vec<[Card; 7]>>();
+
+        let mut values = vec![];
+        gpu.evaluate_batch(&hands, &mut values);
+        assert_eq!(values.len(), hands.len());
+        for (hand, value) in hands.iter().zip(values) {
+            assert_eq!(value, evaluate(hand).into(), "{:?}", hand);
+        }
+    }
+}
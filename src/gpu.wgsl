@@ -0,0 +1,112 @@
+// Ranks seven card hands the same way as the lookup evaluator, writing
+// each one out packed as a HandValue
+
+// Seven cards a hand, one byte each with the suit in the high four bits and
+// the rank counting up from a two in the low four bits
+@group(0) @binding(0) var<storage, read> hands: array<vec2<u32>>;
+@group(0) @binding(1) var<storage, read_write> values: array<u32>;
+
+// High card of the best straight in a rank mask, zero without one
+fn straight(mask: u32) -> u32 {
+    // Shift up one so the ace can also play below the two
+    let ranks = (mask << 1u) | ((mask >> 12u) & 1u);
+    let runs = ranks & (ranks >> 1u) & (ranks >> 2u) & (ranks >> 3u) & (ranks >> 4u);
+    if runs == 0u {
+        return 0u;
+    }
+    return firstLeadingBit(runs) + 5u;
+}
+
+// Add the `count` highest ranks in the mask below the value
+fn add_highest(value: u32, mask: u32, count: u32) -> u32 {
+    var packed = value;
+    var left = mask;
+    for (var i = 0u; i < count; i++) {
+        let rank = firstLeadingBit(left);
+        packed = (packed << 4u) | (rank + 2u);
+        left &= ~(1u << rank);
+    }
+    return packed;
+}
+
+// Rank a hand from the ranks of its flush suit, if any, and the masks of
+// the ranks held at least once, twice, three and four times
+fn classify(flush: u32, any: u32, pairs: u32, trips: u32, quads: u32) -> u32 {
+    // With at most seven cards a flush rules out quads and full houses
+    if flush != 0u {
+        let high = straight(flush);
+        if high != 0u {
+            return (8u << 20u) | (high << 16u);
+        }
+        return add_highest(5u, flush, 5u);
+    }
+
+    if quads != 0u {
+        return add_highest(add_highest(7u, quads, 1u), any & ~quads, 1u) << 12u;
+    }
+
+    if trips != 0u {
+        // The second set of trips plays as the pair
+        let pair = pairs & ~(1u << firstLeadingBit(trips));
+        if pair != 0u {
+            return add_highest(add_highest(6u, trips, 1u), pair, 1u) << 12u;
+        }
+    }
+
+    let high = straight(any);
+    if high != 0u {
+        return (4u << 20u) | (high << 16u);
+    }
+
+    if trips != 0u {
+        return add_highest(add_highest(3u, trips, 1u), any & ~trips, 2u) << 8u;
+    }
+
+    if countOneBits(pairs) >= 2u {
+        let high_pair = 1u << firstLeadingBit(pairs);
+        let low_pair = 1u << firstLeadingBit(pairs & ~high_pair);
+        let two_pair = high_pair | low_pair;
+        return add_highest(add_highest(2u, two_pair, 2u), any & ~two_pair, 1u) << 8u;
+    }
+
+    if pairs != 0u {
+        return add_highest(add_highest(1u, pairs, 1u), any & ~pairs, 3u) << 4u;
+    }
+
+    return add_highest(0u, any, 5u);
+}
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let hand = id.x;
+    if hand >= arrayLength(&values) {
+        return;
+    }
+
+    let cards = hands[hand];
+    var suits = array<u32, 4>(0u, 0u, 0u, 0u);
+    var any = 0u;
+    var pairs = 0u;
+    var trips = 0u;
+    var quads = 0u;
+    for (var i = 0u; i < 7u; i++) {
+        let card = (cards[i / 4u] >> ((i % 4u) * 8u)) & 0xffu;
+        let bit = 1u << (card & 15u);
+        suits[card >> 4u] |= bit;
+
+        // A rank seen before moves up to the next mask
+        quads |= trips & bit;
+        trips |= pairs & bit;
+        pairs |= any & bit;
+        any |= bit;
+    }
+
+    var flush = 0u;
+    for (var suit = 0u; suit < 4u; suit++) {
+        if countOneBits(suits[suit]) >= 5u {
+            flush = suits[suit];
+        }
+    }
+
+    values[hand] = classify(flush, any, pairs, trips, quads);
+}
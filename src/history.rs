@@ -0,0 +1,582 @@
+//! Hand histories in the text format of PokerStars and GGPoker, and the
+//! expected value of all in confrontations in them
+//! The expected value of an all in is each player's equity in every pot they
+//! can win once the betting is over, against the cards that were shown
+
+use rand::Rng;
+
+use crate::card::{cards_from_str, Card};
+use crate::equity::{run_exact_calculation, run_range_calculation, SimulationSettings};
+use crate::error::Error;
+use crate::game::Game;
+use crate::range::Range;
+
+/// Start of the first line of every hand
+const HEADERS: [&str; 3] = [
+    "PokerStars Hand #",
+    "PokerStars Zoom Hand #",
+    "Poker Hand #",
+];
+
+/// One player dealt into a hand
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Player {
+    pub name: String,
+
+    /// Chips in front of the player when the hand started
+    pub stack: f64,
+
+    /// Hole cards, when the player was dealt them face up or showed them
+    pub cards: Option<Vec<Card>>,
+
+    /// Chips put into the pot, less any bet that was returned uncalled
+    pub invested: f64,
+
+    /// Chips taken from the pot
+    pub collected: f64,
+
+    pub folded: bool,
+    pub all_in: bool,
+}
+
+impl Player {
+    /// Chips won or lost in the hand
+    pub fn net(&self) -> f64 {
+        self.collected - self.invested
+    }
+}
+
+/// A single hand read from a hand history
+#[derive(Debug, Clone, PartialEq)]
+pub struct HandHistory {
+    /// Hand number, such as "123456789" or "HD12345"
+    pub id: String,
+
+    pub game: Game,
+
+    /// Player the hole cards were dealt to face up, the one who saved the history
+    pub hero: Option<String>,
+
+    /// Every player in seat order
+    pub players: Vec<Player>,
+
+    /// Every board card dealt
+    pub board: Vec<Card>,
+
+    /// Number of board cards out when the last bet was made
+    pub betting_board: usize,
+
+    /// Whether the hand went to a showdown
+    pub showdown: bool,
+}
+
+///////////////////////////////////////////////
+/// HandHistory Implementations
+///////////////////////////////////////////////
+impl HandHistory {
+    /// Position of a player by name
+    pub fn player(&self, name: &str) -> Option<usize> {
+        self.players.iter().position(|p| p.name == name)
+    }
+
+    /// Whether the hand ended with an all in called by every other player
+    /// left, with every player's cards shown so the equity can be worked out
+    pub fn is_all_in(&self) -> bool {
+        let left = self
+            .players
+            .iter()
+            .filter(|p| !p.folded && p.invested > 0.0);
+        let mut shown = 0;
+        for player in left {
+            if player.cards.is_none() {
+                return false;
+            }
+            shown += 1;
+        }
+
+        self.showdown && shown >= 2 && self.players.iter().any(|p| p.all_in && !p.folded)
+    }
+
+    /// Main pot and side pots as their chips and the players who can win them
+    /// Chips of folded players go into the pots they reached
+    pub fn pots(&self) -> Vec<(f64, Vec<usize>)> {
+        let mut levels = self
+            .players
+            .iter()
+            .filter(|p| !p.folded)
+            .map(|p| p.invested)
+            .filter(|i| *i > 0.0)
+            .collect::<Vec<_>>();
+        levels.sort_by(f64::total_cmp);
+        levels.dedup();
+
+        let mut pots: Vec<(f64, Vec<usize>)> = vec![];
+        let mut below = 0.0;
+        for level in levels {
+            let chips = self
+                .players
+                .iter()
+                .map(|p| p.invested.min(level) - p.invested.min(below))
+                .sum::<f64>();
+            let eligible = (0..self.players.len())
+                .filter(|&i| !self.players[i].folded && self.players[i].invested >= level)
+                .collect::<Vec<_>>();
+
+            // A level only one player reached belongs to the pot below it
+            match pots.last_mut() {
+                Some(last) if eligible.len() == 1 && last.1.len() == 1 => last.0 += chips,
+                _ => pots.push((chips, eligible)),
+            }
+            below = level;
+        }
+
+        pots
+    }
+
+    /// Share of the pots the players took home, less than 1 when rake was taken
+    pub fn paid_out(&self) -> f64 {
+        let pot = self.players.iter().map(|p| p.invested).sum::<f64>();
+        let collected = self.players.iter().map(|p| p.collected).sum::<f64>();
+        if pot > 0.0 {
+            (collected / pot).min(1.0)
+        } else {
+            1.0
+        }
+    }
+
+    /// Chips each player could expect to win or lose when the betting was over,
+    /// from their equity in every pot they can win, less the rake taken
+    /// Boards that still need the flop are simulated with `iterations`,
+    /// later boards are enumerated exactly
+    /// None unless `is_all_in`
+    pub fn all_in_ev<R: Rng>(&self, iterations: u32, rng: &mut R) -> Option<Vec<f64>> {
+        if !self.is_all_in() {
+            return None;
+        }
+
+        let board = self.board[..self.betting_board].to_vec();
+        let paid_out = self.paid_out();
+        let mut won = vec![0.0; self.players.len()];
+
+        for (chips, eligible) in self.pots() {
+            let equities = match eligible.as_slice() {
+                [only] => {
+                    won[*only] += chips * paid_out;
+                    continue;
+                }
+                players => self.equities(players, &board, iterations, rng),
+            };
+            for (&player, equity) in eligible.iter().zip(equities) {
+                won[player] += equity * chips * paid_out;
+            }
+        }
+
+        Some(
+            won.iter()
+                .zip(&self.players)
+                .map(|(won, player)| won - player.invested)
+                .collect(),
+        )
+    }
+
+    /// Equity of each of the players in a pot, with the shown cards of
+    /// everyone else dead
+    fn equities<R: Rng>(
+        &self,
+        players: &[usize],
+        board: &[Card],
+        iterations: u32,
+        rng: &mut R,
+    ) -> Vec<f64> {
+        let cards = |i: usize| self.players[i].cards.clone().unwrap_or_default();
+        let ranges = players
+            .iter()
+            .map(|&i| Range::from_cards(cards(i)))
+            .collect::<Vec<_>>();
+        let dead = (0..self.players.len())
+            .filter(|i| !players.contains(i))
+            .flat_map(cards)
+            .collect::<Vec<_>>();
+
+        let results = if board.len() >= 3 {
+            run_exact_calculation(self.game, board.to_vec(), dead, ranges)
+                .expect("shown cards are not weighted")
+        } else {
+            let settings = SimulationSettings {
+                threads: 1,
+                ..SimulationSettings::new(iterations)
+            };
+            run_range_calculation(self.game, board.to_vec(), dead, ranges, &settings, rng)
+        };
+        results.iter().map(|r| r.equity()).collect()
+    }
+}
+
+/// Split a file of hand histories into the text of each hand
+pub fn split_hand_histories(text: &str) -> Vec<&str> {
+    let mut starts = vec![];
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim_start_matches('\u{feff}').trim_start();
+        if HEADERS.iter().any(|h| trimmed.starts_with(h)) {
+            starts.push(offset);
+        }
+        offset += line.len();
+    }
+
+    starts
+        .iter()
+        .enumerate()
+        .map(|(i, &start)| {
+            let end = starts.get(i + 1).copied().unwrap_or(text.len());
+            text[start..end].trim()
+        })
+        .collect()
+}
+
+/// Read a single hand
+pub fn parse_hand_history(text: &str) -> Result<HandHistory, Error> {
+    let mut lines = text.lines().map(str::trim).filter(|l| !l.is_empty());
+    let header = lines
+        .next()
+        .ok_or_else(|| history_error("", "the hand is empty"))?;
+    let header = header.trim_start_matches('\u{feff}');
+
+    let id = header
+        .split_once('#')
+        .and_then(|(_, rest)| rest.split(':').next())
+        .map(|id| id.trim().to_string())
+        .ok_or_else(|| history_error(header, "expected a hand number after #"))?;
+    let game = if header.contains("Omaha Hi/Lo") || header.contains("Omaha 8") {
+        Game::Omaha8
+    } else if header.contains("5 Card Omaha") {
+        Game::Omaha5
+    } else if header.contains("Omaha") {
+        Game::Omaha
+    } else if header.contains("Hold'em") {
+        Game::Holdem
+    } else {
+        return Err(history_error(
+            &id,
+            "only Hold'em and Omaha hands can be read",
+        ));
+    };
+
+    let mut hand = HandHistory {
+        id,
+        game,
+        hero: None,
+        players: vec![],
+        board: vec![],
+        betting_board: 0,
+        showdown: false,
+    };
+    // Chips each player has put in on the current street
+    let mut street = vec![];
+
+    for line in lines {
+        if let Some(marker) = line.strip_prefix("*** ") {
+            if marker.starts_with("SUMMARY") {
+                break;
+            }
+            if marker.starts_with("FIRST") || marker.starts_with("SECOND") {
+                return Err(history_error(
+                    &hand.id,
+                    "boards run more than once can not be read",
+                ));
+            }
+            if marker.starts_with("SHOW") {
+                hand.showdown = true;
+            }
+            if marker.starts_with("FLOP")
+                || marker.starts_with("TURN")
+                || marker.starts_with("RIVER")
+            {
+                hand.board = bracketed_cards(&hand.id, line)?;
+                street.fill(0.0);
+            }
+            continue;
+        }
+
+        if let Some(seat) = line.strip_prefix("Seat ") {
+            // Seats are listed before anything happens, and again in the summary
+            if hand.board.is_empty() && street.iter().all(|c| *c == 0.0) {
+                if let Some(player) = parse_seat(seat) {
+                    hand.players.push(player);
+                    street.push(0.0);
+                }
+            }
+            continue;
+        }
+
+        if let Some(rest) = line.strip_prefix("Dealt to ") {
+            let Some(i) = hand.players.iter().position(|p| rest.starts_with(&p.name)) else {
+                continue;
+            };
+            let cards = &rest[hand.players[i].name.len()..];
+            if cards.contains('[') {
+                hand.players[i].cards = Some(bracketed_cards(&hand.id, cards)?);
+                hand.hero = Some(hand.players[i].name.clone());
+            }
+            continue;
+        }
+
+        if let Some(rest) = line.strip_prefix("Uncalled bet (") {
+            let (amount, name) = rest
+                .split_once(") returned to ")
+                .ok_or_else(|| history_error(&hand.id, line))?;
+            if let Some(i) = hand.player(name.trim()) {
+                let amount = parse_amount(&hand.id, amount)?;
+                hand.players[i].invested -= amount;
+                street[i] -= amount;
+            }
+            continue;
+        }
+
+        // Everything else that matters starts with a player's name
+        let Some(i) = hand.players.iter().position(|p| {
+            line.starts_with(&p.name) && line[p.name.len()..].starts_with([':', ' '])
+        }) else {
+            continue;
+        };
+        let action = line[hand.players[i].name.len()..]
+            .trim_start_matches(':')
+            .trim();
+
+        if let Some(rest) = action.strip_prefix("collected ") {
+            let amount = rest.split(" from").next().unwrap_or(rest);
+            hand.players[i].collected += parse_amount(&hand.id, amount)?;
+            continue;
+        }
+        if let Some(cards) = action.strip_prefix("shows ") {
+            hand.players[i].cards = Some(bracketed_cards(&hand.id, cards)?);
+            continue;
+        }
+
+        let all_in = action.ends_with("and is all-in");
+        let action = action.trim_end_matches("and is all-in").trim();
+        let put_in = if action == "folds" {
+            hand.players[i].folded = true;
+            None
+        } else if action == "checks" {
+            hand.betting_board = hand.board.len();
+            None
+        } else if let Some(amount) = action.strip_prefix("posts the ante ") {
+            // Antes are dead and do not count towards calling a bet
+            let amount = parse_amount(&hand.id, amount)?;
+            hand.players[i].invested += amount;
+            None
+        } else if action.starts_with("posts ") {
+            let amount = action.rsplit(' ').next().unwrap_or_default();
+            Some(parse_amount(&hand.id, amount)?)
+        } else if let Some(amount) = action
+            .strip_prefix("bets ")
+            .or_else(|| action.strip_prefix("calls "))
+        {
+            hand.betting_board = hand.board.len();
+            Some(parse_amount(&hand.id, amount)?)
+        } else if let Some(raise) = action.strip_prefix("raises ") {
+            hand.betting_board = hand.board.len();
+            let to = raise
+                .split_once(" to ")
+                .map(|(_, to)| to)
+                .ok_or_else(|| history_error(&hand.id, line))?;
+            Some(parse_amount(&hand.id, to)? - street[i])
+        } else {
+            None
+        };
+
+        if let Some(amount) = put_in {
+            hand.players[i].invested += amount;
+            street[i] += amount;
+        }
+        if all_in {
+            hand.players[i].all_in = true;
+        }
+    }
+
+    if hand.players.is_empty() {
+        return Err(history_error(&hand.id, "no seats were listed"));
+    }
+    Ok(hand)
+}
+
+/// Error about a hand that could not be read
+fn history_error(hand: &str, reason: &str) -> Error {
+    Error::HandHistory {
+        hand: hand.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+/// A seat line such as "1: Hero ($2.00 in chips)" without the "Seat "
+fn parse_seat(seat: &str) -> Option<Player> {
+    let (_, rest) = seat.split_once(": ")?;
+    let (name, chips) = rest.rsplit_once(" (")?;
+    let stack = chips.split(" in chips").next()?;
+
+    Some(Player {
+        name: name.trim().to_string(),
+        stack: parse_amount("", stack).ok()?,
+        ..Player::default()
+    })
+}
+
+/// Every card in the brackets of a line such as "[2c 7d 9h] [Ts]"
+fn bracketed_cards(hand: &str, line: &str) -> Result<Vec<Card>, Error> {
+    let mut cards = vec![];
+    for part in line.split('[').skip(1) {
+        let inside = part.split(']').next().unwrap_or_default();
+        let parsed = cards_from_str(&inside.replace(' ', ""))
+            .map_err(|e| history_error(hand, &format!("{}: {}", line, e)))?;
+        cards.extend(parsed);
+    }
+    Ok(cards)
+}
+
+/// An amount of money or chips such as "$1,000.50", "€2" or "1500"
+fn parse_amount(hand: &str, amount: &str) -> Result<f64, Error> {
+    let number = amount
+        .trim()
+        .trim_start_matches(['$', '€', '£', '¥', '₹'])
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .replace(',', "");
+
+    number
+        .parse::<f64>()
+        .map_err(|_| history_error(hand, &format!("invalid amount '{}'", amount.trim())))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const POKERSTARS: &str = "\
+PokerStars Hand #123456789: Hold'em No Limit ($0.01/$0.02 USD) - 2020/01/01 12:00:00 ET
+Table 'Alpha' 6-max Seat #1 is the button
+Seat 1: Hero ($2.00 in chips)
+Seat 2: Villain ($2.50 in chips)
+Hero: posts small blind $0.01
+Villain: posts big blind $0.02
+*** HOLE CARDS ***
+Dealt to Hero [Ah Kh]
+Hero: raises $0.04 to $0.06
+Villain: calls $0.04
+*** FLOP *** [Kc 7d 2s]
+Villain: bets $0.10
+Hero: raises $1.84 to $1.94 and is all-in
+Villain: calls $1.84
+*** TURN *** [Kc 7d 2s] [9c]
+*** RIVER *** [Kc 7d 2s 9c] [7h]
+*** SHOW DOWN ***
+Hero: shows [Ah Kh] (two pair, Kings and Sevens)
+Villain: shows [7c 7s] (four of a kind, Sevens)
+Villain collected $3.90 from pot
+*** SUMMARY ***
+Total pot $4 | Rake $0.10
+Board [Kc 7d 2s 9c 7h]
+Seat 1: Hero (button) (small blind) showed [Ah Kh] and lost with two pair, Kings and Sevens
+Seat 2: Villain (big blind) showed [7c 7s] and won ($3.90) with four of a kind, Sevens
+";
+
+    const GGPOKER: &str = "\
+Poker Hand #HD1234: Hold'em No Limit ($0.05/$0.1) - 2024/01/01 12:00:00
+Table 'NLHGold1' 6-max Seat #3 is the button
+Seat 1: Hero ($10 in chips)
+Seat 2: Short ($2.5 in chips)
+Seat 3: Folder ($10 in chips)
+Hero: posts small blind $0.05
+Short: posts big blind $0.1
+*** HOLE CARDS ***
+Dealt to Hero [Qs Qd]
+Dealt to Short
+Dealt to Folder
+Folder: raises $0.15 to $0.25
+Hero: raises $0.75 to $1
+Short: raises $1.5 to $2.5 and is all-in
+Folder: folds
+Hero: calls $1.5
+*** FLOP *** [Qc 8h 3d]
+*** TURN *** [Qc 8h 3d] [2c]
+*** RIVER *** [Qc 8h 3d 2c] [Jd]
+*** SHOWDOWN ***
+Hero: shows [Qs Qd]
+Short: shows [Ac Ad]
+Hero collected $5.25 from pot
+*** SUMMARY ***
+";
+
+    #[test]
+    fn test_pokerstars_hand() {
+        let text = format!("{}\n\n\n{}", POKERSTARS, GGPOKER);
+        let hands = split_hand_histories(&text);
+        assert_eq!(hands.len(), 2);
+
+        let hand = parse_hand_history(hands[0]).unwrap();
+        assert_eq!(hand.id, "123456789");
+        assert_eq!(hand.hero.as_deref(), Some("Hero"));
+        assert_eq!(hand.board.len(), 5);
+        assert_eq!(hand.betting_board, 3);
+        assert!(hand.is_all_in());
+
+        let hero = &hand.players[0];
+        assert!((hero.invested - 2.0).abs() < 1e-9);
+        assert!((hero.net() + 2.0).abs() < 1e-9);
+        assert!((hand.players[1].net() - 1.9).abs() < 1e-9);
+        assert_eq!(hand.pots().len(), 1);
+        assert!((hand.pots()[0].0 - 4.0).abs() < 1e-9);
+
+        // Top pair against a set of sevens on the flop, with 2.5% rake
+        let ev = hand.all_in_ev(1000, &mut StdRng::seed_from_u64(1)).unwrap();
+        let equity = (ev[0] + 2.0) / 3.9;
+        assert!((equity - 0.0162).abs() < 1e-3, "{}", equity);
+        assert!((ev[0] + ev[1] + 0.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_ggpoker_hand() {
+        let hand = parse_hand_history(GGPOKER).unwrap();
+        assert_eq!(hand.id, "HD1234");
+        assert!(hand.is_all_in());
+        assert_eq!(hand.betting_board, 0);
+        assert!(hand.players[2].folded);
+        assert!((hand.players[2].invested - 0.25).abs() < 1e-9);
+        assert!((hand.players[0].net() - 2.75).abs() < 1e-9);
+
+        // Queens against aces before the flop, no rake
+        let ev = hand
+            .all_in_ev(20_000, &mut StdRng::seed_from_u64(1))
+            .unwrap();
+        let equity = (ev[0] + 2.5) / 5.25;
+        assert!((equity - 0.185).abs() < 0.02, "{}", equity);
+    }
+
+    #[test]
+    fn test_side_pots() {
+        let hand = HandHistory {
+            id: "1".to_string(),
+            game: Game::Holdem,
+            hero: None,
+            players: [(10.0, false), (30.0, false), (30.0, false), (5.0, true)]
+                .iter()
+                .map(|&(invested, folded)| Player {
+                    invested,
+                    folded,
+                    ..Player::default()
+                })
+                .collect(),
+            board: vec![],
+            betting_board: 0,
+            showdown: true,
+        };
+
+        let pots = hand.pots();
+        assert_eq!(pots, vec![(35.0, vec![0, 1, 2]), (40.0, vec![1, 2])]);
+        assert!(!hand.is_all_in());
+        assert!(parse_hand_history("PokerStars Hand #1: Razz").is_err());
+    }
+}
@@ -0,0 +1,270 @@
+//! Independent Chip Model, converting tournament stacks into prize equity
+//! Uses the Malmuth-Harville model, where the chance of finishing in the next
+//! place is proportional to the share of the remaining chips
+
+use crate::error::Error;
+
+/// Prize equity of a player facing an all in, whether they fold, call and
+/// win or call and lose
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AllInCall {
+    /// Chips each player puts in, the smaller of the two stacks
+    pub risk: f64,
+
+    /// Chips already in the pot from other players, such as blinds
+    pub pot: f64,
+
+    /// Prize equity after folding, the all in player taking the pot
+    pub fold: f64,
+
+    /// Prize equity after calling and winning
+    pub win: f64,
+
+    /// Prize equity after calling and losing
+    pub lose: f64,
+}
+
+///////////////////////////////////////////////
+/// AllInCall Implementations
+///////////////////////////////////////////////
+impl AllInCall {
+    /// Prize equity lost by losing the all in over the prize equity won by
+    /// winning it, 1 when chips and prizes are worth the same
+    pub fn bubble_factor(&self) -> f64 {
+        (self.fold - self.lose) / (self.win - self.fold)
+    }
+
+    /// Equity needed to call when only chips count, from the pot odds
+    pub fn chip_threshold(&self) -> f64 {
+        self.risk / (2.0 * self.risk + self.pot)
+    }
+
+    /// Equity needed for calling to be worth as much prize equity as folding
+    pub fn icm_threshold(&self) -> f64 {
+        (self.fold - self.lose) / (self.win - self.lose)
+    }
+
+    /// Extra equity the tournament asks for on top of the pot odds
+    pub fn risk_premium(&self) -> f64 {
+        self.icm_threshold() - self.chip_threshold()
+    }
+
+    /// Prize equity of calling with `equity`, less the prize equity of folding
+    /// Ties count as half a win, which is close enough for the usual spots
+    pub fn call_value(&self, equity: f64) -> f64 {
+        equity * self.win + (1.0 - equity) * self.lose - self.fold
+    }
+}
+
+/// Prize equity of every stack, in the same units as the payouts
+/// `payouts[0]` is first place, places past the number of players are ignored
+/// The cost grows with players^places, fine for the usual final table sizes
+pub fn icm(stacks: &[f64], payouts: &[f64]) -> Result<Vec<f64>, Error> {
+    if let Some(&stack) = stacks.iter().find(|s| !(**s > 0.0 && s.is_finite())) {
+        return Err(Error::InvalidStack(stack));
+    }
+    if let Some(&payout) = payouts.iter().find(|p| !(**p >= 0.0 && p.is_finite())) {
+        return Err(Error::InvalidPayout(payout));
+    }
+
+    let mut equity = vec![0.0; stacks.len()];
+    let mut finished = vec![false; stacks.len()];
+    let places = payouts.len().min(stacks.len());
+    add_place_equity(stacks, &payouts[..places], &mut finished, 1.0, &mut equity);
+
+    Ok(equity)
+}
+
+/// Prize equity of player `hero` calling an all in from player `villain`
+/// Players are indexed from 0 in the order of `stacks`, which are the chips
+/// behind before the hand, and `pot` is dead money from the other players
+/// The winner takes the pot, and a player who busts finishes below everyone
+/// still in
+pub fn all_in_call(
+    stacks: &[f64],
+    payouts: &[f64],
+    hero: usize,
+    villain: usize,
+    pot: f64,
+) -> Result<AllInCall, Error> {
+    let players = stacks.len();
+    if let Some(player) = [hero, villain].into_iter().find(|p| *p >= players) {
+        return Err(Error::NoSuchPlayer { player, players });
+    }
+    if hero == villain {
+        return Err(Error::OwnAllIn(hero));
+    }
+    if !(pot >= 0.0 && pot.is_finite()) {
+        return Err(Error::InvalidAmount(pot));
+    }
+    icm(stacks, payouts)?;
+
+    let risk = stacks[hero].min(stacks[villain]);
+    let after = |winner: usize, loser: usize, chips: f64| {
+        let mut stacks = stacks.to_vec();
+        stacks[winner] += chips + pot;
+        stacks[loser] -= chips;
+        icm_with_busts(&stacks, payouts).map(|equity| equity[hero])
+    };
+
+    Ok(AllInCall {
+        risk,
+        pot,
+        fold: after(villain, hero, 0.0)?,
+        win: after(hero, villain, risk)?,
+        lose: after(villain, hero, risk)?,
+    })
+}
+
+/// Prize equity of every stack where empty stacks have busted, sharing the
+/// places below the players still in
+fn icm_with_busts(stacks: &[f64], payouts: &[f64]) -> Result<Vec<f64>, Error> {
+    let alive = (0..stacks.len())
+        .filter(|p| stacks[*p] > 0.0)
+        .collect::<Vec<_>>();
+    let alive_stacks = alive.iter().map(|p| stacks[*p]).collect::<Vec<_>>();
+
+    let mut equity = vec![0.0; stacks.len()];
+    let busted = stacks.len() - alive.len();
+    for payout in payouts.iter().skip(alive.len()).take(busted) {
+        for player in (0..stacks.len()).filter(|p| stacks[*p] <= 0.0) {
+            equity[player] += payout / busted as f64;
+        }
+    }
+    for (player, share) in alive.into_iter().zip(icm(&alive_stacks, payouts)?) {
+        equity[player] = share;
+    }
+    Ok(equity)
+}
+
+/// Hand out the next payout to every player still in, weighted by the chance
+/// they finish in that place, then recurse into the places below
+fn add_place_equity(
+    stacks: &[f64],
+    payouts: &[f64],
+    finished: &mut [bool],
+    chance: f64,
+    equity: &mut [f64],
+) {
+    let Some((&payout, rest)) = payouts.split_first() else {
+        return;
+    };
+
+    let remaining = stacks
+        .iter()
+        .zip(finished.iter())
+        .filter(|(_, done)| !**done)
+        .map(|(stack, _)| stack)
+        .sum::<f64>();
+
+    for player in 0..stacks.len() {
+        if finished[player] {
+            continue;
+        }
+
+        let place_chance = chance * stacks[player] / remaining;
+        equity[player] += place_chance * payout;
+
+        finished[player] = true;
+        add_place_equity(stacks, rest, finished, place_chance, equity);
+        finished[player] = false;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn assert_close(a: &[f64], b: &[f64]) {
+        assert_eq!(a.len(), b.len());
+        for (x, y) in a.iter().zip(b) {
+            assert!((x - y).abs() < 1e-9, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_icm() {
+        // Equal stacks split the prize pool evenly
+        assert_close(
+            &icm(&[100.0, 100.0, 100.0], &[50.0, 30.0, 20.0]).unwrap(),
+            &[100.0 / 3.0; 3],
+        );
+
+        // Heads up, second place is locked up and first is in proportion to chips
+        assert_close(
+            &icm(&[3000.0, 1000.0], &[70.0, 30.0]).unwrap(),
+            &[30.0 + 40.0 * 0.75, 30.0 + 40.0 * 0.25],
+        );
+
+        // Winner takes all is the same as chip share
+        assert_close(
+            &icm(&[5000.0, 3000.0, 2000.0], &[100.0]).unwrap(),
+            &[50.0, 30.0, 20.0],
+        );
+
+        // The chip leader's equity is less than their chip share
+        let equity = icm(&[5000.0, 3000.0, 1500.0], &[50.0, 30.0, 20.0]).unwrap();
+        assert!((equity.iter().sum::<f64>() - 100.0).abs() < 1e-9);
+        assert!(equity[0] < 100.0 * 5000.0 / 9500.0);
+        assert!(equity[2] > 100.0 * 1500.0 / 9500.0);
+
+        // More payouts than players are ignored
+        assert_close(
+            &icm(&[1.0, 1.0], &[60.0, 40.0, 10.0]).unwrap(),
+            &[50.0, 50.0],
+        );
+    }
+
+    #[test]
+    fn test_all_in_call() {
+        // Winner takes all, so chips are prizes and the pot odds are the price
+        let call = all_in_call(&[5000.0, 3000.0, 2000.0], &[100.0], 2, 0, 0.0).unwrap();
+        assert_close(&[call.fold, call.win, call.lose], &[20.0, 40.0, 0.0]);
+        assert!((call.bubble_factor() - 1.0).abs() < 1e-9);
+        assert!((call.icm_threshold() - 0.5).abs() < 1e-9);
+        assert!(call.risk_premium().abs() < 1e-9);
+
+        // On the bubble, busting gets nothing and a short stack needs a
+        // much better hand to call the chip leader
+        let stacks = [5000.0, 3000.0, 1500.0, 1500.0];
+        let call = all_in_call(&stacks, &[50.0, 30.0, 20.0], 3, 0, 150.0).unwrap();
+        assert_eq!(call.risk, 1500.0);
+        assert_eq!(call.lose, 0.0);
+        assert!((call.chip_threshold() - 1500.0 / 3150.0).abs() < 1e-9);
+        assert!(call.bubble_factor() > 1.2, "{:?}", call);
+        assert!(call.risk_premium() > 0.05, "{:?}", call);
+        assert!(call.call_value(call.icm_threshold()).abs() < 1e-9);
+        assert!(call.call_value(call.icm_threshold() + 0.1) > 0.0);
+
+        // Busting heads up still pays second place
+        let call = all_in_call(&[3000.0, 1000.0], &[70.0, 30.0], 1, 0, 0.0).unwrap();
+        assert_close(&[call.fold, call.win, call.lose], &[40.0, 50.0, 30.0]);
+    }
+
+    #[test]
+    fn test_invalid_all_in_call() {
+        let stacks = [100.0, 200.0];
+        let payouts = [100.0];
+        assert!(matches!(
+            all_in_call(&stacks, &payouts, 0, 2, 0.0),
+            Err(Error::NoSuchPlayer {
+                player: 2,
+                players: 2
+            })
+        ));
+        assert!(matches!(
+            all_in_call(&stacks, &payouts, 1, 1, 0.0),
+            Err(Error::OwnAllIn(1))
+        ));
+        assert!(matches!(
+            all_in_call(&stacks, &payouts, 0, 1, -5.0),
+            Err(Error::InvalidAmount(_))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_icm() {
+        assert!(icm(&[100.0, 0.0], &[50.0, 50.0]).is_err());
+        assert!(icm(&[100.0, 100.0], &[50.0, -1.0]).is_err());
+    }
+}
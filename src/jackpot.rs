@@ -0,0 +1,250 @@
+//! Bad beat jackpots, how often a hand at least as strong as the rule asks
+//! for loses at showdown, for every table size
+//! A jackpot hand is rare enough that every player is taken to the river,
+//! as the rules of most rooms assume when they set the qualifying hand
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::card::Card;
+use crate::deck::Deck;
+use crate::equity::{merge_each, run_workers, Progress, SimulationSettings};
+use crate::error::Error;
+use crate::evaluator::Evaluator;
+use crate::game::Game;
+use crate::poker_hand::HandValue;
+
+/// What the losing hand needs for the jackpot to be hit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JackpotRule {
+    /// Weakest losing hand that qualifies, such as aces full of jacks
+    pub losing: HandValue,
+
+    /// Whether both hole cards have to play in the losing and in the
+    /// winning hand, games that always play two hole cards meet this anyway
+    pub both_cards_play: bool,
+}
+
+/// How often the jackpot is hit at one table size
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JackpotResult {
+    /// Number of players dealt in
+    pub players: usize,
+
+    /// Number of deals played
+    pub deals: u64,
+
+    /// Deals where some player lost with a qualifying hand
+    pub hits: u64,
+
+    /// Deals where the first player lost with a qualifying hand
+    pub seat_hits: u64,
+}
+
+///////////////////////////////////////////////
+/// JackpotResult Implementations
+///////////////////////////////////////////////
+impl JackpotResult {
+    /// Chance of a deal hitting the jackpot
+    pub fn frequency(&self) -> f64 {
+        self.hits as f64 / self.deals.max(1) as f64
+    }
+
+    /// Chance of a deal hitting the jackpot with one given player, such as
+    /// yourself, holding the losing hand
+    pub fn seat_frequency(&self) -> f64 {
+        self.seat_hits as f64 / self.deals.max(1) as f64
+    }
+
+    /// Add the counts of another result into this one
+    pub fn merge(&mut self, other: &JackpotResult) {
+        self.deals += other.deals;
+        self.hits += other.hits;
+        self.seat_hits += other.seat_hits;
+    }
+}
+
+/// Deal `players` hands with a full board over and over, counting how often
+/// the jackpot is hit at every table size from heads up to `players`
+/// The smaller tables are the first players of each deal
+pub fn run_jackpot_simulation<R: Rng>(
+    game: Game,
+    rule: &JackpotRule,
+    players: usize,
+    settings: &SimulationSettings,
+    rng: &mut R,
+) -> Result<Vec<JackpotResult>, Error> {
+    if !game.has_board() {
+        return Err(Error::NoBoard(game));
+    }
+    if players < 2 {
+        return Err(Error::NotEnoughHands(players));
+    }
+    if players * game.hole_cards() + 5 > Deck::new().len() {
+        return Err(Error::DeckTooSmall {
+            cards: game.hole_cards(),
+            players,
+            dead: 0,
+        });
+    }
+
+    let progress = Progress::new(settings);
+    let run = |share: u32, rng: StdRng| {
+        let evaluator = settings.evaluator;
+        simulate(game, rule, players, share, &progress, evaluator, rng)
+    };
+    Ok(run_workers(
+        settings,
+        rng,
+        run,
+        merge_each(JackpotResult::merge),
+    ))
+}
+
+/// Empty results for every table size
+fn empty_jackpot_results(players: usize) -> Vec<JackpotResult> {
+    (2..=players)
+        .map(|players| JackpotResult {
+            players,
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// Run `iterations` deals on the current thread
+fn simulate(
+    game: Game,
+    rule: &JackpotRule,
+    players: usize,
+    iterations: u32,
+    progress: &Progress,
+    evaluator: &dyn Evaluator,
+    mut rng: StdRng,
+) -> Vec<JackpotResult> {
+    let mut results = empty_jackpot_results(players);
+    let mut deck = Deck::new().remaining().to_vec();
+    let hole_cards = game.hole_cards();
+    let mut values = Vec::with_capacity(players);
+    let mut qualifies = Vec::with_capacity(players);
+
+    for _ in 0..iterations {
+        if progress.stopped() {
+            break;
+        }
+        progress.tick();
+
+        let (dealt, _) = deck.partial_shuffle(&mut rng, players * hole_cards + 5);
+        let (board, hands) = dealt.split_at(5);
+        values.clear();
+        values.extend(
+            hands
+                .chunks(hole_cards)
+                .map(|hole| game.rules().best_hand(hole, board, evaluator)),
+        );
+
+        // Most deals have no hand strong enough, and a hand that beats a
+        // qualifying hand is strong enough too
+        qualifies.clear();
+        qualifies.extend(hands.chunks(hole_cards).zip(&values).map(|(hole, value)| {
+            *value >= rule.losing
+                && (!rule.both_cards_play || both_cards_play(hole, board, *value, evaluator))
+        }));
+        for result in &mut results {
+            result.deals += 1;
+            let table = result.players;
+            let best = values[..table].iter().max().unwrap();
+            let winner_qualifies = (0..table).any(|i| values[i] == *best && qualifies[i]);
+            if !winner_qualifies {
+                continue;
+            }
+            let beaten = |i: usize| qualifies[i] && values[i] < *best;
+            if (0..table).any(beaten) {
+                result.hits += 1;
+            }
+            if beaten(0) {
+                result.seat_hits += 1;
+            }
+        }
+    }
+
+    results
+}
+
+/// Whether the best hand of a hold'em hand uses both of its hole cards,
+/// the same as its best hand playing exactly two like in Omaha
+/// Games that always play two hole cards always do
+fn both_cards_play(
+    hole: &[Card],
+    board: &[Card],
+    value: HandValue,
+    evaluator: &dyn Evaluator,
+) -> bool {
+    hole.len() != 2 || Game::Omaha.rules().best_hand(hole, board, evaluator) == value
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::card::cards_from_str;
+    use crate::poker_utils::get_best_hand;
+    use rand::SeedableRng;
+
+    fn hand(s: &str) -> HandValue {
+        get_best_hand(&cards_from_str(s).unwrap()).into()
+    }
+
+    #[test]
+    fn test_both_cards_play() {
+        let evaluator = crate::evaluator::EvaluatorKind::default().evaluator();
+        let board = cards_from_str("AhAdJcJd2s").unwrap();
+        let both = |hole: &str| {
+            let hole = cards_from_str(hole).unwrap();
+            let value = Game::Holdem.rules().best_hand(&hole, &board, evaluator);
+            both_cards_play(&hole, &board, value, evaluator)
+        };
+        assert!(both("AsJs"));
+        assert!(!both("As3c"));
+        assert!(!both("KsQs"));
+    }
+
+    #[test]
+    fn test_jackpot_frequencies() {
+        let settings = SimulationSettings {
+            threads: 2,
+            ..SimulationSettings::new(10_000)
+        };
+        let mut rng = StdRng::seed_from_u64(98);
+
+        // Any losing pair qualifies, which happens in most deals at a full
+        // table and more often the more players there are
+        let rule = JackpotRule {
+            losing: hand("2c2d3h4s5h"),
+            both_cards_play: false,
+        };
+        let results = run_jackpot_simulation(Game::Holdem, &rule, 9, &settings, &mut rng).unwrap();
+        assert_eq!(results.len(), 8);
+        assert_eq!(results[0].players, 2);
+        assert!(results.iter().all(|r| r.deals == 10_000));
+        assert!(results[0].frequency() < results[7].frequency());
+        assert!(results[7].frequency() > 0.8, "{:?}", results[7]);
+        assert!(results.iter().all(|r| r.seat_hits <= r.hits));
+
+        // Both cards playing in both hands is harder than just being ahead
+        let both = JackpotRule {
+            both_cards_play: true,
+            ..rule
+        };
+        let strict = run_jackpot_simulation(Game::Holdem, &both, 9, &settings, &mut rng).unwrap();
+        assert!(strict[7].frequency() < results[7].frequency());
+
+        assert!(matches!(
+            run_jackpot_simulation(Game::Holdem, &rule, 1, &settings, &mut rng),
+            Err(Error::NotEnoughHands(1))
+        ));
+        assert!(matches!(
+            run_jackpot_simulation(Game::Omaha, &rule, 12, &settings, &mut rng),
+            Err(Error::DeckTooSmall { .. })
+        ));
+    }
+}
@@ -0,0 +1,252 @@
+//! How often pots are decided by a kicker and how often a player is only
+//! playing the board
+//! A pot is won on a kicker when its lone winner makes the same hand as the
+//! next best player and only a kicker tells them apart
+
+use rand::rngs::StdRng;
+use rand::Rng;
+
+use crate::card::Card;
+use crate::equity::{
+    merge_each, remove_known_cards, run_workers, simulate_showdowns, DealShowdown, DealtResult,
+    HandResult, Progress, SimulationSettings,
+};
+use crate::error::Error;
+use crate::game::Game;
+use crate::poker_hand::cards_to_hand;
+use crate::range::Range;
+
+/// How often one player wins or loses on a kicker and plays the board
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct KickerResult {
+    /// Number of deals played
+    pub deals: u64,
+
+    /// Deals the player won the high hand alone on a kicker
+    pub kicker_wins: u64,
+
+    /// Deals the player made the winning hand and lost on a kicker
+    pub kicker_losses: u64,
+
+    /// Deals the player's best hand was the board alone
+    pub board_plays: u64,
+
+    /// The showdown of every deal
+    pub showdowns: HandResult,
+}
+
+///////////////////////////////////////////////
+/// KickerResult Implementations
+///////////////////////////////////////////////
+impl KickerResult {
+    /// How often the player won on a kicker
+    pub fn kicker_win_frequency(&self) -> f64 {
+        self.kicker_wins as f64 / self.deals.max(1) as f64
+    }
+
+    /// How often the player lost on a kicker
+    pub fn kicker_loss_frequency(&self) -> f64 {
+        self.kicker_losses as f64 / self.deals.max(1) as f64
+    }
+
+    /// How often the board played for the player
+    pub fn board_frequency(&self) -> f64 {
+        self.board_plays as f64 / self.deals.max(1) as f64
+    }
+
+    /// Add the counts of another result into this one
+    pub fn merge(&mut self, other: &KickerResult) {
+        self.deals += other.deals;
+        self.kicker_wins += other.kicker_wins;
+        self.kicker_losses += other.kicker_losses;
+        self.board_plays += other.board_plays;
+        self.showdowns.merge(&other.showdowns);
+    }
+}
+
+impl DealtResult for KickerResult {
+    fn showdowns(&mut self) -> &mut HandResult {
+        &mut self.showdowns
+    }
+
+    fn add_deal(&mut self) {
+        self.deals += 1;
+    }
+}
+
+/// How often any pot was won on a kicker, every deal having at most one
+/// player who won it that way
+pub fn kicker_pot_frequency(results: &[KickerResult]) -> f64 {
+    let deals = results.first().map_or(0, |r| r.deals);
+    let wins = results.iter().map(|r| r.kicker_wins).sum::<u64>();
+    wins as f64 / deals.max(1) as f64
+}
+
+/// Simulate deals from the ranges, counting the pots decided by a kicker
+/// and the players playing the board
+/// Games without a board have no board to play, and `settings` can not set
+/// a target error, a sampler or a runout filter
+pub fn run_kicker_calculation<R: Rng>(
+    game: Game,
+    board: Vec<Card>,
+    dead: Vec<Card>,
+    ranges: Vec<Range>,
+    settings: &SimulationSettings,
+    rng: &mut R,
+) -> Result<Vec<KickerResult>, Error> {
+    if !game.has_board() {
+        return Err(Error::NoBoard(game));
+    }
+    settings.check_random_runouts()?;
+    let (deck, ranges) = remove_known_cards(&board, &dead, ranges);
+    let progress = Progress::new(settings);
+    let run = |share: u32, rng: StdRng| {
+        let deal = DealShowdown::new(game, deck.remaining(), &ranges, &board, settings.evaluator);
+        let new = |_, showdowns| KickerResult {
+            showdowns,
+            ..Default::default()
+        };
+        simulate_showdowns(deal, share, &progress, rng, new, |deal, results| {
+            count_kickers(game, deal, results)
+        })
+    };
+    Ok(run_workers(
+        settings,
+        rng,
+        run,
+        merge_each(KickerResult::merge),
+    ))
+}
+
+/// Count the kicker wins and losses and the board plays of a deal
+fn count_kickers(game: Game, deal: &DealShowdown, results: &mut [KickerResult]) {
+    let best = &deal.best;
+
+    // Players making the same hand as a lone winner lose on a kicker, and
+    // the winner only wins on one when the next best hand does
+    if let [winner] = deal.winners[..] {
+        let winning = best[winner].rank();
+        for (player, value) in best.iter().enumerate() {
+            if player != winner && winning.kicker_over(&value.rank()).is_some() {
+                results[player].kicker_losses += 1;
+            }
+        }
+        let runner_up = (0..best.len())
+            .filter(|p| *p != winner)
+            .map(|p| best[p])
+            .max();
+        if let Some(runner_up) = runner_up {
+            if winning.kicker_over(&runner_up.rank()).is_some() {
+                results[winner].kicker_wins += 1;
+            }
+        }
+    }
+
+    let on_board = game
+        .rules()
+        .can_play_the_board()
+        .then(|| cards_to_hand(deal.board));
+    for (result, value) in results.iter_mut().zip(best) {
+        if on_board == Some(value.rank()) {
+            result.board_plays += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::card::cards_from_str;
+    use crate::range::hand_ranges;
+    use crate::sampler::Sampler;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_kicker_frequencies() {
+        let settings = SimulationSettings {
+            threads: 2,
+            ..SimulationSettings::new(20_000)
+        };
+        let mut rng = StdRng::seed_from_u64(13);
+        let results = run_kicker_calculation(
+            Game::Holdem,
+            cards_from_str("Ac8d3s").unwrap(),
+            vec![],
+            hand_ranges(&["AhKd", "AdQc"]),
+            &settings,
+            &mut rng,
+        )
+        .unwrap();
+
+        let king = &results[0];
+        assert_eq!(king.deals, 20_000);
+        assert_eq!(king.showdowns.showdowns(), 20_000);
+
+        // Both hands hold top pair, so the king kicker wins most pots and
+        // the queen never wins on its kicker
+        let wins = king.kicker_win_frequency();
+        assert!((0.5..0.8).contains(&wins), "{}", wins);
+        assert_eq!(king.kicker_wins, results[1].kicker_losses);
+        assert_eq!(results[1].kicker_wins, 0);
+        assert_eq!(kicker_pot_frequency(&results), wins);
+        assert_eq!(king.kicker_loss_frequency(), 0.0);
+    }
+
+    #[test]
+    fn test_playing_the_board() {
+        let settings = SimulationSettings::new(1_000);
+        let mut rng = StdRng::seed_from_u64(2);
+        let board = cards_from_str("AsKdQcJhTs").unwrap();
+        let results = run_kicker_calculation(
+            Game::Holdem,
+            board.clone(),
+            vec![],
+            hand_ranges(&["2c2d", "9c8c"]),
+            &settings,
+            &mut rng,
+        )
+        .unwrap();
+        assert!(results.iter().all(|r| r.board_frequency() == 1.0));
+        assert_eq!(kicker_pot_frequency(&results), 0.0);
+
+        // Omaha hands always play two hole cards
+        let results = run_kicker_calculation(
+            Game::Omaha,
+            board,
+            vec![],
+            hand_ranges(&["2c2d3c3d", "9c8c7c6c"]),
+            &settings,
+            &mut rng,
+        )
+        .unwrap();
+        assert!(results.iter().all(|r| r.board_plays == 0));
+
+        assert!(matches!(
+            run_kicker_calculation(
+                Game::Stud,
+                vec![],
+                vec![],
+                hand_ranges(&["AhKhQh", "2c2d2s"]),
+                &settings,
+                &mut rng,
+            ),
+            Err(Error::NoBoard(Game::Stud))
+        ));
+
+        let settings = SimulationSettings {
+            sampler: Sampler::Stratified,
+            ..settings
+        };
+        assert!(matches!(
+            run_kicker_calculation(
+                Game::Holdem,
+                vec![],
+                vec![],
+                hand_ranges(&["AhKd", "AdQc"]),
+                &settings,
+                &mut rng,
+            ),
+            Err(Error::UnsupportedSetting("sampler"))
+        ));
+    }
+}
@@ -0,0 +1,122 @@
+//! Poker equity engine
+//!
+//! Card types, a five card hand evaluator and a Monte Carlo
+//! equity simulator that can be embedded in other programs.
+
+#![cfg_attr(feature = "simd", feature(portable_simd))]
+
+#[cfg(feature = "tokio")]
+pub mod asynchronous;
+pub mod blockers;
+pub mod board;
+pub mod breakdown;
+pub mod canonical;
+pub mod card;
+pub mod compare;
+pub mod conditions;
+pub mod coolers;
+pub mod deals;
+pub mod deck;
+pub mod draws;
+pub mod equity;
+pub mod error;
+pub mod evaluator;
+pub mod explain;
+pub mod flops;
+pub mod game;
+#[cfg(feature = "gpu")]
+pub mod gpu;
+pub mod history;
+pub mod icm;
+pub mod jackpot;
+pub mod kickers;
+pub mod lookup;
+pub mod multiway;
+pub mod odds;
+pub mod outs;
+pub mod pairwise;
+pub mod poker_hand;
+pub mod poker_utils;
+pub mod potential;
+pub mod preflop;
+pub mod presets;
+pub mod push_fold;
+pub mod range;
+pub mod run_it;
+pub mod runout;
+pub mod sampler;
+pub mod showdown;
+pub mod side_pots;
+#[cfg(feature = "simd")]
+pub mod simd;
+pub mod split;
+pub mod streets;
+pub mod stud;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[cfg(feature = "tokio")]
+pub use asynchronous::{calculate_equity_async, calculate_many_async};
+pub use blockers::{blockers, BlockedCount, Blockers};
+pub use board::{Board, Street};
+pub use breakdown::{equity_breakdown, EquityBreakdown, EquitySource};
+pub use canonical::{
+    canonical_results, canonicalize, canonicalize_spot, restore_results, Canonical,
+};
+pub use card::{cards_from_str, Card, HoleCards, ParseCardError, Rank, Suit};
+pub use compare::{run_comparison, Comparison, Scenario};
+pub use conditions::{run_condition_calculation, ConditionResult};
+pub use coolers::{find_coolers, Cooler, Coolers};
+pub use deals::{deals, Deals, SimulatedDeal};
+pub use deck::Deck;
+pub use draws::chance_to_make;
+pub use equity::{
+    check_duplicate_cards, default_threads, run_calculation, run_calculation_with, run_exact,
+    run_exact_calculation, run_filtered_exact_calculation, run_out, run_range_calculation,
+    CategoryResult, ComboResult, EquityResult, HandResult, HiLoResult, SimulationSettings, Stratum,
+    CONVERGENCE_BATCH, PROGRESS_INTERVAL, Z_95,
+};
+pub use error::Error;
+pub use evaluator::{Evaluator, EvaluatorKind};
+pub use explain::{example_showdowns, ExampleHand, ExampleShowdown};
+pub use flops::{
+    distinct_flops, run_flop_report, Flop, FlopReport, FlopResult, Pairing, Suitedness,
+};
+pub use game::{Game, GameRules};
+pub use history::{parse_hand_history, split_hand_histories, HandHistory, Player};
+pub use icm::{all_in_call, icm, AllInCall};
+pub use jackpot::{run_jackpot_simulation, JackpotResult, JackpotRule};
+pub use kickers::{kicker_pot_frequency, run_kicker_calculation, KickerResult};
+pub use multiway::{run_multiway_simulation, MultiwayResult};
+pub use odds::{
+    bluff_break_even, call_ev, minimum_defense_frequency, pot_odds, required_equity, shove_ev,
+    Bounty,
+};
+pub use outs::{find_outs, made_hand, next_cards, rule_of_thumb, Outs};
+pub use pairwise::{run_pairwise_calculation, PairwiseResult};
+pub use poker_hand::{
+    cards_to_hand, BadugiHand, DeuceSevenHand, HandCategory, HandValue, LowHand, PokerHandRank,
+    RazzHand,
+};
+pub use poker_utils::{
+    combinations, determine_low_winners, determine_low_winners_into, determine_winner,
+    determine_winner_into, for_each_combination, get_best_badugi_hand, get_best_deuce_seven_hand,
+    get_best_five_cards, get_best_hand, get_best_hand_cards, get_best_omaha_five_cards,
+    get_best_omaha_hand, get_best_omaha_low, get_best_pineapple_five_cards,
+    get_best_pineapple_hand, get_best_razz_hand, get_combinations, in_hand_order, Combinations,
+};
+pub use potential::{hand_potential, HandPotential};
+pub use preflop::{
+    class_cell, class_name, matchup_equity, preflop_matrix, starting_hand_index, starting_hands,
+    StartingHandClass, STARTING_HANDS,
+};
+pub use presets::{preset_range, presets, ranges_path, top_percent, RangePreset, HAND_RANKING};
+pub use push_fold::{push_fold_chart, PushFoldChart};
+pub use range::{ParseRangeError, Range};
+pub use run_it::{max_runs, run_it_calculation, RunItResult};
+pub use runout::RunoutFilter;
+pub use sampler::Sampler;
+pub use showdown::{what_beats_me, ComboShowdown, Showdowns};
+pub use side_pots::{run_side_pot_calculation, side_pots, Pot, SidePotResult};
+pub use split::{split_range, Bucket, BucketResult, RangeSplit};
+pub use streets::{run_street_calculation, streets_after, StreetResult};
@@ -0,0 +1,169 @@
+//! Fast hand evaluator using rank bitmasks and precomputed tables
+//! The default evaluator with the `fast-eval` feature, it ranks five to
+//! seven cards in one pass instead of scoring every five card combination
+
+use std::sync::OnceLock;
+
+use crate::card::{Card, Rank};
+use crate::poker_hand::PokerHandRank;
+
+/// One bit per rank, bit 0 is a two and bit 12 is an ace
+pub(crate) type RankMask = u16;
+
+const ALL_MASKS: usize = 1 << 13;
+
+/// High card of the best straight in every rank mask
+fn straights() -> &'static [Option<Rank>] {
+    static TABLE: OnceLock<Vec<Option<Rank>>> = OnceLock::new();
+
+    TABLE.get_or_init(|| {
+        (0..ALL_MASKS)
+            .map(|mask| {
+                let mask = mask as RankMask;
+
+                // Highest run of five ranks first
+                let high = (4..13)
+                    .rev()
+                    .find(|&high| mask >> (high - 4) & 0b11111 == 0b11111);
+                if let Some(high) = high {
+                    return Some(Rank::ALL[high]);
+                }
+
+                // The wheel plays the ace low
+                let wheel = 1 << 12 | 0b1111;
+                (mask & wheel == wheel).then_some(Rank::Five)
+            })
+            .collect()
+    })
+}
+
+/// Ranks in the mask from highest to lowest
+fn ranks_in(mask: RankMask) -> impl Iterator<Item = Rank> {
+    (0..13)
+        .rev()
+        .filter(move |i| mask & (1 << i) != 0)
+        .map(|i| Rank::ALL[i])
+}
+
+/// The five highest ranks in the mask, which must hold at least five
+fn top_five(mask: RankMask) -> [Rank; 5] {
+    let mut ranks = ranks_in(mask);
+    [(); 5].map(|_| ranks.next().unwrap())
+}
+
+/// Rank the best five card hand out of five to seven cards
+pub fn evaluate(cards: &[Card]) -> PokerHandRank {
+    let mut suits = [0 as RankMask; 4];
+    let mut held = [0 as RankMask; 4];
+    for card in cards {
+        let bit = 1 << (card.rank.value() - 2);
+        suits[card.suit as usize] |= bit;
+
+        // A rank seen before moves up to the next mask
+        let [any, pairs, trips, quads] = &mut held;
+        *quads |= *trips & bit;
+        *trips |= *pairs & bit;
+        *pairs |= *any & bit;
+        *any |= bit;
+    }
+
+    classify(suits, held)
+}
+
+/// Rank a hand from the ranks held in each suit, and the masks of the ranks
+/// held at least once, twice, three and four times
+pub(crate) fn classify(suits: [RankMask; 4], held: [RankMask; 4]) -> PokerHandRank {
+    use PokerHandRank::*;
+
+    // With at most seven cards a flush rules out quads and full houses
+    // so only a straight flush can beat it
+    if let Some(&flush) = suits.iter().find(|s| s.count_ones() >= 5) {
+        if let Some(high) = straights()[flush as usize] {
+            return StraightFlush(high);
+        }
+        let [a, b, c, d, e] = top_five(flush);
+        return Flush(a, b, c, d, e);
+    }
+
+    let [any, pairs, trips, quads] = held;
+
+    if let Some(quad) = ranks_in(quads).next() {
+        let kicker = ranks_in(any & !quads).next().unwrap();
+        return FourOfAKind(quad, kicker);
+    }
+
+    if let Some(trip) = ranks_in(trips).next() {
+        // The second set of trips plays as the pair
+        if let Some(pair) = ranks_in(pairs & !(1 << (trip.value() - 2))).next() {
+            return FullHouse(trip, pair);
+        }
+    }
+
+    if let Some(high) = straights()[any as usize] {
+        return Straight(high);
+    }
+
+    if let Some(trip) = ranks_in(trips).next() {
+        let mut kickers = ranks_in(any & !trips);
+        return ThreeOfAKind(trip, kickers.next().unwrap(), kickers.next().unwrap());
+    }
+
+    let mut paired = ranks_in(pairs);
+    match (paired.next(), paired.next()) {
+        (Some(high), Some(low)) => {
+            let used = 1 << (high.value() - 2) | 1 << (low.value() - 2);
+            TwoPair(high, low, ranks_in(any & !used).next().unwrap())
+        }
+        (Some(pair), None) => {
+            let mut kickers = ranks_in(any & !pairs);
+            Pair(
+                pair,
+                kickers.next().unwrap(),
+                kickers.next().unwrap(),
+                kickers.next().unwrap(),
+            )
+        }
+        _ => {
+            let [a, b, c, d, e] = top_five(any);
+            HighCard(a, b, c, d, e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::deck::Deck;
+    use crate::poker_utils::get_best_five_cards;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_matches_combination_evaluator() {
+        let mut rng = StdRng::seed_from_u64(19);
+        let mut deck = Deck::new();
+
+        for i in 0..100_000 {
+            deck.shuffle(&mut rng);
+            let cards = &deck.remaining()[..5 + i % 3];
+            assert_eq!(evaluate(cards), get_best_five_cards(cards).1, "{:?}", cards);
+        }
+    }
+
+    #[test]
+    fn test_straights_table() {
+        let mask = |ranks: &[Rank]| ranks.iter().fold(0, |m, r| m | 1 << (r.value() - 2));
+
+        use Rank::*;
+        assert_eq!(
+            straights()[mask(&[Ace, Two, Three, Four, Five])],
+            Some(Five)
+        );
+        assert_eq!(
+            straights()[mask(&[Ace, Two, Three, Four, Five, Six])],
+            Some(Six)
+        );
+        assert_eq!(straights()[mask(&[Ten, Jack, Queen, King, Ace])], Some(Ace));
+        assert_eq!(straights()[mask(&[Ten, Jack, Queen, King, Two])], None);
+    }
+}
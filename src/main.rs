@@ -1,138 +1,194 @@
-mod card;
-mod poker_hand;
-mod poker_utils;
-
-use card::{cards_from_str, Card};
-use clap;
-use poker_utils::{deck_without_cards, determine_winner, new_deck, shuffle_deck};
-
-/// Given a game state, run simulations to determine the frequencies of winning
-fn run_out(
-    deck: Vec<Card>,
-    hands: Vec<[Card; 2]>,
-    community: Vec<Card>,
-    iterations: u32,
-) -> Vec<f32> {
-    let mut wins = vec![0.0; hands.len()];
-
-    for i in 0..iterations {
-        let mut deck = deck.clone();
-        let hands = hands.clone();
-        let mut community = community.clone();
-        shuffle_deck(&mut deck);
-
-        while community.len() < 5 {
-            community.push(deck.pop().unwrap());
-        }
-
-        if i % 10000 == 0 {
-            println!("Iteration: {i}");
-        }
-
-        let (idx, _) = determine_winner(hands, community.try_into().unwrap());
-        for i in &idx {
-            wins[*i] += 1.0 / idx.len() as f32;
-        }
-    }
-
-    wins.iter().map(|c| *c as f32 / iterations as f32).collect()
-}
-
-use clap::Parser;
-
-/// Equity Calculator
-#[derive(Parser, Debug)]
-#[command(
-    name = "equity-cli",
-    version,
-    about = "Simple Equity Calculator for poker"
-)]
-struct Args {
-    /// Number of iterations
-    #[arg(short, long, default_value_t = 100_000)]
-    iterations: u32,
-
-    /// Current board
-    /// Cards should use two letters each
-    /// Example: 5c6hQs
-    #[arg(short, long, default_value = "")]
-    board: String,
-
-    /// Hands to compare.
-    /// Hands should be separated by space, and use two letters for each hand
-    /// such as AhAs or KdQd
-    #[arg()]
-    hands: Vec<String>,
-}
-
-/// Run the actual Caculation
-fn run_calculation(board: Vec<Card>, hands: Vec<[Card; 2]>, iterations: u32) -> Vec<f32> {
-    let deck = new_deck();
-
-    let mut dead_cards = hands
-        .iter()
-        .flat_map(|h| h.iter())
-        .cloned()
-        .collect::<Vec<_>>();
-
-    dead_cards.append(&mut board.clone());
-
-    let deck = deck_without_cards(deck, dead_cards);
-
-    run_out(deck, hands, board, iterations)
-}
-
-fn main() {
-    let args = Args::parse();
-
-    if args.hands.len() < 2 {
-        panic!("You need at least 2 hands to compare");
-    }
-
-    let hands = args
-        .hands
-        .iter()
-        .map(|h| cards_from_str(h))
-        .map(|h| [h[0], h[1]])
-        .collect::<Vec<[Card; 2]>>();
-
-    let board = cards_from_str(&args.board);
-
-    // Print out Hands it will run
-    for (i, hand) in hands.iter().enumerate() {
-        println!("Hand {}: {:?}", i + 1, hand);
-    }
-
-    // Print out board
-    println!("Board: {:?}", board);
-
-    println!("Running {} iterations...", args.iterations);
-    let results = run_calculation(board, hands, args.iterations);
-
-    // Print Results
-    for (i, result) in results.iter().enumerate() {
-        println!("Hand {}: {:.2}%", i + 1, result * 100.0);
-    }
-}
-
-#[cfg(test)]
-mod test {
-    use super::*;
-
-    /// Shorthand for creating pocket cards
-    pub fn c(s: &str) -> [Card; 2] {
-        let cards = cards_from_str(s);
-        assert_eq!(cards.len(), 2);
-        [cards[0], cards[1]]
-    }
-
-    #[test]
-    fn test_aces_vs_kings() {
-        let result = run_calculation(vec![], vec![c("AhAs"), c("KdKh")], 10_000);
-        assert_eq!(result.len(), 2);
-
-        // AA should be ~ 2
-        assert!(result[0] > 0.80, "actual: {}", result[0]);
-        assert!(result[1] < 0.20, "actual: {}", result[1]);
-    }
-}
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+
+mod commands;
+
+/// Equity Calculator
+#[derive(Parser, Debug)]
+#[command(
+    name = "equity-cli",
+    version,
+    about = "Simple Equity Calculator for poker",
+    arg_required_else_help = true
+)]
+struct Cli {
+    /// List the named ranges that can be used as @name wherever a range is accepted
+    #[arg(long, exclusive = true)]
+    list_ranges: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Every holding with at least a given equity against hands or ranges, best first
+    Above(commands::above::Args),
+
+    /// Run every scenario in a CSV or JSON file, one result row each
+    Batch(commands::batch::Args),
+
+    /// Every holding that beats, ties or loses to a hand on a complete board
+    Beats(commands::beats::Args),
+
+    /// Combos of a range blocked by a hand, by what they make on the board
+    Blockers(commands::blockers::Args),
+
+    /// Where a hand's equity on the flop or turn comes from, made hand, draws or backdoor draws
+    Breakdown(commands::breakdown::Args),
+
+    /// Bubble factor and the equity needed to call an all in under ICM
+    Bubble(commands::bubble::Args),
+
+    /// Clear the results cached on disk, or show where they are
+    Cache(commands::cache::Args),
+
+    /// Change in a hand's equity between two boards or two sets of opponents
+    Compare(commands::compare::Args),
+
+    /// How often two ranges run into set over set, flush over flush and other coolers
+    Coolers(commands::coolers::Args),
+
+    /// Equity of hands or ranges against each other
+    Equity(commands::equity::Args),
+
+    /// Expected value of folding, calling or moving all in
+    Ev(commands::ev::Args),
+
+    /// Rank the best five card hand out of five, six or seven cards
+    Eval(commands::eval::Args),
+
+    /// Equity of ranges on every strategically distinct flop, with aggregates by texture
+    Flops(commands::flops::Args),
+
+    /// Expected value of the all ins in PokerStars and GGPoker hand histories
+    History(commands::history::Args),
+
+    /// Tournament prize equity of each stack using the Independent Chip Model
+    Icm(commands::icm::Args),
+
+    /// How often a bad beat jackpot is hit at every table size
+    Jackpot(commands::jackpot::Args),
+
+    /// Chance of making a hand such as a flush by the turn and by the river
+    Make(commands::make::Args),
+
+    /// Minimum defense frequency and how often a bluff has to work, optionally checked against a range
+    Mdf(commands::mdf::Args),
+
+    /// Equity of a hand or range against one to eight random opponents, from the same deals
+    Multiway(commands::multiway::Args),
+
+    /// Equity of every hand after each card that can come next, biggest swings first
+    NextCard(commands::next_card::Args),
+
+    /// Pot odds and the equity needed to call, optionally checked against hands
+    Odds(commands::odds::Args),
+
+    /// Cards still to come that make a hand the best hand, clean and tainted
+    Outs(commands::outs::Args),
+
+    /// Hand strength and the chances of improving or being outdrawn against a range
+    Potential(commands::potential::Args),
+
+    /// Heads up equity of every starting hand against every other, cached on disk
+    PreflopMatrix(commands::preflop_matrix::Args),
+
+    /// Heads up push or fold ranges at equilibrium for a stack in big blinds
+    PushFold(commands::push_fold::Args),
+
+    /// Starting hands a range holds on a 13x13 grid
+    Range(commands::range::Args),
+
+    /// HTTP JSON API answering POST /equity requests
+    #[cfg(feature = "serve")]
+    Serve(commands::serve::Args),
+
+    /// Combos of a range split by their equity into value, marginal hands and air
+    Split(commands::split::Args),
+
+    /// Read scenarios from stdin one per line and answer each with a result line
+    Stream(commands::stream::Args),
+
+    /// Full screen explorer with live equity while editing hands and the board
+    #[cfg(feature = "tui")]
+    Tui(commands::tui::Args),
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let Some(command) = &cli.command else {
+        if cli.list_ranges {
+            commands::ranges::list();
+        }
+        return ExitCode::SUCCESS;
+    };
+
+    let result = match command {
+        Command::Above(args) => commands::above::run(args),
+        Command::Batch(args) => commands::batch::run(args),
+        Command::Beats(args) => commands::beats::run(args),
+        Command::Blockers(args) => commands::blockers::run(args),
+        Command::Breakdown(args) => commands::breakdown::run(args),
+        Command::Bubble(args) => commands::bubble::run(args),
+        Command::Cache(args) => commands::cache::run(args),
+        Command::Compare(args) => commands::compare::run(args),
+        Command::Coolers(args) => commands::coolers::run(args),
+        Command::Equity(args) => commands::equity::run(args),
+        Command::Ev(args) => commands::ev::run(args),
+        Command::Eval(args) => commands::eval::run(args),
+        Command::Flops(args) => commands::flops::run(args),
+        Command::History(args) => commands::history::run(args),
+        Command::Icm(args) => commands::icm::run(args),
+        Command::Jackpot(args) => commands::jackpot::run(args),
+        Command::Make(args) => commands::make::run(args),
+        Command::Mdf(args) => commands::mdf::run(args),
+        Command::Multiway(args) => commands::multiway::run(args),
+        Command::NextCard(args) => commands::next_card::run(args),
+        Command::Odds(args) => commands::odds::run(args),
+        Command::Outs(args) => commands::outs::run(args),
+        Command::Potential(args) => commands::potential::run(args),
+        Command::PreflopMatrix(args) => commands::preflop_matrix::run(args),
+        Command::PushFold(args) => commands::push_fold::run(args),
+        Command::Range(args) => commands::range::run(args),
+        #[cfg(feature = "serve")]
+        Command::Serve(args) => commands::serve::run(args),
+        Command::Split(args) => commands::split::run(args),
+        Command::Stream(args) => commands::stream::run(args),
+        #[cfg(feature = "tui")]
+        Command::Tui(args) => commands::tui::run(args),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_cli_definition() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_subcommands() {
+        let cli = Cli::try_parse_from(["equity-cli", "equity", "AsKs", "QhQd"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Equity(_))));
+        let cli = Cli::try_parse_from(["equity-cli", "next-card", "AsKs", "QhQd", "-b", "Ts9s2d"]);
+        assert!(matches!(cli.unwrap().command, Some(Command::NextCard(_))));
+
+        // Hands are only taken after a subcommand
+        assert!(Cli::try_parse_from(["equity-cli", "AsKs", "QhQd"]).is_err());
+        assert!(Cli::try_parse_from(["equity-cli"]).is_err());
+    }
+}
@@ -0,0 +1,282 @@
+//! How the equity of a hand falls as more random opponents join the pot
+//! Every deal is dealt to the most opponents and shown down against the
+//! first one, the first two and so on, so every point of the curve comes
+//! from the same deals
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::card::Card;
+use crate::equity::{
+    deviation, merge_each, pot_share, remove_known_cards, run_workers, Progress, SimulationSettings,
+};
+use crate::error::Error;
+use crate::game::Game;
+use crate::range::Range;
+
+/// Share of the pot a hand wins against one number of random opponents
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MultiwayResult {
+    /// Number of random opponents in the pot
+    pub opponents: usize,
+
+    /// Number of deals played
+    pub deals: u64,
+
+    /// Total share of the pots won
+    pub share: f64,
+
+    /// Sum of the squared share of the pot won in every deal
+    pub share_squares: f64,
+}
+
+///////////////////////////////////////////////
+/// MultiwayResult Implementations
+///////////////////////////////////////////////
+impl MultiwayResult {
+    /// Share of the pot won on average
+    pub fn equity(&self) -> f64 {
+        self.share / self.deals.max(1) as f64
+    }
+
+    /// Standard error of the equity
+    pub fn std_error(&self) -> f64 {
+        if self.deals == 0 {
+            return 0.0;
+        }
+        deviation(self.share, self.share_squares, self.deals) / (self.deals as f64).sqrt()
+    }
+
+    /// Equity of a hand no better than the others, an even share of the pot
+    pub fn fair_share(&self) -> f64 {
+        1.0 / (self.opponents + 1) as f64
+    }
+
+    /// Add the counts of another result into this one
+    pub fn merge(&mut self, other: &MultiwayResult) {
+        self.deals += other.deals;
+        self.share += other.share;
+        self.share_squares += other.share_squares;
+    }
+}
+
+/// Deal `range` against `opponents` random hands over and over, showing
+/// each deal down against every number of opponents from one to all of
+/// them
+/// The smaller pots are the first opponents of each deal
+pub fn run_multiway_simulation<R: Rng>(
+    game: Game,
+    range: Range,
+    board: Vec<Card>,
+    dead: Vec<Card>,
+    opponents: usize,
+    settings: &SimulationSettings,
+    rng: &mut R,
+) -> Result<Vec<MultiwayResult>, Error> {
+    if !game.has_board() {
+        return Err(Error::NoBoard(game));
+    }
+    let (deck, ranges) = remove_known_cards(&board, &dead, vec![range]);
+    let range = &ranges[0];
+    if range.is_empty() {
+        return Err(Error::EmptyRange("hand 1".to_string()));
+    }
+    let needed = (opponents + 1) * game.hole_cards() + 5 - board.len();
+    if needed > deck.len() {
+        return Err(Error::DeckTooSmall {
+            cards: game.hole_cards(),
+            players: opponents + 1,
+            dead: board.len() + dead.len(),
+        });
+    }
+
+    let progress = Progress::new(settings);
+    let run = |share: u32, rng: StdRng| {
+        simulate(
+            game,
+            deck.remaining(),
+            range,
+            &board,
+            opponents,
+            share,
+            &progress,
+            settings,
+            rng,
+        )
+    };
+    Ok(run_workers(
+        settings,
+        rng,
+        run,
+        merge_each(MultiwayResult::merge),
+    ))
+}
+
+/// Empty results for every number of opponents
+fn empty_multiway_results(opponents: usize) -> Vec<MultiwayResult> {
+    (1..=opponents)
+        .map(|opponents| MultiwayResult {
+            opponents,
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// Run `iterations` deals on the current thread
+#[allow(clippy::too_many_arguments)]
+fn simulate(
+    game: Game,
+    deck: &[Card],
+    range: &Range,
+    community: &[Card],
+    opponents: usize,
+    iterations: u32,
+    progress: &Progress,
+    settings: &SimulationSettings,
+    mut rng: StdRng,
+) -> Vec<MultiwayResult> {
+    let mut results = empty_multiway_results(opponents);
+    let mut deck = deck.to_vec();
+    let hole_cards = game.hole_cards();
+    let runout = 5 - community.len();
+    let mut board = [deck[0]; 5];
+    board[..community.len()].copy_from_slice(community);
+    let mut best = Vec::with_capacity(opponents + 1);
+    let mut winners = Vec::with_capacity(opponents + 1);
+    let mut low_winners = Vec::with_capacity(opponents + 1);
+
+    for _ in 0..iterations {
+        if progress.stopped() {
+            break;
+        }
+        progress.tick();
+
+        let Some(i) = range.sample_index(&mut rng) else {
+            break;
+        };
+        let hole = range.combos()[i].as_slice();
+
+        // The hole cards go to the end of the deck, out of reach of the
+        // opponents and the runout
+        let mut open = deck.len();
+        for card in hole {
+            let pick = deck[..open].iter().position(|c| c == card).unwrap();
+            open -= 1;
+            deck.swap(pick, open);
+        }
+        let (dealt, _) = deck[..open].partial_shuffle(&mut rng, opponents * hole_cards + runout);
+        let (runout, others) = dealt.split_at(runout);
+        board[community.len()..].copy_from_slice(runout);
+
+        let hands = [hole]
+            .into_iter()
+            .chain(others.chunks(hole_cards))
+            .collect::<Vec<_>>();
+        best.clear();
+        game.rules()
+            .best_hands(&hands, &board, settings.evaluator, &mut best);
+        let lows = game.is_hi_lo().then(|| {
+            hands
+                .iter()
+                .map(|hole| game.rules().best_low(hole, &board))
+                .collect::<Vec<_>>()
+        });
+
+        // The pot with `opponents` opponents is the hand and the first of them
+        for result in &mut results {
+            let players = result.opponents + 1;
+            let winning_hand = *best[..players].iter().max().unwrap();
+            winners.clear();
+            winners.extend((0..players).filter(|p| best[*p] == winning_hand));
+            let low_winners = lows.as_ref().map(|lows| {
+                low_winners.clear();
+                if let Some(low) = lows[..players].iter().flatten().min() {
+                    low_winners.extend((0..players).filter(|p| lows[*p].as_ref() == Some(low)));
+                }
+                low_winners.as_slice()
+            });
+
+            let share = pot_share(0, &winners, low_winners);
+            result.deals += 1;
+            result.share += share;
+            result.share_squares += share * share;
+        }
+    }
+
+    results
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::card::cards_from_str;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_multiway_equities() {
+        let settings = SimulationSettings {
+            threads: 2,
+            ..SimulationSettings::new(20_000)
+        };
+        let mut rng = StdRng::seed_from_u64(41);
+        let aces = Range::parse("AA").unwrap();
+        let results =
+            run_multiway_simulation(Game::Holdem, aces, vec![], vec![], 8, &settings, &mut rng)
+                .unwrap();
+
+        assert_eq!(results.len(), 8);
+        assert_eq!(results[0].opponents, 1);
+        assert!(results.iter().all(|r| r.deals == 20_000));
+
+        // Aces are about 85% heads up and lose value with every opponent,
+        // while staying well above an even share
+        let heads_up = results[0].equity();
+        assert!((0.83..0.87).contains(&heads_up), "{}", heads_up);
+        assert!(results.windows(2).all(|w| w[0].equity() > w[1].equity()));
+        assert!(results.iter().all(|r| r.equity() > r.fair_share() + 0.2));
+        assert!(results[7].std_error() > 0.0 && results[7].std_error() < 0.01);
+    }
+
+    #[test]
+    fn test_multiway_known_cards() {
+        let settings = SimulationSettings::new(100);
+        let mut rng = StdRng::seed_from_u64(5);
+        let board = cards_from_str("AhAd2c").unwrap();
+        let aces = Range::parse("AA").unwrap();
+        assert!(matches!(
+            run_multiway_simulation(Game::Holdem, aces.clone(), board, vec![], 2, &settings, &mut rng),
+            Ok(results) if results.len() == 2
+        ));
+
+        let dead = cards_from_str("AhAdAc").unwrap();
+        assert!(matches!(
+            run_multiway_simulation(
+                Game::Holdem,
+                aces.clone(),
+                vec![],
+                dead,
+                2,
+                &settings,
+                &mut rng
+            ),
+            Err(Error::EmptyRange(_))
+        ));
+        assert!(matches!(
+            run_multiway_simulation(
+                Game::Omaha,
+                Range::from_cards(cards_from_str("AhAsKdKc").unwrap()),
+                vec![],
+                vec![],
+                11,
+                &settings,
+                &mut rng
+            ),
+            Err(Error::DeckTooSmall { .. })
+        ));
+        assert!(matches!(
+            run_multiway_simulation(Game::Stud, aces, vec![], vec![], 2, &settings, &mut rng),
+            Err(Error::NoBoard(Game::Stud))
+        ));
+    }
+}
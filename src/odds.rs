@@ -0,0 +1,209 @@
+//! Pot odds, the equity needed to call a bet and the expected value
+//! of folding, calling or moving all in
+
+use crate::error::Error;
+
+/// Bounty on the bettor in a knockout tournament, won by the player who
+/// knocks them out
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounty {
+    /// What knocking the bettor out is worth, in the same units as the pot
+    pub value: f64,
+
+    /// Bettor's chips in the hand, their bet and everything behind it
+    pub stack: f64,
+}
+
+///////////////////////////////////////////////
+/// Bounty Implementations
+///////////////////////////////////////////////
+impl Bounty {
+    /// Bounty worth `value` on a bettor with `stack` chips
+    pub fn new(value: f64, stack: f64) -> Result<Self, Error> {
+        check_amounts(value, stack)?;
+        Ok(Bounty { value, stack })
+    }
+
+    /// Whether a player putting in `chips` covers the bettor, so winning
+    /// the hand knocks them out
+    pub fn covered_by(&self, chips: f64) -> bool {
+        chips >= self.stack
+    }
+
+    /// Bounty expected from calling a bet of `call` with `equity`, nothing
+    /// unless the bettor is all in
+    pub fn call_ev(&self, call: f64, equity: f64) -> Result<f64, Error> {
+        check_chance(equity)?;
+        Ok(if self.covered_by(call) {
+            equity * self.value
+        } else {
+            0.0
+        })
+    }
+
+    /// Bounty expected from moving all in for `stack`, won when the bettor
+    /// calls, is covered and loses the hand
+    pub fn shove_ev(&self, stack: f64, equity: f64, fold_equity: f64) -> Result<f64, Error> {
+        check_chance(equity)?;
+        check_chance(fold_equity)?;
+        Ok(if self.covered_by(stack) {
+            (1.0 - fold_equity) * equity * self.value
+        } else {
+            0.0
+        })
+    }
+}
+
+/// Pot odds of calling a bet, as the amount that can be won for each chip called
+/// `pot` is the pot before the bet being faced, which is the same size as `call`
+pub fn pot_odds(pot: f64, call: f64) -> Result<f64, Error> {
+    check_amounts(pot, call)?;
+    Ok((pot + call) / call)
+}
+
+/// Equity needed for a call to break even
+pub fn required_equity(pot: f64, call: f64) -> Result<f64, Error> {
+    check_amounts(pot, call)?;
+    Ok(call / (pot + call + call))
+}
+
+/// Minimum defense frequency, the share of a range that has to continue
+/// against a bet of `bet` into `pot` so that a bluff with any two cards
+/// does not show a profit
+pub fn minimum_defense_frequency(pot: f64, bet: f64) -> Result<f64, Error> {
+    check_amounts(pot, bet)?;
+    Ok(pot / (pot + bet))
+}
+
+/// How often a bluff of `bet` into `pot` has to make the other player fold
+/// to break even
+pub fn bluff_break_even(pot: f64, bet: f64) -> Result<f64, Error> {
+    check_amounts(pot, bet)?;
+    Ok(bet / (pot + bet))
+}
+
+/// Expected value of calling a bet with no more betting to come, relative to
+/// folding, in the same units as the amounts
+pub fn call_ev(pot: f64, call: f64, equity: f64) -> Result<f64, Error> {
+    check_amounts(pot, call)?;
+    check_chance(equity)?;
+    Ok(equity * (pot + call + call) - call)
+}
+
+/// Expected value of moving all in for `stack` when facing a bet of `call`,
+/// relative to folding
+/// The bettor folds `fold_equity` of the time, giving up the pot and their
+/// bet, and otherwise calls and the hand is played out with `equity`
+pub fn shove_ev(
+    pot: f64,
+    call: f64,
+    stack: f64,
+    equity: f64,
+    fold_equity: f64,
+) -> Result<f64, Error> {
+    check_amounts(pot, call)?;
+    check_chance(equity)?;
+    check_chance(fold_equity)?;
+    if !(stack >= call && stack.is_finite()) {
+        return Err(Error::StackBelowCall { stack, call });
+    }
+
+    let called = equity * (pot + stack + stack) - stack;
+    Ok(fold_equity * (pot + call) + (1.0 - fold_equity) * called)
+}
+
+fn check_chance(chance: f64) -> Result<(), Error> {
+    if !(0.0..=1.0).contains(&chance) {
+        return Err(Error::InvalidChance(chance));
+    }
+    Ok(())
+}
+
+fn check_amounts(pot: f64, call: f64) -> Result<(), Error> {
+    if !(pot >= 0.0 && pot.is_finite()) {
+        return Err(Error::InvalidPot(pot));
+    }
+    if !(call > 0.0 && call.is_finite()) {
+        return Err(Error::InvalidBet(call));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_pot_odds() {
+        // Calling 35 to win the 100 pot and the 35 bet
+        assert!((pot_odds(100.0, 35.0).unwrap() - 135.0 / 35.0).abs() < 1e-12);
+        assert!((required_equity(100.0, 35.0).unwrap() - 0.2059).abs() < 1e-4);
+
+        // A pot sized bet needs a third of the final pot
+        assert!((required_equity(10.0, 10.0).unwrap() - 1.0 / 3.0).abs() < 1e-12);
+
+        assert!(required_equity(100.0, 0.0).is_err());
+        assert!(pot_odds(-1.0, 10.0).is_err());
+    }
+
+    #[test]
+    fn test_minimum_defense() {
+        // A pot sized bet has to be called half the time and work half the time
+        assert!((minimum_defense_frequency(100.0, 100.0).unwrap() - 0.5).abs() < 1e-12);
+        assert!((bluff_break_even(100.0, 100.0).unwrap() - 0.5).abs() < 1e-12);
+
+        // A third pot bet has to be defended three quarters of the time
+        assert!((minimum_defense_frequency(90.0, 30.0).unwrap() - 0.75).abs() < 1e-12);
+        assert!((bluff_break_even(90.0, 30.0).unwrap() - 0.25).abs() < 1e-12);
+
+        assert!(matches!(
+            minimum_defense_frequency(100.0, 0.0),
+            Err(Error::InvalidBet(_))
+        ));
+        assert!(matches!(
+            bluff_break_even(f64::NAN, 10.0),
+            Err(Error::InvalidPot(_))
+        ));
+    }
+
+    #[test]
+    fn test_ev() {
+        // Calling 80 into 120 + 80 with 40% wins 0.4 * 280 and risks 80
+        assert!((call_ev(120.0, 80.0, 0.4).unwrap() - 32.0).abs() < 1e-9);
+        let breakeven = required_equity(120.0, 80.0).unwrap();
+        assert!(call_ev(120.0, 80.0, breakeven).unwrap().abs() < 1e-9);
+
+        // Shoving for exactly the call is just a call
+        let shove = shove_ev(120.0, 80.0, 80.0, 0.4, 0.0).unwrap();
+        assert!((shove - 32.0).abs() < 1e-9);
+
+        // Always folding out the bettor wins the pot and their bet
+        let shove = shove_ev(120.0, 80.0, 500.0, 0.1, 1.0).unwrap();
+        assert!((shove - 200.0).abs() < 1e-9);
+
+        assert!(shove_ev(120.0, 80.0, 50.0, 0.4, 0.0).is_err());
+        assert!(call_ev(120.0, 80.0, 1.5).is_err());
+    }
+
+    #[test]
+    fn test_bounty() {
+        // Calling an all in wins the bounty whenever the hand is won
+        let bounty = Bounty::new(50.0, 80.0).unwrap();
+        assert!((bounty.call_ev(80.0, 0.4).unwrap() - 20.0).abs() < 1e-9);
+        let breakeven = required_equity(120.0, 80.0).unwrap();
+        let with_bounty =
+            call_ev(120.0, 80.0, breakeven).unwrap() + bounty.call_ev(80.0, breakeven).unwrap();
+        assert!(with_bounty > 0.0);
+
+        // A bettor with chips behind can not be knocked out by a call, and a
+        // shove has to cover them and be called
+        let bounty = Bounty::new(50.0, 300.0).unwrap();
+        assert_eq!(bounty.call_ev(80.0, 0.4).unwrap(), 0.0);
+        assert_eq!(bounty.shove_ev(200.0, 0.4, 0.0).unwrap(), 0.0);
+        assert!((bounty.shove_ev(500.0, 0.4, 0.5).unwrap() - 10.0).abs() < 1e-9);
+
+        assert!(Bounty::new(-1.0, 80.0).is_err());
+        assert!(Bounty::new(50.0, 0.0).is_err());
+        assert!(bounty.call_ev(80.0, 1.5).is_err());
+    }
+}
@@ -0,0 +1,199 @@
+//! Outs, the cards still to come that turn a hand into the best hand
+//! Only the high hand is looked at, in split pot games as well
+
+use crate::card::Card;
+use crate::deck::Deck;
+use crate::error::Error;
+use crate::evaluator::EvaluatorKind;
+use crate::game::Game;
+use crate::poker_hand::PokerHandRank;
+use crate::range::Range;
+
+/// The next cards sorted by what they do for a hand
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Outs {
+    /// Cards that put the hand ahead of every opponent
+    pub clean: Vec<Card>,
+
+    /// Cards that would beat what the opponents hold now, such as making the
+    /// flush, but improve an opponent to a better hand still
+    pub tainted: Vec<Card>,
+
+    /// Cards that leave the hand tied for the best
+    pub splits: Vec<Card>,
+
+    /// Number of cards that could come next
+    pub unseen: usize,
+}
+
+impl Outs {
+    /// Chance the next card is a clean out
+    pub fn next_card(&self) -> f64 {
+        self.clean.len() as f64 / self.unseen.max(1) as f64
+    }
+}
+
+/// Best high hand out of the hole cards and a board of three to five cards
+pub fn made_hand(game: Game, hole: &[Card], board: &[Card]) -> PokerHandRank {
+    let evaluator = EvaluatorKind::default().evaluator();
+    game.rules().best_hand(hole, board, evaluator).rank()
+}
+
+/// Sort every card that could come next on a flop or turn board by
+/// whether it makes `hand` the best hand against every opponent
+/// Cards held by anyone, on the board or `dead` can not come
+/// Fails without at least one opponent
+pub fn find_outs(
+    game: Game,
+    hand: &[Card],
+    opponents: &[Vec<Card>],
+    board: &[Card],
+    dead: &[Card],
+) -> Result<Outs, Error> {
+    if opponents.is_empty() {
+        return Err(Error::NotEnoughHands(1));
+    }
+    let known = [hand, board, dead]
+        .into_iter()
+        .chain(opponents.iter().map(Vec::as_slice))
+        .flatten()
+        .cloned()
+        .collect::<Vec<_>>();
+    let unseen = Vec::from(Deck::without(&known));
+    let best_opponent = |board: &[Card]| {
+        opponents
+            .iter()
+            .map(|opponent| made_hand(game, opponent, board))
+            .max()
+            .unwrap()
+    };
+    let before = best_opponent(board);
+
+    let mut outs = Outs {
+        unseen: unseen.len(),
+        ..Outs::default()
+    };
+    let mut next = board.to_vec();
+    next.push(unseen[0]);
+
+    for card in unseen {
+        *next.last_mut().unwrap() = card;
+        let mine = made_hand(game, hand, &next);
+        let best = best_opponent(&next);
+
+        if mine > best {
+            outs.clean.push(card);
+        } else if mine == best {
+            outs.splits.push(card);
+        } else if mine > before {
+            outs.tainted.push(card);
+        }
+    }
+
+    Ok(outs)
+}
+
+/// Every card that can come next, in deck order
+/// Leaves out cards on the board, dead cards and cards every combo of a
+/// range holds, as well as cards that would leave a range with no combos
+pub fn next_cards(board: &[Card], dead: &[Card], ranges: &[Range]) -> Vec<Card> {
+    let known = board.iter().chain(dead).cloned().collect::<Vec<_>>();
+
+    Deck::without(&known)
+        .remaining()
+        .iter()
+        .copied()
+        .filter(|card| {
+            let mut removed = known.clone();
+            removed.push(*card);
+            ranges
+                .iter()
+                .all(|range| !range.without_cards(&removed).is_empty())
+        })
+        .collect()
+}
+
+/// Equity the rule of 2 and 4 gives for a number of outs, 4% an out with
+/// two cards to come and 2% an out with one
+pub fn rule_of_thumb(outs: usize, cards_to_come: usize) -> f64 {
+    (outs * cards_to_come * 2) as f64 / 100.0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::card::cards_from_str;
+
+    fn cards(s: &str) -> Vec<Card> {
+        cards_from_str(s).unwrap()
+    }
+
+    fn names(cards: &[Card]) -> String {
+        cards.iter().map(|c| format!("{:?}", c)).collect()
+    }
+
+    #[test]
+    fn test_flush_draw_outs() {
+        let outs = find_outs(
+            Game::Holdem,
+            &cards("9h8h"),
+            &[cards("AcAd")],
+            &cards("7h2hKs"),
+            &[],
+        )
+        .unwrap();
+
+        // Every heart makes the flush, even the ones that improve the aces,
+        // while the straight needs two more cards
+        assert_eq!(outs.unseen, 45);
+        assert_eq!(names(&outs.clean), "3h4h5h6hThJhQhKhAh");
+        assert!(outs.splits.is_empty());
+        assert!(outs.tainted.is_empty());
+        assert!((outs.next_card() - 9.0 / 45.0).abs() < 1e-9);
+        assert_eq!(rule_of_thumb(9, 2), 0.36);
+    }
+
+    #[test]
+    fn test_tainted_outs() {
+        // The diamonds that pair the board give the set a full house
+        let outs = find_outs(
+            Game::Holdem,
+            &cards("AdKd"),
+            &[cards("7s7c")],
+            &cards("7d2d9c3h"),
+            &[],
+        )
+        .unwrap();
+        assert_eq!(outs.unseen, 44);
+        assert_eq!(names(&outs.tainted), "3d9d");
+        assert_eq!(names(&outs.clean), "4d5d6d8dTdJdQd");
+    }
+
+    #[test]
+    fn test_outs_need_an_opponent() {
+        let outs = find_outs(Game::Holdem, &cards("9h8h"), &[], &cards("7h2hKs"), &[]);
+        assert!(matches!(outs, Err(Error::NotEnoughHands(1))));
+    }
+
+    #[test]
+    fn test_next_cards() {
+        let ranges = [Range::parse("AhAs").unwrap(), Range::parse("KK").unwrap()];
+        let next = next_cards(&cards("Kh7c2d"), &cards("Kd"), &ranges);
+
+        // The aces are held, and only KcKs is left of the kings so neither can come
+        assert_eq!(next.len(), 52 - 4 - 4);
+        for card in cards("AhAsKcKsKh") {
+            assert!(!next.contains(&card));
+        }
+        assert!(next.contains(&cards("Ac")[0]));
+    }
+
+    #[test]
+    fn test_omaha_made_hand() {
+        // A single heart in the hand can not make a flush
+        let hand = made_hand(Game::Omaha, &cards("AhKsQsJc"), &cards("2h3h4h9h"));
+        assert!(hand.category() < crate::poker_hand::HandCategory::Flush);
+        let hand = made_hand(Game::Holdem, &cards("AhKs"), &cards("2h3h4h9h"));
+        assert!(hand.category() >= crate::poker_hand::HandCategory::Flush);
+    }
+}
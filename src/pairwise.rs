@@ -0,0 +1,256 @@
+//! Heads up equity between every pair of hands in a multiway pot
+//! Each simulated deal is also shown down between every two hands, so the
+//! sub-matchups share the multiway deals, with the cards of the other hands
+//! dead for both of them
+
+use rand::rngs::StdRng;
+use rand::Rng;
+use std::cmp::Ordering;
+
+use crate::card::Card;
+use crate::equity::{
+    merge_each, remove_known_cards, run_workers, simulate_showdowns, DealShowdown, DealtResult,
+    HandResult, Progress, SimulationSettings,
+};
+use crate::error::Error;
+use crate::game::Game;
+use crate::poker_hand::{HandValue, LowHand};
+use crate::range::Range;
+
+/// Shares of the pot one player wins heads up against each other player
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PairwiseResult {
+    /// Index of the player's hand
+    pub hand: usize,
+
+    /// Number of deals played
+    pub deals: u64,
+
+    /// Total share of the pot won heads up against every hand, over every
+    /// deal, indexed by the other hand and zero against itself
+    pub shares: Vec<f64>,
+
+    /// The multiway showdown of every deal
+    pub showdowns: HandResult,
+}
+
+///////////////////////////////////////////////
+/// PairwiseResult Implementations
+///////////////////////////////////////////////
+impl PairwiseResult {
+    /// Empty result of one hand in a pot of `hands` hands
+    fn new(hand: usize, hands: usize, showdowns: HandResult) -> Self {
+        PairwiseResult {
+            hand,
+            shares: vec![0.0; hands],
+            showdowns,
+            ..Default::default()
+        }
+    }
+
+    /// Heads up equity against another hand, None against itself
+    pub fn equity_vs(&self, hand: usize) -> Option<f64> {
+        if hand == self.hand {
+            return None;
+        }
+        Some(self.shares.get(hand)? / self.deals.max(1) as f64)
+    }
+
+    /// Add the counts of another result into this one
+    pub fn merge(&mut self, other: &PairwiseResult) {
+        self.deals += other.deals;
+        for (total, share) in self.shares.iter_mut().zip(&other.shares) {
+            *total += share;
+        }
+        self.showdowns.merge(&other.showdowns);
+    }
+}
+
+impl DealtResult for PairwiseResult {
+    fn showdowns(&mut self) -> &mut HandResult {
+        &mut self.showdowns
+    }
+
+    fn add_deal(&mut self) {
+        self.deals += 1;
+    }
+}
+
+/// Simulate deals from the ranges, showing each one down between all the
+/// hands and between every two of them
+/// Games without a board have no runout to deal, and `settings` can not set
+/// a target error, a sampler or a runout filter
+pub fn run_pairwise_calculation<R: Rng>(
+    game: Game,
+    board: Vec<Card>,
+    dead: Vec<Card>,
+    ranges: Vec<Range>,
+    settings: &SimulationSettings,
+    rng: &mut R,
+) -> Result<Vec<PairwiseResult>, Error> {
+    if !game.has_board() {
+        return Err(Error::NoBoard(game));
+    }
+    settings.check_random_runouts()?;
+    let (deck, ranges) = remove_known_cards(&board, &dead, ranges);
+    let progress = Progress::new(settings);
+    let players = ranges.len();
+    let run = |share: u32, rng: StdRng| {
+        let deal = DealShowdown::new(game, deck.remaining(), &ranges, &board, settings.evaluator);
+        let new = |hand, showdowns| PairwiseResult::new(hand, players, showdowns);
+        simulate_showdowns(deal, share, &progress, rng, new, |deal, results| {
+            for a in 0..players {
+                for b in a + 1..players {
+                    let share = heads_up_share(a, b, &deal.best, deal.lows());
+                    results[a].shares[b] += share;
+                    results[b].shares[a] += 1.0 - share;
+                }
+            }
+        })
+    };
+    Ok(run_workers(
+        settings,
+        rng,
+        run,
+        merge_each(PairwiseResult::merge),
+    ))
+}
+
+/// Share of the pot hand `a` wins heads up against hand `b`, split between
+/// the high and the low when a low qualifies
+fn heads_up_share(a: usize, b: usize, best: &[HandValue], lows: Option<&[Option<LowHand>]>) -> f64 {
+    let share = |ordering: Ordering| match ordering {
+        Ordering::Greater => 1.0,
+        Ordering::Equal => 0.5,
+        Ordering::Less => 0.0,
+    };
+    let high = share(best[a].cmp(&best[b]));
+
+    // The lowest low wins, and any low beats none
+    let low = lows.and_then(|lows| match (lows[a], lows[b]) {
+        (None, None) => None,
+        (Some(_), None) => Some(1.0),
+        (None, Some(_)) => Some(0.0),
+        (Some(a), Some(b)) => Some(share(b.cmp(&a))),
+    });
+    match low {
+        Some(low) => (high + low) / 2.0,
+        None => high,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::card::cards_from_str;
+    use crate::equity::run_exact_calculation;
+    use crate::range::hand_ranges;
+    use crate::sampler::Sampler;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_pairwise_equities() {
+        let settings = SimulationSettings {
+            threads: 2,
+            ..SimulationSettings::new(20_000)
+        };
+        let mut rng = StdRng::seed_from_u64(99);
+        let board = cards_from_str("Ts9s2d").unwrap();
+        let hands = ["AsKs", "QhJh", "TcTd"];
+        let results = run_pairwise_calculation(
+            Game::Holdem,
+            board.clone(),
+            vec![],
+            hand_ranges(&hands),
+            &settings,
+            &mut rng,
+        )
+        .unwrap();
+
+        assert_eq!(results[1].deals, 20_000);
+        assert_eq!(results[1].showdowns.showdowns(), 20_000);
+        assert_eq!(results[0].equity_vs(0), None);
+        assert_eq!(results[0].equity_vs(3), None);
+        for a in 0..3 {
+            for b in 0..3 {
+                if a != b {
+                    let sum = results[a].equity_vs(b).unwrap() + results[b].equity_vs(a).unwrap();
+                    assert!((sum - 1.0).abs() < 1e-9);
+                }
+            }
+        }
+
+        // Each matchup is close to the heads up equity with the third hand
+        // dead
+        for (a, b, other) in [(0, 1, 2), (0, 2, 1), (1, 2, 0)] {
+            let dead = cards_from_str(hands[other]).unwrap();
+            let exact = run_exact_calculation(
+                Game::Holdem,
+                board.clone(),
+                dead,
+                hand_ranges(&[hands[a], hands[b]]),
+            )
+            .unwrap()[0]
+                .equity();
+            let simulated = results[a].equity_vs(b).unwrap();
+            assert!((simulated - exact).abs() < 0.02, "{} {}", simulated, exact);
+        }
+    }
+
+    #[test]
+    fn test_hi_lo_heads_up() {
+        let settings = SimulationSettings::new(2_000);
+        let mut rng = StdRng::seed_from_u64(7);
+        let results = run_pairwise_calculation(
+            Game::Omaha8,
+            cards_from_str("Kh7c2d").unwrap(),
+            vec![],
+            hand_ranges(&["As2s3d4d", "KdKsQhQd", "AhAcJhTh"]),
+            &settings,
+            &mut rng,
+        )
+        .unwrap();
+
+        // The low draw gets half of most pots against the set, which only
+        // has the high
+        let low_vs_set = results[0].equity_vs(1).unwrap();
+        assert!((0.25..0.6).contains(&low_vs_set), "{}", low_vs_set);
+        assert!(results[2].showdowns.hi_lo.is_some());
+    }
+    #[test]
+    fn test_needs_a_board_game() {
+        let settings = SimulationSettings::new(100);
+        let mut rng = StdRng::seed_from_u64(1);
+        assert!(matches!(
+            run_pairwise_calculation(
+                Game::Stud,
+                vec![],
+                vec![],
+                hand_ranges(&["AsAhKd", "QsQhJd"]),
+                &settings,
+                &mut rng,
+            ),
+            Err(Error::NoBoard(Game::Stud))
+        ));
+    }
+
+    #[test]
+    fn test_needs_random_runouts() {
+        let settings = SimulationSettings {
+            sampler: Sampler::Sobol,
+            ..SimulationSettings::new(100)
+        };
+        let mut rng = StdRng::seed_from_u64(1);
+        assert!(matches!(
+            run_pairwise_calculation(
+                Game::Holdem,
+                vec![],
+                vec![],
+                hand_ranges(&["AsKs", "QhQd"]),
+                &settings,
+                &mut rng,
+            ),
+            Err(Error::UnsupportedSetting("sampler"))
+        ));
+    }
+}
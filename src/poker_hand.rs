@@ -1,7 +1,12 @@
-use crate::card::{Rank, Card};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use crate::card::{Card, Rank};
 
 /// Enumeration of all Poker Hands
-#[derive(Debug, Clone, Copy, Ord, Eq)]
+/// Variants are declared weakest to strongest so the derived ordering
+/// compares the category first and then each rank in turn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum PokerHandRank {
     // All five cards of different ranks
     HighCard(Rank, Rank, Rank, Rank, Rank),
@@ -31,95 +36,485 @@ pub enum PokerHandRank {
     StraightFlush(Rank),
 }
 
-// Decide if two poker hands are equivalent
-impl PartialEq for PokerHandRank {
-    fn eq(&self, other: &Self) -> bool {
+/// `PokerHandRank` packed into an integer that orders the same way, for
+/// comparing hands in the hot path
+/// The category is in bits 20 to 23 and each rank takes four bits below it,
+/// highest first, with unused ranks left as zero
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HandValue(pub u32);
+
+/// Category of a hand without its ranks, such as a flush
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HandCategory {
+    HighCard,
+    Pair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+}
+
+/// Ace to five low for split pot games, five different ranks of eight or
+/// lower with the ace playing as one
+/// Ranks are held highest first, so the derived ordering puts the best low first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LowHand([u8; 5]);
+
+/// Ace to five low as played in razz, any five cards with the ace playing
+/// as one and straights and flushes not counting
+/// Holds how many cards of each rank there are, most first, and then those
+/// ranks highest first, so the derived ordering puts the best low first and
+/// every paired hand after the unpaired ones
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RazzHand {
+    counts: [u8; 5],
+    ranks: [u8; 5],
+}
+
+/// Deuce to seven low, the worst high hand wins with aces always high, so
+/// straights and flushes count against the hand and A-2-3-4-5 is not a straight
+/// Holds the high hand ranking, so the derived ordering puts the best low first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DeuceSevenHand(PokerHandRank);
+
+/// Badugi, the most cards of different ranks and suits out of four, the
+/// ace playing low, with lower ranks better between hands of the same size
+/// Holds the cards left out and then the ranks highest first, so the
+/// derived ordering puts the best hand first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BadugiHand {
+    missing: u8,
+    ranks: [u8; 4],
+}
+
+///////////////////////////////////////////////
+/// HandCategory Implementations
+///////////////////////////////////////////////
+impl HandCategory {
+    /// All categories from weakest to strongest
+    pub const ALL: [HandCategory; 9] = [
+        HandCategory::HighCard,
+        HandCategory::Pair,
+        HandCategory::TwoPair,
+        HandCategory::ThreeOfAKind,
+        HandCategory::Straight,
+        HandCategory::Flush,
+        HandCategory::FullHouse,
+        HandCategory::FourOfAKind,
+        HandCategory::StraightFlush,
+    ];
+
+    /// Name of the category, such as "Two Pair"
+    pub fn name(self) -> &'static str {
+        match self {
+            HandCategory::HighCard => "High Card",
+            HandCategory::Pair => "Pair",
+            HandCategory::TwoPair => "Two Pair",
+            HandCategory::ThreeOfAKind => "Three of a Kind",
+            HandCategory::Straight => "Straight",
+            HandCategory::Flush => "Flush",
+            HandCategory::FullHouse => "Full House",
+            HandCategory::FourOfAKind => "Four of a Kind",
+            HandCategory::StraightFlush => "Straight Flush",
+        }
+    }
+}
+
+impl Display for HandCategory {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for HandCategory {
+    type Err = String;
+
+    /// A category by its name in any case, with dashes, underscores or
+    /// spaces between the words, such as "two-pair", or a short name such
+    /// as "trips"
+    /// An "or better" on the end is left out, as "two-pair-or-better"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.to_ascii_lowercase().replace(['-', '_'], " ");
+        let name = name.trim().trim_end_matches(" or better");
+        match name {
+            "trips" | "set" => return Ok(HandCategory::ThreeOfAKind),
+            "boat" => return Ok(HandCategory::FullHouse),
+            "quads" => return Ok(HandCategory::FourOfAKind),
+            _ => {}
+        }
+        HandCategory::ALL
+            .into_iter()
+            .find(|category| category.name().to_ascii_lowercase() == name)
+            .ok_or_else(|| {
+                format!(
+                    "Unknown hand category \"{}\", expected high-card, pair, two-pair, trips, straight, flush, full-house, quads or straight-flush",
+                    s
+                )
+            })
+    }
+}
+
+///////////////////////////////////////////////
+/// PokerHandRank Implementations
+///////////////////////////////////////////////
+impl PokerHandRank {
+    /// Category of the hand, such as two pair
+    pub fn category(&self) -> HandCategory {
         use PokerHandRank::*;
-        match (self, other) {
-            (HighCard(a1, b1, c1, d1, e1), HighCard(a2, b2, c2, d2, e2)) => {
-                a1 == a2 && b1 == b2 && c1 == c2 && d1 == d2 && e1 == e2
-            }
-            (Pair(a1, b1, c1, d1), Pair(a2, b2, c2, d2)) => {
-                a1 == a2 && b1 == b2 && c1 == c2 && d1 == d2
+        match self {
+            HighCard(..) => HandCategory::HighCard,
+            Pair(..) => HandCategory::Pair,
+            TwoPair(..) => HandCategory::TwoPair,
+            ThreeOfAKind(..) => HandCategory::ThreeOfAKind,
+            Straight(..) => HandCategory::Straight,
+            Flush(..) => HandCategory::Flush,
+            FullHouse(..) => HandCategory::FullHouse,
+            FourOfAKind(..) => HandCategory::FourOfAKind,
+            StraightFlush(..) => HandCategory::StraightFlush,
+        }
+    }
+
+    /// Ranks of the kickers, the cards that only count when the rest of the
+    /// hand ties, highest first
+    /// A high card hand's kickers are the four cards under its high card,
+    /// and straights, flushes and full houses have none
+    pub fn kickers(&self) -> Vec<Rank> {
+        self.split().1
+    }
+
+    /// The kicker this hand beats `other` with, when both make the same
+    /// hand and only a kicker tells them apart
+    pub fn kicker_over(&self, other: &PokerHandRank) -> Option<Rank> {
+        let (made, kickers) = self.split();
+        let (other_made, other_kickers) = other.split();
+        if self.category() != other.category() || made != other_made {
+            return None;
+        }
+        kickers
+            .into_iter()
+            .zip(other_kickers)
+            .find(|(ours, theirs)| ours != theirs)
+            .and_then(|(ours, theirs)| (ours > theirs).then_some(ours))
+    }
+
+    /// Ranks of the made hand and of its kickers
+    fn split(&self) -> (Vec<Rank>, Vec<Rank>) {
+        use PokerHandRank::*;
+        match *self {
+            HighCard(a, b, c, d, e) => (vec![a], vec![b, c, d, e]),
+            Pair(pair, a, b, c) => (vec![pair], vec![a, b, c]),
+            TwoPair(high, low, kicker) => (vec![high, low], vec![kicker]),
+            ThreeOfAKind(trips, a, b) => (vec![trips], vec![a, b]),
+            FourOfAKind(quads, kicker) => (vec![quads], vec![kicker]),
+            Straight(high) | StraightFlush(high) => (vec![high], vec![]),
+            Flush(a, b, c, d, e) => (vec![a, b, c, d, e], vec![]),
+            FullHouse(trips, pair) => (vec![trips, pair], vec![]),
+        }
+    }
+}
+
+///////////////////////////////////////////////
+/// HandValue Implementations
+///////////////////////////////////////////////
+impl HandValue {
+    /// Category of the hand, such as two pair
+    pub fn category(self) -> HandCategory {
+        HandCategory::ALL[(self.0 >> 20) as usize]
+    }
+
+    /// The hand this value was packed from
+    pub fn rank(self) -> PokerHandRank {
+        use PokerHandRank::*;
+        // Only the ranks the category uses are read, the rest are zero
+        let r = |i: u32| Rank::ALL[(self.0 >> (16 - 4 * i) & 0xf) as usize - 2];
+
+        match self.category() {
+            HandCategory::HighCard => HighCard(r(0), r(1), r(2), r(3), r(4)),
+            HandCategory::Pair => Pair(r(0), r(1), r(2), r(3)),
+            HandCategory::TwoPair => TwoPair(r(0), r(1), r(2)),
+            HandCategory::ThreeOfAKind => ThreeOfAKind(r(0), r(1), r(2)),
+            HandCategory::Straight => Straight(r(0)),
+            HandCategory::Flush => Flush(r(0), r(1), r(2), r(3), r(4)),
+            HandCategory::FullHouse => FullHouse(r(0), r(1)),
+            HandCategory::FourOfAKind => FourOfAKind(r(0), r(1)),
+            HandCategory::StraightFlush => StraightFlush(r(0)),
+        }
+    }
+}
+
+impl From<PokerHandRank> for HandValue {
+    fn from(hand: PokerHandRank) -> Self {
+        use PokerHandRank::*;
+        let ranks = match hand {
+            HighCard(a, b, c, d, e) | Flush(a, b, c, d, e) => [a, b, c, d, e].map(Some),
+            Pair(a, b, c, d) => [Some(a), Some(b), Some(c), Some(d), None],
+            TwoPair(a, b, c) | ThreeOfAKind(a, b, c) => [Some(a), Some(b), Some(c), None, None],
+            FullHouse(a, b) | FourOfAKind(a, b) => [Some(a), Some(b), None, None, None],
+            Straight(a) | StraightFlush(a) => [Some(a), None, None, None, None],
+        };
+
+        let category = hand.category() as u32;
+        HandValue(ranks.iter().fold(category, |value, rank| {
+            value << 4 | rank.map_or(0, |rank| rank.value())
+        }))
+    }
+}
+
+impl From<HandValue> for PokerHandRank {
+    fn from(value: HandValue) -> Self {
+        value.rank()
+    }
+}
+
+impl From<HandValue> for HandCategory {
+    fn from(value: HandValue) -> Self {
+        value.category()
+    }
+}
+
+/// Same as the description of the hand it was packed from
+impl Display for HandValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.rank())
+    }
+}
+
+/// Human readable description, such as "Full House, Aces full of Kings"
+impl Display for PokerHandRank {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        use PokerHandRank::*;
+        match *self {
+            HighCard(high, ..) => write!(f, "{} High", high.name()),
+            Pair(pair, ..) => write!(f, "Pair of {}", pair.plural()),
+            TwoPair(high, low, _) => {
+                write!(f, "Two Pair, {} and {}", high.plural(), low.plural())
             }
-            (TwoPair(a1, b1, c1), TwoPair(a2, b2, c2)) => a1 == a2 && b1 == b2 && c1 == c2,
-            (ThreeOfAKind(a1, b1, c1), ThreeOfAKind(a2, b2, c2)) => {
-                a1 == a2 && b1 == b2 && c1 == c2
+            ThreeOfAKind(trips, ..) => write!(f, "Three of a Kind, {}", trips.plural()),
+            Straight(high) => write!(f, "Straight, {} High", high.name()),
+            Flush(high, ..) => write!(f, "Flush, {} High", high.name()),
+            FullHouse(trips, pair) => {
+                write!(
+                    f,
+                    "Full House, {} full of {}",
+                    trips.plural(),
+                    pair.plural()
+                )
             }
-            (Straight(a1), Straight(a2)) => a1 == a2,
-            (Flush(a1, b1, c1, d1, e1), Flush(a2, b2, c2, d2, e2)) => {
-                a1 == a2 && b1 == b2 && c1 == c2 && d1 == d2 && e1 == e2
+            FourOfAKind(quads, _) => write!(f, "Four of a Kind, {}", quads.plural()),
+            StraightFlush(Rank::Ace) => write!(f, "Royal Flush"),
+            StraightFlush(high) => write!(f, "Straight Flush, {} High", high.name()),
+        }
+    }
+}
+
+///////////////////////////////////////////////
+/// LowHand Implementations
+///////////////////////////////////////////////
+impl LowHand {
+    /// The low made by five cards, None unless it is eight or better
+    pub fn from_cards(cards: [Card; 5]) -> Option<LowHand> {
+        let mut ranks = cards.map(|card| match card.rank {
+            Rank::Ace => 1,
+            rank => rank.value() as u8,
+        });
+        ranks.sort_unstable_by(|a, b| b.cmp(a));
+
+        let qualifies = ranks[0] <= 8 && ranks.windows(2).all(|w| w[0] > w[1]);
+        qualifies.then_some(LowHand(ranks))
+    }
+}
+
+/// Ranks from the highest down, such as "8-6-4-2-A"
+impl Display for LowHand {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&low_ranks(&self.0))
+    }
+}
+
+///////////////////////////////////////////////
+/// RazzHand Implementations
+///////////////////////////////////////////////
+impl RazzHand {
+    /// The low made by any five cards
+    pub fn from_cards(cards: [Card; 5]) -> RazzHand {
+        let mut groups: Vec<(u8, u8)> = vec![];
+        for card in cards {
+            let rank = match card.rank {
+                Rank::Ace => 1,
+                rank => rank.value() as u8,
+            };
+            match groups.iter_mut().find(|(_, r)| *r == rank) {
+                Some((count, _)) => *count += 1,
+                None => groups.push((1, rank)),
             }
-            (FullHouse(a1, b1), FullHouse(a2, b2)) => a1 == a2 && b1 == b2,
-            (FourOfAKind(a1, b1), FourOfAKind(a2, b2)) => a1 == a2 && b1 == b2,
-            (StraightFlush(a1), StraightFlush(b2)) => a1 == b2,
+        }
+        groups.sort_unstable_by(|a, b| b.cmp(a));
+
+        let mut hand = RazzHand {
+            counts: [0; 5],
+            ranks: [0; 5],
+        };
+        for (i, (count, rank)) in groups.into_iter().enumerate() {
+            hand.counts[i] = count;
+            hand.ranks[i] = rank;
+        }
+        hand
+    }
 
-            // If they arent the same type, they arent equal
-            _ => false
+    /// Pairs and trips the hand is stuck with, a high card when it has none
+    pub fn category(&self) -> HandCategory {
+        match self.counts {
+            [4, ..] => HandCategory::FourOfAKind,
+            [3, 2, ..] => HandCategory::FullHouse,
+            [3, ..] => HandCategory::ThreeOfAKind,
+            [2, 2, ..] => HandCategory::TwoPair,
+            [2, ..] => HandCategory::Pair,
+            _ => HandCategory::HighCard,
         }
     }
 }
 
-impl PartialOrd for PokerHandRank {
-    #[allow(unused_assignments)]
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        use std::cmp::Ordering;
+/// Ranks from the highest down, such as "8-6-4-2-A" or "5-5-3-2-A"
+impl Display for RazzHand {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut ranks = self
+            .counts
+            .iter()
+            .zip(self.ranks)
+            .flat_map(|(&count, rank)| std::iter::repeat_n(rank, count as usize))
+            .collect::<Vec<_>>();
+        ranks.sort_unstable_by(|a, b| b.cmp(a));
+        f.write_str(&low_ranks(&ranks))
+    }
+}
+
+///////////////////////////////////////////////
+/// DeuceSevenHand Implementations
+///////////////////////////////////////////////
+impl DeuceSevenHand {
+    /// The low made by five cards
+    pub fn from_cards(cards: [Card; 5]) -> DeuceSevenHand {
         use PokerHandRank::*;
+        use Rank::*;
 
-        // Convert the rank into a number for comparing
-        fn rank_to_value(rank: &PokerHandRank) -> u8 {
-            match rank {
-                HighCard(..) => 1,
-                Pair(..) => 2,
-                TwoPair(..) => 3,
-                ThreeOfAKind(..) => 4,
-                Straight(..) => 5,
-                Flush(..) => 6,
-                FullHouse(..) => 7,
-                FourOfAKind(..) => 8,
-                StraightFlush(..) => 9,
+        // The ace only plays high, so the wheel is ace high
+        DeuceSevenHand(match cards_to_hand(cards) {
+            Straight(Five) => HighCard(Ace, Five, Four, Three, Two),
+            StraightFlush(Five) => Flush(Ace, Five, Four, Three, Two),
+            hand => hand,
+        })
+    }
+
+    /// The hand as a high hand, such as a pair or a straight
+    pub fn rank(&self) -> PokerHandRank {
+        self.0
+    }
+}
+
+/// Ranks from the highest down, such as "7-5-4-3-2", or the high hand it
+/// makes when it is worse than a high card
+impl Display for DeuceSevenHand {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            PokerHandRank::HighCard(a, b, c, d, e) => {
+                let ranks = [a, b, c, d, e].map(|r| r.as_str());
+                f.write_str(&ranks.join("-"))
             }
+            hand => write!(f, "{}", hand),
         }
+    }
+}
 
-        let self_value = rank_to_value(self);
-        let other_value = rank_to_value(other);
-
-        if self_value < other_value {
-            Some(Ordering::Less)
-        } else if self_value > other_value {
-            Some(Ordering::Greater)
-        } else {
-            // If hand ranks are the same, comparison logic needed for each hand type
-            match (self, other) {
-                // Compare based on the highest card or cards in the hand
-                (HighCard(a1, b1, c1, d1, e1), HighCard(a2, b2, c2, d2, e2)) => {
-                    compare_ranks(&[a1, b1, c1, d1, e1], &[a2, b2, c2, d2, e2])
-                }
-                (Pair(a1, b1, c1, d1), Pair(a2, b2, c2, d2)) => {
-                    compare_ranks(&[a1, b1, c1, d1], &[a2, b2, c2, d2])
-                }
-                (TwoPair(a1, b1, c1), TwoPair(a2, b2, c2)) => {
-                    compare_ranks(&[a1, b1, c1], &[a2, b2, c2])
-                }
-                (ThreeOfAKind(a1, b1, c1), ThreeOfAKind(a2, b2, c2)) => {
-                    compare_ranks(&[a1, b1, c1], &[a2, b2, c2])
-                }
-                (Straight(r1), Straight(r2)) => r1.partial_cmp(r2),
-                (Flush(a1, b1, c1, d1, e1), Flush(a2, b2, c2, d2, e2)) => {
-                    compare_ranks(&[a1, b1, c1, d1, e1], &[a2, b2, c2, d2, e2])
-                }
-                (FullHouse(r1, t1), FullHouse(r2, t2)) => compare_ranks(&[r1, t1], &[r2, t2]),
-                (FourOfAKind(r1, k1), FourOfAKind(r2, k2)) => compare_ranks(&[r1, k1], &[r2, k2]),
-                (StraightFlush(r1), StraightFlush(r2)) => r1.partial_cmp(r2),
-                _ => unreachable!("All matches should be covered"),
-            }
+///////////////////////////////////////////////
+/// BadugiHand Implementations
+///////////////////////////////////////////////
+impl BadugiHand {
+    /// The hand made by one to four cards, None when two of them share
+    /// a rank or a suit
+    pub fn from_cards(cards: &[Card]) -> Option<BadugiHand> {
+        if cards.is_empty() || cards.len() > 4 {
+            return None;
+        }
+        let distinct = cards.iter().enumerate().all(|(i, a)| {
+            cards[i + 1..]
+                .iter()
+                .all(|b| a.rank != b.rank && a.suit != b.suit)
+        });
+        if !distinct {
+            return None;
         }
+
+        let mut ranks = [0; 4];
+        for (rank, card) in ranks.iter_mut().zip(cards) {
+            *rank = match card.rank {
+                Rank::Ace => 1,
+                rank => rank.value() as u8,
+            };
+        }
+        ranks.sort_unstable_by(|a, b| b.cmp(a));
+
+        Some(BadugiHand {
+            missing: 4 - cards.len() as u8,
+            ranks,
+        })
+    }
+
+    /// Number of cards that play, four for a badugi
+    pub fn size(&self) -> usize {
+        4 - self.missing as usize
+    }
+}
+
+/// Ranks from the highest down, such as "7-5-3-A"
+impl Display for BadugiHand {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&low_ranks(&self.ranks[..self.size()]))
+    }
+}
+
+/// Badugi has no made hands, so every hand counts as a high card
+impl From<BadugiHand> for HandCategory {
+    fn from(_: BadugiHand) -> Self {
+        HandCategory::HighCard
+    }
+}
+
+impl From<DeuceSevenHand> for HandCategory {
+    fn from(hand: DeuceSevenHand) -> Self {
+        hand.0.category()
+    }
+}
+
+impl From<RazzHand> for HandCategory {
+    fn from(hand: RazzHand) -> Self {
+        hand.category()
+    }
+}
+
+impl From<PokerHandRank> for HandCategory {
+    fn from(hand: PokerHandRank) -> Self {
+        hand.category()
     }
 }
 
-/// 
+/// Ace to five ranks joined with dashes, the ace written as "A"
+fn low_ranks(ranks: &[u8]) -> String {
+    const NAMES: [&str; 13] = [
+        "A", "2", "3", "4", "5", "6", "7", "8", "9", "T", "J", "Q", "K",
+    ];
+    ranks
+        .iter()
+        .map(|&rank| NAMES[rank as usize - 1])
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+///
 /// Important function that takes 5 cards and creates a poker hand out of it
-/// 
+///
 pub fn cards_to_hand(cards: [Card; 5]) -> PokerHandRank {
     // sort the cards
     let mut cards = cards;
@@ -128,13 +523,12 @@ pub fn cards_to_hand(cards: [Card; 5]) -> PokerHandRank {
     // Reverse the cards so the highest card is first
     cards.reverse();
 
+    let straight = straight_high_card(&cards);
+    let flush = cards.iter().all(|card| card.suit == cards[0].suit);
+
     // Check for straight flush
-    if cards
-        .windows(2)
-        .all(|pair| pair[1].rank.next() == pair[0].rank)
-        && cards.iter().all(|card| card.suit == cards[0].suit)
-    {
-        return PokerHandRank::StraightFlush(cards[0].rank);
+    if let (Some(high), true) = (straight, flush) {
+        return PokerHandRank::StraightFlush(high);
     }
 
     // Check for four of a kind
@@ -169,7 +563,7 @@ pub fn cards_to_hand(cards: [Card; 5]) -> PokerHandRank {
     }
 
     // Check for flush
-    if cards.iter().all(|card| card.suit == cards[0].suit) {
+    if flush {
         return PokerHandRank::Flush(
             cards[0].rank,
             cards[1].rank,
@@ -180,11 +574,8 @@ pub fn cards_to_hand(cards: [Card; 5]) -> PokerHandRank {
     }
 
     // Check for striaght
-    if cards
-        .windows(2)
-        .all(|pair| pair[1].rank.next() == pair[0].rank)
-    {
-        return PokerHandRank::Straight(cards[0].rank);
+    if let Some(high) = straight {
+        return PokerHandRank::Straight(high);
     }
 
     // Check for three of a kind
@@ -236,24 +627,24 @@ pub fn cards_to_hand(cards: [Card; 5]) -> PokerHandRank {
     )
 }
 
-/// Function for comparing two lists of numbers for determining which hand is greater
-/// Compares first two numbers. If they are the same,
-/// continues comparing the next two numbers
-fn compare_ranks(n_list: &[&Rank], m_list: &[&Rank]) -> Option<std::cmp::Ordering> {
-    let mut n_sum: i32 = 0;
-    let mut n_multiplier = 15 * 5;
-
-    for n in n_list.into_iter() {
-        n_sum += **n as i32 * n_multiplier;
-        n_multiplier /= 15;
+/// High card of the straight if the cards (sorted highest first) make one
+/// The wheel A-2-3-4-5 plays the ace low, so its high card is the five
+fn straight_high_card(cards: &[Card; 5]) -> Option<Rank> {
+    if cards
+        .windows(2)
+        .all(|pair| pair[1].rank.next() == pair[0].rank)
+    {
+        return Some(cards[0].rank);
     }
 
-    let mut m_sum: i32 = 0;
-    let mut m_multiplier = 15 * 5;
-    for m in m_list.into_iter() {
-        m_sum += **m as i32 * m_multiplier;
-        m_multiplier /= 15;
+    let wheel = [Rank::Ace, Rank::Five, Rank::Four, Rank::Three, Rank::Two];
+    if cards
+        .iter()
+        .zip(wheel)
+        .all(|(card, rank)| card.rank == rank)
+    {
+        return Some(Rank::Five);
     }
 
-    n_sum.partial_cmp(&m_sum)
+    None
 }
@@ -1,123 +1,396 @@
-use crate::card::{Card, Rank, Suit};
-use crate::poker_hand::{cards_to_hand, PokerHandRank};
-
-
-// Get all possible combinations of 5 cards in 7
-pub fn get_combinations(cards: [Card; 7]) -> Vec<[Card; 5]> {
-    let mut combinations = Vec::new();
-
-    for i in 0..3 {
-        for j in i + 1..4 {
-            for k in j + 1..5 {
-                for l in k + 1..6 {
-                    for m in l + 1..7 {
-                        combinations.push([cards[i], cards[j], cards[k], cards[l], cards[m]]);
-                    }
-                }
+use crate::card::{Card, Rank};
+use crate::evaluator::{Evaluator, EvaluatorKind};
+use crate::game::Game;
+use crate::poker_hand::{
+    cards_to_hand, BadugiHand, DeuceSevenHand, HandValue, LowHand, PokerHandRank, RazzHand,
+};
+
+/// Every combination of 5 cards from the given cards, without allocating
+pub fn get_combinations(cards: &[Card]) -> Combinations<'_, Card, 5> {
+    combinations(cards)
+}
+
+/// Iterator over every way of choosing `K` of the items, in order
+/// Each combination is an array, so nothing is allocated as it runs
+#[derive(Debug, Clone)]
+pub struct Combinations<'a, T, const K: usize> {
+    items: &'a [T],
+    indices: [usize; K],
+    remaining: usize,
+}
+
+/// Every combination of `K` of the items, such as the 3 board cards an
+/// Omaha hand plays with `combinations::<_, 3>(&board)`
+pub fn combinations<T: Copy, const K: usize>(items: &[T]) -> Combinations<'_, T, K> {
+    Combinations {
+        items,
+        indices: std::array::from_fn(|i| i),
+        remaining: binomial(items.len(), K),
+    }
+}
+
+/// Number of ways of choosing `k` of `n` things
+pub(crate) fn binomial(n: usize, k: usize) -> usize {
+    if k > n {
+        return 0;
+    }
+    (0..k).fold(1, |count, i| count * (n - i) / (i + 1))
+}
+
+// Rank all possibles hands and return the best one
+// Works with five, six or seven cards
+pub fn get_best_hand(cards: &[Card]) -> PokerHandRank {
+    #[cfg(feature = "fast-eval")]
+    return crate::lookup::evaluate(cards);
+
+    #[cfg(not(feature = "fast-eval"))]
+    get_best_five_cards(cards).1
+}
+
+/// The five cards making the best hand, along with its rank
+/// Panics with fewer than five cards
+pub fn get_best_five_cards(cards: &[Card]) -> ([Card; 5], PokerHandRank) {
+    let mut best = None;
+
+    for hand in get_combinations(cards) {
+        let rank = cards_to_hand(hand);
+        if best.is_none_or(|(_, best)| rank > best) {
+            best = Some((hand, rank));
+        }
+    }
+
+    best.expect("a hand needs at least five cards")
+}
+
+/// The five cards making the best hand in the order the hand is read,
+/// along with its rank, so the cards that play can be shown or highlighted
+/// See `in_hand_order` for the order
+pub fn get_best_hand_cards(cards: &[Card]) -> ([Card; 5], PokerHandRank) {
+    let (best, rank) = get_best_five_cards(cards);
+    (in_hand_order(best, rank), rank)
+}
+
+/// Five cards in the order their hand is read, the cards of the quads,
+/// trips or pairs first, bigger groups before smaller ones, and then the
+/// kickers, highest first
+/// A wheel is read down from the five, so its ace comes last
+pub fn in_hand_order(cards: [Card; 5], rank: PokerHandRank) -> [Card; 5] {
+    let count = |rank: Rank| cards.iter().filter(|card| card.rank == rank).count();
+    let mut ordered = cards.map(|card| (count(card.rank), card));
+    ordered.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.rank.cmp(&a.1.rank)));
+    let mut cards = ordered.map(|(_, card)| card);
+
+    if let PokerHandRank::Straight(Rank::Five) | PokerHandRank::StraightFlush(Rank::Five) = rank {
+        cards.rotate_left(1);
+    }
+    cards
+}
+
+/// Best Omaha hand, which must use exactly two hole cards and three board cards
+pub fn get_best_omaha_hand(hole: &[Card], board: &[Card]) -> PokerHandRank {
+    let mut best = None;
+
+    for [a, b] in combinations(hole) {
+        for [c, d, e] in combinations(board) {
+            best = best.max(Some(cards_to_hand([a, b, c, d, e])));
+        }
+    }
+
+    best.expect("Omaha hands need at least two hole cards")
+}
+
+/// The five cards making the best Omaha hand, two from the hole cards and
+/// three from the board, along with its rank
+pub fn get_best_omaha_five_cards(hole: &[Card], board: &[Card]) -> ([Card; 5], PokerHandRank) {
+    let mut best = None;
+
+    for [a, b] in combinations(hole) {
+        for [c, d, e] in combinations(board) {
+            let hand = [a, b, c, d, e];
+            let rank = cards_to_hand(hand);
+            if best.is_none_or(|(_, best)| rank > best) {
+                best = Some((hand, rank));
             }
         }
     }
 
-    combinations
+    best.expect("Omaha hands need at least two hole cards")
 }
 
-// Rank all possibles hands and return the best one
-pub fn get_best_hand(cards: [Card; 7]) -> PokerHandRank {
-    get_combinations(cards)
-        .iter()
-        .map(|hand| cards_to_hand(*hand))
-        .max()
-        .unwrap()
+/// Best hand out of at most two hole cards and any number of board cards,
+/// so a pineapple hand that still holds three plays its best two
+pub fn get_best_pineapple_hand(hole: &[Card], board: &[Card]) -> PokerHandRank {
+    let mut cards = Vec::with_capacity(2 + board.len());
+    let mut best = None;
+
+    for two in combinations::<_, 2>(hole) {
+        cards.clear();
+        cards.extend(two);
+        cards.extend_from_slice(board);
+        best = best.max(Some(get_best_hand(&cards)));
+    }
+
+    best.expect("Pineapple hands need at least two hole cards")
 }
 
-// Create a deck of 52 cards
-pub fn new_deck() -> Vec<Card> {
-    use Rank::*;
-    use Suit::*;
-    let mut deck = Vec::new();
-    for &rank in &[
-        Two, Three, Four, Five, Six, Seven, Eight, Nine, Ten, Jack, Queen, King, Ace,
-    ] {
-        for &suit in &[Spades, Hearts, Clubs, Diamonds] {
-            deck.push(Card::new(rank, suit));
+/// The five cards making the best pineapple hand, along with its rank
+pub fn get_best_pineapple_five_cards(hole: &[Card], board: &[Card]) -> ([Card; 5], PokerHandRank) {
+    let mut cards = Vec::with_capacity(2 + board.len());
+    let mut best = None;
+
+    for two in combinations::<_, 2>(hole) {
+        cards.clear();
+        cards.extend(two);
+        cards.extend_from_slice(board);
+        let (hand, rank) = get_best_five_cards(&cards);
+        if best.is_none_or(|(_, best)| rank > best) {
+            best = Some((hand, rank));
         }
     }
-    deck
+
+    best.expect("Pineapple hands need at least two hole cards")
 }
 
-// Shuffle the deck in-place
-pub fn shuffle_deck(deck: &mut Vec<Card>) {
-    use rand::seq::SliceRandom;
-    use rand::thread_rng;
-    deck.shuffle(&mut thread_rng());
+/// Best eight or better low in Omaha, using exactly two hole cards and three
+/// from the board, None when no low qualifies
+pub fn get_best_omaha_low(hole: &[Card], board: &[Card]) -> Option<LowHand> {
+    let mut best: Option<LowHand> = None;
+
+    for [a, b] in combinations(hole) {
+        for [c, d, e] in combinations(board) {
+            if let Some(low) = LowHand::from_cards([a, b, c, d, e]) {
+                best = Some(best.map_or(low, |best| best.min(low)));
+            }
+        }
+    }
+
+    best
+}
+
+/// Best razz low out of five, six or seven cards
+pub fn get_best_razz_hand(cards: &[Card]) -> RazzHand {
+    let mut best: Option<RazzHand> = None;
+    for_each_combination(cards, 5, |hand| {
+        let low = RazzHand::from_cards([hand[0], hand[1], hand[2], hand[3], hand[4]]);
+        best = Some(best.map_or(low, |best| best.min(low)));
+    });
+
+    best.expect("a razz hand needs at least five cards")
+}
+
+/// Best deuce to seven low out of five or more cards
+pub fn get_best_deuce_seven_hand(cards: &[Card]) -> DeuceSevenHand {
+    let mut best: Option<DeuceSevenHand> = None;
+    for_each_combination(cards, 5, |hand| {
+        let low = DeuceSevenHand::from_cards([hand[0], hand[1], hand[2], hand[3], hand[4]]);
+        best = Some(best.map_or(low, |best| best.min(low)));
+    });
+
+    best.expect("a deuce to seven hand needs at least five cards")
+}
+
+/// Best badugi out of any number of cards, using up to four of them
+pub fn get_best_badugi_hand(cards: &[Card]) -> BadugiHand {
+    let mut best: Option<BadugiHand> = None;
+    for size in 1..=cards.len().min(4) {
+        for_each_combination(cards, size, |hand| {
+            if let Some(hand) = BadugiHand::from_cards(hand) {
+                best = Some(best.map_or(hand, |best| best.min(hand)));
+            }
+        });
+    }
+
+    best.expect("a badugi hand needs at least one card")
 }
 
 // Determine the winner of the game
 // based on multiple hands and a community board
-pub fn determine_winner(
-    hands: Vec<[Card; 2]>,
+pub fn determine_winner<H: AsRef<[Card]>>(
+    game: Game,
+    hands: &[H],
+    community: [Card; 5],
+) -> (Vec<usize>, Vec<HandValue>) {
+    let mut best_hands = Vec::with_capacity(hands.len());
+    let mut winners = Vec::new();
+    let evaluator = EvaluatorKind::default().evaluator();
+    let hands = hands.iter().map(|hand| hand.as_ref()).collect::<Vec<_>>();
+    determine_winner_into(
+        game,
+        &hands,
+        community,
+        evaluator,
+        &mut best_hands,
+        &mut winners,
+    );
+    (winners, best_hands)
+}
+
+/// Same as `determine_winner` but writing into buffers that can be reused
+/// `best_hands` gets each player's best hand and `winners` the winning players
+/// Hands are ranked with `evaluator`
+pub fn determine_winner_into(
+    game: Game,
+    hands: &[&[Card]],
     community: [Card; 5],
-) -> (Vec<usize>, Vec<PokerHandRank>) {
-    // Get the best hands for each hand
-    let best_hands = hands
-        .iter()
-        .map(|hand| {
-            get_best_hand([
-                hand[0],
-                hand[1],
-                community[0],
-                community[1],
-                community[2],
-                community[3],
-                community[4],
-            ])
-        })
-        .collect::<Vec<_>>();
+    evaluator: &dyn Evaluator,
+    best_hands: &mut Vec<HandValue>,
+    winners: &mut Vec<usize>,
+) {
+    // Get the best hands for each hand, packed so they compare as integers
+    best_hands.clear();
+    game.rules()
+        .best_hands(hands, &community, evaluator, best_hands);
 
     // Get the hightest ranking hand of the best hands
-    let winning_hand = best_hands.iter().max().unwrap();
+    let winning_hand = *best_hands.iter().max().unwrap();
 
     // Find the players that match the winning hand
     // (Allows for ties)
-    (
+    winners.clear();
+    winners.extend(
         best_hands
             .iter()
             .enumerate()
-            .filter_map(
-                |(i, hand)| {
-                    if hand == winning_hand {
-                        Some(i)
-                    } else {
-                        None
-                    }
-                },
-            )
-            .collect(),
-        best_hands,
-    )
+            .filter(|(_, hand)| **hand == winning_hand)
+            .map(|(i, _)| i),
+    );
 }
 
-/// Get a deck of cards but remove the given cards
-pub fn deck_without_cards(deck: Vec<Card>, cards: Vec<Card>) -> Vec<Card> {
-    let mut deck = deck;
-    for card in cards {
-        deck.retain(|c| *c != card);
+/// Players sharing the low half of the pot in a split pot game
+/// Empty when nobody has a qualifying low or the game has no low
+pub fn determine_low_winners<H: AsRef<[Card]>>(
+    game: Game,
+    hands: &[H],
+    community: [Card; 5],
+) -> Vec<usize> {
+    let mut lows = Vec::with_capacity(hands.len());
+    let mut winners = Vec::new();
+    determine_low_winners_into(game, hands, community, &mut lows, &mut winners);
+    winners
+}
+
+/// Same as `determine_low_winners` but writing into buffers that can be reused
+/// `lows` gets each player's best low and `winners` the players sharing the low
+pub fn determine_low_winners_into<H: AsRef<[Card]>>(
+    game: Game,
+    hands: &[H],
+    community: [Card; 5],
+    lows: &mut Vec<Option<LowHand>>,
+    winners: &mut Vec<usize>,
+) {
+    lows.clear();
+    lows.extend(
+        hands
+            .iter()
+            .map(|hand| game.best_low(hand.as_ref(), community)),
+    );
+
+    // The lowest qualifying low wins
+    winners.clear();
+    let Some(best) = lows.iter().flatten().min().copied() else {
+        return;
+    };
+    winners.extend(
+        lows.iter()
+            .enumerate()
+            .filter(|(_, low)| **low == Some(best))
+            .map(|(i, _)| i),
+    );
+}
+
+/// Call `f` with every combination of `k` cards from `cards`
+/// Combinations are visited in order without allocating for each one
+pub fn for_each_combination<F: FnMut(&[Card])>(cards: &[Card], k: usize, mut f: F) {
+    fn recurse<F: FnMut(&[Card])>(
+        cards: &[Card],
+        k: usize,
+        start: usize,
+        chosen: &mut Vec<Card>,
+        f: &mut F,
+    ) {
+        if chosen.len() == k {
+            f(chosen);
+            return;
+        }
+
+        // Leave enough cards to fill the rest of the combination
+        let remaining = k - chosen.len();
+        for i in start..=cards.len().saturating_sub(remaining) {
+            chosen.push(cards[i]);
+            recurse(cards, k, i + 1, chosen, f);
+            chosen.pop();
+        }
+    }
+
+    if k > cards.len() {
+        return;
     }
-    deck
+
+    recurse(cards, k, 0, &mut Vec::with_capacity(k), &mut f);
 }
 
+///////////////////////////////////////////////
+/// Combinations Implementations
+///////////////////////////////////////////////
+impl<T: Copy, const K: usize> Iterator for Combinations<'_, T, K> {
+    type Item = [T; K];
+
+    fn next(&mut self) -> Option<[T; K]> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let combination = self.indices.map(|i| self.items[i]);
+
+        // Move the last index that can still move up by one and restart
+        // every index after it just behind it
+        let n = self.items.len();
+        if let Some(i) = (0..K).rev().find(|&i| self.indices[i] < n - K + i) {
+            self.indices[i] += 1;
+            for j in i + 1..K {
+                self.indices[j] = self.indices[j - 1] + 1;
+            }
+        }
+
+        Some(combination)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T: Copy, const K: usize> ExactSizeIterator for Combinations<'_, T, K> {}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::card::{cards_from_str, Rank, Suit};
+    use crate::deck::Deck;
+    use crate::poker_hand::HandCategory;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
     use PokerHandRank::*;
     use Rank::*;
     use Suit::*;
 
+    #[test]
+    fn test_compare_every_kicker() {
+        // The last kicker decides between otherwise equal hands
+        assert!(HighCard(Ace, King, Queen, Jack, Nine) > HighCard(Ace, King, Queen, Jack, Eight));
+        assert!(Pair(Two, Ace, King, Four) > Pair(Two, Ace, King, Three));
+        assert!(Flush(Ace, King, Queen, Jack, Nine) > Flush(Ace, King, Queen, Jack, Eight));
+        assert_ne!(
+            HighCard(Ace, King, Queen, Jack, Nine),
+            HighCard(Ace, King, Queen, Jack, Eight)
+        );
+    }
+
     #[test]
     fn test_get_best_hand() {
         // Test high card
         assert_eq!(
-            get_best_hand([
+            get_best_hand(&[
                 Card::new(Ace, Spades),
                 Card::new(Queen, Clubs),
                 Card::new(Nine, Hearts),
@@ -130,7 +403,7 @@ mod test {
         );
         // Test pair
         assert_eq!(
-            get_best_hand([
+            get_best_hand(&[
                 Card::new(Ace, Spades),
                 Card::new(Queen, Clubs),
                 Card::new(Nine, Hearts),
@@ -143,7 +416,7 @@ mod test {
         );
         // Test two pair
         assert_eq!(
-            get_best_hand([
+            get_best_hand(&[
                 Card::new(Ace, Spades),
                 Card::new(Queen, Clubs),
                 Card::new(Nine, Hearts),
@@ -156,7 +429,7 @@ mod test {
         );
         // Test three of a kind
         assert_eq!(
-            get_best_hand([
+            get_best_hand(&[
                 Card::new(Ace, Spades),
                 Card::new(Queen, Clubs),
                 Card::new(Nine, Hearts),
@@ -169,7 +442,7 @@ mod test {
         );
         // Test straight
         assert_eq!(
-            get_best_hand([
+            get_best_hand(&[
                 Card::new(Ace, Hearts),
                 Card::new(King, Hearts),
                 Card::new(Queen, Clubs),
@@ -182,7 +455,7 @@ mod test {
         );
         // Test flush
         assert_eq!(
-            get_best_hand([
+            get_best_hand(&[
                 Card::new(Ace, Spades),
                 Card::new(Queen, Spades),
                 Card::new(Nine, Spades),
@@ -195,7 +468,7 @@ mod test {
         );
         // Test Full House
         assert_eq!(
-            get_best_hand([
+            get_best_hand(&[
                 Card::new(Ace, Spades),
                 Card::new(Ace, Clubs),
                 Card::new(Ace, Hearts),
@@ -209,7 +482,7 @@ mod test {
 
         // Test Four of a kind
         assert_eq!(
-            get_best_hand([
+            get_best_hand(&[
                 Card::new(Ace, Spades),
                 Card::new(Ace, Clubs),
                 Card::new(Ace, Hearts),
@@ -223,7 +496,7 @@ mod test {
 
         // Test Straight Flush
         assert_eq!(
-            get_best_hand([
+            get_best_hand(&[
                 Card::new(Ace, Spades),
                 Card::new(King, Spades),
                 Card::new(Queen, Spades),
@@ -236,6 +509,273 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_five_and_six_cards() {
+        let five = [
+            Card::new(Ace, Spades),
+            Card::new(Ace, Clubs),
+            Card::new(King, Hearts),
+            Card::new(King, Spades),
+            Card::new(Seven, Spades),
+        ];
+        assert_eq!(get_best_hand(&five), TwoPair(Ace, King, Seven));
+
+        let six = [
+            five[0],
+            five[1],
+            five[2],
+            five[3],
+            five[4],
+            Card::new(Ace, Hearts),
+        ];
+        let (cards, rank) = get_best_five_cards(&six);
+        assert_eq!(rank, FullHouse(Ace, King));
+        assert!(!cards.contains(&five[4]));
+    }
+
+    #[test]
+    fn test_best_hand_cards() {
+        let best = |cards: &str| {
+            let (cards, rank) = get_best_hand_cards(&cards_from_str(cards).unwrap());
+            (cards.map(|card| card.to_string()).concat(), rank)
+        };
+
+        // The trips come before the pair, and the two unused cards are left out
+        let (cards, rank) = best("7s2dAhAc7d7hKs");
+        assert_eq!(rank, FullHouse(Seven, Ace));
+        assert_eq!(cards, "7s7d7hAhAc");
+
+        // Pairs from the highest, then the kicker
+        let (cards, rank) = best("9c4h4s9dQh2c3d");
+        assert_eq!(rank, TwoPair(Nine, Four, Queen));
+        assert_eq!(cards, "9c9d4h4sQh");
+
+        // A wheel is read down from the five
+        let (cards, rank) = best("Ah2c3d4s5hKdKc");
+        assert_eq!(rank, Straight(Five));
+        assert_eq!(cards, "5h4s3d2cAh");
+
+        let (cards, rank) = best("KhQhJhThAh");
+        assert_eq!(rank, StraightFlush(Ace));
+        assert_eq!(cards, "AhKhQhJhTh");
+    }
+
+    #[test]
+    fn test_kickers() {
+        assert_eq!(Pair(Ace, King, Nine, Four).kickers(), [King, Nine, Four]);
+        assert!(FullHouse(Seven, Ace).kickers().is_empty());
+
+        // The same pair is decided by the first kicker that differs
+        let queen = Pair(Ace, Queen, Nine, Four);
+        let jack = Pair(Ace, Jack, Ten, Four);
+        assert_eq!(queen.kicker_over(&jack), Some(Queen));
+        assert_eq!(jack.kicker_over(&queen), None);
+        assert_eq!(queen.kicker_over(&queen), None);
+        let nine = Pair(Ace, Queen, Nine, Four);
+        let eight = Pair(Ace, Queen, Eight, Seven);
+        assert_eq!(nine.kicker_over(&eight), Some(Nine));
+
+        // A bigger pair or a higher flush card is not a kicker
+        assert_eq!(
+            Pair(Ace, Two, Three, Four).kicker_over(&Pair(King, Ace, Queen, Jack)),
+            None
+        );
+        assert_eq!(
+            Flush(Ace, King, Nine, Four, Two).kicker_over(&Flush(Ace, Queen, Nine, Four, Two)),
+            None
+        );
+        assert_eq!(
+            HighCard(Ace, King, Nine, Four, Two)
+                .kicker_over(&HighCard(Ace, Queen, Nine, Four, Two)),
+            Some(King)
+        );
+    }
+
+    #[test]
+    fn test_omaha_lows() {
+        let low = |hole: &str, board: &str| {
+            let board = cards_from_str(board).unwrap();
+            get_best_omaha_low(&cards_from_str(hole).unwrap(), &board).map(|l| l.to_string())
+        };
+
+        assert_eq!(low("Ah2sKdKc", "3c4d5hQsJs").as_deref(), Some("5-4-3-2-A"));
+        assert_eq!(low("8s7sKdKc", "2c3d4hKsQh").as_deref(), Some("8-7-4-3-2"));
+        // Exactly two hole cards play, so the 3 and 4 can not both be used
+        assert_eq!(low("Ah2s3s4s", "5c6d8hKsQh").as_deref(), Some("8-6-5-2-A"));
+        // Only two low cards on the board
+        assert_eq!(low("Ah2sKdKc", "3c9dThQsJs"), None);
+        // A pair in the hand can not both play
+        assert_eq!(low("AhAcKdKs", "2c3d4hQsJs"), None);
+        // Nine high is not a low
+        assert_eq!(low("9s7sKdKc", "2c3d4hKsQh"), None);
+
+        let hands = ["Ah2sKdKc", "As2cQdQc", "8s7sJdJc"].map(|h| cards_from_str(h).unwrap());
+        let board = cards_from_str("3c4d5hKsQh").unwrap().try_into().unwrap();
+        assert_eq!(determine_low_winners(Game::Omaha8, &hands, board), [0, 1]);
+        assert!(determine_low_winners(Game::Omaha, &hands, board).is_empty());
+    }
+
+    #[test]
+    fn test_razz_lows() {
+        let razz = |cards: &str| get_best_razz_hand(&cards_from_str(cards).unwrap());
+
+        // Straights and flushes do not count
+        assert_eq!(razz("As2s3s4s5sKsKd").to_string(), "5-4-3-2-A");
+        assert_eq!(razz("9h7h6h4h2hKcQd").to_string(), "9-7-6-4-2");
+        // Pairs play only when there are not five different ranks
+        assert_eq!(razz("AhAd2c2s3h3dKc").to_string(), "K-3-2-A-A");
+        assert_eq!(razz("AhAd2c2s3h3dKc").category(), HandCategory::Pair);
+
+        // Any unpaired hand beats a pair, and lower pairs beat higher ones
+        let order = [
+            "5-4-3-2-A",
+            "6-4-3-2-A",
+            "6-5-4-3-2",
+            "K-Q-J-T-9",
+            "5-5-3-2-A",
+            "6-6-3-2-A",
+        ];
+        let hands = [
+            "5h4d3c2sAh",
+            "6h4d3c2sAh",
+            "6h5d4c3s2h",
+            "KhQdJcTs9h",
+            "5h5d3c2sAh",
+            "6h6d3c2sAh",
+        ]
+        .map(razz);
+        assert!(hands.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(hands.map(|h| h.to_string()), order);
+    }
+
+    #[test]
+    fn test_deuce_seven_lows() {
+        let low = |cards: &str| get_best_deuce_seven_hand(&cards_from_str(cards).unwrap());
+
+        assert_eq!(low("7h5d4c3s2h").to_string(), "7-5-4-3-2");
+        // The wheel is ace high, and straights and flushes count
+        assert_eq!(low("Ah5d4c3s2h").to_string(), "A-5-4-3-2");
+        assert_eq!(low("6h5d4c3s2h").to_string(), "Straight, Six High");
+        assert_eq!(low("7h5h4h3h2h").rank().category(), HandCategory::Flush);
+
+        let order = [
+            "7h5d4c3s2h",
+            "8h6d4c3s2h",
+            "KhQdJc9s8h",
+            "Ah5d4c3s2h",
+            "2h2d4c3s7h",
+        ];
+        let hands = order.map(low);
+        assert!(hands.windows(2).all(|w| w[0] < w[1]));
+        assert!(low("6h5d4c3s2h") > hands[4]);
+    }
+
+    #[test]
+    fn test_pineapple_plays_two_hole_cards() {
+        let board = cards_from_str("Kh8d3c2s9h").unwrap();
+        let kings = get_best_pineapple_hand(&cards_from_str("KsKdKc").unwrap(), &board);
+        assert_eq!(kings, get_best_hand(&cards_from_str("KsKdKh8d9h").unwrap()));
+
+        // A discarded hand is played like holdem
+        let aces = get_best_pineapple_hand(&cards_from_str("AhAd").unwrap(), &board);
+        assert!(aces < kings);
+    }
+
+    #[test]
+    fn test_badugi_hands() {
+        let badugi = |cards: &str| get_best_badugi_hand(&cards_from_str(cards).unwrap());
+
+        assert_eq!(badugi("Ah2c3d4s").to_string(), "4-3-2-A");
+        // Two hearts, the best three card hand keeps the ace
+        assert_eq!(badugi("Ah2h3d4s").to_string(), "4-3-A");
+        assert_eq!(badugi("Ah2h3d4s").size(), 3);
+        // Paired and suited down to two cards
+        assert_eq!(badugi("Kh2hKd2d").to_string(), "K-2");
+        assert_eq!(badugi("5s6s7s8s").to_string(), "5");
+
+        let order = [
+            "Ah2c3d4s", "KhQcJdTs", "Ah2h3d4s", "KhQhJdTs", "Kh2hKd2d", "5s6s7s8s",
+        ];
+        let hands = order.map(badugi);
+        assert!(hands.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_hand_descriptions() {
+        assert_eq!(StraightFlush(Ace).to_string(), "Royal Flush");
+        assert_eq!(StraightFlush(Five).to_string(), "Straight Flush, Five High");
+        assert_eq!(
+            FullHouse(Six, King).to_string(),
+            "Full House, Sixes full of Kings"
+        );
+        assert_eq!(
+            TwoPair(Ace, Two, Nine).to_string(),
+            "Two Pair, Aces and Twos"
+        );
+        assert_eq!(
+            HighCard(Queen, Jack, Nine, Four, Two).to_string(),
+            "Queen High"
+        );
+        assert_eq!(
+            ThreeOfAKind(Ten, Ace, King).category().to_string(),
+            "Three of a Kind"
+        );
+    }
+
+    #[test]
+    fn test_wheel_straights() {
+        let wheel = cards_to_hand([
+            Card::new(Ace, Spades),
+            Card::new(Two, Clubs),
+            Card::new(Three, Hearts),
+            Card::new(Four, Clubs),
+            Card::new(Five, Spades),
+        ]);
+        assert_eq!(wheel, Straight(Five));
+
+        let steel_wheel = cards_to_hand([
+            Card::new(Ace, Hearts),
+            Card::new(Two, Hearts),
+            Card::new(Three, Hearts),
+            Card::new(Four, Hearts),
+            Card::new(Five, Hearts),
+        ]);
+        assert_eq!(steel_wheel, StraightFlush(Five));
+
+        let king_queen_high = cards_to_hand([
+            Card::new(King, Spades),
+            Card::new(Queen, Clubs),
+            Card::new(Three, Hearts),
+            Card::new(Four, Clubs),
+            Card::new(Five, Spades),
+        ]);
+        let six_high = cards_to_hand([
+            Card::new(Two, Spades),
+            Card::new(Three, Clubs),
+            Card::new(Four, Hearts),
+            Card::new(Five, Clubs),
+            Card::new(Six, Spades),
+        ]);
+        assert!(wheel > king_queen_high);
+        assert!(wheel < six_high);
+        assert!(steel_wheel > FourOfAKind(Ace, King));
+        assert!(steel_wheel < StraightFlush(Six));
+
+        // The wheel is found among seven cards
+        assert_eq!(
+            get_best_hand(&[
+                Card::new(Ace, Spades),
+                Card::new(King, Clubs),
+                Card::new(Two, Hearts),
+                Card::new(Three, Clubs),
+                Card::new(Four, Spades),
+                Card::new(Five, Diamonds),
+                Card::new(Nine, Hearts),
+            ]),
+            Straight(Five)
+        );
+    }
+
     #[test]
     fn test_hand_order() {
         // Make 20 hands to test compairing
@@ -279,6 +819,30 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_hand_values() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut deck = Deck::new();
+
+        let mut hands = Vec::new();
+        for _ in 0..2_000 {
+            deck.shuffle(&mut rng);
+            hands.push(get_best_hand(&deck.remaining()[..7]));
+        }
+        hands.extend([
+            Straight(Five),
+            StraightFlush(Ace),
+            HighCard(Seven, Five, Four, Three, Two),
+        ]);
+
+        for (hand, other) in hands.iter().zip(hands.iter().rev()) {
+            let value = HandValue::from(*hand);
+            assert_eq!(value.rank(), *hand);
+            assert_eq!(value.category(), hand.category());
+            assert_eq!(value.cmp(&HandValue::from(*other)), hand.cmp(other));
+        }
+    }
+
     #[test]
     fn test_get_combinations() {
         let cards = [
@@ -290,7 +854,96 @@ mod test {
             Card::new(Nine, Spades),
             Card::new(Eight, Spades),
         ];
-        let combinations = get_combinations(cards);
-        assert_eq!(combinations.len(), 21);
+        assert_eq!(get_combinations(&cards).len(), 21);
+        assert_eq!(get_combinations(&cards[..6]).len(), 6);
+        assert_eq!(get_combinations(&cards[..5]).len(), 1);
+    }
+
+    #[test]
+    fn test_omaha_uses_two_hole_cards() {
+        let board = [
+            Card::new(Ace, Spades),
+            Card::new(King, Spades),
+            Card::new(Queen, Spades),
+            Card::new(Seven, Hearts),
+            Card::new(Two, Clubs),
+        ];
+
+        // A single spade cannot make a flush in Omaha
+        let hole = [
+            Card::new(Jack, Spades),
+            Card::new(Jack, Hearts),
+            Card::new(Three, Diamonds),
+            Card::new(Four, Clubs),
+        ];
+        assert_eq!(
+            get_best_omaha_hand(&hole, &board),
+            Pair(Jack, Ace, King, Queen)
+        );
+
+        // Two spades can
+        let hole = [
+            Card::new(Jack, Spades),
+            Card::new(Ten, Spades),
+            Card::new(Three, Diamonds),
+            Card::new(Four, Clubs),
+        ];
+        assert_eq!(get_best_omaha_hand(&hole, &board), StraightFlush(Ace));
+
+        // Four of a kind on the board only plays three
+        let board = [
+            Card::new(Nine, Spades),
+            Card::new(Nine, Hearts),
+            Card::new(Nine, Clubs),
+            Card::new(Nine, Diamonds),
+            Card::new(Two, Clubs),
+        ];
+        let hole = [
+            Card::new(Ace, Spades),
+            Card::new(King, Hearts),
+            Card::new(Three, Diamonds),
+            Card::new(Four, Clubs),
+        ];
+        assert_eq!(
+            get_best_omaha_hand(&hole, &board),
+            ThreeOfAKind(Nine, Ace, King)
+        );
+    }
+
+    #[test]
+    fn test_combinations() {
+        let deck = Vec::from(Deck::new());
+        let mut count = 0;
+        for_each_combination(&deck[..7], 3, |cards| {
+            count += 1;
+            assert!(combinations::<_, 3>(&deck[..7]).any(|c| c == cards));
+        });
+        assert_eq!(combinations::<_, 3>(&deck[..7]).len(), count);
+
+        // Omaha plays two of four hole cards with three of a five card board
+        let omaha = combinations::<_, 2>(&deck[..4])
+            .flat_map(|two| combinations::<_, 3>(&deck[4..9]).map(move |three| (two, three)))
+            .count();
+        assert_eq!(omaha, 6 * 10);
+
+        assert_eq!(combinations::<_, 5>(&deck[..4]).next(), None);
+        assert_eq!(combinations::<_, 0>(&deck[..4]).count(), 1);
+    }
+
+    #[test]
+    fn test_for_each_combination() {
+        let deck = Vec::from(Deck::new());
+
+        let mut count = 0;
+        for_each_combination(&deck[..10], 3, |_| count += 1);
+        assert_eq!(count, 120);
+
+        let mut count = 0;
+        for_each_combination(&deck, 2, |_| count += 1);
+        assert_eq!(count, 1326);
+
+        let mut count = 0;
+        for_each_combination(&deck[..2], 3, |_| count += 1);
+        assert_eq!(count, 0);
     }
 }
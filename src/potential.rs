@@ -0,0 +1,214 @@
+//! Hand strength and hand potential of a hand against a range on the flop
+//! or turn, as described by Billings, Papp, Schaeffer and Szafron
+//! Hand strength is the chance of being ahead now, positive potential the
+//! chance of ending ahead when behind now, and negative potential the
+//! chance of ending behind when ahead now
+//! Effective hand strength puts the strength and positive potential
+//! together, the measure bots usually play by
+//! Only the high hand is looked at, in split pot games as well
+
+use std::cmp::Ordering;
+
+use crate::card::Card;
+use crate::deck::Deck;
+use crate::error::Error;
+use crate::evaluator::EvaluatorKind;
+use crate::game::Game;
+use crate::poker_hand::HandValue;
+use crate::poker_utils::for_each_combination;
+use crate::range::Range;
+
+/// Where a hand stands against an opponent, used to index the tallies
+const AHEAD: usize = 0;
+const TIED: usize = 1;
+const BEHIND: usize = 2;
+
+/// Hand strength and potentials of a hand, ties counting half
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct HandPotential {
+    /// Chance of being ahead of the range now
+    pub strength: f64,
+
+    /// Chance of ending ahead at the river when behind now
+    pub positive: f64,
+
+    /// Chance of ending behind at the river when ahead now
+    pub negative: f64,
+
+    /// Chance of being ahead at the river, the equity against the range
+    pub equity: f64,
+}
+
+///////////////////////////////////////////////
+/// HandPotential Implementations
+///////////////////////////////////////////////
+impl HandPotential {
+    /// Effective hand strength, EHS = HS + (1 - HS) x PPOT
+    /// The chance of being ahead now or getting there, leaving out the
+    /// negative potential as a hand that bets is rarely outdrawn for free
+    pub fn effective_strength(&self) -> f64 {
+        self.strength + (1.0 - self.strength) * self.positive
+    }
+}
+
+/// Hand strength and potentials of `hand` against every combo of `range` on
+/// a flop or turn board
+/// Every runout to the river is enumerated, so on the flop both cards to
+/// come are looked at, and combos count by their weight in the range
+/// Cards held, on the board or `dead` are removed from the range first
+pub fn hand_potential(
+    game: Game,
+    hand: &[Card],
+    range: &Range,
+    board: &[Card],
+    dead: &[Card],
+) -> Result<HandPotential, Error> {
+    if !game.has_board() {
+        return Err(Error::NoBoard(game));
+    }
+    if !matches!(board.len(), 3 | 4) {
+        return Err(Error::NotFlopOrTurn(board.len()));
+    }
+    let known = [hand, board, dead].concat();
+    let range = range.without_cards(&known);
+    if range.is_empty() {
+        return Err(Error::EmptyRange("the range".to_string()));
+    }
+
+    let evaluator = EvaluatorKind::default().evaluator();
+    let best = |hole: &[Card], board: &[Card]| game.rules().best_hand(hole, board, evaluator);
+    let standing = |mine: HandValue, theirs: HandValue| match mine.cmp(&theirs) {
+        Ordering::Greater => AHEAD,
+        Ordering::Equal => TIED,
+        Ordering::Less => BEHIND,
+    };
+
+    let deck = Deck::without(&known);
+    let mine_now = best(hand, board);
+    let mut now = [0.0; 3];
+    let mut totals = [0.0; 3];
+    let mut tallies = [[0.0; 3]; 3];
+    let mut river = board.to_vec();
+
+    for (combo, weight) in range.combos().iter().zip(range.weights()) {
+        let before = standing(mine_now, best(combo, board));
+        now[before] += weight;
+
+        let mut unseen = deck.clone();
+        unseen.remove(combo);
+        for_each_combination(unseen.remaining(), 5 - board.len(), |runout| {
+            river.truncate(board.len());
+            river.extend_from_slice(runout);
+            let after = standing(best(hand, &river), best(combo, &river));
+            totals[before] += weight;
+            tallies[before][after] += weight;
+        });
+    }
+
+    let ratio = |part: f64, whole: f64| if whole > 0.0 { part / whole } else { 0.0 };
+    let river = (0..3)
+        .map(|before| tallies[before][AHEAD] + tallies[before][TIED] / 2.0)
+        .sum::<f64>();
+    Ok(HandPotential {
+        strength: ratio(now[AHEAD] + now[TIED] / 2.0, now.iter().sum()),
+        positive: ratio(
+            tallies[BEHIND][AHEAD] + tallies[BEHIND][TIED] / 2.0 + tallies[TIED][AHEAD] / 2.0,
+            totals[BEHIND] + totals[TIED] / 2.0,
+        ),
+        negative: ratio(
+            tallies[AHEAD][BEHIND] + tallies[TIED][BEHIND] / 2.0 + tallies[AHEAD][TIED] / 2.0,
+            totals[AHEAD] + totals[TIED] / 2.0,
+        ),
+        equity: ratio(river, totals.iter().sum()),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::card::cards_from_str;
+    use crate::equity::run_exact_calculation;
+
+    fn cards(s: &str) -> Vec<Card> {
+        cards_from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_drawing_hand() {
+        let range = Range::parse("AA").unwrap();
+        let board = cards("7h2hKs");
+        let potential = hand_potential(Game::Holdem, &cards("9h8h"), &range, &board, &[]).unwrap();
+
+        // Always behind now, so the positive potential is the equity
+        let ranges = vec![Range::from_cards(cards("9h8h")), range];
+        let equity =
+            run_exact_calculation(Game::Holdem, board, vec![], ranges).unwrap()[0].equity();
+        assert_eq!(potential.strength, 0.0);
+        assert_eq!(potential.negative, 0.0);
+        assert!((potential.positive - equity).abs() < 1e-9);
+        assert!((potential.equity - equity).abs() < 1e-9);
+        assert!((potential.effective_strength() - equity).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_effective_strength() {
+        // The nuts can't be caught, so every measure is certain
+        let range = Range::parse("AA,KK,JTs").unwrap();
+        let board = cards("QhJhTh2c");
+        let royal = hand_potential(Game::Holdem, &cards("AhKh"), &range, &board, &[]).unwrap();
+        assert_eq!(royal.effective_strength(), 1.0);
+        assert_eq!(royal.equity, 1.0);
+
+        // Ahead of every combo now but able to be outdrawn, EHS stays at
+        // the strength while the equity drops
+        let range = Range::parse("KQs").unwrap();
+        let board = cards("Kd7c2h3s");
+        let aces = hand_potential(Game::Holdem, &cards("AhAs"), &range, &board, &[]).unwrap();
+        assert_eq!(aces.strength, 1.0);
+        assert!(aces.negative > 0.0);
+        assert_eq!(aces.effective_strength(), 1.0);
+        assert!(aces.equity < 1.0);
+    }
+
+    #[test]
+    fn test_made_hand() {
+        let range = Range::parse("KK,QQ,JTs").unwrap();
+        let board = cards("AdAc2h7s");
+        let quads = hand_potential(Game::Holdem, &cards("AhAs"), &range, &board, &[]).unwrap();
+        assert_eq!(quads.strength, 1.0);
+        assert_eq!(quads.negative, 0.0);
+        assert_eq!(quads.positive, 0.0);
+
+        // Nines up are behind the bigger pairs and ahead of JT, which can
+        // pair up to pass them
+        let nines = hand_potential(Game::Holdem, &cards("9c9d"), &range, &board, &[]).unwrap();
+        assert!((0.2..0.5).contains(&nines.strength), "{:?}", nines);
+        assert!(nines.negative > 0.0);
+        assert!(nines.positive > 0.0);
+
+        // EHS adds the chance of catching up and leaves out being outdrawn,
+        // so it is above both the strength and the equity
+        let ranges = vec![Range::from_cards(cards("9c9d")), range.clone()];
+        let equity =
+            run_exact_calculation(Game::Holdem, board.clone(), vec![], ranges).unwrap()[0].equity();
+        assert!((nines.equity - equity).abs() < 1e-9);
+        let ehs = nines.strength + (1.0 - nines.strength) * nines.positive;
+        assert_eq!(nines.effective_strength(), ehs);
+        assert!(ehs > nines.equity && ehs > nines.strength);
+
+        assert!(matches!(
+            hand_potential(Game::Holdem, &cards("9c9d"), &range, &cards("AdAc"), &[]),
+            Err(Error::NotFlopOrTurn(2))
+        ));
+        assert!(matches!(
+            hand_potential(
+                Game::Holdem,
+                &cards("KcKd"),
+                &Range::parse("KK").unwrap(),
+                &cards("KhAcAd"),
+                &[]
+            ),
+            Err(Error::EmptyRange(_))
+        ));
+    }
+}
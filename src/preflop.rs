@@ -0,0 +1,307 @@
+//! The 169 canonical starting hands and their heads up equities
+//! Hands are laid out on a 13x13 grid, pairs on the diagonal,
+//! suited above it and offsuit below, aces first
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::card::{Card, Rank};
+use crate::equity::{run_range_calculation, SimulationSettings};
+use crate::error::Error;
+use crate::game::Game;
+use crate::range::Range;
+
+/// Number of starting hands once suits are ignored
+pub const STARTING_HANDS: usize = 169;
+
+/// One of the 169 starting hands once suits are ignored, such as AKs,
+/// kept as its cell of the grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StartingHandClass {
+    row: u8,
+    col: u8,
+}
+
+/// Rank shown on a row or column of the grid
+fn grid_rank(i: usize) -> Rank {
+    Rank::ALL[12 - i]
+}
+
+/// Cell of the grid a pair of hole cards belongs to
+pub fn class_cell(a: Card, b: Card) -> (usize, usize) {
+    let (high, low) = if a.rank >= b.rank { (a, b) } else { (b, a) };
+    let high = 12 - (high.rank.value() as usize - 2);
+    let low = 12 - (low.rank.value() as usize - 2);
+
+    if a.suit == b.suit {
+        (high, low)
+    } else {
+        (low, high)
+    }
+}
+
+/// Name of the starting hand in a grid cell, such as "AKs"
+pub fn class_name(row: usize, col: usize) -> String {
+    let suffix = match row.cmp(&col) {
+        std::cmp::Ordering::Less => "s",
+        std::cmp::Ordering::Greater => "o",
+        std::cmp::Ordering::Equal => "",
+    };
+    let (high, low) = (row.min(col), row.max(col));
+    format!("{}{}{}", grid_rank(high), grid_rank(low), suffix)
+}
+
+///////////////////////////////////////////////
+/// StartingHandClass Implementations
+///////////////////////////////////////////////
+impl StartingHandClass {
+    /// The starting hand of two hole cards
+    pub fn of(a: Card, b: Card) -> Self {
+        let (row, col) = class_cell(a, b);
+        StartingHandClass {
+            row: row as u8,
+            col: col as u8,
+        }
+    }
+
+    /// The starting hand at a position of `starting_hands`
+    pub fn from_index(index: usize) -> Option<Self> {
+        (index < STARTING_HANDS).then_some(StartingHandClass {
+            row: (index / 13) as u8,
+            col: (index % 13) as u8,
+        })
+    }
+
+    /// Position in `starting_hands`
+    pub fn index(self) -> usize {
+        self.row as usize * 13 + self.col as usize
+    }
+
+    /// Row and column of the grid
+    pub fn cell(self) -> (usize, usize) {
+        (self.row as usize, self.col as usize)
+    }
+
+    pub fn is_pair(self) -> bool {
+        self.row == self.col
+    }
+
+    pub fn is_suited(self) -> bool {
+        self.row < self.col
+    }
+
+    /// Number of combos of the starting hand, 6 for a pair, 4 suited and
+    /// 12 offsuit
+    pub fn combos(self) -> usize {
+        match self.row.cmp(&self.col) {
+            std::cmp::Ordering::Equal => 6,
+            std::cmp::Ordering::Less => 4,
+            std::cmp::Ordering::Greater => 12,
+        }
+    }
+}
+
+impl Display for StartingHandClass {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&class_name(self.row as usize, self.col as usize))
+    }
+}
+
+impl FromStr for StartingHandClass {
+    type Err = Error;
+
+    /// A single starting hand such as "AKs", "KAs" or "77", or any one
+    /// combo of it such as "AhKh"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidStartingHand(s.to_string());
+        let range = Range::parse(s).map_err(|_| invalid())?;
+        let mut classes = range
+            .combos()
+            .iter()
+            .filter(|combo| combo.len() == 2)
+            .map(|combo| StartingHandClass::of(combo[0], combo[1]));
+
+        // Only a single starting hand is accepted, not a wider range
+        let class = classes.next().ok_or_else(invalid)?;
+        if classes.any(|other| other != class) {
+            return Err(invalid());
+        }
+        Ok(class)
+    }
+}
+
+/// Every starting hand, row by row through the grid
+pub fn starting_hands() -> Vec<String> {
+    (0..13)
+        .flat_map(|row| (0..13).map(move |col| class_name(row, col)))
+        .collect()
+}
+
+/// Position of a starting hand such as "AKs" or "KAs" in `starting_hands`
+pub fn starting_hand_index(name: &str) -> Option<usize> {
+    name.parse::<StartingHandClass>()
+        .ok()
+        .map(StartingHandClass::index)
+}
+
+/// Heads up equity of one starting hand against another
+/// Every combo of the first hand plays the same once suits are relabelled,
+/// so it is fixed to a single combo and only the second hand is sampled
+pub fn matchup_equity(hand: &str, other: &str, iterations: u32, seed: u64) -> Option<f64> {
+    let combo = Range::parse(hand).ok()?.combos().first()?.clone();
+    let other = Range::parse(other).ok()?.without_cards(&combo);
+    if other.is_empty() {
+        return None;
+    }
+
+    let hand = Range::from_combo([combo[0], combo[1]]);
+    let settings = SimulationSettings {
+        threads: 1,
+        ..SimulationSettings::new(iterations)
+    };
+    let results = run_range_calculation(
+        Game::Holdem,
+        vec![],
+        vec![],
+        vec![hand, other],
+        &settings,
+        &mut StdRng::seed_from_u64(seed),
+    );
+
+    Some(results[0].equity())
+}
+
+/// Heads up equity of every starting hand against every other, indexed like
+/// `starting_hands`, with `matrix[a][b]` the equity of `a` against `b`
+/// Only one side of each matchup is simulated, the other is its complement,
+/// and a hand against itself is exactly even
+/// `progress` is called with the number of finished matchups
+pub fn preflop_matrix(
+    iterations: u32,
+    threads: usize,
+    seed: u64,
+    progress: &(dyn Fn(usize) + Sync),
+) -> Vec<Vec<f64>> {
+    let hands = starting_hands();
+    let matchups = (0..STARTING_HANDS)
+        .flat_map(|a| (a + 1..STARTING_HANDS).map(move |b| (a, b)))
+        .collect::<Vec<_>>();
+
+    let mut matrix = vec![vec![0.5; STARTING_HANDS]; STARTING_HANDS];
+    let next = AtomicUsize::new(0);
+    let done = AtomicUsize::new(0);
+    let shared = Mutex::new(&mut matrix);
+
+    // Each matchup runs on a single thread with its own seed, so the
+    // matrix is the same whatever the thread count
+    thread::scope(|scope| {
+        for _ in 0..threads.max(1) {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(&(a, b)) = matchups.get(i) else {
+                    break;
+                };
+                let equity = matchup_equity(
+                    &hands[a],
+                    &hands[b],
+                    iterations,
+                    seed.wrapping_add(i as u64),
+                )
+                .unwrap_or(0.5);
+
+                let mut matrix = shared.lock().unwrap();
+                matrix[a][b] = equity;
+                matrix[b][a] = 1.0 - equity;
+                drop(matrix);
+
+                progress(done.fetch_add(1, Ordering::Relaxed) + 1);
+            });
+        }
+    });
+
+    matrix
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::card::cards_from_str;
+
+    #[test]
+    fn test_grid_cells() {
+        let cards = cards_from_str("AhKhAhKdQsQcTd9d").unwrap();
+
+        let (row, col) = class_cell(cards[0], cards[1]);
+        assert_eq!(class_name(row, col), "AKs");
+        let (row, col) = class_cell(cards[3], cards[2]);
+        assert_eq!(class_name(row, col), "AKo");
+        let (row, col) = class_cell(cards[4], cards[5]);
+        assert_eq!(class_name(row, col), "QQ");
+        let (row, col) = class_cell(cards[7], cards[6]);
+        assert_eq!(class_name(row, col), "T9s");
+    }
+
+    #[test]
+    fn test_starting_hands() {
+        let hands = starting_hands();
+        assert_eq!(hands.len(), STARTING_HANDS);
+        assert_eq!(hands[0], "AA");
+        assert_eq!(hands[1], "AKs");
+        assert_eq!(hands[13], "AKo");
+        assert_eq!(hands[168], "22");
+
+        for (i, hand) in hands.iter().enumerate() {
+            assert_eq!(starting_hand_index(hand), Some(i), "{}", hand);
+        }
+        assert_eq!(starting_hand_index("KAs"), Some(1));
+        assert_eq!(starting_hand_index("AhKd"), Some(13));
+        assert_eq!(starting_hand_index("QQ+"), None);
+        assert_eq!(starting_hand_index("AK"), None);
+    }
+
+    #[test]
+    fn test_starting_hand_classes() {
+        let classes = (0..STARTING_HANDS)
+            .map(|i| StartingHandClass::from_index(i).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(classes.iter().map(|c| c.combos()).sum::<usize>(), 1326);
+        assert_eq!(StartingHandClass::from_index(STARTING_HANDS), None);
+
+        let suited = "AKs".parse::<StartingHandClass>().unwrap();
+        assert_eq!((suited.index(), suited.cell()), (1, (0, 1)));
+        assert!(suited.is_suited() && !suited.is_pair());
+        assert_eq!(suited.to_string(), "AKs");
+        assert_eq!("7c7d".parse::<StartingHandClass>().unwrap().combos(), 6);
+        assert!(matches!(
+            "AK".parse::<StartingHandClass>(),
+            Err(Error::InvalidStartingHand(_))
+        ));
+
+        let cards = cards_from_str("KdAh").unwrap();
+        assert_eq!(StartingHandClass::of(cards[0], cards[1]).to_string(), "AKo");
+    }
+
+    #[test]
+    fn test_preflop_matrix() {
+        let aces = matchup_equity("AA", "72o", 20_000, 1).unwrap();
+        assert!((aces - 0.87).abs() < 0.02, "{}", aces);
+
+        // Suits are fixed on the first hand, so AA against AA can still be dealt
+        assert!(matchup_equity("AA", "AA", 100, 1).is_some());
+        assert!(matchup_equity("AA", "AhAs", 100, 1).is_none());
+
+        let matrix = preflop_matrix(1, 4, 1, &|_| {});
+        for (a, row) in matrix.iter().enumerate() {
+            assert_eq!(row[a], 0.5);
+            for (b, equity) in row.iter().enumerate() {
+                assert!((equity + matrix[b][a] - 1.0).abs() < 1e-12);
+            }
+        }
+    }
+}
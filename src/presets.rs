@@ -0,0 +1,240 @@
+//! Named preflop ranges such as "@utg-open", usable anywhere a range is
+//! accepted, and "@top10%", the best starting hands by heads up equity
+//! against a random hand
+//!
+//! The built in ranges can be replaced and new ones added in a file with a
+//! `name = range` line for each, where lines starting with # are comments
+//! The file is read from $EQUITY_CLI_RANGES when it is set, otherwise from
+//! $XDG_CONFIG_HOME/equity-cli/ranges.txt or ~/.config/equity-cli/ranges.txt
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// A named range
+#[derive(Debug, Clone, PartialEq)]
+pub struct RangePreset {
+    /// Name without the @, such as "utg-open"
+    pub name: String,
+
+    /// What the range is for
+    pub description: String,
+
+    /// Range notation the name stands for
+    pub range: String,
+}
+
+/// Ranges that ship with the program, as name, description and range
+/// The opening ranges are for a six handed game with 100 big blind stacks
+const BUILT_IN: &[(&str, &str, &str)] = &[
+    (
+        "utg-open",
+        "Open raise under the gun",
+        "77+,ATs+,KTs+,QTs+,JTs,T9s,98s,AJo+,KQo",
+    ),
+    (
+        "mp-open",
+        "Open raise from the hijack",
+        "66+,A9s+,A5s-A4s,K9s+,Q9s+,J9s+,T9s,98s,87s,ATo+,KJo+,QJo",
+    ),
+    (
+        "co-open",
+        "Open raise from the cutoff",
+        "44+,A2s+,K8s+,Q9s+,J9s+,T8s+,97s+,86s+,76s,65s,A9o+,KTo+,QTo+,JTo",
+    ),
+    (
+        "btn-open",
+        "Open raise on the button",
+        "22+,A2s+,K2s+,Q5s+,J7s+,T7s+,96s+,85s+,75s+,64s+,54s,A2o+,K8o+,Q9o+,J9o+,T9o",
+    ),
+    (
+        "sb-open",
+        "Open raise from the small blind",
+        "22+,A2s+,K5s+,Q7s+,J7s+,T7s+,97s+,86s+,75s+,65s,54s,A5o+,K9o+,Q9o+,J9o+,T9o",
+    ),
+    (
+        "bb-defend",
+        "Call from the big blind against a button open",
+        "22+,A2s+,K2s+,Q2s+,J4s+,T6s+,96s+,85s+,74s+,63s+,53s+,43s,A2o+,K7o+,Q8o+,J8o+,T8o+,98o,87o",
+    ),
+    (
+        "3bet",
+        "Re-raise against an open",
+        "TT+,AQs+,KQs,A5s-A4s,AKo",
+    ),
+    (
+        "4bet",
+        "Re-raise against a re-raise",
+        "QQ+,AKs,A5s,AKo",
+    ),
+];
+
+/// Every starting hand from best to worst by heads up equity against a
+/// random hand
+pub const HAND_RANKING: [&str; 169] = [
+    "AA", "KK", "QQ", "JJ", "TT", "99", "88", "AKs", "77", "AQs", "AJs", "AKo", "ATs", "AQo",
+    "AJo", "KQs", "66", "A9s", "ATo", "KJs", "A8s", "KTs", "KQo", "A7s", "A9o", "KJo", "55", "QJs",
+    "K9s", "A5s", "A6s", "A8o", "KTo", "QTs", "A4s", "A7o", "K8s", "A3s", "QJo", "K9o", "A5o",
+    "Q9s", "A6o", "K7s", "JTs", "A2s", "QTo", "44", "A4o", "K6s", "K8o", "Q8s", "A3o", "K5s",
+    "J9s", "Q9o", "JTo", "K7o", "A2o", "K4s", "K6o", "Q7s", "T9s", "J8s", "K3s", "33", "Q6s",
+    "Q8o", "J9o", "K5o", "K2s", "Q5s", "T8s", "K4o", "J7s", "Q4s", "Q7o", "T9o", "K3o", "J8o",
+    "Q6o", "Q3s", "98s", "T7s", "J6s", "K2o", "22", "Q5o", "Q2s", "J5s", "T8o", "J7o", "Q4o",
+    "J4s", "97s", "T6s", "J3s", "Q3o", "98o", "T7o", "J6o", "87s", "96s", "J2s", "T5s", "Q2o",
+    "J5o", "T4s", "97o", "86s", "J4o", "T6o", "95s", "T3s", "76s", "J3o", "87o", "T2s", "85s",
+    "96o", "J2o", "T5o", "94s", "75s", "T4o", "86o", "93s", "65s", "84s", "95o", "T3o", "92s",
+    "76o", "74s", "T2o", "54s", "85o", "64s", "83s", "94o", "75o", "82s", "93o", "73s", "65o",
+    "53s", "63s", "84o", "92o", "43s", "74o", "54o", "72s", "64o", "52s", "62s", "83o", "82o",
+    "42s", "73o", "53o", "63o", "32s", "43o", "72o", "52o", "62o", "42o", "32o",
+];
+
+/// Every named range, the built in ones first with any from the ranges
+/// file replacing them or added after
+pub fn presets() -> Vec<RangePreset> {
+    let mut presets = BUILT_IN
+        .iter()
+        .map(|(name, description, range)| RangePreset {
+            name: name.to_string(),
+            description: description.to_string(),
+            range: range.to_string(),
+        })
+        .collect::<Vec<_>>();
+
+    for preset in overrides() {
+        match presets.iter_mut().find(|p| p.name == preset.name) {
+            Some(existing) => *existing = preset.clone(),
+            None => presets.push(preset.clone()),
+        }
+    }
+
+    presets
+}
+
+/// Range notation of a named range, without the @
+/// Names such as "top10%" are worked out from `HAND_RANKING`
+pub fn preset_range(name: &str) -> Option<String> {
+    let name = name.to_ascii_lowercase();
+    if let Some(percent) = name
+        .strip_prefix("top")
+        .and_then(|n| n.strip_suffix('%'))
+        .and_then(|n| n.parse::<f64>().ok())
+    {
+        return top_percent(percent);
+    }
+
+    presets()
+        .into_iter()
+        .find(|p| p.name == name)
+        .map(|p| p.range)
+}
+
+/// The best starting hands making up `percent` of every combo, such as
+/// "AA,KK,QQ,JJ,TT,99,88,AKs" for 3%
+/// Hands are added until the share is reached, None outside 0 to 100
+pub fn top_percent(percent: f64) -> Option<String> {
+    if !(percent > 0.0 && percent <= 100.0) {
+        return None;
+    }
+
+    let target = percent / 100.0 * 1326.0;
+    let mut combos = 0;
+    let mut hands = vec![];
+    for hand in HAND_RANKING {
+        if combos as f64 >= target {
+            break;
+        }
+        combos += match hand.len() {
+            2 => 6,
+            _ if hand.ends_with('s') => 4,
+            _ => 12,
+        };
+        hands.push(hand);
+    }
+
+    Some(hands.join(","))
+}
+
+/// Path of the file with the user's own ranges
+/// None when no variable to find it from is set
+pub fn ranges_path() -> Option<PathBuf> {
+    if let Some(path) = env::var_os("EQUITY_CLI_RANGES").filter(|p| !p.is_empty()) {
+        return Some(PathBuf::from(path));
+    }
+
+    let base = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+
+    Some(base.join("equity-cli").join("ranges.txt"))
+}
+
+/// Ranges from the ranges file, read once
+fn overrides() -> &'static [RangePreset] {
+    static OVERRIDES: OnceLock<Vec<RangePreset>> = OnceLock::new();
+
+    OVERRIDES.get_or_init(|| {
+        ranges_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|text| parse_ranges_file(&text))
+            .unwrap_or_default()
+    })
+}
+
+/// Parse the `name = range` lines of a ranges file
+/// Blank lines, comments and lines without an = are skipped
+fn parse_ranges_file(text: &str) -> Vec<RangePreset> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(name, range)| RangePreset {
+            name: name.trim().trim_start_matches('@').to_ascii_lowercase(),
+            description: "From the ranges file".to_string(),
+            range: range.trim().to_string(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::range::Range;
+
+    #[test]
+    fn test_built_in_ranges_parse() {
+        for (name, _, range) in BUILT_IN {
+            assert!(Range::parse(range).is_ok(), "{}", name);
+        }
+
+        let utg = Range::parse("@utg-open").unwrap();
+        assert_eq!(utg.len(), Range::parse(BUILT_IN[0].2).unwrap().len());
+        assert!(Range::parse("@no-such-range").is_err());
+    }
+
+    #[test]
+    fn test_top_percent() {
+        let ranking = Range::parse(&HAND_RANKING.join(",")).unwrap();
+        assert_eq!(ranking.len(), 1326);
+
+        assert_eq!(top_percent(3.0).unwrap(), "AA,KK,QQ,JJ,TT,99,88");
+        assert_eq!(Range::parse("@top100%").unwrap().len(), 1326);
+        let top10 = Range::parse("@top10%").unwrap();
+        assert!((133..150).contains(&top10.len()), "{}", top10.len());
+
+        assert!(top_percent(0.0).is_none());
+        assert!(top_percent(101.0).is_none());
+        assert!(Range::parse("@top0%").is_err());
+    }
+
+    #[test]
+    fn test_ranges_file() {
+        let text = "# my ranges\n\nutg-open = QQ+,AK\n@squeeze = JJ+, AQs+\nnot a range\n";
+        let ranges = parse_ranges_file(text);
+        assert_eq!(ranges.len(), 2);
+        assert_eq!(ranges[0].name, "utg-open");
+        assert_eq!(ranges[0].range, "QQ+,AK");
+        assert_eq!(ranges[1].name, "squeeze");
+        assert_eq!(ranges[1].range, "JJ+, AQs+");
+    }
+}
@@ -0,0 +1,271 @@
+//! Heads up push or fold equilibrium, where the small blind either moves all
+//! in or folds and the big blind either calls or folds
+//! Both sides are solved by fictitious play, each round playing the best
+//! response to the other side's average strategy so far, which converges to
+//! the Nash equilibrium of this zero sum game
+//! Equities come from the preflop matrix and card removal from how many
+//! combos of each hand are left once the other hand is dealt
+
+use crate::error::Error;
+use crate::preflop::{starting_hands, StartingHandClass, STARTING_HANDS};
+use crate::range::Range;
+
+/// Strategies below this frequency are left out of the ranges
+const MIN_FREQUENCY: f64 = 0.005;
+
+/// How often each starting hand pushes from the small blind and calls from
+/// the big blind, indexed like `starting_hands`
+#[derive(Debug, Clone, PartialEq)]
+pub struct PushFoldChart {
+    /// Effective stack in big blinds, before the blinds and antes are posted
+    pub stack: f64,
+
+    /// Ante each player posts, in big blinds
+    pub ante: f64,
+
+    /// Share of each starting hand's combos the small blind moves all in with
+    pub push: Vec<f64>,
+
+    /// Share of each starting hand's combos the big blind calls the all in with
+    pub call: Vec<f64>,
+}
+
+///////////////////////////////////////////////
+/// PushFoldChart Implementations
+///////////////////////////////////////////////
+impl PushFoldChart {
+    /// Small blind's range, weighted where the strategy is mixed
+    pub fn push_range(&self) -> Range {
+        chart_range(&self.push)
+    }
+
+    /// Big blind's range, weighted where the strategy is mixed
+    pub fn call_range(&self) -> Range {
+        chart_range(&self.call)
+    }
+
+    /// Share of every combo the small blind moves all in with
+    pub fn push_share(&self) -> f64 {
+        combo_share(&self.push)
+    }
+
+    /// Share of every combo the big blind calls with
+    pub fn call_share(&self) -> f64 {
+        combo_share(&self.call)
+    }
+}
+
+/// Solve heads up push or fold for an effective `stack` in big blinds
+/// `matrix` is the heads up equity of every starting hand against every
+/// other, as given by `preflop_matrix`
+/// More `rounds` get closer to the equilibrium, a few thousand are plenty
+pub fn push_fold_chart(
+    matrix: &[Vec<f64>],
+    stack: f64,
+    ante: f64,
+    rounds: u32,
+) -> Result<PushFoldChart, Error> {
+    if !(ante >= 0.0 && ante.is_finite()) {
+        return Err(Error::InvalidAmount(ante));
+    }
+    // The big blind has to have chips left after posting
+    if !(stack > 1.0 + ante && stack.is_finite()) {
+        return Err(Error::InvalidStack(stack));
+    }
+
+    let blockers = compatible_combos();
+    let fold_push = -(0.5 + ante);
+    let fold_call = -(1.0 + ante);
+    let showdown = |equity: f64| equity * 2.0 * stack - stack;
+
+    // Best response of the small blind to a calling strategy
+    let best_push = |call: &[f64]| {
+        (0..STARTING_HANDS)
+            .map(|a| {
+                let (mut total, mut ev) = (0.0, 0.0);
+                for b in 0..STARTING_HANDS {
+                    let weight = blockers[a][b];
+                    total += weight;
+                    ev += weight * (1.0 - call[b]) * (1.0 + ante);
+                    ev += weight * call[b] * showdown(matrix[a][b]);
+                }
+                if ev / total > fold_push {
+                    1.0
+                } else {
+                    0.0
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // Best response of the big blind to a pushing strategy
+    let best_call = |push: &[f64]| {
+        (0..STARTING_HANDS)
+            .map(|b| {
+                let (mut total, mut ev) = (0.0, 0.0);
+                for a in 0..STARTING_HANDS {
+                    let weight = blockers[b][a] * push[a];
+                    total += weight;
+                    ev += weight * showdown(matrix[b][a]);
+                }
+                if total > 0.0 && ev / total > fold_call {
+                    1.0
+                } else {
+                    0.0
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let mut push = vec![1.0; STARTING_HANDS];
+    let mut call = best_call(&push);
+    for round in 1..=rounds {
+        let pushing = best_push(&call);
+        average(&mut push, &pushing, round + 1);
+        let calling = best_call(&push);
+        average(&mut call, &calling, round + 1);
+    }
+
+    Ok(PushFoldChart {
+        stack,
+        ante,
+        push,
+        call,
+    })
+}
+
+/// Move a running average over `count` strategies towards the newest one
+fn average(strategy: &mut [f64], newest: &[f64], count: u32) {
+    for (average, newest) in strategy.iter_mut().zip(newest) {
+        *average += (newest - *average) / count as f64;
+    }
+}
+
+/// Combos of every starting hand left once one combo of another is dealt,
+/// `combos[a][b]` for `b` against `a`
+/// Any combo of `a` leaves the same number, as suits can be relabelled
+fn compatible_combos() -> Vec<Vec<f64>> {
+    let combos = starting_hands()
+        .iter()
+        .map(|hand| Range::parse(hand).unwrap().combos().to_vec())
+        .collect::<Vec<_>>();
+
+    combos
+        .iter()
+        .map(|hand| {
+            let dealt = &hand[0];
+            combos
+                .iter()
+                .map(|other| {
+                    other
+                        .iter()
+                        .filter(|combo| !combo.iter().any(|c| dealt.contains(c)))
+                        .count() as f64
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Range of the starting hands played at a frequency, leaving out the
+/// hands that are hardly ever played
+fn chart_range(frequencies: &[f64]) -> Range {
+    let hands = starting_hands()
+        .into_iter()
+        .zip(frequencies)
+        .filter(|(_, frequency)| **frequency >= MIN_FREQUENCY)
+        .map(|(hand, frequency)| {
+            if *frequency >= 1.0 - MIN_FREQUENCY {
+                hand
+            } else {
+                format!("{}:{:.2}", hand, frequency)
+            }
+        })
+        .collect::<Vec<_>>();
+
+    Range::parse(&hands.join(",")).expect("starting hands with weights")
+}
+
+/// Share of every combo played, counting each starting hand by its combos
+fn combo_share(frequencies: &[f64]) -> f64 {
+    frequencies
+        .iter()
+        .enumerate()
+        .map(|(i, frequency)| {
+            let class = StartingHandClass::from_index(i).unwrap();
+            frequency * class.combos() as f64
+        })
+        .sum::<f64>()
+        / 1326.0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::preflop::starting_hand_index;
+    use crate::presets::HAND_RANKING;
+
+    /// Made up matrix where hands higher in `HAND_RANKING` are favourites
+    fn ranked_matrix() -> Vec<Vec<f64>> {
+        let mut strength = vec![0.0; STARTING_HANDS];
+        for (rank, hand) in HAND_RANKING.iter().enumerate() {
+            strength[starting_hand_index(hand).unwrap()] = 1.0 - rank as f64 / 168.0;
+        }
+        (0..STARTING_HANDS)
+            .map(|a| {
+                (0..STARTING_HANDS)
+                    .map(|b| 0.5 + 0.35 * (strength[a] - strength[b]))
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_coin_flips() {
+        // When every hand is even, pushing and calling always win chips
+        let matrix = vec![vec![0.5; STARTING_HANDS]; STARTING_HANDS];
+        let chart = push_fold_chart(&matrix, 10.0, 0.0, 50).unwrap();
+        assert!(chart.push.iter().all(|p| *p == 1.0));
+        assert!(chart.call.iter().all(|c| *c == 1.0));
+        assert!((chart.push_share() - 1.0).abs() < 1e-9);
+        assert_eq!(chart.call_range().len(), 1326);
+    }
+
+    #[test]
+    fn test_push_fold_chart() {
+        let matrix = ranked_matrix();
+        let short = push_fold_chart(&matrix, 3.0, 0.0, 500).unwrap();
+        let deep = push_fold_chart(&matrix, 20.0, 0.0, 500).unwrap();
+
+        // Aces always get it in, and deeper stacks push and call less
+        let aces = starting_hand_index("AA").unwrap();
+        assert_eq!(deep.push[aces], 1.0);
+        assert_eq!(deep.call[aces], 1.0);
+        assert!(deep.push_share() < short.push_share());
+        assert!(deep.call_share() < short.call_share());
+
+        // The small blind gets the big blind's chips when they fold, so it
+        // plays more hands than the big blind calls with
+        assert!(deep.push_share() > deep.call_share());
+        let worst = starting_hand_index("32o").unwrap();
+        assert_eq!(deep.call[worst], 0.0);
+        assert!(!deep.push_range().is_empty());
+
+        // Antes make the pot worth fighting for
+        let antes = push_fold_chart(&matrix, 20.0, 0.125, 500).unwrap();
+        assert!(antes.push_share() > deep.push_share());
+    }
+
+    #[test]
+    fn test_invalid_push_fold() {
+        let matrix = ranked_matrix();
+        assert!(matches!(
+            push_fold_chart(&matrix, 1.0, 0.0, 10),
+            Err(Error::InvalidStack(_))
+        ));
+        assert!(matches!(
+            push_fold_chart(&matrix, 10.0, -1.0, 10),
+            Err(Error::InvalidAmount(_))
+        ));
+    }
+}
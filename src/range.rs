@@ -0,0 +1,629 @@
+//! Hand range notation such as "QQ+,AKs,AJo+,A5s-A2s" or "random"
+//! Ranges are expanded into the concrete two card combos they contain
+//! Any token can be given a weight such as "AA:0.5", the share of its
+//! combos that are in the range
+//! Tokens starting with @ are named ranges, see `presets`
+
+use rand::Rng;
+
+use crate::card::{cards_from_str, Card, Rank, Suit};
+use crate::preflop::{StartingHandClass, STARTING_HANDS};
+use crate::presets;
+
+/// Most named ranges that can be nested inside each other
+const MAX_PRESET_DEPTH: usize = 8;
+
+/// A set of possible hole cards for one player
+/// Range notation only describes two card hands, but exact hole cards
+/// of any size can be given for games such as Omaha
+#[derive(Debug, Clone, PartialEq)]
+pub struct Range {
+    combos: Vec<Vec<Card>>,
+
+    /// Weight of each combo, 1 unless given in the notation
+    weights: Vec<f64>,
+
+    /// Running total of the weights for sampling, empty when every combo
+    /// has the same weight
+    cumulative: Vec<f64>,
+}
+
+/// Error from parsing range notation, naming the token that failed
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("invalid range \"{token}\": {reason}")]
+pub struct ParseRangeError {
+    pub token: String,
+    pub reason: String,
+}
+
+impl ParseRangeError {
+    fn new(token: &str, reason: &str) -> Self {
+        ParseRangeError {
+            token: token.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+/// Whether a hand class is suited, offsuit or either
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Suitedness {
+    Suited,
+    Offsuit,
+    Any,
+}
+
+/// A hand class before it is expanded, such as "AKs" or "TT"
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct HandClass {
+    high: Rank,
+    low: Rank,
+    suitedness: Suitedness,
+}
+
+///////////////////////////////////////////////
+/// Range Implementations
+///////////////////////////////////////////////
+impl Range {
+    /// A range containing exactly one combo
+    pub fn from_combo(combo: [Card; 2]) -> Self {
+        Self::from_cards(combo.to_vec())
+    }
+
+    /// A range containing exactly one set of hole cards
+    pub fn from_cards(cards: Vec<Card>) -> Self {
+        Self::weighted(vec![cards], vec![1.0])
+    }
+
+    /// Parse a comma separated list of range tokens
+    /// A combo listed more than once takes the weight it was given last,
+    /// and combos with a weight of 0 are left out
+    pub fn parse(s: &str) -> Result<Self, ParseRangeError> {
+        Self::parse_nested(s, 0)
+    }
+
+    /// Parse a range exported from a solver, spread over any number of lines
+    /// Both PioSolver's "AA,KK:0.5,AhKh:0.25" and GTO+'s "[50.0]KK,AKs[/50.0]",
+    /// where the weight is a percentage, are accepted
+    pub fn parse_solver(text: &str) -> Result<Self, ParseRangeError> {
+        let mut tokens = vec![];
+        let mut weight = None;
+
+        for token in text.split([',', '\n', '\r']).map(str::trim) {
+            let mut token = token;
+            if let Some(rest) = token.strip_prefix('[') {
+                let (percent, rest) = rest
+                    .split_once(']')
+                    .ok_or_else(|| ParseRangeError::new(token, "expected ] after the weight"))?;
+                let percent = percent.trim().parse::<f64>().map_err(|_| {
+                    let reason = format!("invalid weight '{}', expected a percentage", percent);
+                    ParseRangeError::new(token, &reason)
+                })?;
+                weight = Some(percent / 100.0);
+                token = rest.trim();
+            }
+
+            let (body, closed) = match token.split_once("[/") {
+                Some((body, _)) => (body.trim(), true),
+                None => (token, false),
+            };
+            match weight {
+                Some(weight) if !body.is_empty() => tokens.push(format!("{}:{}", body, weight)),
+                _ => tokens.push(body.to_string()),
+            }
+            if closed {
+                weight = None;
+            }
+        }
+
+        Range::parse(&tokens.join(","))
+    }
+
+    /// Parse a range that is `depth` named ranges deep
+    fn parse_nested(s: &str, depth: usize) -> Result<Self, ParseRangeError> {
+        let mut combos: Vec<Vec<Card>> = vec![];
+        let mut weights = vec![];
+
+        for token in s.split(',').map(|t| t.trim()).filter(|t| !t.is_empty()) {
+            let (token, weight) = parse_weight(token)?;
+            let expanded = match token.strip_prefix('@') {
+                Some(name) => parse_preset(token, name, depth)?,
+                None => parse_token(token)?.into_iter().map(|c| (c, 1.0)).collect(),
+            };
+
+            for (combo, scale) in expanded {
+                let weight = weight * scale;
+                match combos.iter().position(|c| same_combo(c, &combo)) {
+                    Some(i) => weights[i] = weight,
+                    None => {
+                        combos.push(combo);
+                        weights.push(weight);
+                    }
+                }
+            }
+        }
+
+        let (combos, weights) = combos
+            .into_iter()
+            .zip(weights)
+            .filter(|(_, weight)| *weight > 0.0)
+            .unzip::<_, _, Vec<_>, Vec<_>>();
+        if combos.is_empty() {
+            return Err(ParseRangeError::new(s, "range is empty"));
+        }
+
+        Ok(Self::weighted(combos, weights))
+    }
+
+    /// Range of combos with a weight each
+    fn weighted(combos: Vec<Vec<Card>>, weights: Vec<f64>) -> Self {
+        let cumulative = if weights.windows(2).all(|w| w[0] == w[1]) {
+            vec![]
+        } else {
+            weights
+                .iter()
+                .scan(0.0, |total, weight| {
+                    *total += weight;
+                    Some(*total)
+                })
+                .collect()
+        };
+
+        Range {
+            combos,
+            weights,
+            cumulative,
+        }
+    }
+
+    /// All combos in the range
+    pub fn combos(&self) -> &[Vec<Card>] {
+        &self.combos
+    }
+
+    /// Weight of every combo, in the same order as `combos`
+    pub fn weights(&self) -> &[f64] {
+        &self.weights
+    }
+
+    /// Whether some combos have a different weight than others
+    pub fn is_weighted(&self) -> bool {
+        !self.cumulative.is_empty()
+    }
+
+    /// Sum of the weights, the number of combos when none are weighted
+    pub fn total_weight(&self) -> f64 {
+        self.weights.iter().sum()
+    }
+
+    pub fn len(&self) -> usize {
+        self.combos.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.combos.is_empty()
+    }
+
+    /// How much of each starting hand is in the range, indexed like
+    /// `starting_hands`, as the weight of its combos over the most it can have
+    /// Only two card combos are counted
+    pub fn class_frequencies(&self) -> Vec<f64> {
+        let mut weights = vec![0.0; STARTING_HANDS];
+        for (combo, weight) in self.combos.iter().zip(&self.weights) {
+            if let [a, b] = combo[..] {
+                weights[StartingHandClass::of(a, b).index()] += weight;
+            }
+        }
+
+        weights
+            .iter()
+            .enumerate()
+            .map(|(i, weight)| {
+                let class = StartingHandClass::from_index(i).unwrap();
+                weight / class.combos() as f64
+            })
+            .collect()
+    }
+
+    /// Remove every combo that uses one of the given cards
+    /// The weights of the combos left keep their proportions to each other
+    pub fn without_cards(&self, dead: &[Card]) -> Range {
+        let (combos, weights) = self
+            .combos
+            .iter()
+            .zip(&self.weights)
+            .filter(|(combo, _)| !combo.iter().any(|c| dead.contains(c)))
+            .map(|(combo, weight)| (combo.clone(), *weight))
+            .unzip();
+        Self::weighted(combos, weights)
+    }
+
+    /// Pick a random combo from the range, in proportion to its weight
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> Option<&[Card]> {
+        self.sample_index(rng).map(|i| self.combos[i].as_slice())
+    }
+
+    /// Pick the index of a random combo from the range, in proportion to its weight
+    pub fn sample_index<R: Rng>(&self, rng: &mut R) -> Option<usize> {
+        if self.combos.is_empty() {
+            return None;
+        }
+        let Some(total) = self.cumulative.last() else {
+            return Some(rng.gen_range(0..self.combos.len()));
+        };
+
+        let point = rng.gen::<f64>() * total;
+        let i = self.cumulative.partition_point(|&sum| sum <= point);
+        Some(i.min(self.combos.len() - 1))
+    }
+}
+
+/// Two combos are the same regardless of card order
+fn same_combo(a: &[Card], b: &[Card]) -> bool {
+    a.len() == b.len() && a.iter().all(|c| b.contains(c))
+}
+
+///////////////////////////////////////////////
+/// HandClass Implementations
+///////////////////////////////////////////////
+impl HandClass {
+    fn is_pair(&self) -> bool {
+        self.high == self.low
+    }
+
+    /// Expand the class into every concrete combo
+    fn combos(&self) -> Vec<Vec<Card>> {
+        let mut combos = vec![];
+
+        for (i, &s1) in Suit::ALL.iter().enumerate() {
+            for (j, &s2) in Suit::ALL.iter().enumerate() {
+                // Pairs only need each suit pairing once
+                if self.is_pair() && j <= i {
+                    continue;
+                }
+
+                let include = match self.suitedness {
+                    Suitedness::Suited => s1 == s2,
+                    Suitedness::Offsuit => s1 != s2,
+                    Suitedness::Any => true,
+                };
+
+                if include {
+                    combos.push(vec![Card::new(self.high, s1), Card::new(self.low, s2)]);
+                }
+            }
+        }
+
+        combos
+    }
+}
+
+/// Expand a named range such as "@utg-open" into its weighted combos
+fn parse_preset(
+    token: &str,
+    name: &str,
+    depth: usize,
+) -> Result<Vec<(Vec<Card>, f64)>, ParseRangeError> {
+    // Ranges from the ranges file can name each other
+    if depth >= MAX_PRESET_DEPTH {
+        return Err(ParseRangeError::new(token, "named ranges nest too deeply"));
+    }
+
+    let Some(range) = presets::preset_range(name) else {
+        let reason = format!("unknown range '{}', see --list-ranges", name);
+        return Err(ParseRangeError::new(token, &reason));
+    };
+
+    let range = Range::parse_nested(&range, depth + 1)
+        .map_err(|e| ParseRangeError::new(token, &e.to_string()))?;
+    Ok(range.combos.into_iter().zip(range.weights).collect())
+}
+
+/// Split the weight off a token such as "AA:0.5", 1 when none is given
+fn parse_weight(token: &str) -> Result<(&str, f64), ParseRangeError> {
+    let Some((body, weight)) = token.split_once(':') else {
+        return Ok((token, 1.0));
+    };
+
+    match weight.trim().parse::<f64>() {
+        Ok(weight) if weight >= 0.0 && weight.is_finite() => Ok((body.trim(), weight)),
+        _ => Err(ParseRangeError::new(
+            token,
+            &format!("invalid weight '{}', expected a number such as 0.5", weight),
+        )),
+    }
+}
+
+/// Parse a class such as "AK", "AKs", "AKo" or "QQ"
+fn parse_class(token: &str) -> Result<HandClass, ParseRangeError> {
+    let chars = token.chars().collect::<Vec<_>>();
+
+    if chars.len() < 2 || chars.len() > 3 {
+        return Err(ParseRangeError::new(
+            token,
+            "expected a hand like AKs or QQ",
+        ));
+    }
+
+    let rank = |c: char| {
+        Rank::from_char(c).ok_or(ParseRangeError::new(
+            token,
+            &format!("unknown rank '{}'", c),
+        ))
+    };
+    let first = rank(chars[0])?;
+    let second = rank(chars[1])?;
+
+    let suitedness = match chars.get(2).map(|c| c.to_ascii_lowercase()) {
+        None => Suitedness::Any,
+        Some('s') => Suitedness::Suited,
+        Some('o') => Suitedness::Offsuit,
+        Some(c) => {
+            let reason = format!("unexpected '{}', expected s or o", c);
+            return Err(ParseRangeError::new(token, &reason));
+        }
+    };
+
+    if first == second && suitedness != Suitedness::Any {
+        return Err(ParseRangeError::new(
+            token,
+            "pairs cannot be suited or offsuit",
+        ));
+    }
+
+    Ok(HandClass {
+        high: first.max(second),
+        low: first.min(second),
+        suitedness,
+    })
+}
+
+/// Expand a single range token into combos
+fn parse_token(token: &str) -> Result<Vec<Vec<Card>>, ParseRangeError> {
+    // Any two cards
+    if token.eq_ignore_ascii_case("random") {
+        return Ok(any_two_cards());
+    }
+
+    // Specific hole cards such as "AhKd" or "AhKdQsJs"
+    // Range notation never has a suit as its second character
+    if token.chars().nth(1).and_then(Suit::from_char).is_some() {
+        let cards =
+            cards_from_str(token).map_err(|e| ParseRangeError::new(token, &e.to_string()))?;
+
+        let unique = cards
+            .iter()
+            .enumerate()
+            .all(|(i, c)| !cards[i + 1..].contains(c));
+        if !unique {
+            return Err(ParseRangeError::new(token, "duplicate card"));
+        }
+
+        return Ok(vec![cards]);
+    }
+
+    let classes = if let Some((from, to)) = token.split_once('-') {
+        parse_span(token, parse_class(from)?, parse_class(to)?)?
+    } else if let Some(class) = token.strip_suffix('+') {
+        parse_plus(parse_class(class)?)
+    } else {
+        vec![parse_class(token)?]
+    };
+
+    Ok(classes.iter().flat_map(|c| c.combos()).collect())
+}
+
+/// Every one of the 1326 two card combos
+fn any_two_cards() -> Vec<Vec<Card>> {
+    let cards = Rank::ALL
+        .iter()
+        .flat_map(|&rank| Suit::ALL.iter().map(move |&suit| Card::new(rank, suit)))
+        .collect::<Vec<_>>();
+
+    cards
+        .iter()
+        .enumerate()
+        .flat_map(|(i, &a)| cards[i + 1..].iter().map(move |&b| vec![a, b]))
+        .collect()
+}
+
+/// "QQ+" is every pair from QQ up, "AJo+" raises the kicker up to AKo
+fn parse_plus(class: HandClass) -> Vec<HandClass> {
+    Rank::ALL
+        .iter()
+        .filter(|&&r| r >= class.low)
+        .filter(|&&r| class.is_pair() || r < class.high)
+        .map(|&r| {
+            if class.is_pair() {
+                HandClass {
+                    high: r,
+                    low: r,
+                    ..class
+                }
+            } else {
+                HandClass { low: r, ..class }
+            }
+        })
+        .collect()
+}
+
+/// "A5s-A2s" or "55-22", the top card stays the same and the kicker varies
+fn parse_span(
+    token: &str,
+    from: HandClass,
+    to: HandClass,
+) -> Result<Vec<HandClass>, ParseRangeError> {
+    if from.is_pair() != to.is_pair() || from.suitedness != to.suitedness {
+        return Err(ParseRangeError::new(
+            token,
+            "both ends must be the same kind",
+        ));
+    }
+
+    if !from.is_pair() && from.high != to.high {
+        return Err(ParseRangeError::new(
+            token,
+            "both ends need the same top card",
+        ));
+    }
+
+    let low = from.low.min(to.low);
+    let high = from.low.max(to.low);
+
+    Ok(Rank::ALL
+        .iter()
+        .filter(|&&r| r >= low && r <= high)
+        .map(|&r| {
+            if from.is_pair() {
+                HandClass {
+                    high: r,
+                    low: r,
+                    ..from
+                }
+            } else {
+                HandClass { low: r, ..from }
+            }
+        })
+        .collect())
+}
+
+/// Ranges of a single hand each, such as "AsKd", for tests
+#[cfg(test)]
+pub(crate) fn hand_ranges(hands: &[&str]) -> Vec<Range> {
+    hands
+        .iter()
+        .map(|h| Range::from_cards(cards_from_str(h).unwrap()))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_single_classes() {
+        assert_eq!(Range::parse("AA").unwrap().len(), 6);
+        assert_eq!(Range::parse("AKs").unwrap().len(), 4);
+        assert_eq!(Range::parse("AKo").unwrap().len(), 12);
+        assert_eq!(Range::parse("AK").unwrap().len(), 16);
+        assert_eq!(Range::parse("KA").unwrap().len(), 16);
+        assert_eq!(Range::parse("AhKh").unwrap().len(), 1);
+        assert_eq!(Range::parse("AhKhQdJd").unwrap().combos()[0].len(), 4);
+    }
+
+    #[test]
+    fn test_plus_and_spans() {
+        // QQ, KK, AA
+        assert_eq!(Range::parse("QQ+").unwrap().len(), 18);
+        // AJo, AQo, AKo
+        assert_eq!(Range::parse("AJo+").unwrap().len(), 36);
+        // A5s, A4s, A3s, A2s
+        assert_eq!(Range::parse("A5s-A2s").unwrap().len(), 16);
+        // 22, 33, 44, 55
+        assert_eq!(Range::parse("22-55").unwrap().len(), 24);
+    }
+
+    #[test]
+    fn test_combined_range_removes_duplicates() {
+        let range = Range::parse("QQ+,AKs,AJo+").unwrap();
+        assert_eq!(range.len(), 18 + 4 + 36);
+
+        let range = Range::parse("AK,AKs,AhKh").unwrap();
+        assert_eq!(range.len(), 16);
+    }
+
+    #[test]
+    fn test_invalid_ranges() {
+        assert!(Range::parse("").is_err());
+        assert!(Range::parse("AAs").is_err());
+        assert!(Range::parse("AXs").is_err());
+        assert!(Range::parse("A5s-K2s").is_err());
+        assert!(Range::parse("AhAh").is_err());
+        assert!(Range::parse("AhK").is_err());
+
+        let error = Range::parse("AhKx").unwrap_err();
+        assert_eq!(error.reason, "unknown suit 'x' at character 4");
+    }
+
+    #[test]
+    fn test_random() {
+        assert_eq!(Range::parse("random").unwrap().len(), 1326);
+        assert_eq!(Range::parse("Random,AA").unwrap().len(), 1326);
+    }
+
+    #[test]
+    fn test_weights() {
+        let range = Range::parse("AA:0.5, KQs:0.25, QQ").unwrap();
+        assert_eq!(range.len(), 6 + 4 + 6);
+        assert!(range.is_weighted());
+        assert_eq!(range.total_weight(), 3.0 + 1.0 + 6.0);
+        assert!(!Range::parse("AA:0.5,KK:0.5").unwrap().is_weighted());
+
+        // The last weight given wins, and a weight of 0 takes combos out
+        let range = Range::parse("AK:0.5,AKs,AhKh:0").unwrap();
+        assert_eq!(range.len(), 15);
+        assert_eq!(range.total_weight(), 12.0 * 0.5 + 3.0);
+
+        assert!(Range::parse("AA:x").is_err());
+        assert!(Range::parse("AA:-1").is_err());
+        assert!(Range::parse("AA:0").is_err());
+    }
+
+    #[test]
+    fn test_solver_formats() {
+        let pio = Range::parse_solver("AA,KK:0.5,\nAhKh:0.25,QQ:0\n").unwrap();
+        assert_eq!(pio.len(), 13);
+        assert_eq!(pio.total_weight(), 6.0 + 3.0 + 0.25);
+
+        let gto = Range::parse_solver("AA, [50.0]KK, AKs[/50.0], [25]AhKd[/25]").unwrap();
+        assert_eq!(gto.len(), 6 + 6 + 4 + 1);
+        assert_eq!(gto.total_weight(), 6.0 + 3.0 + 2.0 + 0.25);
+
+        assert!(Range::parse_solver("[50.0KK").is_err());
+        assert!(Range::parse_solver("[half]KK[/half]").is_err());
+    }
+
+    #[test]
+    fn test_weighted_sampling() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let range = Range::parse("AA:0.25,KK").unwrap();
+        let mut rng = StdRng::seed_from_u64(1);
+        let aces = (0..20_000)
+            .filter(|_| range.sample(&mut rng).unwrap()[0].rank == Rank::Ace)
+            .count();
+        assert!((aces as f64 / 20_000.0 - 0.2).abs() < 0.01, "{}", aces);
+
+        // Removing cards keeps the weights of what is left
+        let dead = [Card::new(Rank::King, Suit::Spades)];
+        let range = range.without_cards(&dead);
+        assert_eq!(range.total_weight(), 1.5 + 3.0);
+        let aces = (0..20_000)
+            .filter(|_| range.sample(&mut rng).unwrap()[0].rank == Rank::Ace)
+            .count();
+        assert!(
+            (aces as f64 / 20_000.0 - 1.0 / 3.0).abs() < 0.01,
+            "{}",
+            aces
+        );
+    }
+
+    #[test]
+    fn test_without_cards() {
+        let range = Range::parse("AA").unwrap();
+        let dead = [Card::new(Rank::Ace, Suit::Spades)];
+        assert_eq!(range.without_cards(&dead).len(), 3);
+    }
+
+    #[test]
+    fn test_class_frequencies() {
+        let range = Range::parse("AA,AKs:0.5,AhKd,JTo").unwrap();
+        let frequencies = range.class_frequencies();
+        assert_eq!(frequencies[0], 1.0);
+        assert_eq!(frequencies[1], 0.5);
+        assert_eq!(frequencies[13], 1.0 / 12.0);
+        assert_eq!(frequencies[4 * 13 + 3], 1.0);
+        assert_eq!(frequencies.iter().filter(|f| **f > 0.0).count(), 4);
+    }
+}
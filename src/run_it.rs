@@ -0,0 +1,319 @@
+//! Running the board out more than once per deal, as in cash games where
+//! players agree to run it twice
+//! Every run is dealt from the cards left after the runs before it, and
+//! each run is worth an equal share of the pot
+
+use rand::rngs::StdRng;
+use rand::Rng;
+
+use crate::card::Card;
+use crate::equity::{
+    deviation, merge_each, remove_known_cards, run_workers, simulate_deals, DealShowdown,
+    DealtResult, HandResult, Progress, SimulationSettings,
+};
+use crate::error::Error;
+use crate::game::Game;
+use crate::range::Range;
+
+/// Outcomes of one player when every deal is run out several times
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunItResult {
+    /// Times the board is run out per deal
+    pub runs: u32,
+
+    /// Number of deals played
+    pub deals: u64,
+
+    /// Total share of the pots won over every deal
+    pub share: f64,
+
+    /// Sum of the squared share of the pot won in every deal
+    pub share_squares: f64,
+
+    /// Total share of the pots won on the first run alone, which is what
+    /// running it once would have given
+    pub first_run_share: f64,
+
+    /// Sum of the squared share of the pot won on the first run
+    pub first_run_squares: f64,
+
+    /// Number of deals by how many runs were won outright
+    /// `runs_won[2]` is how often exactly two runs were won
+    pub runs_won: Vec<u64>,
+
+    /// Every run counted as a showdown of its own
+    pub showdowns: HandResult,
+}
+
+///////////////////////////////////////////////
+/// RunItResult Implementations
+///////////////////////////////////////////////
+impl RunItResult {
+    /// Empty result of a range
+    fn new(runs: u32, showdowns: HandResult) -> Self {
+        RunItResult {
+            runs,
+            runs_won: vec![0; runs as usize + 1],
+            showdowns,
+            ..Default::default()
+        }
+    }
+
+    /// Share of the pot won on average, the same as running it once
+    pub fn equity(&self) -> f64 {
+        self.share / self.deals.max(1) as f64
+    }
+
+    /// Standard deviation of the share of the pot won in a single deal
+    pub fn std_dev(&self) -> f64 {
+        deviation(self.share, self.share_squares, self.deals)
+    }
+
+    /// Standard deviation of the share of the pot won in a single deal
+    /// had the board only been run once
+    pub fn single_run_std_dev(&self) -> f64 {
+        deviation(self.first_run_share, self.first_run_squares, self.deals)
+    }
+
+    /// Fraction of deals where exactly `runs` runs were won outright
+    pub fn runs_won(&self, runs: usize) -> f64 {
+        let count = self.runs_won.get(runs).copied().unwrap_or(0);
+        count as f64 / self.deals.max(1) as f64
+    }
+
+    /// Add the counts of another result into this one
+    pub fn merge(&mut self, other: &RunItResult) {
+        self.deals += other.deals;
+        self.share += other.share;
+        self.share_squares += other.share_squares;
+        self.first_run_share += other.first_run_share;
+        self.first_run_squares += other.first_run_squares;
+
+        if self.runs_won.len() < other.runs_won.len() {
+            self.runs_won.resize(other.runs_won.len(), 0);
+        }
+        for (total, count) in self.runs_won.iter_mut().zip(&other.runs_won) {
+            *total += count;
+        }
+
+        self.showdowns.merge(&other.showdowns);
+    }
+}
+
+impl DealtResult for RunItResult {
+    fn showdowns(&mut self) -> &mut HandResult {
+        &mut self.showdowns
+    }
+
+    fn add_deal(&mut self) {
+        self.deals += 1;
+    }
+}
+
+/// Most times the board can be run out without running out of cards
+/// None when the board is complete and every run is the same
+pub fn max_runs(game: Game, players: usize, board: usize, dead: usize) -> Option<u32> {
+    let missing = 5usize.checked_sub(board).filter(|m| *m > 0)?;
+    let left = 52usize.saturating_sub(board + dead + players * game.hole_cards());
+    Some((left / missing) as u32)
+}
+
+/// Simulate deals from the ranges, running the board out `runs` times each
+/// The iterations are the number of deals
+/// Fails when the deck can not deal every run, see `max_runs`, for games
+/// without a board to run out, and when `settings` sets a target error, a
+/// sampler or a runout filter
+pub fn run_it_calculation<R: Rng>(
+    game: Game,
+    board: Vec<Card>,
+    dead: Vec<Card>,
+    ranges: Vec<Range>,
+    runs: u32,
+    settings: &SimulationSettings,
+    rng: &mut R,
+) -> Result<Vec<RunItResult>, Error> {
+    if !game.has_board() {
+        return Err(Error::NoBoard(game));
+    }
+    let most = max_runs(game, ranges.len(), board.len(), dead.len());
+    if let Some(most) = most.filter(|most| runs > *most) {
+        return Err(Error::TooManyRuns { runs, most });
+    }
+    settings.check_random_runouts()?;
+    let (deck, ranges) = remove_known_cards(&board, &dead, ranges);
+    let progress = Progress::new(settings);
+    let missing = 5 - board.len();
+    let run = |share: u32, rng: StdRng| {
+        let deck = deck.remaining();
+        let deal = DealShowdown::new(game, deck, &ranges, &board, settings.evaluator);
+        let new = |_, showdowns| RunItResult::new(runs, showdowns);
+        let mut available = Vec::with_capacity(deck.len());
+        let mut shares = vec![0.0; ranges.len()];
+        let mut won = vec![0; ranges.len()];
+
+        let play = |deal: &mut DealShowdown,
+                    rng: &mut StdRng,
+                    showdowns: &mut [HandResult],
+                    results: &mut [RunItResult]| {
+            if !deal.deal_hands(rng) {
+                return false;
+            }
+
+            // Draw the cards of every run at once so no card comes twice
+            let hands = &deal.hands;
+            available.clear();
+            available.extend(deck.iter().filter(|c| !hands.iter().any(|h| h.contains(c))));
+            for i in 0..missing * runs as usize {
+                let pick = rng.gen_range(i..available.len());
+                available.swap(i, pick);
+            }
+
+            shares.fill(0.0);
+            won.fill(0);
+            for run in 0..runs as usize {
+                let mut runout = [available[0]; 5];
+                runout[..board.len()].copy_from_slice(&board);
+                runout[board.len()..].copy_from_slice(&available[run * missing..][..missing]);
+
+                deal.show_down(runout);
+                deal.record(showdowns);
+
+                for (player, result) in results.iter_mut().enumerate() {
+                    let share = deal.pot_share(player);
+                    if run == 0 {
+                        result.first_run_share += share;
+                        result.first_run_squares += share * share;
+                    }
+                    shares[player] += share / runs as f64;
+                    won[player] += usize::from(share == 1.0);
+                }
+            }
+
+            for (player, result) in results.iter_mut().enumerate() {
+                result.share += shares[player];
+                result.share_squares += shares[player] * shares[player];
+                result.runs_won[won[player]] += 1;
+            }
+            true
+        };
+        simulate_deals(deal, share, &progress, rng, new, play)
+    };
+    Ok(run_workers(
+        settings,
+        rng,
+        run,
+        merge_each(RunItResult::merge),
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::card::cards_from_str;
+    use crate::range::hand_ranges;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_run_it_twice() {
+        let settings = SimulationSettings {
+            threads: 2,
+            ..SimulationSettings::new(20_000)
+        };
+        let board = cards_from_str("Ah7c2d").unwrap();
+        let results = run_it_calculation(
+            Game::Holdem,
+            board,
+            vec![],
+            hand_ranges(&["AsKs", "QhQd"]),
+            2,
+            &settings,
+            &mut StdRng::seed_from_u64(1),
+        )
+        .unwrap();
+
+        let aces = &results[0];
+        assert_eq!(aces.deals, 20_000);
+        assert_eq!(aces.showdowns.showdowns(), 40_000);
+        assert_eq!(aces.runs_won.iter().sum::<u64>(), 20_000);
+        assert!((aces.equity() - aces.showdowns.equity()).abs() < 1e-9);
+        assert!((aces.equity() + results[1].equity() - 1.0).abs() < 1e-9);
+
+        // Running it twice keeps the equity but lowers the variance
+        assert!((aces.equity() - 0.91).abs() < 0.02, "{}", aces.equity());
+        assert!(aces.std_dev() < aces.single_run_std_dev());
+        assert!(aces.runs_won(1) > 0.0);
+    }
+
+    #[test]
+    fn test_runs_never_share_cards() {
+        // Running the river out 44 times deals every card left exactly once,
+        // and sixes win on the four fives and the two sixes
+        let settings = SimulationSettings {
+            threads: 1,
+            ..SimulationSettings::new(100)
+        };
+        let board = cards_from_str("2s3s4h8c").unwrap();
+        let results = run_it_calculation(
+            Game::Holdem,
+            board,
+            vec![],
+            hand_ranges(&["6c6d", "9c9d"]),
+            44,
+            &settings,
+            &mut StdRng::seed_from_u64(2),
+        )
+        .unwrap();
+        assert_eq!(results[0].showdowns.showdowns(), 4400);
+        assert_eq!(results[0].runs_won(6), 1.0);
+        assert!((results[0].equity() - 6.0 / 44.0).abs() < 1e-9);
+        assert!(results[0].std_dev() < 1e-9);
+
+        assert_eq!(max_runs(Game::Holdem, 2, 4, 0), Some(44));
+        assert_eq!(max_runs(Game::Holdem, 2, 0, 0), Some(9));
+        assert_eq!(max_runs(Game::Omaha, 6, 3, 0), Some(12));
+        assert_eq!(max_runs(Game::Holdem, 2, 5, 0), None);
+    }
+
+    #[test]
+    fn test_runs_the_deck_can_deal() {
+        let settings = SimulationSettings::new(100);
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut run = |game, hands: &[&str], runs| {
+            run_it_calculation(
+                game,
+                vec![],
+                vec![],
+                hand_ranges(hands),
+                runs,
+                &settings,
+                &mut rng,
+            )
+        };
+        assert!(matches!(
+            run(Game::Holdem, &["AsKs", "QhQd"], 10),
+            Err(Error::TooManyRuns { runs: 10, most: 9 })
+        ));
+        assert!(matches!(
+            run(Game::Stud, &["AsAhKd", "QsQhJd"], 2),
+            Err(Error::NoBoard(Game::Stud))
+        ));
+        assert!(run(Game::Holdem, &["AsKs", "QhQd"], 9).is_ok());
+
+        let settings = SimulationSettings {
+            target_error: Some(0.01),
+            ..SimulationSettings::new(100)
+        };
+        assert!(matches!(
+            run_it_calculation(
+                Game::Holdem,
+                vec![],
+                vec![],
+                hand_ranges(&["AsKs", "QhQd"]),
+                2,
+                &settings,
+                &mut rng,
+            ),
+            Err(Error::UnsupportedSetting("target_error"))
+        ));
+    }
+}
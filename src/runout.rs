@@ -0,0 +1,254 @@
+//! Conditions on how the board runs out, such as "no-flush" or "turn:h",
+//! for asking what the equity is when a heart comes
+//! Simulations deal again until the condition holds and exact enumerations
+//! skip the runouts where it does not, so results are conditional on it
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use crate::card::{Card, Rank, Suit};
+use crate::error::Error;
+
+/// Every condition a runout has to meet, parsed from a comma separated
+/// list such as "no-flush,river:h"
+/// Board conditions are `flush`, `paired` and `straight`, each of which
+/// can be turned around with a `no-` prefix, and look at the board once
+/// it is dealt out
+/// Card conditions are `turn:`, `river:` or `runout:`, the last meaning any
+/// card still to come, followed by a rank, a suit or a card such as `A`,
+/// `h` or `Ah`, and can be turned around with a `!` prefix
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunoutFilter {
+    text: String,
+    conditions: Vec<Condition>,
+}
+
+/// One condition of a filter and whether it is turned around
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Condition {
+    test: Test,
+    negated: bool,
+}
+
+/// What a condition looks at
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Test {
+    /// Three or more cards of one suit
+    Flush,
+
+    /// Two or more cards of one rank
+    Paired,
+
+    /// Three ranks that fit in one straight
+    Straight,
+
+    /// The card dealt at a board position, or any card still to come
+    Card(Position, Pattern),
+}
+
+/// Where a card condition looks
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Position {
+    Turn,
+    River,
+    Runout,
+}
+
+/// What a card has to be
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Pattern {
+    Rank(Rank),
+    Suit(Suit),
+    Card(Card),
+}
+
+///////////////////////////////////////////////
+/// RunoutFilter Implementations
+///////////////////////////////////////////////
+impl RunoutFilter {
+    /// Parse a comma separated list of conditions, see `RunoutFilter`
+    pub fn parse(s: &str) -> Result<Self, Error> {
+        let conditions = s
+            .split(',')
+            .map(str::trim)
+            .map(|token| parse_condition(token).ok_or_else(|| invalid(token)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(RunoutFilter {
+            text: s.trim().to_string(),
+            conditions,
+        })
+    }
+
+    /// Whether a complete board meets every condition, where the first
+    /// `known` cards were on the board before the runout
+    pub fn matches(&self, board: &[Card; 5], known: usize) -> bool {
+        self.conditions
+            .iter()
+            .all(|condition| condition.test.matches(board, known) != condition.negated)
+    }
+}
+
+impl Display for RunoutFilter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl FromStr for RunoutFilter {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        RunoutFilter::parse(s)
+    }
+}
+
+///////////////////////////////////////////////
+/// Test Implementations
+///////////////////////////////////////////////
+impl Test {
+    fn matches(self, board: &[Card; 5], known: usize) -> bool {
+        match self {
+            Test::Flush => Suit::ALL
+                .iter()
+                .any(|suit| board.iter().filter(|c| c.suit == *suit).count() >= 3),
+            Test::Paired => (1..5).any(|i| board[..i].iter().any(|c| c.rank == board[i].rank)),
+            Test::Straight => {
+                // Aces play low as well as high
+                let mut ranks = 0u16;
+                for card in board {
+                    ranks |= 1 << card.rank.value();
+                    if card.rank == Rank::Ace {
+                        ranks |= 1 << 1;
+                    }
+                }
+                (1..=10).any(|low| (ranks >> low & 0b11111).count_ones() >= 3)
+            }
+            Test::Card(position, pattern) => {
+                let cards = match position {
+                    Position::Turn => &board[3..4],
+                    Position::River => &board[4..],
+                    Position::Runout => &board[known.min(5)..],
+                };
+                cards.iter().any(|card| pattern.matches(*card))
+            }
+        }
+    }
+}
+
+///////////////////////////////////////////////
+/// Pattern Implementations
+///////////////////////////////////////////////
+impl Pattern {
+    fn matches(self, card: Card) -> bool {
+        match self {
+            Pattern::Rank(rank) => card.rank == rank,
+            Pattern::Suit(suit) => card.suit == suit,
+            Pattern::Card(wanted) => card == wanted,
+        }
+    }
+}
+
+/// Parse a single condition, None when it is not one
+fn parse_condition(token: &str) -> Option<Condition> {
+    let lower = token.to_ascii_lowercase();
+
+    let board = |name: &str| match name {
+        "flush" => Some(Test::Flush),
+        "paired" => Some(Test::Paired),
+        "straight" => Some(Test::Straight),
+        _ => None,
+    };
+    if let Some(test) = board(&lower) {
+        return Some(Condition {
+            test,
+            negated: false,
+        });
+    }
+    if let Some(test) = lower.strip_prefix("no-").and_then(board) {
+        return Some(Condition {
+            test,
+            negated: true,
+        });
+    }
+
+    let (negated, rest) = match lower.strip_prefix('!') {
+        Some(rest) => (true, rest),
+        None => (false, lower.as_str()),
+    };
+    let (position, pattern) = rest.split_once(':')?;
+    let position = match position.trim() {
+        "turn" => Position::Turn,
+        "river" => Position::River,
+        "runout" => Position::Runout,
+        _ => return None,
+    };
+    let pattern = pattern.trim();
+    let mut chars = pattern.chars();
+    let pattern = match (chars.next(), chars.next(), chars.next()) {
+        (Some(c), None, None) => Rank::from_char(c)
+            .map(Pattern::Rank)
+            .or_else(|| Suit::from_char(c).map(Pattern::Suit))?,
+        (Some(_), Some(_), None) => Pattern::Card(pattern.parse().ok()?),
+        _ => return None,
+    };
+
+    Some(Condition {
+        test: Test::Card(position, pattern),
+        negated,
+    })
+}
+
+fn invalid(token: &str) -> Error {
+    Error::InvalidRunoutFilter(token.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::card::cards_from_str;
+
+    fn board(cards: &str) -> [Card; 5] {
+        cards_from_str(cards).unwrap().try_into().unwrap()
+    }
+
+    #[test]
+    fn test_board_conditions() {
+        let flush = RunoutFilter::parse("flush").unwrap();
+        assert!(flush.matches(&board("2h7hKh9c3d"), 3));
+        assert!(!flush.matches(&board("2h7hKs9c3d"), 3));
+
+        let dry = RunoutFilter::parse("no-flush, no-paired").unwrap();
+        assert!(dry.matches(&board("2h7hKs9c3d"), 3));
+        assert!(!dry.matches(&board("2h7hKs9c7d"), 3));
+
+        // The wheel counts the ace low
+        let straight = RunoutFilter::parse("straight").unwrap();
+        assert!(straight.matches(&board("Ah2c4dJsQs"), 3));
+        assert!(straight.matches(&board("9hTcJd2s2c"), 3));
+        assert!(!straight.matches(&board("2h7cKdKsAc"), 3));
+    }
+
+    #[test]
+    fn test_card_conditions() {
+        let runout = board("Kc7h2sAh9d");
+        assert!(RunoutFilter::parse("turn:h").unwrap().matches(&runout, 3));
+        assert!(RunoutFilter::parse("turn:A").unwrap().matches(&runout, 3));
+        assert!(RunoutFilter::parse("River:9d").unwrap().matches(&runout, 3));
+        assert!(!RunoutFilter::parse("!river:d").unwrap().matches(&runout, 3));
+
+        // Only the cards still to come count for the runout
+        let hearts = RunoutFilter::parse("runout:h").unwrap();
+        assert!(hearts.matches(&runout, 3));
+        assert!(!hearts.matches(&runout, 4));
+        assert_eq!(hearts.to_string(), "runout:h");
+
+        for bad in ["", "flop:h", "turn:x", "turn:Ahh", "no-turn:h", "backdoor"] {
+            assert!(
+                matches!(RunoutFilter::parse(bad), Err(Error::InvalidRunoutFilter(_))),
+                "{}",
+                bad
+            );
+        }
+    }
+}
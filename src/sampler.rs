@@ -0,0 +1,237 @@
+//! Ways of drawing the runout in a simulation
+//! Random sampling draws every card independently, stratified sampling
+//! spreads the first card of the runout evenly over the deck so that
+//! no card comes up more often than its share by chance, and Sobol
+//! sampling picks every card of the runout from a low discrepancy sequence
+//! that covers the runouts evenly as it goes
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/// Most runout cards picked from a Sobol point, a whole board
+pub(crate) const SOBOL_DIMENSIONS: usize = 5;
+
+/// Primitive polynomial and initial direction numbers of every dimension
+/// past the first, as (degree, coefficients, initial numbers), from the
+/// table of Joe and Kuo
+const SOBOL_POLYNOMIALS: [(u32, u32, &[u32]); SOBOL_DIMENSIONS - 1] = [
+    (1, 0, &[1]),
+    (2, 1, &[1, 3]),
+    (3, 1, &[1, 3, 1]),
+    (3, 2, &[1, 1, 1]),
+];
+
+/// How the cards of the runout are drawn, such as with `--sampler`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Sampler {
+    /// Every card drawn at random
+    #[default]
+    Random,
+
+    /// The first runout card drawn from each of its strata in turn, the
+    /// rest at random
+    Stratified,
+
+    /// Every runout card picked from a randomly shifted Sobol sequence
+    Sobol,
+}
+
+/// Points of a Sobol sequence in `SOBOL_DIMENSIONS` dimensions, shifted by
+/// a random XOR in every dimension so that each point on its own is uniform
+/// and separate sequences differ
+pub(crate) struct Sobol {
+    index: u32,
+    point: [u32; SOBOL_DIMENSIONS],
+    directions: [[u32; 32]; SOBOL_DIMENSIONS],
+    shift: [u32; SOBOL_DIMENSIONS],
+}
+
+/// Strata of the first runout card, visited in a shuffled order one
+/// block of every stratum at a time
+pub(crate) struct Strata {
+    order: Vec<usize>,
+    next: usize,
+}
+
+///////////////////////////////////////////////
+/// Sampler Implementations
+///////////////////////////////////////////////
+impl Display for Sampler {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Sampler::Random => "random",
+            Sampler::Stratified => "stratified",
+            Sampler::Sobol => "sobol",
+        })
+    }
+}
+
+impl FromStr for Sampler {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "random" => Ok(Sampler::Random),
+            "stratified" => Ok(Sampler::Stratified),
+            "sobol" => Ok(Sampler::Sobol),
+            _ => Err(format!(
+                "Unknown sampler \"{}\", expected random, stratified or sobol",
+                s
+            )),
+        }
+    }
+}
+
+///////////////////////////////////////////////
+/// Strata Implementations
+///////////////////////////////////////////////
+impl Strata {
+    /// Split the first runout card into `count` strata
+    pub(crate) fn new(count: usize) -> Self {
+        Strata {
+            order: (0..count).collect(),
+            next: count,
+        }
+    }
+
+    /// Number of strata
+    pub(crate) fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    /// The next stratum and a position from 0 to 1 drawn uniformly inside it
+    /// Every stratum comes once per block, so any number of draws is spread
+    /// as evenly as it can be while each draw on its own is still uniform
+    pub(crate) fn next<R: Rng>(&mut self, rng: &mut R) -> (usize, f64) {
+        if self.next == self.order.len() {
+            self.order.shuffle(rng);
+            self.next = 0;
+        }
+        let stratum = self.order[self.next];
+        self.next += 1;
+
+        let position = (stratum as f64 + rng.gen::<f64>()) / self.len() as f64;
+        (stratum, position)
+    }
+}
+
+///////////////////////////////////////////////
+/// Sobol Implementations
+///////////////////////////////////////////////
+impl Sobol {
+    /// Start a sequence with a random shift
+    pub(crate) fn new<R: Rng>(rng: &mut R) -> Self {
+        Sobol {
+            index: 0,
+            point: [0; SOBOL_DIMENSIONS],
+            directions: Sobol::directions(),
+            shift: rng.gen(),
+        }
+    }
+
+    /// Direction numbers of every dimension, the first being the van der
+    /// Corput sequence
+    fn directions() -> [[u32; 32]; SOBOL_DIMENSIONS] {
+        let mut directions = [[0; 32]; SOBOL_DIMENSIONS];
+        for (k, v) in directions[0].iter_mut().enumerate() {
+            *v = 1 << (31 - k);
+        }
+
+        for (v, (degree, coefficients, initial)) in
+            directions[1..].iter_mut().zip(SOBOL_POLYNOMIALS)
+        {
+            let degree = degree as usize;
+            for k in 0..32 {
+                v[k] = if k < degree {
+                    initial[k] << (31 - k)
+                } else {
+                    let mut next = v[k - degree] ^ (v[k - degree] >> degree);
+                    for j in 1..degree {
+                        if coefficients >> (degree - 1 - j) & 1 == 1 {
+                            next ^= v[k - j];
+                        }
+                    }
+                    next
+                };
+            }
+        }
+
+        directions
+    }
+
+    /// The next point, each coordinate from 0 to 1
+    /// Points are made in Gray code order, flipping one direction number
+    /// per dimension each time
+    pub(crate) fn next(&mut self) -> [f64; SOBOL_DIMENSIONS] {
+        let point =
+            std::array::from_fn(|d| (self.point[d] ^ self.shift[d]) as f64 / (1u64 << 32) as f64);
+
+        let bit = self.index.trailing_ones() as usize;
+        self.index = self.index.wrapping_add(1);
+        for (x, v) in self.point.iter_mut().zip(&self.directions) {
+            *x ^= v[bit.min(31)];
+        }
+
+        point
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_every_stratum_once_per_block() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut strata = Strata::new(45);
+
+        for _ in 0..3 {
+            let mut seen = (0..45)
+                .map(|_| {
+                    let (stratum, position) = strata.next(&mut rng);
+                    assert_eq!((position * 45.0) as usize, stratum);
+                    stratum
+                })
+                .collect::<Vec<_>>();
+            seen.sort();
+            assert_eq!(seen, (0..45).collect::<Vec<_>>());
+        }
+
+        assert_eq!("Stratified".parse(), Ok(Sampler::Stratified));
+        assert_eq!("sobol".parse(), Ok(Sampler::Sobol));
+        assert!("sobel".parse::<Sampler>().is_err());
+    }
+
+    #[test]
+    fn test_sobol_points_are_spread_evenly() {
+        let mut sobol = Sobol::new(&mut StdRng::seed_from_u64(6));
+        let points = (0..64).map(|_| sobol.next()).collect::<Vec<_>>();
+
+        // Every block of 2^k points puts one point in each 1/2^k of every
+        // dimension, shifted or not
+        for d in 0..SOBOL_DIMENSIONS {
+            for block in points.chunks(16) {
+                let mut cells = block
+                    .iter()
+                    .map(|p| (p[d] * 16.0) as usize)
+                    .collect::<Vec<_>>();
+                cells.sort();
+                assert_eq!(cells, (0..16).collect::<Vec<_>>(), "dimension {}", d);
+            }
+        }
+
+        // And the first two dimensions together split the square evenly
+        let mut cells = points[..16]
+            .iter()
+            .map(|p| ((p[0] * 4.0) as usize, (p[1] * 4.0) as usize))
+            .collect::<Vec<_>>();
+        cells.sort();
+        cells.dedup();
+        assert_eq!(cells.len(), 16);
+    }
+}
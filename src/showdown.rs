@@ -0,0 +1,195 @@
+//! Every holding an opponent can have on a complete board and whether
+//! it beats, ties or loses to a hand
+
+use std::cmp::{Ordering, Reverse};
+
+use crate::card::{Card, Rank};
+use crate::deck::Deck;
+use crate::error::Error;
+use crate::game::Game;
+use crate::poker_hand::{HandCategory, PokerHandRank};
+use crate::poker_utils::for_each_combination;
+use crate::range::Range;
+
+/// One opponent holding and how it does at showdown
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComboShowdown {
+    /// Hole cards of the opponent
+    pub cards: Vec<Card>,
+
+    /// Best hand they make
+    pub hand: PokerHandRank,
+
+    /// How their hand compares to ours, `Greater` when it beats us
+    pub outcome: Ordering,
+
+    /// The winning kicker when the same hand is decided by one, theirs
+    /// when they win and ours when we do
+    pub kicker: Option<Rank>,
+}
+
+/// Showdown of a hand against every possible opponent holding
+#[derive(Debug, Clone, PartialEq)]
+pub struct Showdowns {
+    /// Best hand we make
+    pub hand: PokerHandRank,
+
+    /// Whether our best hand is the board alone
+    pub plays_the_board: bool,
+
+    /// Every holding, best first
+    pub combos: Vec<ComboShowdown>,
+}
+
+///////////////////////////////////////////////
+/// Showdowns Implementations
+///////////////////////////////////////////////
+impl Showdowns {
+    /// Holdings with the given outcome against us
+    pub fn with_outcome(&self, outcome: Ordering) -> impl Iterator<Item = &ComboShowdown> {
+        self.combos.iter().filter(move |c| c.outcome == outcome)
+    }
+
+    /// Number of holdings in a category with the given outcome
+    pub fn count(&self, category: HandCategory, outcome: Ordering) -> usize {
+        self.with_outcome(outcome)
+            .filter(|c| c.hand.category() == category)
+            .count()
+    }
+
+    /// Number of holdings with the given outcome decided by a kicker
+    pub fn kicker_count(&self, outcome: Ordering) -> usize {
+        self.with_outcome(outcome)
+            .filter(|c| c.kicker.is_some())
+            .count()
+    }
+}
+
+/// Compare `hand` on a complete board against every holding an opponent
+/// can have, or only the combos of `range` when one is given
+/// Holdings using our cards, the board or `dead` cards are left out, and
+/// every combo counts once, so weighted ranges are rejected
+pub fn what_beats_me(
+    game: Game,
+    hand: &[Card],
+    board: [Card; 5],
+    dead: &[Card],
+    range: Option<&Range>,
+) -> Result<Showdowns, Error> {
+    if range.is_some_and(Range::is_weighted) {
+        return Err(Error::WeightedCombos);
+    }
+
+    let known = [hand, &board, dead].concat();
+    let ours = game.best_hand(hand, board);
+
+    let mut holdings = vec![];
+    match range {
+        Some(range) => holdings.extend(range.without_cards(&known).combos().iter().cloned()),
+        None => {
+            let deck = Deck::without(&known);
+            for_each_combination(deck.remaining(), game.hole_cards(), |cards| {
+                holdings.push(cards.to_vec())
+            });
+        }
+    }
+
+    let mut combos = holdings
+        .into_iter()
+        .map(|cards| {
+            let theirs = game.best_hand(&cards, board);
+            let outcome = theirs.cmp(&ours);
+            let kicker = match outcome {
+                Ordering::Greater => theirs.kicker_over(&ours),
+                Ordering::Less => ours.kicker_over(&theirs),
+                Ordering::Equal => None,
+            };
+            ComboShowdown {
+                cards,
+                hand: theirs,
+                outcome,
+                kicker,
+            }
+        })
+        .collect::<Vec<_>>();
+    combos.sort_by_key(|combo| Reverse(combo.hand));
+
+    Ok(Showdowns {
+        hand: ours,
+        plays_the_board: game.plays_the_board(hand, board),
+        combos,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::card::cards_from_str;
+
+    #[test]
+    fn test_what_beats_me() {
+        let hand = cards_from_str("AhQd").unwrap();
+        let board = cards_from_str("AcKs7d7h2c").unwrap().try_into().unwrap();
+        let result = what_beats_me(Game::Holdem, &hand, board, &[], None).unwrap();
+
+        assert_eq!(result.hand.category(), HandCategory::TwoPair);
+        assert_eq!(result.combos.len(), 45 * 44 / 2);
+
+        // Either seven left with any of the 35 cards that does not fill up
+        let beats = result.count(HandCategory::ThreeOfAKind, Ordering::Greater);
+        assert_eq!(beats, 2 * 35);
+        assert_eq!(
+            result.count(HandCategory::FourOfAKind, Ordering::Greater),
+            1
+        );
+        assert!(result.with_outcome(Ordering::Equal).all(|c| {
+            c.cards
+                .iter()
+                .any(|card| card.rank == crate::card::Rank::Ace)
+        }));
+        assert_eq!(result.combos[0].hand.category(), HandCategory::FourOfAKind);
+
+        let range = Range::parse("AK,QQ").unwrap();
+        let result = what_beats_me(Game::Holdem, &hand, board, &[], Some(&range)).unwrap();
+        // Two aces and three kings are left, and three queens
+        assert_eq!(result.combos.len(), 2 * 3 + 3);
+        assert_eq!(result.with_outcome(Ordering::Less).count(), 3);
+        assert!(!result.plays_the_board);
+        assert_eq!(result.kicker_count(Ordering::Greater), 0);
+    }
+
+    #[test]
+    fn test_kickers() {
+        let hand = cards_from_str("AhQd").unwrap();
+        let board = cards_from_str("Ac9s7d4h2c").unwrap().try_into().unwrap();
+        let range = Range::parse("AK,AJ").unwrap();
+        let result = what_beats_me(Game::Holdem, &hand, board, &[], Some(&range)).unwrap();
+
+        // Ace king beats our queen kicker, which beats ace jack
+        assert_eq!(result.kicker_count(Ordering::Greater), 2 * 4);
+        assert_eq!(result.kicker_count(Ordering::Less), 2 * 4);
+        assert!(result.combos.iter().all(|c| match c.outcome {
+            Ordering::Greater => c.kicker == Some(Rank::King),
+            _ => c.kicker == Some(Rank::Queen),
+        }));
+
+        let board = cards_from_str("AcKsQdJhTc").unwrap().try_into().unwrap();
+        let hand = cards_from_str("2h2d").unwrap();
+        assert!(
+            what_beats_me(Game::Holdem, &hand, board, &[], None)
+                .unwrap()
+                .plays_the_board
+        );
+    }
+
+    #[test]
+    fn test_weighted_range() {
+        let hand = cards_from_str("AhQd").unwrap();
+        let board = cards_from_str("Ac9s7d4h2c").unwrap().try_into().unwrap();
+        let range = Range::parse("AK,AJ:0.5").unwrap();
+        assert!(matches!(
+            what_beats_me(Game::Holdem, &hand, board, &[], Some(&range)),
+            Err(Error::WeightedCombos)
+        ));
+    }
+}
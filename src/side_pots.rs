@@ -0,0 +1,275 @@
+//! Multiway all ins with stacks of different sizes, where a short stack can
+//! only win the main pot and the bigger stacks play for side pots too
+//! Each pot goes to the best hand of the players in it, so a player's
+//! expected chips are not their equity times the whole pot
+
+use rand::rngs::StdRng;
+use rand::Rng;
+
+use crate::card::Card;
+use crate::equity::{
+    merge_each, pot_share, remove_known_cards, run_workers, simulate_showdowns, DealShowdown,
+    DealtResult, HandResult, Progress, SimulationSettings,
+};
+use crate::error::Error;
+use crate::game::Game;
+use crate::poker_hand::{HandValue, LowHand};
+use crate::range::Range;
+
+/// One pot of an all in, the main pot or a side pot
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pot {
+    /// Chips in the pot
+    pub amount: f64,
+
+    /// Players who can win it, a single one when nobody covers the rest of
+    /// their stack and it goes back to them
+    pub players: Vec<usize>,
+}
+
+/// Chips one player wins when every stack is all in
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SidePotResult {
+    /// Chips the player put in
+    pub stack: f64,
+
+    /// Number of deals played
+    pub deals: u64,
+
+    /// Total chips won over every deal, from every pot the player is in
+    pub won: f64,
+
+    /// The showdown of every deal between all the players
+    pub showdowns: HandResult,
+}
+
+///////////////////////////////////////////////
+/// SidePotResult Implementations
+///////////////////////////////////////////////
+impl SidePotResult {
+    /// Chips won on average
+    pub fn expected_chips(&self) -> f64 {
+        self.won / self.deals.max(1) as f64
+    }
+
+    /// Chips won on average less the stack put in
+    pub fn ev(&self) -> f64 {
+        self.expected_chips() - self.stack
+    }
+
+    /// Add the counts of another result into this one
+    pub fn merge(&mut self, other: &SidePotResult) {
+        self.deals += other.deals;
+        self.won += other.won;
+        self.showdowns.merge(&other.showdowns);
+    }
+}
+
+impl DealtResult for SidePotResult {
+    fn showdowns(&mut self) -> &mut HandResult {
+        &mut self.showdowns
+    }
+
+    fn add_deal(&mut self) {
+        self.deals += 1;
+    }
+}
+
+/// Split all in stacks into the main pot and the side pots, smallest stack
+/// first
+/// Every player puts in up to each stack size in turn, and the players
+/// with at least that much can win what it adds
+pub fn side_pots(stacks: &[f64]) -> Result<Vec<Pot>, Error> {
+    if let Some(&stack) = stacks.iter().find(|s| !(**s > 0.0 && s.is_finite())) {
+        return Err(Error::InvalidStack(stack));
+    }
+    let mut levels = stacks.to_vec();
+    levels.sort_by(f64::total_cmp);
+    levels.dedup();
+
+    let mut pots = vec![];
+    let mut below = 0.0;
+    for level in levels {
+        let amount = stacks
+            .iter()
+            .map(|stack| stack.min(level) - stack.min(below))
+            .sum();
+        let players = (0..stacks.len()).filter(|i| stacks[*i] >= level).collect();
+        pots.push(Pot { amount, players });
+        below = level;
+    }
+    Ok(pots)
+}
+
+/// Simulate deals from the ranges with every player all in for their stack,
+/// paying out the main pot and each side pot to the best hand in it
+/// Fails for games without a board, and when `settings` sets a target
+/// error, a sampler or a runout filter
+pub fn run_side_pot_calculation<R: Rng>(
+    game: Game,
+    board: Vec<Card>,
+    dead: Vec<Card>,
+    ranges: Vec<Range>,
+    stacks: &[f64],
+    settings: &SimulationSettings,
+    rng: &mut R,
+) -> Result<Vec<SidePotResult>, Error> {
+    if !game.has_board() {
+        return Err(Error::NoBoard(game));
+    }
+    if stacks.len() != ranges.len() {
+        return Err(Error::WrongStackCount {
+            stacks: stacks.len(),
+            hands: ranges.len(),
+        });
+    }
+    let pots = side_pots(stacks)?;
+    settings.check_random_runouts()?;
+    let (deck, ranges) = remove_known_cards(&board, &dead, ranges);
+    let progress = Progress::new(settings);
+    let run = |share: u32, rng: StdRng| {
+        let deal = DealShowdown::new(game, deck.remaining(), &ranges, &board, settings.evaluator);
+        let new = |player: usize, showdowns| SidePotResult {
+            stack: stacks[player],
+            showdowns,
+            ..Default::default()
+        };
+        let mut pot_winners = Vec::with_capacity(ranges.len());
+        let mut pot_low_winners = Vec::with_capacity(ranges.len());
+        simulate_showdowns(deal, share, &progress, rng, new, |deal, results| {
+            for pot in &pots {
+                pot_winners_into(&pot.players, &deal.best, &mut pot_winners);
+                let low_winners = deal.lows().map(|lows| {
+                    pot_low_winners_into(&pot.players, lows, &mut pot_low_winners);
+                    pot_low_winners.as_slice()
+                });
+                for &player in &pot.players {
+                    results[player].won +=
+                        pot.amount * pot_share(player, &pot_winners, low_winners);
+                }
+            }
+        })
+    };
+    Ok(run_workers(
+        settings,
+        rng,
+        run,
+        merge_each(SidePotResult::merge),
+    ))
+}
+
+/// Players of a pot with the best high hand among them
+fn pot_winners_into(players: &[usize], best: &[HandValue], winners: &mut Vec<usize>) {
+    let top = players.iter().map(|i| best[*i]).max().unwrap();
+    winners.clear();
+    winners.extend(players.iter().filter(|i| best[**i] == top));
+}
+
+/// Players of a pot with the best qualifying low among them, empty when
+/// none of them has one
+fn pot_low_winners_into(players: &[usize], lows: &[Option<LowHand>], winners: &mut Vec<usize>) {
+    winners.clear();
+    if let Some(best) = players.iter().filter_map(|i| lows[*i]).min() {
+        winners.extend(players.iter().filter(|i| lows[**i] == Some(best)));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::card::cards_from_str;
+    use crate::range::hand_ranges;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_side_pots() {
+        let pots = side_pots(&[2000.0, 1500.0, 800.0]).unwrap();
+        assert_eq!(
+            pots,
+            [
+                Pot {
+                    amount: 2400.0,
+                    players: vec![0, 1, 2]
+                },
+                Pot {
+                    amount: 1400.0,
+                    players: vec![0, 1]
+                },
+                Pot {
+                    amount: 500.0,
+                    players: vec![0]
+                },
+            ]
+        );
+        assert_eq!(side_pots(&[100.0, 100.0]).unwrap().len(), 1);
+        assert!(matches!(
+            side_pots(&[100.0, 0.0]),
+            Err(Error::InvalidStack(_))
+        ));
+    }
+
+    #[test]
+    fn test_side_pot_ev() {
+        let settings = SimulationSettings {
+            threads: 2,
+            ..SimulationSettings::new(20_000)
+        };
+        let mut rng = StdRng::seed_from_u64(100);
+
+        // The short stack holds the nuts, so it always takes the main pot
+        // and the other two play for the side pot
+        let board = cards_from_str("AhAdAsKh2c").unwrap();
+        let stacks = [2000.0, 1500.0, 800.0];
+        let results = run_side_pot_calculation(
+            Game::Holdem,
+            board,
+            vec![],
+            hand_ranges(&["QcQd", "JcJd", "AcKd"]),
+            &stacks,
+            &settings,
+            &mut rng,
+        )
+        .unwrap();
+        assert_eq!(results[2].expected_chips(), 2400.0);
+        assert_eq!(results[0].expected_chips(), 1400.0 + 500.0);
+        assert_eq!(results[1].expected_chips(), 0.0);
+        assert_eq!(results[1].ev(), -1500.0);
+        assert_eq!(results[0].showdowns.showdowns(), 20_000);
+
+        let chips = results.iter().map(|r| r.won).sum::<f64>();
+        assert_eq!(chips, 4300.0 * 20_000.0);
+        assert!(matches!(
+            run_side_pot_calculation(
+                Game::Holdem,
+                vec![],
+                vec![],
+                hand_ranges(&["QcQd", "JcJd"]),
+                &stacks,
+                &settings,
+                &mut rng,
+            ),
+            Err(Error::WrongStackCount {
+                stacks: 3,
+                hands: 2
+            })
+        ));
+
+        let filter = "no-flush".parse().unwrap();
+        let settings = SimulationSettings {
+            runout_filter: Some(&filter),
+            ..settings
+        };
+        assert!(matches!(
+            run_side_pot_calculation(
+                Game::Holdem,
+                vec![],
+                vec![],
+                hand_ranges(&["QcQd", "JcJd"]),
+                &stacks[..2],
+                &settings,
+                &mut rng,
+            ),
+            Err(Error::UnsupportedSetting("runout_filter"))
+        ));
+    }
+}
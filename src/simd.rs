@@ -0,0 +1,78 @@
+//! Seven card hand evaluation several hands at a time with `std::simd`
+//! Enabled with the `simd` feature, which needs a nightly toolchain. The
+//! rank and suit masks of every lane are counted together, and each lane is
+//! then ranked from its masks the same way as the lookup evaluator
+
+use std::array;
+use std::simd::prelude::*;
+
+use crate::card::Card;
+use crate::lookup::{classify, RankMask};
+use crate::poker_hand::HandValue;
+
+/// Hands ranked together in one batch
+pub const LANES: usize = 8;
+
+/// Rank the best five card hand of every seven card hand, `LANES` at a time
+pub fn evaluate_batch(hands: &[[Card; 7]], values: &mut Vec<HandValue>) {
+    for chunk in hands.chunks(LANES) {
+        // Lanes past the end of the chunk repeat its first hand
+        let mut lanes = [chunk[0]; LANES];
+        lanes[..chunk.len()].copy_from_slice(chunk);
+
+        // Ranks in the low 13 bits of each 16 bit suit
+        let mut suited = Simd::<u64, LANES>::splat(0);
+        let mut held = [Simd::<RankMask, LANES>::splat(0); 4];
+        for card in 0..7 {
+            let cards = lanes.map(|hand| hand[card]);
+            let ranks = cards.map(|card| card.rank.value() as u64 - 2);
+            let suits = cards.map(|card| card.suit as u64 * 16);
+
+            let ranks = Simd::from_array(ranks);
+            suited |= Simd::splat(1) << (Simd::from_array(suits) + ranks);
+
+            // A rank seen before moves up to the next mask
+            let bit = Simd::<RankMask, LANES>::splat(1) << ranks.cast();
+            let [any, pairs, trips, quads] = &mut held;
+            *quads |= *trips & bit;
+            *trips |= *pairs & bit;
+            *pairs |= *any & bit;
+            *any |= bit;
+        }
+
+        for lane in 0..chunk.len() {
+            let suits = array::from_fn(|s| (suited[lane] >> (16 * s)) as RankMask);
+            values.push(classify(suits, held.map(|mask| mask[lane])).into());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::deck::Deck;
+    use crate::lookup::evaluate;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_matches_lookup_evaluator() {
+        let mut rng = StdRng::seed_from_u64(23);
+        let mut deck = Deck::new();
+
+        // An odd count so the last batch is partly filled
+        let hands = (0..10_001)
+            .map(|_| {
+                deck.shuffle(&mut rng);
+                array::from_fn(|i| deck.remaining()[i])
+            })
+            .collect::<Vec<[Card; 7]>>();
+
+        let mut values = vec![];
+        evaluate_batch(&hands, &mut values);
+        assert_eq!(values.len(), hands.len());
+        for (hand, value) in hands.iter().zip(values) {
+            assert_eq!(value, evaluate(hand).into(), "{:?}", hand);
+        }
+    }
+}
@@ -0,0 +1,159 @@
+//! Splitting a range by the equity of each of its combos into value hands,
+//! marginal hands such as bluff catchers, and air
+//! Built on the combo breakdown of an equity calculation
+
+use crate::card::Card;
+use crate::equity::HandResult;
+use crate::error::Error;
+
+/// Where a combo falls by its equity
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bucket {
+    /// At least the value threshold
+    Value,
+
+    /// Between the two thresholds
+    Marginal,
+
+    /// Below the air threshold
+    Air,
+}
+
+/// Combos of one bucket and how they do
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BucketResult {
+    /// Hole cards of every combo in the bucket
+    pub combos: Vec<Vec<Card>>,
+
+    /// Share of the range's showdowns played with these combos, so
+    /// weighted combos and combos blocked by the other hands count less
+    pub share: f64,
+
+    /// Equity of the bucket as a whole
+    pub equity: f64,
+}
+
+/// A range split into value, marginal and air
+#[derive(Debug, Clone, PartialEq)]
+pub struct RangeSplit {
+    /// Equity a combo needs to be value
+    pub value_threshold: f64,
+
+    /// Equity below which a combo is air
+    pub air_threshold: f64,
+
+    /// Results of each bucket, indexed by `Bucket as usize`
+    pub buckets: [BucketResult; 3],
+}
+
+///////////////////////////////////////////////
+/// Bucket Implementations
+///////////////////////////////////////////////
+impl Bucket {
+    /// Every bucket from the strongest down
+    pub const ALL: [Bucket; 3] = [Bucket::Value, Bucket::Marginal, Bucket::Air];
+
+    /// Name of the bucket
+    pub fn name(&self) -> &'static str {
+        match self {
+            Bucket::Value => "Value",
+            Bucket::Marginal => "Marginal",
+            Bucket::Air => "Air",
+        }
+    }
+}
+
+///////////////////////////////////////////////
+/// RangeSplit Implementations
+///////////////////////////////////////////////
+impl RangeSplit {
+    /// Results of one bucket
+    pub fn bucket(&self, bucket: Bucket) -> &BucketResult {
+        &self.buckets[bucket as usize]
+    }
+
+    /// Bucket a combo with `equity` falls in
+    pub fn bucket_of(&self, equity: f64) -> Bucket {
+        if equity >= self.value_threshold {
+            Bucket::Value
+        } else if equity >= self.air_threshold {
+            Bucket::Marginal
+        } else {
+            Bucket::Air
+        }
+    }
+}
+
+/// Split the combos of a player's range by their equity, value at
+/// `value` or more, air below `air` and marginal in between
+/// Combos that never reached a showdown are left out
+pub fn split_range(result: &HandResult, air: f64, value: f64) -> Result<RangeSplit, Error> {
+    for threshold in [air, value] {
+        if !(0.0..=1.0).contains(&threshold) {
+            return Err(Error::InvalidChance(threshold));
+        }
+    }
+    if air > value {
+        return Err(Error::BucketOrder { air, value });
+    }
+
+    let mut split = RangeSplit {
+        value_threshold: value,
+        air_threshold: air,
+        buckets: Default::default(),
+    };
+
+    let mut totals = [(0.0, 0); 3];
+    for combo in result.combos.iter().filter(|c| c.showdowns > 0) {
+        let bucket = split.bucket_of(combo.equity()) as usize;
+        split.buckets[bucket].combos.push(combo.cards.clone());
+        totals[bucket].0 += combo.pot_share;
+        totals[bucket].1 += combo.showdowns;
+    }
+
+    let showdowns = totals.iter().map(|(_, showdowns)| showdowns).sum::<u64>();
+    for (bucket, (share, count)) in split.buckets.iter_mut().zip(totals) {
+        bucket.share = count as f64 / showdowns.max(1) as f64;
+        bucket.equity = share / count.max(1) as f64;
+    }
+
+    Ok(split)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::card::cards_from_str;
+    use crate::equity::run_exact_calculation;
+    use crate::game::Game;
+    use crate::range::Range;
+
+    #[test]
+    fn test_split_range() {
+        // Against a pair of queens on a king high board, sets and top pair
+        // are value and ace high is drawing to the aces
+        let range = Range::parse("77,KQs,A5s,22").unwrap();
+        let queens = Range::parse("QhQd").unwrap();
+        let board = cards_from_str("Kc7h2s").unwrap();
+        let result =
+            run_exact_calculation(Game::Holdem, board, vec![], vec![range, queens]).unwrap();
+
+        let split = split_range(&result[0], 0.1, 0.66).unwrap();
+        let value = split.bucket(Bucket::Value);
+        // Three sets of sevens, three of deuces and the one king queen
+        // suited left once the board and the queens are dealt
+        assert_eq!(value.combos.len(), 7);
+        assert!(value.equity > 0.66);
+        assert_eq!(split.bucket(Bucket::Air).combos.len(), 0);
+        assert_eq!(split.bucket(Bucket::Marginal).combos.len(), 4);
+        let shares = split.buckets.iter().map(|b| b.share).sum::<f64>();
+        assert!((shares - 1.0).abs() < 1e-9);
+        assert_eq!(split.bucket_of(0.05), Bucket::Air);
+
+        assert!(matches!(
+            split_range(&result[0], 0.7, 0.3),
+            Err(Error::BucketOrder { .. })
+        ));
+        assert!(split_range(&result[0], 0.3, 1.5).is_err());
+    }
+}
@@ -0,0 +1,270 @@
+//! How the hands stand on every street of the runout
+//! Every deal is shown down on each street with the board dealt so far, so
+//! a hand's share on the flop is how often it is ahead there, and its share
+//! on the river is its equity
+
+use rand::rngs::StdRng;
+use rand::Rng;
+
+use crate::board::Street;
+use crate::card::Card;
+use crate::equity::{
+    merge_each, pot_share, remove_known_cards, run_workers, simulate_showdowns, DealShowdown,
+    DealtResult, HandResult, Progress, SimulationSettings,
+};
+use crate::error::Error;
+use crate::evaluator::Evaluator;
+use crate::game::Game;
+use crate::poker_hand::{HandValue, LowHand};
+use crate::range::Range;
+
+/// Shares of the pot one player wins when the deals are shown down on
+/// each street
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StreetResult {
+    /// Streets shown down, every street after the known board
+    pub streets: Vec<Street>,
+
+    /// Number of deals played
+    pub deals: u64,
+
+    /// Total share of the pots won on each street, over every deal
+    pub shares: Vec<f64>,
+
+    /// The river showdown of every deal
+    pub showdowns: HandResult,
+}
+
+///////////////////////////////////////////////
+/// StreetResult Implementations
+///////////////////////////////////////////////
+impl StreetResult {
+    /// Empty result of a range
+    fn new(streets: Vec<Street>, showdowns: HandResult) -> Self {
+        StreetResult {
+            shares: vec![0.0; streets.len()],
+            streets,
+            showdowns,
+            ..Default::default()
+        }
+    }
+
+    /// Share of the pot won on average when the deal is shown down on
+    /// `street`, None for the known board and the streets before it
+    pub fn equity(&self, street: Street) -> Option<f64> {
+        let i = self.streets.iter().position(|s| *s == street)?;
+        Some(self.shares[i] / self.deals.max(1) as f64)
+    }
+
+    /// Add the counts of another result into this one
+    pub fn merge(&mut self, other: &StreetResult) {
+        self.deals += other.deals;
+        for (total, share) in self.shares.iter_mut().zip(&other.shares) {
+            *total += share;
+        }
+        self.showdowns.merge(&other.showdowns);
+    }
+}
+
+impl DealtResult for StreetResult {
+    fn showdowns(&mut self) -> &mut HandResult {
+        &mut self.showdowns
+    }
+
+    fn add_deal(&mut self) {
+        self.deals += 1;
+    }
+}
+
+/// Streets dealt after a board of `board` cards
+/// Courchevel's exposed card is dealt before the flop, which is then played
+pub fn streets_after(game: Game, board: usize) -> Vec<Street> {
+    if !game.has_board() {
+        return vec![];
+    }
+    Street::ALL
+        .into_iter()
+        .filter(|street| street.cards() > board)
+        .collect()
+}
+
+/// Simulate deals from the ranges, showing each one down on every street
+/// after the board
+/// Games without a board have no streets, and `settings` can not set a
+/// target error, a sampler or a runout filter
+pub fn run_street_calculation<R: Rng>(
+    game: Game,
+    board: Vec<Card>,
+    dead: Vec<Card>,
+    ranges: Vec<Range>,
+    settings: &SimulationSettings,
+    rng: &mut R,
+) -> Result<Vec<StreetResult>, Error> {
+    settings.check_random_runouts()?;
+    let (deck, ranges) = remove_known_cards(&board, &dead, ranges);
+    let streets = streets_after(game, board.len());
+    let progress = Progress::new(settings);
+    let run = |share: u32, rng: StdRng| {
+        let evaluator = settings.evaluator;
+        let deal = DealShowdown::new(game, deck.remaining(), &ranges, &board, evaluator);
+        let new = |_, showdowns| StreetResult::new(streets.clone(), showdowns);
+
+        // Nothing is dealt without a street to come
+        let share = if streets.is_empty() { 0 } else { share };
+
+        // The river is the showdown of the deal, the streets before it are
+        // shown down with buffers of their own
+        let mut best = Vec::with_capacity(ranges.len());
+        let mut winners = Vec::with_capacity(ranges.len());
+        let mut lows = Vec::with_capacity(ranges.len());
+        let mut low_winners = Vec::with_capacity(ranges.len());
+
+        simulate_showdowns(deal, share, &progress, rng, new, |deal, results| {
+            for (i, street) in streets.iter().enumerate() {
+                if *street == Street::River {
+                    for (player, result) in results.iter_mut().enumerate() {
+                        result.shares[i] += deal.pot_share(player);
+                    }
+                    continue;
+                }
+
+                let dealt_board = &deal.board[..street.cards()];
+                street_winners(
+                    game,
+                    &deal.hands,
+                    dealt_board,
+                    evaluator,
+                    &mut best,
+                    &mut winners,
+                );
+                let low_winners = game.is_hi_lo().then(|| {
+                    street_low_winners(game, &deal.hands, dealt_board, &mut lows, &mut low_winners);
+                    low_winners.as_slice()
+                });
+                for (player, result) in results.iter_mut().enumerate() {
+                    result.shares[i] += pot_share(player, &winners, low_winners);
+                }
+            }
+        })
+    };
+    Ok(run_workers(
+        settings,
+        rng,
+        run,
+        merge_each(StreetResult::merge),
+    ))
+}
+
+/// Players with the best high hand on a board of three to five cards
+fn street_winners(
+    game: Game,
+    hands: &[&[Card]],
+    board: &[Card],
+    evaluator: &dyn Evaluator,
+    best: &mut Vec<HandValue>,
+    winners: &mut Vec<usize>,
+) {
+    best.clear();
+    game.rules().best_hands(hands, board, evaluator, best);
+
+    let winning_hand = *best.iter().max().unwrap();
+    winners.clear();
+    winners.extend((0..best.len()).filter(|i| best[*i] == winning_hand));
+}
+
+/// Players with the best qualifying low on a board of three to five cards,
+/// empty when nobody has one
+fn street_low_winners(
+    game: Game,
+    hands: &[&[Card]],
+    board: &[Card],
+    lows: &mut Vec<Option<LowHand>>,
+    winners: &mut Vec<usize>,
+) {
+    lows.clear();
+    lows.extend(hands.iter().map(|hand| game.rules().best_low(hand, board)));
+
+    winners.clear();
+    if let Some(best) = lows.iter().flatten().min() {
+        winners.extend((0..lows.len()).filter(|i| lows[*i].as_ref() == Some(best)));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::card::cards_from_str;
+    use crate::range::hand_ranges;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_street_equities() {
+        let settings = SimulationSettings {
+            threads: 2,
+            ..SimulationSettings::new(20_000)
+        };
+        let mut rng = StdRng::seed_from_u64(91);
+        let results = run_street_calculation(
+            Game::Holdem,
+            vec![],
+            vec![],
+            hand_ranges(&["AhKh", "2c2d"]),
+            &settings,
+            &mut rng,
+        )
+        .unwrap();
+
+        let ak = &results[0];
+        assert_eq!(ak.streets, [Street::Flop, Street::Turn, Street::River]);
+        assert_eq!(ak.deals, 20_000);
+        assert_eq!(ak.equity(Street::Preflop), None);
+
+        // Deuces are ahead on most flops, and AK catches up by the river
+        let flop = ak.equity(Street::Flop).unwrap();
+        let turn = ak.equity(Street::Turn).unwrap();
+        let river = ak.equity(Street::River).unwrap();
+        assert!(flop < 0.4, "{}", flop);
+        assert!(flop < turn && turn < river, "{} {} {}", flop, turn, river);
+        assert!((river - ak.showdowns.equity()).abs() < 1e-9);
+        assert!((0.45..0.55).contains(&river), "{}", river);
+    }
+
+    #[test]
+    fn test_streets_after() {
+        assert_eq!(streets_after(Game::Holdem, 4), [Street::River]);
+        assert!(streets_after(Game::Holdem, 5).is_empty());
+        assert_eq!(streets_after(Game::Courchevel, 1).len(), 3);
+        assert!(streets_after(Game::Stud, 0).is_empty());
+
+        let settings = SimulationSettings::new(1_000);
+        let mut rng = StdRng::seed_from_u64(7);
+        let board = cards_from_str("Kh7c2d").unwrap();
+        let results = run_street_calculation(
+            Game::Omaha8,
+            board,
+            vec![],
+            hand_ranges(&["As2s3d4d", "KdKsQhQd"]),
+            &settings,
+            &mut rng,
+        )
+        .unwrap();
+        assert_eq!(results[0].streets, [Street::Turn, Street::River]);
+        assert_eq!(results[1].showdowns.showdowns(), 1_000);
+
+        let settings = SimulationSettings {
+            target_error: Some(0.01),
+            ..settings
+        };
+        assert!(matches!(
+            run_street_calculation(
+                Game::Holdem,
+                vec![],
+                vec![],
+                hand_ranges(&["AhKh", "2c2d"]),
+                &settings,
+                &mut rng,
+            ),
+            Err(Error::UnsupportedSetting("target_error"))
+        ));
+    }
+}
@@ -0,0 +1,284 @@
+//! Games without a board, where every player is dealt cards of their own
+//! In stud and razz a player's hand is every card they have been dealt so
+//! far, up and down, and the rest of their seven cards are dealt from the
+//! stub. In 2-7 single draw and badugi it is the cards they keep, and they
+//! draw the rest of their five or four
+
+use rand::rngs::StdRng;
+use rand::Rng;
+
+use crate::card::Card;
+use crate::deck::Deck;
+use crate::equity::{
+    deal_hands, dealt_hands, empty_results, for_each_deal, record_showdown, HandResult, Progress,
+};
+use crate::game::Game;
+use crate::poker_hand::{BadugiHand, DeuceSevenHand, HandCategory, PokerHandRank, RazzHand};
+use crate::poker_utils::{
+    for_each_combination, get_best_badugi_hand, get_best_deuce_seven_hand, get_best_hand,
+    get_best_razz_hand,
+};
+use crate::range::Range;
+
+/// Buffers for the hands made at each showdown, allocated once
+#[derive(Default)]
+struct Showdown {
+    highs: Vec<PokerHandRank>,
+    razz: Vec<RazzHand>,
+    deuce_seven: Vec<DeuceSevenHand>,
+    badugi: Vec<BadugiHand>,
+    winners: Vec<usize>,
+}
+
+/// Run `iterations` simulations on the current thread, dealing every
+/// player the rest of their cards
+pub(crate) fn simulate(
+    game: Game,
+    deck: &[Card],
+    ranges: &[Range],
+    iterations: u32,
+    progress: &Progress,
+    mut rng: StdRng,
+) -> Vec<HandResult> {
+    let mut results = empty_results(ranges);
+
+    let mut deck = deck.to_vec();
+    let mut dealt = Vec::with_capacity(ranges.len());
+    let mut cards = vec![Vec::with_capacity(game.hole_cards()); ranges.len()];
+    let mut showdown = Showdown::default();
+
+    for _ in 0..iterations {
+        if progress.stopped() {
+            break;
+        }
+        progress.tick();
+
+        if !deal_hands(ranges, &mut rng, &mut dealt) {
+            continue;
+        }
+        let hands = dealt_hands(ranges, &dealt);
+        deal_rest(&mut deck, &hands, game.hole_cards(), &mut cards, &mut rng);
+
+        showdown.record(game, &cards, &dealt, &mut results);
+    }
+
+    results
+}
+
+/// Enumerate every deal of the ranges and every way the rest of the cards
+/// can be dealt to each player
+/// Only practical with a card or two left to come, or in draw games
+pub(crate) fn run_exact(game: Game, deck: Deck, ranges: Vec<Range>) -> Vec<HandResult> {
+    let mut results = empty_results(&ranges);
+    let mut showdown = Showdown::default();
+
+    for_each_deal(&ranges, &mut vec![], &mut |dealt| {
+        let hands = dealt_hands(&ranges, dealt);
+        let held = hands.iter().copied().flatten().cloned().collect::<Vec<_>>();
+        let mut deck = deck.clone();
+        deck.remove(&held);
+        let mut cards = hands.iter().map(|hand| hand.to_vec()).collect::<Vec<_>>();
+
+        for_each_own_deal(
+            deck.remaining(),
+            game.hole_cards(),
+            &mut cards,
+            0,
+            &mut |cards| {
+                showdown.record(game, cards, dealt, &mut results);
+            },
+        );
+    });
+
+    results
+}
+
+/// Fill every player's cards up to `size` from the deck, skipping any held card
+/// The deck is partially shuffled in place like a board runout
+fn deal_rest<R: Rng>(
+    deck: &mut [Card],
+    hands: &[&[Card]],
+    size: usize,
+    cards: &mut [Vec<Card>],
+    rng: &mut R,
+) {
+    let mut next = 0;
+    for (hand, cards) in hands.iter().zip(cards.iter_mut()) {
+        cards.clear();
+        cards.extend_from_slice(hand);
+
+        while cards.len() < size {
+            let pick = rng.gen_range(next..deck.len());
+            deck.swap(next, pick);
+            let card = deck[next];
+            next += 1;
+
+            if !hands.iter().any(|hand| hand.contains(&card)) {
+                cards.push(card);
+            }
+        }
+    }
+}
+
+/// Call `f` with every way of dealing the players from `player` on the
+/// rest of their `size` cards out of the deck
+fn for_each_own_deal(
+    deck: &[Card],
+    size: usize,
+    cards: &mut Vec<Vec<Card>>,
+    player: usize,
+    f: &mut dyn FnMut(&[Vec<Card>]),
+) {
+    if player == cards.len() {
+        f(cards);
+        return;
+    }
+
+    let left = deck
+        .iter()
+        .filter(|card| !cards[..player].iter().any(|c| c.contains(card)))
+        .cloned()
+        .collect::<Vec<_>>();
+    let known = cards[player].len();
+
+    for_each_combination(&left, size - known, |rest| {
+        cards[player].extend_from_slice(rest);
+        for_each_own_deal(deck, size, cards, player + 1, f);
+        cards[player].truncate(known);
+    });
+}
+
+///////////////////////////////////////////////
+/// Showdown Implementations
+///////////////////////////////////////////////
+impl Showdown {
+    /// Find the best hand of every player, high or low depending on the
+    /// game, and record the showdown
+    fn record(
+        &mut self,
+        game: Game,
+        cards: &[Vec<Card>],
+        dealt: &[usize],
+        results: &mut [HandResult],
+    ) {
+        let winners = &mut self.winners;
+        match game {
+            Game::Razz => {
+                evaluate(&mut self.razz, winners, cards, get_best_razz_hand, true);
+                record_showdown(results, dealt, &self.razz, winners, None);
+            }
+            Game::Deuce7 => {
+                let hands = &mut self.deuce_seven;
+                evaluate(hands, winners, cards, get_best_deuce_seven_hand, true);
+                record_showdown(results, dealt, hands, winners, None);
+            }
+            Game::Badugi => {
+                evaluate(&mut self.badugi, winners, cards, get_best_badugi_hand, true);
+                record_showdown(results, dealt, &self.badugi, winners, None);
+            }
+            _ => {
+                evaluate(&mut self.highs, winners, cards, get_best_hand, false);
+                record_showdown(results, dealt, &self.highs, winners, None);
+            }
+        }
+    }
+}
+
+/// Best hand of every player and the players holding the best of them,
+/// the lowest hand when `lowest` and the highest otherwise
+fn evaluate<H: Copy + Ord + Into<HandCategory>>(
+    hands: &mut Vec<H>,
+    winners: &mut Vec<usize>,
+    cards: &[Vec<Card>],
+    best_hand: fn(&[Card]) -> H,
+    lowest: bool,
+) {
+    hands.clear();
+    hands.extend(cards.iter().map(|cards| best_hand(cards)));
+
+    let best = match lowest {
+        true => hands.iter().min(),
+        false => hands.iter().max(),
+    };
+    let best = *best.unwrap();
+    winners.clear();
+    winners.extend((0..hands.len()).filter(|&i| hands[i] == best));
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::card::cards_from_str;
+    use crate::equity::{run_exact_calculation, run_range_calculation, SimulationSettings};
+    use rand::SeedableRng;
+
+    fn hands(hands: &[&str]) -> Vec<Range> {
+        hands
+            .iter()
+            .map(|h| Range::from_cards(cards_from_str(h).unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn test_stud_sixth_street() {
+        // Trip aces only beat quad kings by catching the last ace
+        let ranges = hands(&["AsAhAd2c3c4c", "KsKhKdKc7d8h"]);
+
+        let exact = run_exact_calculation(Game::Stud, vec![], vec![], ranges.clone()).unwrap();
+        assert_eq!(exact[0].showdowns(), 40 * 39);
+        assert!((exact[0].equity() - 1.0 / 40.0).abs() < 1e-9);
+
+        let settings = SimulationSettings::new(20_000);
+        let mut rng = StdRng::seed_from_u64(1);
+        let simulated =
+            run_range_calculation(Game::Stud, vec![], vec![], ranges, &settings, &mut rng);
+        assert!((simulated[0].equity() - 0.025).abs() < 0.005);
+    }
+
+    #[test]
+    fn test_stud_third_street() {
+        // Rolled up aces against a small three flush, with the flush cards dead
+        let ranges = hands(&["AsAhAd", "7c8c9c"]);
+        let dead = cards_from_str("2c3c4c").unwrap();
+
+        let settings = SimulationSettings::new(20_000);
+        let mut rng = StdRng::seed_from_u64(1);
+        let results = run_range_calculation(Game::Stud, vec![], dead, ranges, &settings, &mut rng);
+        assert!(results[0].equity() > 0.75, "{}", results[0].equity());
+        assert_eq!(results[0].showdowns(), 20_000);
+    }
+
+    #[test]
+    fn test_razz_sixth_street() {
+        // A-2-3-4 needs a five or a six to beat a made six, and ties when
+        // the five comes along with an ace for the six
+        let ranges = hands(&["As2d3c4hKsKd", "2h3h4s5d6cQc"]);
+
+        let results = run_exact_calculation(Game::Razz, vec![], vec![], ranges).unwrap();
+        let equity = (6.0 * 36.0 + 0.5 * 3.0 * 3.0) / (40.0 * 39.0);
+        assert!((results[0].equity() - equity).abs() < 1e-9);
+        assert_eq!(results[0].ties, 9);
+    }
+
+    #[test]
+    fn test_deuce_seven_single_draw() {
+        // Drawing one to 8-7-5-3 against a pat 9-6-4-3-2
+        let ranges = hands(&["8s7d5c3h", "9h6c4d3s2c"]);
+        let exact = run_exact_calculation(Game::Deuce7, vec![], vec![], ranges).unwrap();
+
+        // Any 2, 4 or 6 makes an eight, less the ones the nine holds
+        let outs = 4.0 * 3.0 - 3.0;
+        let left = 52.0 - 9.0;
+        assert!((exact[0].equity() - outs / left).abs() < 1e-9);
+        assert_eq!(exact[0].showdowns(), 43);
+    }
+
+    #[test]
+    fn test_badugi_draw() {
+        // Drawing one to A-2-3 without a spade against a pat 8-7-5-4, where
+        // only the 4, 5, 6 and 7 of spades make a better badugi
+        let ranges = hands(&["Ah2c3d", "8s7h5c4d"]);
+        let exact = run_exact_calculation(Game::Badugi, vec![], vec![], ranges).unwrap();
+        assert!((exact[0].equity() - 4.0 / 45.0).abs() < 1e-9);
+    }
+}
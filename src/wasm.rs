@@ -0,0 +1,189 @@
+//! JavaScript bindings for running the engine in a browser
+//! Enabled with the `wasm` feature, everything runs on the calling thread
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use wasm_bindgen::prelude::*;
+
+use crate::card::cards_from_str;
+use crate::equity::{
+    check_duplicate_cards, run_exact_calculation, run_range_calculation, EquityResult,
+    SimulationSettings,
+};
+use crate::error::Error;
+use crate::game::Game;
+use crate::poker_utils::get_best_hand;
+use crate::range::Range;
+
+/// Describe the best five card hand out of five to seven cards,
+/// such as "Full House, Aces full of Kings"
+#[wasm_bindgen]
+pub fn evaluate(cards: &str) -> Result<String, JsError> {
+    let cards = cards_from_str(cards).map_err(Error::from)?;
+    if !(5..=7).contains(&cards.len()) {
+        return Err(Error::InvalidHandSize(cards.len()).into());
+    }
+
+    Ok(get_best_hand(&cards).to_string())
+}
+
+/// Results of every hand or range, in the order given, as an object like
+/// the serialized `EquityResult`
+/// `game` is holdem, omaha, omaha8, omaha5, courchevel, pineapple, stud, razz, deuce7 or badugi, and `seed` makes the result reproducible
+/// Iterations are ignored when `exact` enumerates every runout
+#[wasm_bindgen]
+pub fn equity(
+    hands: Vec<String>,
+    board: &str,
+    dead: &str,
+    game: &str,
+    iterations: u32,
+    exact: bool,
+    seed: Option<u64>,
+) -> Result<JsValue, JsError> {
+    let game = game.parse::<Game>().map_err(|e| JsError::new(&e))?;
+    let result = calculate(hands, board, dead, game, iterations, exact, seed)?;
+    Ok(serde_wasm_bindgen::to_value(&result)?)
+}
+
+/// The checks and calculation behind `equity`, kept apart from JavaScript
+/// values so they run on every target
+fn calculate(
+    hands: Vec<String>,
+    board: &str,
+    dead: &str,
+    game: Game,
+    iterations: u32,
+    exact: bool,
+    seed: Option<u64>,
+) -> Result<EquityResult, Error> {
+    if hands.len() < 2 {
+        return Err(Error::NotEnoughHands(hands.len()));
+    }
+
+    let ranges = hands
+        .iter()
+        .map(|hand| {
+            let range = Range::parse(hand).map_err(|e| Error::from(e).input("hand", hand))?;
+            for combo in range.combos() {
+                game.check_hole_cards(combo.len())
+                    .map_err(|e| e.input("hand", hand))?;
+            }
+            Ok(range)
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let board = cards_from_str(board).map_err(|e| Error::from(e).input("board", board))?;
+    if !game.is_valid_board(board.len()) {
+        return Err(Error::InvalidBoard(board.len()));
+    }
+    let dead = cards_from_str(dead).map_err(|e| Error::from(e).input("dead", dead))?;
+    if !game.has_board() && !board.is_empty() {
+        return Err(Error::NoBoard(game));
+    }
+    if !game.has_board() && ranges.len() * game.hole_cards() + dead.len() > 52 {
+        return Err(Error::DeckTooSmall {
+            cards: game.hole_cards(),
+            players: ranges.len(),
+            dead: dead.len(),
+        });
+    }
+
+    let mut known = ranges
+        .iter()
+        .enumerate()
+        .filter(|(_, range)| range.len() == 1)
+        .map(|(i, range)| (format!("hand {}", i + 1), range.combos()[0].clone()))
+        .collect::<Vec<_>>();
+    known.push(("board".to_string(), board.clone()));
+    known.push(("dead".to_string(), dead.clone()));
+    check_duplicate_cards(&known)?;
+
+    let per_hand = if exact {
+        run_exact_calculation(game, board.clone(), dead.clone(), ranges)?
+    } else {
+        let settings = SimulationSettings {
+            threads: 1,
+            ..SimulationSettings::new(iterations)
+        };
+        let mut rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        run_range_calculation(
+            game,
+            board.clone(),
+            dead.clone(),
+            ranges,
+            &settings,
+            &mut rng,
+        )
+    };
+
+    Ok(EquityResult::new(hands, board, dead, per_hand))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn hands(hands: &[&str]) -> Vec<String> {
+        hands.iter().map(|h| h.to_string()).collect()
+    }
+
+    #[test]
+    fn test_evaluate() {
+        assert_eq!(
+            evaluate("AsAhAdKcKs2h").unwrap(),
+            "Full House, Aces full of Kings"
+        );
+    }
+
+    #[test]
+    fn test_calculate() {
+        let exact = calculate(
+            hands(&["AsAh", "KdKc"]),
+            "Ac7d2h9s3c",
+            "",
+            Game::Holdem,
+            0,
+            true,
+            None,
+        )
+        .unwrap();
+        assert_eq!(exact.per_hand[0].equity(), 1.0);
+
+        // A seed gives the same simulation every time
+        let run = || {
+            calculate(
+                hands(&["AsKs", "QQ"]),
+                "",
+                "",
+                Game::Holdem,
+                2_000,
+                false,
+                Some(4),
+            )
+        };
+        let simulated = run().unwrap();
+        assert_eq!(simulated.per_hand[0].showdowns(), 2_000);
+        assert_eq!(simulated, run().unwrap());
+
+        assert!(matches!(
+            calculate(hands(&["AsKs"]), "", "", Game::Holdem, 100, false, None),
+            Err(Error::NotEnoughHands(1))
+        ));
+        assert!(matches!(
+            calculate(
+                hands(&["AsKs", "QQ"]),
+                "2c3c",
+                "",
+                Game::Holdem,
+                100,
+                false,
+                None
+            ),
+            Err(Error::InvalidBoard(2))
+        ));
+    }
+}